@@ -254,6 +254,29 @@ impl ActivityIndicator {
             });
         }
 
+        // Show background tasks that are starting up or doing a cycle of work, like a rebuild.
+        let busy_tasks = self.project.read(cx).busy_background_tasks(cx);
+        if let Some(label) = busy_tasks.first() {
+            let mut message = format!("Waiting for task `{label}`...");
+            if busy_tasks.len() > 1 {
+                write!(&mut message, " + {} more", busy_tasks.len() - 1).unwrap();
+            }
+            return Some(Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            });
+        }
+
         // Show any language server installation info.
         let mut downloading = SmallVec::<[_; 3]>::new();
         let mut checking_for_update = SmallVec::<[_; 3]>::new();
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt as _;
+use gpui::{AsyncApp, BackgroundExecutor, Task};
+
+/// Forwards credential prompts coming from an askpass script to whoever is able to answer them,
+/// e.g. a modal in the UI or a downstream client when running on a remote host.
+#[derive(Clone)]
+pub struct AskPassDelegate {
+    tx: mpsc::UnboundedSender<(String, oneshot::Sender<String>)>,
+}
+
+impl AskPassDelegate {
+    pub fn new(
+        cx: &mut AsyncApp,
+        password_prompt: impl Fn(String, oneshot::Sender<String>, &mut AsyncApp) + 'static,
+    ) -> Self {
+        let (tx, mut rx) = mpsc::unbounded::<(String, oneshot::Sender<String>)>();
+        cx.spawn(|mut cx| async move {
+            while let Some((prompt, channel)) = rx.next().await {
+                password_prompt(prompt, channel, &mut cx);
+            }
+        })
+        .detach();
+        Self { tx }
+    }
+
+    /// Asks for a response to the given prompt. Resolves to an error if the prompt was dismissed.
+    pub async fn ask_password(&self, prompt: String) -> Result<String> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .unbounded_send((prompt, tx))
+            .context("askpass delegate was dropped")?;
+        rx.await.context("askpass prompt was dismissed")
+    }
+}
+
+/// A temporary askpass script, and the socket listener answering its requests, that lives for
+/// the duration of a single command. Point `GIT_ASKPASS` / `SSH_ASKPASS` at [`Self::script_path`].
+pub struct AskPassSession {
+    script_path: Option<PathBuf>,
+    _askpass_task: Task<()>,
+    _temp_dir: Option<tempfile::TempDir>,
+}
+
+impl AskPassSession {
+    #[cfg(unix)]
+    pub async fn new(executor: &BackgroundExecutor, delegate: AskPassDelegate) -> Result<Self> {
+        use futures::{io::BufReader, AsyncBufReadExt as _, AsyncWriteExt as _};
+        use smol::{fs::unix::PermissionsExt as _, net::unix::UnixListener};
+        use util::ResultExt as _;
+
+        let temp_dir = tempfile::Builder::new().prefix("zed-askpass").tempdir()?;
+        let askpass_socket = temp_dir.path().join("askpass.sock");
+        let askpass_script_path = temp_dir.path().join("askpass.sh");
+
+        let listener =
+            UnixListener::bind(&askpass_socket).context("failed to create askpass socket")?;
+
+        let askpass_task = executor.spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = Vec::new();
+                let mut reader = BufReader::new(&mut stream);
+                if reader.read_until(b'\0', &mut buffer).await.is_err() {
+                    buffer.clear();
+                }
+                let prompt = String::from_utf8_lossy(&buffer)
+                    .trim_end_matches('\0')
+                    .to_string();
+                // If the prompt is dismissed we close the stream without writing anything,
+                // which makes the askpass script print nothing and the command fail to authenticate.
+                if let Some(password) = delegate.ask_password(prompt).await.log_err() {
+                    stream.write_all(password.as_bytes()).await.log_err();
+                }
+            }
+        });
+
+        // Create an askpass script that communicates back to this process.
+        let askpass_script = format!(
+            "{shebang}\n{print_args} | {nc} -U {askpass_socket} 2> /dev/null \n",
+            // on macOS `brew install netcat` provides the GNU netcat implementation
+            // which does not support -U.
+            nc = if cfg!(target_os = "macos") {
+                "/usr/bin/nc"
+            } else {
+                "nc"
+            },
+            askpass_socket = askpass_socket.display(),
+            print_args = "printf '%s\\0' \"$@\"",
+            shebang = "#!/bin/sh",
+        );
+        smol::fs::write(&askpass_script_path, askpass_script).await?;
+        smol::fs::set_permissions(&askpass_script_path, std::fs::Permissions::from_mode(0o755))
+            .await?;
+
+        Ok(Self {
+            script_path: Some(askpass_script_path),
+            _askpass_task: askpass_task,
+            _temp_dir: Some(temp_dir),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub async fn new(_executor: &BackgroundExecutor, _delegate: AskPassDelegate) -> Result<Self> {
+        Ok(Self {
+            script_path: None,
+            _askpass_task: Task::ready(()),
+            _temp_dir: None,
+        })
+    }
+
+    /// The path to the askpass script, or `None` on platforms where prompting isn't supported,
+    /// in which case the command falls back to the user's configured credential helpers.
+    pub fn script_path(&self) -> Option<&Path> {
+        self.script_path.as_deref()
+    }
+}
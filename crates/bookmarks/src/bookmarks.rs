@@ -0,0 +1,298 @@
+mod label_bookmark;
+
+use editor::bookmarks::{open_bookmark, sorted_bookmarks};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    ParentElement, Render, Styled, WeakEntity, Window,
+};
+use label_bookmark::LabelBookmarkModal;
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use project::ProjectPath;
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(bookmarks, [Toggle, LabelBookmark]);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(BookmarksPicker::register).detach();
+    cx.observe_new(LabelBookmarkModal::register).detach();
+}
+
+/// Lists the bookmarks of all of the project's files.
+pub struct BookmarksPicker {
+    picker: Entity<Picker<BookmarksPickerDelegate>>,
+}
+
+impl BookmarksPicker {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            let workspace_handle = cx.entity().downgrade();
+            let bookmarks = BookmarkEntry::all(workspace, cx);
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                BookmarksPicker::new(workspace_handle, bookmarks, window, cx)
+            });
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        bookmarks: Vec<BookmarkEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = BookmarksPickerDelegate::new(cx.entity().downgrade(), workspace, bookmarks);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for BookmarksPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl Focusable for BookmarksPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarksPicker {}
+impl ModalView for BookmarksPicker {}
+
+struct BookmarkEntry {
+    path: ProjectPath,
+    row: u32,
+    /// The bookmark's label, or the text of the bookmarked line when it has none and its file
+    /// is open.
+    title: String,
+    /// The bookmark's path and line number, as `path:line`.
+    location: String,
+}
+
+impl BookmarkEntry {
+    fn all(workspace: &Workspace, cx: &App) -> Vec<Self> {
+        let project = workspace.project().read(cx);
+        sorted_bookmarks(workspace.project(), cx)
+            .into_iter()
+            .map(|bookmark| {
+                let location = format!(
+                    "{}:{}",
+                    bookmark.path.path.to_string_lossy(),
+                    bookmark.row + 1
+                );
+                let title = match bookmark.label {
+                    Some(label) => label.to_string(),
+                    None => project
+                        .get_open_buffer(&bookmark.path, cx)
+                        .map(|buffer| {
+                            let buffer = buffer.read(cx);
+                            let line_end = Point::new(bookmark.row, buffer.line_len(bookmark.row));
+                            buffer
+                                .text_for_range(Point::new(bookmark.row, 0)..line_end)
+                                .collect::<String>()
+                                .trim()
+                                .to_string()
+                        })
+                        .unwrap_or_default(),
+                };
+                Self {
+                    path: bookmark.path,
+                    row: bookmark.row,
+                    title,
+                    location,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct BookmarksPickerDelegate {
+    bookmarks_picker: WeakEntity<BookmarksPicker>,
+    workspace: WeakEntity<Workspace>,
+    bookmarks: Vec<BookmarkEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarksPickerDelegate {
+    fn new(
+        bookmarks_picker: WeakEntity<BookmarksPicker>,
+        workspace: WeakEntity<Workspace>,
+        bookmarks: Vec<BookmarkEntry>,
+    ) -> Self {
+        let candidates = bookmarks
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, bookmark)| {
+                StringMatchCandidate::new(
+                    candidate_id,
+                    &format!("{} {}", bookmark.title, bookmark.location),
+                )
+            })
+            .collect();
+
+        Self {
+            bookmarks_picker,
+            workspace,
+            bookmarks,
+            candidates,
+            matches: vec![],
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarksPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Go to bookmark…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> SharedString {
+        if self.bookmarks.is_empty() {
+            "No bookmarks".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let bookmark = &self.bookmarks[mat.candidate_id];
+            let (path, row) = (bookmark.path.clone(), bookmark.row);
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(workspace, path, row, window, cx);
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmarks_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, |this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let bookmark = &self.bookmarks[mat.candidate_id];
+        // Candidates are the title and the location, separated by a space.
+        let location_start = bookmark.title.len() + 1;
+        let title_positions = mat
+            .positions
+            .iter()
+            .copied()
+            .filter(|position| *position < bookmark.title.len())
+            .collect();
+        let location_positions = mat
+            .positions
+            .iter()
+            .filter_map(|position| position.checked_sub(location_start))
+            .collect();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::Bookmark)
+                        .size(IconSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            bookmark.title.clone(),
+                            title_positions,
+                        ))
+                        .child(
+                            HighlightedLabel::new(bookmark.location.clone(), location_positions)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    Subscription, Window,
+};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::LabelBookmark;
+
+/// Bookmarks the line of the active editor's cursor with a label, or changes the label of
+/// the bookmark already there.
+pub struct LabelBookmarkModal {
+    label_editor: Entity<Editor>,
+    active_editor: Entity<Editor>,
+    is_bookmarked: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for LabelBookmarkModal {}
+
+impl Focusable for LabelBookmarkModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LabelBookmarkModal {}
+
+impl LabelBookmarkModal {
+    pub(crate) fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &LabelBookmark, window, cx| {
+            let Some(active_editor) = workspace
+                .active_item(cx)
+                .and_then(|item| item.act_as::<Editor>(cx))
+            else {
+                return;
+            };
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                LabelBookmarkModal::new(active_editor, window, cx)
+            });
+        });
+    }
+
+    fn new(active_editor: Entity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let bookmark = active_editor.read(cx).bookmark_at_cursor(cx);
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label", cx);
+            if let Some(Some(label)) = &bookmark {
+                editor.set_text(label.to_string(), window, cx);
+                editor.select_all(&Default::default(), window, cx);
+            }
+            editor
+        });
+        let label_editor_change =
+            cx.subscribe_in(&label_editor, window, Self::on_label_editor_event);
+
+        Self {
+            label_editor,
+            active_editor,
+            is_bookmarked: bookmark.is_some(),
+            _subscriptions: vec![label_editor_change],
+        }
+    }
+
+    fn on_label_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let EditorEvent::Blurred = event {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let label = self.label_editor.read(cx).text(cx);
+        let label = label.trim();
+        let label = (!label.is_empty()).then(|| SharedString::from(label.to_string()));
+        self.active_editor.update(cx, |editor, cx| {
+            editor.set_bookmark_at_cursor(label, cx);
+            editor.focus_handle(cx).focus(window);
+        });
+
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for LabelBookmarkModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = if self.is_bookmarked {
+            "Change the label of the bookmark on this line"
+        } else {
+            "Bookmark this line with a label"
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("LabelBookmark")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
@@ -306,6 +306,23 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferById>)
             .add_request_handler(forward_read_only_project_request::<proto::SynchronizeBuffers>)
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSemanticTokens>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSemanticTokensDelta>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareTypeHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSupertypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSubtypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetIncomingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetOutgoingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetCodeLens>)
+            .add_request_handler(forward_read_only_project_request::<proto::ResolveCodeLens>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetDocumentLinks>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetFoldingRanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSelectionRanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetRenameEdits>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetInlineValues>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetDocumentColors>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetColorPresentations>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
             .add_request_handler(forward_read_only_project_request::<proto::GitBranches>)
@@ -346,6 +363,10 @@ impl Server {
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::RefreshSemanticTokens>,
+            )
+            .add_message_handler(broadcast_project_message_from_host::<proto::RefreshCodeLens>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
@@ -399,6 +420,26 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::GitReset>)
             .add_request_handler(forward_read_only_project_request::<proto::GitCheckoutFiles>)
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
+            .add_request_handler(forward_mutating_project_request::<proto::Push>)
+            .add_request_handler(forward_mutating_project_request::<proto::Pull>)
+            .add_request_handler(forward_mutating_project_request::<proto::Fetch>)
+            .add_request_handler(forward_askpass_request)
+            .add_request_handler(forward_remote_progress)
+            .add_request_handler(forward_read_only_project_request::<proto::GetRemotes>)
+            .add_request_handler(forward_mutating_project_request::<proto::StashPush>)
+            .add_request_handler(forward_mutating_project_request::<proto::StashApply>)
+            .add_request_handler(forward_mutating_project_request::<proto::StashPop>)
+            .add_request_handler(forward_mutating_project_request::<proto::StashDrop>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetStashEntries>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetStashChanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetLog>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetCommitChanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetRebaseTodo>)
+            .add_request_handler(forward_mutating_project_request::<proto::RebaseInteractive>)
+            .add_request_handler(forward_mutating_project_request::<proto::RebaseContinue>)
+            .add_request_handler(forward_mutating_project_request::<proto::RebaseAbort>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetRebaseStatus>)
+            .add_request_handler(forward_mutating_project_request::<proto::AmendCommitMessage>)
             .add_request_handler(forward_mutating_project_request::<proto::OpenCommitMessageBuffer>)
             .add_message_handler(broadcast_project_message_from_host::<proto::AdvertiseContexts>)
             .add_message_handler(update_context)
@@ -2191,6 +2232,52 @@ async fn create_buffer_for_peer(
     Ok(())
 }
 
+/// Forward a credential prompt from the host to the guest whose git push, pull or fetch needs it.
+async fn forward_askpass_request(
+    request: proto::AskPassRequest,
+    response: Response<proto::AskPassRequest>,
+    session: Session,
+) -> Result<()> {
+    session
+        .db()
+        .await
+        .check_user_is_project_host(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+        )
+        .await?;
+    let peer_id = request.peer_id.ok_or_else(|| anyhow!("invalid peer id"))?;
+    let payload = session
+        .peer
+        .forward_request(session.connection_id, peer_id.into(), request)
+        .await?;
+    response.send(payload)?;
+    Ok(())
+}
+
+/// Forward the progress of a git push, pull or fetch from the host to the guest that started it.
+async fn forward_remote_progress(
+    request: proto::RemoteProgress,
+    response: Response<proto::RemoteProgress>,
+    session: Session,
+) -> Result<()> {
+    session
+        .db()
+        .await
+        .check_user_is_project_host(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+        )
+        .await?;
+    let peer_id = request.peer_id.ok_or_else(|| anyhow!("invalid peer id"))?;
+    let payload = session
+        .peer
+        .forward_request(session.connection_id, peer_id.into(), request)
+        .await?;
+    response.send(payload)?;
+    Ok(())
+}
+
 /// Notify other participants that a buffer has been updated. This is
 /// allowed for guests as long as the update is limited to selections.
 async fn update_buffer(
@@ -4951,6 +4951,153 @@ async fn test_references(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_call_and_type_hierarchy(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a.language_registry().add(rust_lang());
+    let mut fake_language_servers = client_a.language_registry().register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/root",
+            json!({
+                "main.rs": "fn main() {}\nfn helper() {}\n",
+                "other.rs": "fn caller() { helper(); }",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/root", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    let (buffer_b, _handle) = project_b
+        .update(cx_b, |p, cx| {
+            p.open_buffer_with_lsp((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+
+    let lsp_item = |name: &str, path: &str, row: u32, name_start: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 14)),
+        selection_range: lsp::Range::new(
+            lsp::Position::new(row, name_start),
+            lsp::Position::new(row, name_start + name.len() as u32),
+        ),
+        // Servers keep their own state here, which has to come back verbatim.
+        data: Some(json!({ "id": name })),
+    };
+    fake_language_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>(
+        move |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(1, 4)
+            );
+            Ok(Some(vec![lsp_item("helper", "/root/main.rs", 1, 3)]))
+        },
+    );
+    fake_language_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.item, lsp_item("helper", "/root/main.rs", 1, 3));
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: lsp_item("caller", "/root/other.rs", 0, 3),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 14),
+                    lsp::Position::new(0, 20),
+                )],
+            }]))
+        },
+    );
+    fake_language_server
+        .handle_request::<lsp::request::TypeHierarchyPrepare, _, _>(|_, _| async move { Ok(None) });
+
+    let items = project_b
+        .update(cx_b, |p, cx| {
+            p.prepare_call_hierarchy(&buffer_b, Point::new(1, 4), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    cx_b.read(|cx| {
+        let item = &items[0];
+        assert_eq!(item.name, "helper");
+        assert_eq!(item.kind, lsp::SymbolKind::FUNCTION);
+        assert_eq!(item.location.buffer, buffer_b);
+        let buffer = item.location.buffer.read(cx);
+        assert_eq!(
+            item.location.range.to_point(buffer),
+            Point::new(1, 0)..Point::new(1, 14)
+        );
+        assert_eq!(
+            item.selection_range.to_point(buffer),
+            Point::new(1, 3)..Point::new(1, 9)
+        );
+    });
+
+    let calls = project_b
+        .update(cx_b, |p, cx| p.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx_b.read(|cx| {
+        let call = &calls[0];
+        assert_eq!(call.item.name, "caller");
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer.file().unwrap().path().as_ref(),
+            Path::new("other.rs")
+        );
+        assert_eq!(call.call_sites.len(), 1);
+        assert_eq!(call.call_sites[0].buffer, call.item.location.buffer);
+        assert_eq!(
+            call.call_sites[0].range.to_point(caller_buffer),
+            Point::new(0, 14)..Point::new(0, 20)
+        );
+    });
+
+    // Call hierarchy items cannot be used to look up types.
+    project_b
+        .update(cx_b, |p, cx| p.supertypes(&items[0], cx))
+        .await
+        .unwrap_err();
+    let type_items = project_b
+        .update(cx_b, |p, cx| {
+            p.prepare_type_hierarchy(&buffer_b, Point::new(1, 4), cx)
+        })
+        .await
+        .unwrap();
+    assert!(type_items.is_empty());
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_project_search(
     executor: BackgroundExecutor,
@@ -6808,3 +6955,72 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.name, "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_remote_git_push_progress(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    // The host writes a real askpass script to a temporary directory.
+    executor.allow_parking();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/project", json!({ ".git": {}, "a.txt": "a" }))
+        .await;
+    let dot_git = Path::new("/project/.git");
+    client_a.fs().insert_branches(dot_git, &["main"]);
+    client_a.fs().with_git_state(dot_git, true, |state| {
+        state.remotes.insert("origin".to_string());
+        state.remote_progress = vec![
+            "Enumerating objects: 5, done.".to_string(),
+            "Writing objects: 100% (3/3), 290 bytes | 290.00 KiB/s, done.".to_string(),
+        ];
+    });
+
+    let (project_a, _) = client_a.build_local_project("/project", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    let repository_b =
+        project_b.read_with(cx_b, |project, cx| project.active_repository(cx).unwrap());
+    let askpass = askpass::AskPassDelegate::new(&mut cx_b.to_async(), |_, _, _| {});
+    let (progress_tx, progress_rx) = mpsc::unbounded();
+    let output = repository_b
+        .update(cx_b, |repository, cx| {
+            repository.push(
+                "main".into(),
+                "origin".into(),
+                None,
+                askpass,
+                progress_tx,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        output.stderr,
+        "Enumerating objects: 5, done.\nWriting objects: 100% (3/3), 290 bytes | 290.00 KiB/s, done."
+    );
+
+    // The host only forwards the latest line when several queue up, but always the last one,
+    // and before responding to the push.
+    let progress = progress_rx.collect::<Vec<_>>().await;
+    assert_eq!(
+        progress.last().map(String::as_str),
+        Some("Writing objects: 100% (3/3), 290 bytes | 290.00 KiB/s, done.")
+    );
+}
@@ -397,6 +397,7 @@ impl Copilot {
                 path: "path/to/copilot".into(),
                 arguments: vec![],
                 env: None,
+                transport: Default::default(),
             },
             "copilot".into(),
             Default::default(),
@@ -435,6 +436,7 @@ impl Copilot {
                 arguments,
                 // TODO: We could set HTTP_PROXY etc here and fix the copilot issue.
                 env: None,
+                transport: Default::default(),
             };
 
             let root_path = if cfg!(target_os = "windows") {
@@ -452,7 +454,8 @@ impl Copilot {
                 root_path,
                 None,
                 cx.clone(),
-            )?;
+            )
+            .await?;
 
             server
                 .on_notification::<StatusNotification, _>(|_, _| { /* Silence the notification */ })
@@ -1279,5 +1282,13 @@ mod tests {
         fn load_bytes(&self, _cx: &App) -> Task<Result<Vec<u8>>> {
             unimplemented!()
         }
+
+        fn load_with_encoding(
+            &self,
+            _: language::Encoding,
+            _: &App,
+        ) -> Task<Result<(String, language::Encoding)>> {
+            unimplemented!()
+        }
     }
 }
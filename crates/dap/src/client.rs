@@ -0,0 +1,731 @@
+use crate::{
+    transport::{self, AnyEvent, AnyRequest, AnyResponse, Message},
+    DebugAdapterBinary, DebugAdapterClientId, Event, Request,
+};
+use anyhow::{anyhow, Context as _, Result};
+use collections::HashMap;
+use futures::{channel::oneshot, io::BufWriter, select, AsyncRead, AsyncWrite, Future, FutureExt};
+use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use smol::{
+    channel,
+    io::{AsyncBufReadExt, BufReader},
+    process::Child,
+};
+use std::{
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering::SeqCst},
+        Arc, Weak,
+    },
+    time::Duration,
+};
+use util::ResultExt;
+
+const DAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
+
+type ResponseHandler = Box<dyn Send + FnOnce(Result<Value>)>;
+type EventHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
+type RequestHandler = Box<dyn Send + FnMut(u64, Value, AsyncApp)>;
+
+/// A connection to a running debug adapter.
+///
+/// Both sides of the protocol can send requests, so the same type also backs the fake adapter
+/// used in tests.
+pub struct DebugAdapterClient {
+    id: DebugAdapterClientId,
+    name: SharedString,
+    next_seq: Arc<AtomicU64>,
+    outbound_tx: channel::Sender<String>,
+    response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+    executor: BackgroundExecutor,
+    #[allow(clippy::type_complexity)]
+    io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
+    process: Arc<Mutex<Option<Child>>>,
+}
+
+/// Handle to a debug adapter event or request subscription. The handler is removed when this is
+/// dropped, unless it is detached.
+pub enum Subscription {
+    Event {
+        event: &'static str,
+        event_handlers: Option<Weak<Mutex<HashMap<&'static str, EventHandler>>>>,
+    },
+    Request {
+        command: &'static str,
+        request_handlers: Option<Weak<Mutex<HashMap<&'static str, RequestHandler>>>>,
+    },
+}
+
+impl DebugAdapterClient {
+    /// Starts a debug adapter process.
+    pub fn new(
+        id: DebugAdapterClientId,
+        name: SharedString,
+        binary: DebugAdapterBinary,
+        cx: AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "starting debug adapter process. command: {:?}, working directory: {:?}, args: {:?}",
+            binary.command,
+            binary.cwd,
+            binary.arguments
+        );
+
+        let mut command = util::command::new_smol_command(&binary.command);
+        if let Some(cwd) = &binary.cwd {
+            command.current_dir(cwd);
+        }
+        let mut process = command
+            .args(&binary.arguments)
+            .envs(binary.env.clone())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "failed to spawn debug adapter. command: {:?}, working directory: {:?}, args: {:?}",
+                    binary.command, binary.cwd, binary.arguments
+                )
+            })?;
+
+        let stdin = process.stdin.take().unwrap();
+        let stdout = process.stdout.take().unwrap();
+        let stderr = process.stderr.take().unwrap();
+        Ok(Self::new_internal(
+            id,
+            name,
+            stdin,
+            stdout,
+            Some(stderr),
+            Some(process),
+            cx,
+        ))
+    }
+
+    fn new_internal<Stdin, Stdout, Stderr>(
+        id: DebugAdapterClientId,
+        name: SharedString,
+        stdin: Stdin,
+        stdout: Stdout,
+        stderr: Option<Stderr>,
+        process: Option<Child>,
+        cx: AsyncApp,
+    ) -> Self
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+        Stdout: AsyncRead + Unpin + Send + 'static,
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let next_seq = Arc::new(AtomicU64::new(1));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+        let event_handlers = Arc::new(Mutex::new(HashMap::<_, EventHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+
+        let stdout_input_task = cx.spawn({
+            let response_handlers = response_handlers.clone();
+            let event_handlers = event_handlers.clone();
+            let request_handlers = request_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
+            let next_seq = next_seq.clone();
+            let name = name.clone();
+            move |cx| {
+                Self::handle_input(
+                    stdout,
+                    name,
+                    response_handlers,
+                    event_handlers,
+                    request_handlers,
+                    outbound_tx,
+                    next_seq,
+                    cx,
+                )
+                .log_err()
+            }
+        });
+        let stderr_input_task = stderr
+            .map(|stderr| {
+                let name = name.clone();
+                cx.background_executor()
+                    .spawn(Self::handle_stderr(stderr, name).log_err())
+            })
+            .unwrap_or_else(|| Task::ready(None));
+        let input_task = cx.spawn(|_| async move {
+            let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            stdout.or(stderr)
+        });
+        let output_task = cx
+            .background_executor()
+            .spawn(Self::handle_output(stdin, outbound_rx, response_handlers.clone()).log_err());
+
+        Self {
+            id,
+            name,
+            next_seq,
+            outbound_tx,
+            response_handlers,
+            event_handlers,
+            request_handlers,
+            executor: cx.background_executor().clone(),
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            process: Arc::new(Mutex::new(process)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_input<Stdout>(
+        stdout: Stdout,
+        name: SharedString,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+        event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        outbound_tx: channel::Sender<String>,
+        next_seq: Arc<AtomicU64>,
+        cx: AsyncApp,
+    ) -> Result<()>
+    where
+        Stdout: AsyncRead + Unpin + Send + 'static,
+    {
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut stdout = BufReader::new(stdout);
+        let mut buffer = Vec::new();
+
+        while let Some(message) = transport::read_message(&mut stdout, &mut buffer).await? {
+            log::trace!("incoming debug adapter message: {message}");
+            let message = match serde_json::from_str::<Message>(&message) {
+                Ok(message) => message,
+                Err(error) => {
+                    log::warn!("failed to deserialize DAP message: {error}\n{message}");
+                    continue;
+                }
+            };
+
+            match message {
+                Message::Response(response) => {
+                    let handler = response_handlers
+                        .lock()
+                        .as_mut()
+                        .and_then(|handlers| handlers.remove(&response.request_seq));
+                    if let Some(handler) = handler {
+                        if response.success {
+                            handler(Ok(response.body.unwrap_or(Value::Null)));
+                        } else {
+                            handler(Err(anyhow!(response
+                                .message
+                                .unwrap_or_else(|| format!("{} failed", response.command)))));
+                        }
+                    }
+                }
+                Message::Event(event) => {
+                    let mut event_handlers = event_handlers.lock();
+                    if let Some(handler) = event_handlers.get_mut(event.event.as_str()) {
+                        handler(event.body.unwrap_or(Value::Null), cx.clone());
+                    } else {
+                        log::info!(
+                            "debug adapter {name} sent unhandled event {}:\n{}",
+                            event.event,
+                            event.body.unwrap_or(Value::Null),
+                        );
+                    }
+                }
+                Message::Request(request) => {
+                    let mut request_handlers = request_handlers.lock();
+                    if let Some(handler) = request_handlers.get_mut(request.command.as_str()) {
+                        handler(
+                            request.seq,
+                            request.arguments.unwrap_or(Value::Null),
+                            cx.clone(),
+                        );
+                    } else {
+                        drop(request_handlers);
+                        log::info!(
+                            "debug adapter {name} sent unsupported request {}",
+                            request.command
+                        );
+                        let response = AnyResponse {
+                            seq: next_seq.fetch_add(1, SeqCst),
+                            request_seq: request.seq,
+                            success: false,
+                            message: Some(format!("unsupported request {}", request.command)),
+                            command: request.command,
+                            body: None,
+                        };
+                        send_message(&outbound_tx, &Message::Response(response)).log_err();
+                    }
+                }
+            }
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+        Ok(())
+    }
+
+    async fn handle_stderr<Stderr>(stderr: Stderr, name: SharedString) -> Result<()>
+    where
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stderr = BufReader::new(stderr);
+        let mut buffer = String::new();
+        loop {
+            buffer.clear();
+            if stderr.read_line(&mut buffer).await? == 0 {
+                return Ok(());
+            }
+            log::debug!("debug adapter {name} stderr: {}", buffer.trim_end());
+        }
+    }
+
+    async fn handle_output<Stdin>(
+        stdin: Stdin,
+        outbound_rx: channel::Receiver<String>,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    ) -> Result<()>
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut stdin = BufWriter::new(stdin);
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing debug adapter message: {message}");
+            transport::write_message(&mut stdin, &message).await?;
+        }
+        Ok(())
+    }
+
+    pub fn id(&self) -> DebugAdapterClientId {
+        self.id
+    }
+
+    pub fn name(&self) -> SharedString {
+        self.name.clone()
+    }
+
+    /// Sends a request to the debug adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Request)
+    pub fn request<R: Request>(
+        &self,
+        arguments: R::Arguments,
+    ) -> impl 'static + Future<Output = Result<R::Response>> {
+        let seq = self.next_seq.fetch_add(1, SeqCst);
+        let message = to_optional_value(arguments).map(|arguments| {
+            Message::Request(AnyRequest {
+                seq,
+                command: R::COMMAND.to_string(),
+                arguments,
+            })
+        });
+
+        let (tx, rx) = oneshot::channel();
+        let handle_response = self
+            .response_handlers
+            .lock()
+            .as_mut()
+            .ok_or_else(|| anyhow!("debug adapter {} shut down", self.name))
+            .map(|handlers| {
+                handlers.insert(
+                    seq,
+                    Box::new(move |result| {
+                        tx.send(result).ok();
+                    }),
+                );
+            });
+        let send = message
+            .and_then(|message| send_message(&self.outbound_tx, &message))
+            .context("failed to write to debug adapter's stdin");
+
+        let mut timeout = self.executor.timer(DAP_REQUEST_TIMEOUT).fuse();
+        async move {
+            handle_response?;
+            send?;
+
+            let command = R::COMMAND;
+            select! {
+                response = rx.fuse() => {
+                    let body = response.map_err(|_| anyhow!("debug adapter shut down"))??;
+                    serde_json::from_value(body)
+                        .with_context(|| format!("failed to deserialize {command} response"))
+                }
+
+                _ = timeout => {
+                    anyhow::bail!("{command} request to debug adapter timed out");
+                }
+            }
+        }
+    }
+
+    /// Sends an event to the other side of the connection. Only debug adapters send events, so
+    /// this is used by the fake adapter.
+    pub fn send_event<E: Event>(&self, body: E::Body) -> Result<()> {
+        let body = to_optional_value(body)?;
+        send_message(
+            &self.outbound_tx,
+            &Message::Event(AnyEvent {
+                seq: self.next_seq.fetch_add(1, SeqCst),
+                event: E::EVENT.to_string(),
+                body,
+            }),
+        )
+    }
+
+    /// Registers a handler for events of the given kind.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Event)
+    #[must_use]
+    pub fn on_event<E, F>(&self, mut f: F) -> Subscription
+    where
+        E: Event,
+        F: 'static + Send + FnMut(E::Body, AsyncApp),
+    {
+        let prev_handler = self.event_handlers.lock().insert(
+            E::EVENT,
+            Box::new(move |body, cx| {
+                match serde_json::from_value(body) {
+                    Ok(body) => f(body, cx),
+                    Err(error) => {
+                        log::error!("error deserializing {} event: {error:?}", E::EVENT)
+                    }
+                };
+            }),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP event"
+        );
+        Subscription::Event {
+            event: E::EVENT,
+            event_handlers: Some(Arc::downgrade(&self.event_handlers)),
+        }
+    }
+
+    /// Registers a handler for requests of the given kind, such as the reverse requests that a
+    /// debug adapter sends to its client.
+    #[must_use]
+    pub fn on_request<R, F, Fut>(&self, mut f: F) -> Subscription
+    where
+        R: Request,
+        F: 'static + Send + FnMut(R::Arguments, AsyncApp) -> Fut,
+        Fut: 'static + Future<Output = Result<R::Response>>,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        let next_seq = self.next_seq.clone();
+        let prev_handler = self.request_handlers.lock().insert(
+            R::COMMAND,
+            Box::new(move |request_seq, arguments, cx| {
+                let response = serde_json::from_value(arguments)
+                    .with_context(|| format!("error deserializing {} request", R::COMMAND))
+                    .map(|arguments| f(arguments, cx.clone()));
+                let outbound_tx = outbound_tx.clone();
+                let next_seq = next_seq.clone();
+                cx.foreground_executor()
+                    .spawn(async move {
+                        let result = match response {
+                            Ok(response) => response.await.and_then(to_optional_value),
+                            Err(error) => Err(error),
+                        };
+                        let (success, message, body) = match result {
+                            Ok(body) => (true, None, body),
+                            Err(error) => (false, Some(format!("{error:#}")), None),
+                        };
+                        let response = AnyResponse {
+                            seq: next_seq.fetch_add(1, SeqCst),
+                            request_seq,
+                            success,
+                            command: R::COMMAND.to_string(),
+                            message,
+                            body,
+                        };
+                        send_message(&outbound_tx, &Message::Response(response)).log_err();
+                    })
+                    .detach();
+            }),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP request"
+        );
+        Subscription::Request {
+            command: R::COMMAND,
+            request_handlers: Some(Arc::downgrade(&self.request_handlers)),
+        }
+    }
+
+    /// Removes a request handler registered via [`Self::on_request`].
+    pub fn remove_request_handler<R: Request>(&self) {
+        self.request_handlers.lock().remove(R::COMMAND);
+    }
+
+    /// Removes an event handler registered via [`Self::on_event`].
+    pub fn remove_event_handler<E: Event>(&self) {
+        self.event_handlers.lock().remove(E::EVENT);
+    }
+
+    /// Stops the debug adapter process and closes the connection to it. Pending requests fail.
+    pub fn shutdown(&self) {
+        self.outbound_tx.close();
+        self.response_handlers.lock().take();
+        if let Some(mut process) = self.process.lock().take() {
+            process.kill().log_err();
+        }
+        self.io_tasks.lock().take();
+    }
+}
+
+impl Drop for DebugAdapterClient {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl std::fmt::Debug for DebugAdapterClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugAdapterClient")
+            .field("id", &self.id.0)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Converts request arguments or a response or event body into JSON, omitting it entirely when
+/// there is none, since some adapters reject `null`.
+fn to_optional_value(value: impl Serialize) -> Result<Option<Value>> {
+    match serde_json::to_value(value)? {
+        Value::Null => Ok(None),
+        value => Ok(Some(value)),
+    }
+}
+
+fn send_message(outbound_tx: &channel::Sender<String>, message: &Message) -> Result<()> {
+    let message = serde_json::to_string(message)?;
+    outbound_tx.try_send(message)?;
+    Ok(())
+}
+
+impl Subscription {
+    /// Detaching a subscription handle prevents it from unsubscribing on drop.
+    pub fn detach(&mut self) {
+        match self {
+            Subscription::Event { event_handlers, .. } => *event_handlers = None,
+            Subscription::Request {
+                request_handlers, ..
+            } => *request_handlers = None,
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        match self {
+            Subscription::Event {
+                event,
+                event_handlers,
+            } => {
+                if let Some(handlers) = event_handlers.as_ref().and_then(|h| h.upgrade()) {
+                    handlers.lock().remove(event);
+                }
+            }
+            Subscription::Request {
+                command,
+                request_handlers,
+            } => {
+                if let Some(handlers) = request_handlers.as_ref().and_then(|h| h.upgrade()) {
+                    handlers.lock().remove(command);
+                }
+            }
+        }
+    }
+}
+
+/// Mock debug adapter for use in tests.
+#[cfg(any(test, feature = "test-support"))]
+pub struct FakeAdapter {
+    pub adapter: Arc<DebugAdapterClient>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeAdapter {
+    /// Constructs a fake debug adapter, connected to the returned client.
+    ///
+    /// The fake answers the requests needed to start a session with empty successful responses.
+    /// Use [`Self::handle_request`] to override them or handle other requests.
+    pub fn new(id: DebugAdapterClientId, cx: AsyncApp) -> (DebugAdapterClient, FakeAdapter) {
+        use crate::requests;
+
+        let (stdin_writer, stdin_reader) = async_pipe::pipe();
+        let (stdout_writer, stdout_reader) = async_pipe::pipe();
+        let client = DebugAdapterClient::new_internal(
+            id,
+            "fake-adapter".into(),
+            stdin_writer,
+            stdout_reader,
+            None::<async_pipe::PipeReader>,
+            None,
+            cx.clone(),
+        );
+        let fake = FakeAdapter {
+            adapter: Arc::new(DebugAdapterClient::new_internal(
+                id,
+                "fake-adapter".into(),
+                stdout_writer,
+                stdin_reader,
+                None::<async_pipe::PipeReader>,
+                None,
+                cx,
+            )),
+        };
+
+        fake.handle_request::<requests::Initialize, _, _>(|_, _| async move {
+            Ok(crate::types::Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            })
+        });
+        fake.handle_request::<requests::Launch, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::Attach, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::ConfigurationDone, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::Disconnect, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::SetBreakpoints, _, _>(|arguments, _| async move {
+            Ok(crate::types::SetBreakpointsResponse {
+                breakpoints: arguments
+                    .breakpoints
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|breakpoint| crate::types::Breakpoint {
+                        verified: true,
+                        line: Some(breakpoint.line),
+                        ..Default::default()
+                    })
+                    .collect(),
+            })
+        });
+
+        (client, fake)
+    }
+
+    /// Sends an event to the client.
+    pub fn send_event<E: Event>(&self, body: E::Body) {
+        self.adapter.send_event::<E>(body).ok();
+    }
+
+    /// Sends a reverse request to the client.
+    pub async fn request<R: Request>(&self, arguments: R::Arguments) -> Result<R::Response> {
+        self.adapter.executor.start_waiting();
+        self.adapter.request::<R>(arguments).await
+    }
+
+    /// Registers a handler for a specific kind of request, replacing any existing handler for it.
+    ///
+    /// The returned channel receives the arguments of every request the handler responded to.
+    pub fn handle_request<R, F, Fut>(
+        &self,
+        mut handler: F,
+    ) -> futures::channel::mpsc::UnboundedReceiver<R::Arguments>
+    where
+        R: Request,
+        R::Arguments: Clone,
+        F: 'static + Send + FnMut(R::Arguments, AsyncApp) -> Fut,
+        Fut: 'static + Send + Future<Output = Result<R::Response>>,
+    {
+        let (responded_tx, responded_rx) = futures::channel::mpsc::unbounded();
+        self.adapter.remove_request_handler::<R>();
+        self.adapter
+            .on_request::<R, _, _>(move |arguments, cx| {
+                let result = handler(arguments.clone(), cx.clone());
+                let responded_tx = responded_tx.clone();
+                let executor = cx.background_executor().clone();
+                async move {
+                    executor.simulate_random_delay().await;
+                    let result = result.await;
+                    responded_tx.unbounded_send(arguments).ok();
+                    result
+                }
+            })
+            .detach();
+        responded_rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{events, requests, types::*};
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+
+    #[ctor::ctor]
+    fn init_logger() {
+        if std::env::var("RUST_LOG").is_ok() {
+            env_logger::init();
+        }
+    }
+
+    #[gpui::test]
+    async fn test_fake_adapter(cx: &mut TestAppContext) {
+        let (client, fake) = FakeAdapter::new(DebugAdapterClientId(0), cx.to_async());
+
+        let capabilities = client
+            .request::<requests::Initialize>(InitializeRequestArguments {
+                adapter_id: "fake".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(capabilities.supports_configuration_done_request, Some(true));
+
+        let mut threads_requests =
+            fake.handle_request::<requests::Threads, _, _>(|_, _| async move {
+                Ok(ThreadsResponse {
+                    threads: vec![Thread {
+                        id: 1,
+                        name: "main".into(),
+                    }],
+                })
+            });
+        let threads = client.request::<requests::Threads>(()).await.unwrap();
+        assert_eq!(threads.threads[0].name, "main");
+        assert!(threads_requests.next().await.is_some());
+
+        // Requests that the adapter doesn't handle fail rather than hanging.
+        assert!(client
+            .request::<requests::Scopes>(ScopesArguments { frame_id: 1 })
+            .await
+            .is_err());
+
+        let (stopped_tx, stopped_rx) = channel::unbounded();
+        client
+            .on_event::<events::Stopped, _>(move |body, _| {
+                stopped_tx.try_send(body).unwrap();
+            })
+            .detach();
+        fake.send_event::<events::Stopped>(StoppedEventBody {
+            reason: "breakpoint".into(),
+            thread_id: Some(1),
+            ..Default::default()
+        });
+        let stopped = stopped_rx.recv().await.unwrap();
+        assert_eq!(stopped.reason, "breakpoint");
+        assert_eq!(stopped.thread_id, Some(1));
+
+        client.shutdown();
+        assert!(client.request::<requests::Threads>(()).await.is_err());
+    }
+}
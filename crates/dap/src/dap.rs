@@ -0,0 +1,30 @@
+mod client;
+mod transport;
+pub mod types;
+
+pub use client::*;
+pub use types::{events, requests, Event, Request};
+
+use collections::HashMap;
+use std::{fmt, path::PathBuf};
+
+/// A launchable debug adapter: the adapter process speaks the Debug Adapter Protocol over its
+/// stdio, and in turn launches or attaches to the program being debugged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugAdapterBinary {
+    pub command: String,
+    pub arguments: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub cwd: Option<PathBuf>,
+}
+
+/// Identifies a running debug adapter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct DebugAdapterClientId(pub usize);
+
+impl fmt::Display for DebugAdapterClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
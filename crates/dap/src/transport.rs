@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use futures::{AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::io::BufReader;
+
+const CONTENT_LEN_HEADER: &str = "Content-Length: ";
+const HEADER_DELIMITER: &[u8; 4] = b"\r\n\r\n";
+
+/// A message of the Debug Adapter Protocol before its arguments or body are deserialized into
+/// concrete types.
+///
+/// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_ProtocolMessage)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Message {
+    Request(AnyRequest),
+    Response(AnyResponse),
+    Event(AnyEvent),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AnyRequest {
+    pub seq: u64,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AnyResponse {
+    pub seq: u64,
+    pub request_seq: u64,
+    pub success: bool,
+    pub command: String,
+    /// The error message when `success` is false.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AnyEvent {
+    pub seq: u64,
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+async fn read_headers<Input>(reader: &mut BufReader<Input>, buffer: &mut Vec<u8>) -> Result<()>
+where
+    Input: AsyncRead + Unpin + Send + 'static,
+{
+    loop {
+        if buffer.len() >= HEADER_DELIMITER.len()
+            && buffer[(buffer.len() - HEADER_DELIMITER.len())..] == HEADER_DELIMITER[..]
+        {
+            return Ok(());
+        }
+
+        if reader.read_until(b'\n', buffer).await? == 0 {
+            return Err(anyhow!("cannot read DAP message headers"));
+        }
+    }
+}
+
+/// Reads the next message from a debug adapter's output, returning `None` once the output is
+/// closed.
+pub(crate) async fn read_message<Input>(
+    reader: &mut BufReader<Input>,
+    buffer: &mut Vec<u8>,
+) -> Result<Option<String>>
+where
+    Input: AsyncRead + Unpin + Send + 'static,
+{
+    buffer.clear();
+    if reader.fill_buf().await?.is_empty() {
+        return Ok(None);
+    }
+    read_headers(reader, buffer).await?;
+
+    let headers = std::str::from_utf8(buffer)?;
+    let message_len = headers
+        .split('\n')
+        .find_map(|line| line.strip_prefix(CONTENT_LEN_HEADER))
+        .ok_or_else(|| anyhow!("invalid DAP message header {headers:?}"))?
+        .trim_end()
+        .parse()?;
+
+    buffer.resize(message_len, 0);
+    reader.read_exact(buffer).await?;
+    Ok(Some(String::from_utf8(buffer.clone())?))
+}
+
+pub(crate) async fn write_message<Output>(output: &mut Output, message: &str) -> Result<()>
+where
+    Output: AsyncWrite + Unpin + Send + 'static,
+{
+    output
+        .write_all(format!("{CONTENT_LEN_HEADER}{}\r\n\r\n", message.len()).as_bytes())
+        .await?;
+    output.write_all(message.as_bytes()).await?;
+    output.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_read_message() {
+        let input = b"Content-Length: 13\r\n\r\n{\"seq\":1,...}Content-Type: application/json\r\nContent-Length: 2\r\n\r\n{}";
+        let mut reader = BufReader::new(input as &[u8]);
+        let mut buffer = Vec::new();
+        assert_eq!(
+            read_message(&mut reader, &mut buffer).await.unwrap(),
+            Some("{\"seq\":1,...}".to_string())
+        );
+        assert_eq!(
+            read_message(&mut reader, &mut buffer).await.unwrap(),
+            Some("{}".to_string())
+        );
+        assert_eq!(read_message(&mut reader, &mut buffer).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_message_serialization() {
+        let event: Message = serde_json::from_value(json!({
+            "seq": 3,
+            "type": "event",
+            "event": "stopped",
+            "body": { "reason": "breakpoint", "threadId": 1 }
+        }))
+        .unwrap();
+        assert_eq!(
+            event,
+            Message::Event(AnyEvent {
+                seq: 3,
+                event: "stopped".into(),
+                body: Some(json!({ "reason": "breakpoint", "threadId": 1 })),
+            })
+        );
+
+        let request = Message::Request(AnyRequest {
+            seq: 1,
+            command: "threads".into(),
+            arguments: None,
+        });
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({ "seq": 1, "type": "request", "command": "threads" })
+        );
+    }
+}
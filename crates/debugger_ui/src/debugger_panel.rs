@@ -0,0 +1,703 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use dap::types::{Scope, StackFrame, Variable};
+use editor::Editor;
+use gpui::{
+    actions, Action, AnyElement, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, Pixels, Render, Subscription, Task, WeakEntity, Window,
+};
+use language::Point;
+use panel::PanelHeader;
+use project::{
+    dap_store::{DapStore, DapStoreEvent},
+    debug_session::{ConsoleEntryKind, DebugSession, DebugSessionEvent, SessionStatus},
+};
+use settings::Settings;
+use ui::{prelude::*, Divider, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::debugger_panel_settings::DebuggerPanelSettings;
+
+actions!(
+    debugger,
+    [
+        ToggleFocus,
+        Continue,
+        Pause,
+        StepOver,
+        StepIn,
+        StepOut,
+        Stop
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
+            workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<DebugPanel>(window, cx);
+            });
+            workspace.register_action(|workspace, _: &Continue, _, cx| {
+                DebugPanel::update_active_session(workspace, cx, |session, cx| {
+                    session.continue_thread(cx)
+                });
+            });
+            workspace.register_action(|workspace, _: &Pause, _, cx| {
+                DebugPanel::update_active_session(workspace, cx, |session, cx| session.pause(cx));
+            });
+            workspace.register_action(|workspace, _: &StepOver, _, cx| {
+                DebugPanel::update_active_session(workspace, cx, |session, cx| {
+                    session.step_over(cx)
+                });
+            });
+            workspace.register_action(|workspace, _: &StepIn, _, cx| {
+                DebugPanel::update_active_session(workspace, cx, |session, cx| session.step_in(cx));
+            });
+            workspace.register_action(|workspace, _: &StepOut, _, cx| {
+                DebugPanel::update_active_session(workspace, cx, |session, cx| {
+                    session.step_out(cx)
+                });
+            });
+            workspace.register_action(|workspace, _: &Stop, _, cx| {
+                DebugPanel::update_active_session(workspace, cx, |session, cx| session.stop(cx));
+            });
+        },
+    )
+    .detach();
+}
+
+/// Shows the state of the active debug session: its threads, the call stack of the stopped
+/// thread, the variables in the selected stack frame, and a console to evaluate expressions in.
+pub struct DebugPanel {
+    focus_handle: FocusHandle,
+    workspace: WeakEntity<Workspace>,
+    dap_store: Entity<DapStore>,
+    active_session: Option<Entity<DebugSession>>,
+    scopes: Vec<Scope>,
+    /// Children of expanded scopes and variables, keyed by their variables reference.
+    variables: HashMap<u64, Vec<Variable>>,
+    expanded_variables: HashSet<u64>,
+    /// Incremented whenever the selected stack frame changes, so that variables loaded for a
+    /// previous frame are discarded.
+    variables_generation: usize,
+    console_input: Entity<Editor>,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    _session_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DebugPanel {
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let dap_store = workspace.project().read(cx).dap_store().clone();
+        let workspace = cx.entity().downgrade();
+
+        cx.new(|cx| {
+            let console_input = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Evaluate an expression", cx);
+                editor
+            });
+            let subscriptions =
+                vec![cx.subscribe_in(&dap_store, window, Self::handle_dap_store_event)];
+
+            let mut this = Self {
+                focus_handle: cx.focus_handle(),
+                workspace,
+                dap_store: dap_store.clone(),
+                active_session: None,
+                scopes: Vec::new(),
+                variables: HashMap::default(),
+                expanded_variables: HashSet::default(),
+                variables_generation: 0,
+                console_input,
+                width: None,
+                height: None,
+                _session_subscription: None,
+                _subscriptions: subscriptions,
+            };
+            let last_session = dap_store.read(cx).sessions().last().cloned();
+            this.set_active_session(last_session, window, cx);
+            this
+        })
+    }
+
+    fn update_active_session(
+        workspace: &Workspace,
+        cx: &mut App,
+        update: impl FnOnce(&mut DebugSession, &mut Context<DebugSession>) -> Task<Result<()>>,
+    ) {
+        let Some(session) = workspace
+            .panel::<Self>(cx)
+            .and_then(|panel| panel.read(cx).active_session.clone())
+        else {
+            return;
+        };
+        session.update(cx, update).detach_and_log_err(cx);
+    }
+
+    fn handle_dap_store_event(
+        &mut self,
+        dap_store: &Entity<DapStore>,
+        event: &DapStoreEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            DapStoreEvent::SessionStarted(id) => {
+                let session = dap_store.read(cx).session(*id).cloned();
+                self.set_active_session(session, window, cx);
+                cx.emit(PanelEvent::Activate);
+            }
+            DapStoreEvent::SessionRemoved(id) => {
+                if self
+                    .active_session
+                    .as_ref()
+                    .map_or(false, |session| session.read(cx).id() == *id)
+                {
+                    let session = dap_store.read(cx).sessions().last().cloned();
+                    self.set_active_session(session, window, cx);
+                }
+            }
+            DapStoreEvent::BreakpointsChanged(_) => {}
+        }
+    }
+
+    fn set_active_session(
+        &mut self,
+        session: Option<Entity<DebugSession>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self._session_subscription = session.as_ref().map(|session| {
+            cx.subscribe_in(session, window, |this, session, event, window, cx| {
+                if let DebugSessionEvent::StackFramesChanged = event {
+                    this.load_scopes(cx);
+                    if let Some(frame) = session.read(cx).selected_frame().cloned() {
+                        this.go_to_frame(&frame, window, cx);
+                    }
+                }
+                cx.notify();
+            })
+        });
+        self.active_session = session;
+        self.load_scopes(cx);
+        cx.notify();
+    }
+
+    fn load_scopes(&mut self, cx: &mut Context<Self>) {
+        self.scopes.clear();
+        self.variables.clear();
+        self.expanded_variables.clear();
+        self.variables_generation += 1;
+
+        let Some(session) = self.active_session.clone() else {
+            return;
+        };
+        let Some(frame_id) = session.read(cx).selected_frame().map(|frame| frame.id) else {
+            return;
+        };
+        let generation = self.variables_generation;
+        let scopes = session.update(cx, |session, cx| session.scopes(frame_id, cx));
+        cx.spawn(|this, mut cx| async move {
+            let scopes = scopes.await?;
+            this.update(&mut cx, |this, cx| {
+                if this.variables_generation != generation {
+                    return;
+                }
+                // The innermost scope holds the locals, which are usually what one is after.
+                if let Some(scope) = scopes.first().filter(|scope| !scope.expensive) {
+                    this.toggle_variables(scope.variables_reference, cx);
+                }
+                this.scopes = scopes;
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn toggle_variables(&mut self, variables_reference: u64, cx: &mut Context<Self>) {
+        if !self.expanded_variables.insert(variables_reference) {
+            self.expanded_variables.remove(&variables_reference);
+            cx.notify();
+            return;
+        }
+        cx.notify();
+        if self.variables.contains_key(&variables_reference) {
+            return;
+        }
+        let Some(session) = self.active_session.clone() else {
+            return;
+        };
+
+        let generation = self.variables_generation;
+        let variables =
+            session.update(cx, |session, cx| session.variables(variables_reference, cx));
+        cx.spawn(|this, mut cx| async move {
+            let variables = variables.await?;
+            this.update(&mut cx, |this, cx| {
+                if this.variables_generation == generation {
+                    this.variables.insert(variables_reference, variables);
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn go_to_frame(&mut self, frame: &StackFrame, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(path) = frame.source.as_ref().and_then(|source| source.path.clone()) else {
+            return;
+        };
+        let point = Point::new(
+            frame.line.saturating_sub(1) as u32,
+            frame.column.saturating_sub(1) as u32,
+        );
+        let Some(open_task) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_abs_path(PathBuf::from(path), false, window, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn_in(window, |_, mut cx| async move {
+            let item = open_task.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update_in(&mut cx, |editor, window, cx| {
+                    editor.go_to_singleton_buffer_point(point, window, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(session) = self.active_session.clone() else {
+            return;
+        };
+        let expression = self.console_input.read(cx).text(cx);
+        if expression.trim().is_empty() {
+            return;
+        }
+        self.console_input
+            .update(cx, |editor, cx| editor.clear(window, cx));
+        session
+            .update(cx, |session, cx| session.evaluate(expression, cx))
+            .detach();
+    }
+
+    fn close_session(&mut self, _: &ClickEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(session) = self.active_session.clone() {
+            let id = session.read(cx).id();
+            self.dap_store
+                .update(cx, |dap_store, cx| dap_store.remove_session(id, cx));
+        }
+    }
+
+    fn render_toolbar(
+        &self,
+        session: &Entity<DebugSession>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let header = self.panel_header_container(window, cx);
+        let label = session.read(cx).label();
+        let status = session.read(cx).status();
+        let is_stopped = status == SessionStatus::Stopped;
+        let is_running = status == SessionStatus::Running;
+        let status_label = match status {
+            SessionStatus::Starting => "Starting",
+            SessionStatus::Running => "Running",
+            SessionStatus::Stopped => "Paused",
+            SessionStatus::Exited => "Exited",
+        };
+
+        let action_button = |id: &'static str,
+                             icon: IconName,
+                             action: &dyn Action,
+                             label: &'static str,
+                             enabled: bool| {
+            let action = action.boxed_clone();
+            IconButton::new(id, icon)
+                .icon_size(IconSize::Small)
+                .disabled(!enabled)
+                .tooltip(Tooltip::for_action_title(label, action.as_ref()))
+                .on_click(move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx))
+        };
+
+        header
+            .gap_1()
+            .child(Label::new(label).size(LabelSize::Small).single_line())
+            .child(
+                Label::new(status_label)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(action_button(
+                "continue",
+                IconName::Play,
+                &Continue,
+                "Continue",
+                is_stopped,
+            ))
+            .child(action_button(
+                "pause",
+                IconName::Pause,
+                &Pause,
+                "Pause",
+                is_running,
+            ))
+            .child(action_button(
+                "step-over",
+                IconName::ArrowRight,
+                &StepOver,
+                "Step Over",
+                is_stopped,
+            ))
+            .child(action_button(
+                "step-in",
+                IconName::ArrowDown,
+                &StepIn,
+                "Step In",
+                is_stopped,
+            ))
+            .child(action_button(
+                "step-out",
+                IconName::ArrowUp,
+                &StepOut,
+                "Step Out",
+                is_stopped,
+            ))
+            .child(action_button(
+                "stop",
+                IconName::Stop,
+                &Stop,
+                "Stop",
+                status != SessionStatus::Exited,
+            ))
+            .child(
+                IconButton::new("close-session", IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Close Session"))
+                    .on_click(cx.listener(Self::close_session)),
+            )
+    }
+
+    fn render_section_header(title: &'static str) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+    }
+
+    fn render_threads(&self, session: &Entity<DebugSession>, cx: &mut Context<Self>) -> Div {
+        let active_thread = session.read(cx).active_thread();
+        let threads = session.read(cx).threads().to_vec();
+        v_flex()
+            .child(Self::render_section_header("Threads"))
+            .children(threads.into_iter().map(|thread| {
+                let thread_id = thread.id;
+                let session = session.clone();
+                ListItem::new(("thread", thread_id as usize))
+                    .toggle_state(active_thread == Some(thread_id))
+                    .child(Label::new(thread.name).size(LabelSize::Small))
+                    .on_click(cx.listener(move |_, _, _, cx| {
+                        session.update(cx, |session, cx| session.select_thread(thread_id, cx));
+                    }))
+            }))
+    }
+
+    fn render_stack_frames(&self, session: &Entity<DebugSession>, cx: &mut Context<Self>) -> Div {
+        let selected_frame = session.read(cx).selected_frame().map(|frame| frame.id);
+        let stack_frames = session.read(cx).stack_frames().to_vec();
+        v_flex()
+            .child(Self::render_section_header("Call Stack"))
+            .children(stack_frames.into_iter().map(|frame| {
+                let location = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.name.clone().or_else(|| source.path.clone()))
+                    .map(|name| format!("{name}:{}", frame.line));
+                let session = session.clone();
+                ListItem::new(("stack-frame", frame.id as usize))
+                    .toggle_state(selected_frame == Some(frame.id))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(frame.name.clone()).size(LabelSize::Small))
+                            .children(location.map(|location| {
+                                Label::new(location)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                            })),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        session.update(cx, |session, cx| session.select_frame(frame.id, cx));
+                        this.go_to_frame(&frame, window, cx);
+                    }))
+            }))
+    }
+
+    fn render_variables(&self, cx: &mut Context<Self>) -> Div {
+        let mut rows = Vec::new();
+        for scope in &self.scopes {
+            self.render_variable_row(
+                scope.name.clone(),
+                None,
+                scope.variables_reference,
+                0,
+                &mut rows,
+                cx,
+            );
+        }
+        v_flex()
+            .child(Self::render_section_header("Variables"))
+            .children(rows)
+    }
+
+    fn render_variable_row(
+        &self,
+        name: String,
+        value: Option<String>,
+        variables_reference: u64,
+        depth: usize,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let is_expandable = variables_reference > 0;
+        let is_expanded = is_expandable && self.expanded_variables.contains(&variables_reference);
+        rows.push(
+            ListItem::new(("variable", rows.len()))
+                .indent_level(depth)
+                .indent_step_size(px(12.))
+                .toggle(is_expandable.then_some(is_expanded))
+                .on_toggle(cx.listener(move |this, _, _, cx| {
+                    this.toggle_variables(variables_reference, cx);
+                }))
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Label::new(name).size(LabelSize::Small))
+                        .children(value.map(|value| {
+                            Label::new(value)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line()
+                        })),
+                )
+                .into_any_element(),
+        );
+
+        if !is_expanded {
+            return;
+        }
+        match self.variables.get(&variables_reference) {
+            Some(variables) => {
+                for variable in variables {
+                    self.render_variable_row(
+                        variable.name.clone(),
+                        Some(variable.value.clone()),
+                        variable.variables_reference,
+                        depth + 1,
+                        rows,
+                        cx,
+                    );
+                }
+            }
+            None => rows.push(
+                ListItem::new(("variable", rows.len()))
+                    .indent_level(depth + 1)
+                    .indent_step_size(px(12.))
+                    .child(
+                        Label::new("Loading…")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .into_any_element(),
+            ),
+        }
+    }
+
+    fn render_console(&self, session: &Entity<DebugSession>, cx: &mut Context<Self>) -> Div {
+        let entries = session.read(cx).console();
+        v_flex()
+            .size_full()
+            .child(Self::render_section_header("Console"))
+            .child(
+                v_flex()
+                    .id("console-output")
+                    .flex_1()
+                    .px_2()
+                    .overflow_y_scroll()
+                    .children(entries.iter().map(|entry| {
+                        let (prefix, color) = match entry.kind {
+                            ConsoleEntryKind::Output => ("", Color::Default),
+                            ConsoleEntryKind::Error => ("", Color::Error),
+                            ConsoleEntryKind::Input => ("> ", Color::Muted),
+                            ConsoleEntryKind::Result => ("", Color::Accent),
+                        };
+                        Label::new(format!("{prefix}{}", entry.text))
+                            .size(LabelSize::Small)
+                            .buffer_font(cx)
+                            .color(color)
+                    })),
+            )
+            .child(Divider::horizontal())
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .on_action(cx.listener(Self::confirm))
+                    .child(self.console_input.clone()),
+            )
+    }
+}
+
+impl Render for DebugPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .key_context("DebugPanel")
+            .track_focus(&self.focus_handle)
+            .size_full();
+
+        let Some(session) = self.active_session.clone() else {
+            return panel.child(
+                v_flex()
+                    .size_full()
+                    .items_center()
+                    .justify_center()
+                    .gap_1()
+                    .child(Label::new("No debug session"))
+                    .child(
+                        Label::new(
+                            "Add a \"debug\" configuration to a task and run it to start debugging.",
+                        )
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    ),
+            );
+        };
+
+        let border_color = cx.theme().colors().border;
+        panel
+            .child(self.render_toolbar(&session, window, cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .size_full()
+                    .items_start()
+                    .child(
+                        v_flex()
+                            .id("threads-and-stack")
+                            .w_1_4()
+                            .h_full()
+                            .overflow_y_scroll()
+                            .border_r_1()
+                            .border_color(border_color)
+                            .child(self.render_threads(&session, cx))
+                            .child(self.render_stack_frames(&session, cx)),
+                    )
+                    .child(
+                        v_flex()
+                            .id("variables")
+                            .w_1_3()
+                            .h_full()
+                            .overflow_y_scroll()
+                            .border_r_1()
+                            .border_color(border_color)
+                            .child(self.render_variables(cx)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_full()
+                            .child(self.render_console(&session, cx)),
+                    ),
+            )
+    }
+}
+
+impl Focusable for DebugPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DebugPanel {}
+
+impl Panel for DebugPanel {
+    fn persistent_name() -> &'static str {
+        "DebugPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        DebuggerPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(fs) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.app_state().fs.clone())
+            .log_err()
+        else {
+            return;
+        };
+        settings::update_settings_file::<DebuggerPanelSettings>(fs, cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, window: &Window, cx: &App) -> Pixels {
+        let settings = DebuggerPanelSettings::get_global(cx);
+        match self.position(window, cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        match self.position(window, cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        Some(IconName::Debug).filter(|_| DebuggerPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Debugger Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        9
+    }
+}
+
+impl PanelHeader for DebugPanel {}
@@ -0,0 +1,11 @@
+use debugger_panel_settings::DebuggerPanelSettings;
+use gpui::App;
+use settings::Settings;
+
+pub mod debugger_panel;
+mod debugger_panel_settings;
+
+pub fn init(cx: &mut App) {
+    DebuggerPanelSettings::register(cx);
+    debugger_panel::init(cx);
+}
@@ -249,6 +249,7 @@ gpui::actions!(
         Backspace,
         Cancel,
         CancelLanguageServerWork,
+        ClearInlineValues,
         ConfirmRename,
         ContextMenuFirst,
         ContextMenuLast,
@@ -300,6 +301,8 @@ gpui::actions!(
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToNextBookmark,
+        GoToPreviousBookmark,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -348,6 +351,7 @@ gpui::actions!(
         PageDown,
         PageUp,
         Paste,
+        PreviewRename,
         PreviousEditPrediction,
         Redo,
         RedoSelection,
@@ -394,6 +398,8 @@ gpui::actions!(
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
+        ToggleBookmark,
+        ToggleBreakpoint,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleIndentGuides,
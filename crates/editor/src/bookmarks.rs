@@ -0,0 +1,307 @@
+use std::{collections::BTreeMap, ops::Range, path::PathBuf};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use gpui::{Context, Entity, SharedString, Task, Window};
+use language::{Bias, Point, ToPoint as _};
+use project::{
+    bookmark_store::{Bookmark, BookmarkStoreEvent},
+    Project, ProjectItem as _, ProjectPath,
+};
+use ui::{prelude::*, IconButton, IconButtonShape, Tooltip};
+use util::ResultExt as _;
+use workspace::{Workspace, SERIALIZATION_THROTTLE_TIME};
+
+use crate::{
+    display_map::ToDisplayPoint as _, persistence::DB, scroll::Autoscroll, Direction, DisplayPoint,
+    DisplayRow, Editor, EditorMode, EditorSnapshot, GoToNextBookmark, GoToPreviousBookmark,
+    ToggleBookmark,
+};
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &GoToNextBookmark, window, cx| {
+        go_to_bookmark(workspace, Direction::Next, window, cx);
+    });
+    workspace.register_action(|workspace, _: &GoToPreviousBookmark, window, cx| {
+        go_to_bookmark(workspace, Direction::Prev, window, cx);
+    });
+}
+
+/// The project's bookmarks, ordered by path and row.
+pub fn sorted_bookmarks(project: &Entity<Project>, cx: &App) -> Vec<Bookmark> {
+    let mut bookmarks = project.read(cx).bookmark_store().read(cx).bookmarks(cx);
+    bookmarks.sort_by(|a, b| (&a.path, a.row).cmp(&(&b.path, b.row)));
+    bookmarks
+}
+
+/// Opens the file of a bookmark in the active pane, with the cursor on the bookmarked line.
+pub fn open_bookmark(
+    workspace: &mut Workspace,
+    path: ProjectPath,
+    row: u32,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let open_task = workspace.open_path(path, None, true, window, cx);
+    cx.spawn_in(window, |_, mut cx| async move {
+        let editor = open_task
+            .await?
+            .downcast::<Editor>()
+            .context("bookmarked file was not opened in an editor")?;
+        editor.update_in(&mut cx, |editor, window, cx| {
+            let position = Point::new(row, 0);
+            editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                selections.select_ranges([position..position])
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Opens the bookmark after or before the cursor of the active editor, in any of the
+/// project's files, wrapping around at either end.
+fn go_to_bookmark(
+    workspace: &mut Workspace,
+    direction: Direction,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let bookmarks = sorted_bookmarks(workspace.project(), cx);
+    if bookmarks.is_empty() {
+        return;
+    }
+
+    let cursor = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+        .and_then(|editor| {
+            let editor = editor.read(cx);
+            let head = editor.selections.newest_anchor().head();
+            let (buffer, position) = editor.buffer.read(cx).text_anchor_for_position(head, cx)?;
+            let buffer = buffer.read(cx);
+            Some((buffer.project_path(cx)?, position.to_point(buffer).row))
+        });
+    let ix = match (cursor, direction) {
+        (Some((path, row)), Direction::Next) => bookmarks
+            .iter()
+            .position(|bookmark| (&bookmark.path, bookmark.row) > (&path, row))
+            .unwrap_or(0),
+        (Some((path, row)), Direction::Prev) => bookmarks
+            .iter()
+            .rposition(|bookmark| (&bookmark.path, bookmark.row) < (&path, row))
+            .unwrap_or(bookmarks.len() - 1),
+        (None, Direction::Next) => 0,
+        (None, Direction::Prev) => bookmarks.len() - 1,
+    };
+    let bookmark = bookmarks[ix].clone();
+    open_bookmark(workspace, bookmark.path, bookmark.row, window, cx);
+}
+
+/// Restores the bookmarks saved for the workspace, without opening their files, and saves
+/// them whenever they change, move with edits, or the workspace is serialized.
+pub(crate) fn restore_bookmarks(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    let Some(saved_bookmarks) = DB.get_bookmarks(workspace_id).log_err() else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let bookmark_store = project.read(cx).bookmark_store().clone();
+
+    let mut bookmarks_by_path = BTreeMap::<PathBuf, Vec<(u32, Option<SharedString>)>>::new();
+    for (abs_path, row, label) in saved_bookmarks {
+        bookmarks_by_path
+            .entry(abs_path)
+            .or_default()
+            .push((row, label.map(SharedString::from)));
+    }
+    for (abs_path, rows) in bookmarks_by_path {
+        // Bookmarks of files that are no longer part of the project are dropped.
+        let Some((worktree, path)) = project.read(cx).find_worktree(&abs_path, cx) else {
+            continue;
+        };
+        let path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.into(),
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.add_bookmarks(path, rows, cx);
+        });
+    }
+
+    // Edits are saved once they stop, rather than on every keystroke.
+    let mut pending_save = None::<Task<()>>;
+    cx.subscribe(
+        &bookmark_store,
+        move |workspace, _, event: &BookmarkStoreEvent, cx| match event {
+            BookmarkStoreEvent::BookmarkedBufferEdited(_) => {
+                pending_save = Some(cx.spawn(|workspace, mut cx| async move {
+                    cx.background_executor()
+                        .timer(SERIALIZATION_THROTTLE_TIME)
+                        .await;
+                    workspace.update(&mut cx, save_bookmarks).log_err();
+                }));
+            }
+            BookmarkStoreEvent::BookmarksChanged | BookmarkStoreEvent::BookmarkedBufferSaved(_) => {
+                pending_save.take();
+                save_bookmarks(workspace, cx);
+            }
+        },
+    )
+    .detach();
+    let workspace_handle = cx.entity();
+    cx.subscribe(
+        &workspace_handle,
+        |workspace, _, event: &workspace::Event, cx| {
+            if let workspace::Event::Serialized = event {
+                save_bookmarks(workspace, cx);
+            }
+        },
+    )
+    .detach();
+}
+
+fn save_bookmarks(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    let project = workspace.project().read(cx);
+    let bookmarks = sorted_bookmarks(workspace.project(), cx)
+        .into_iter()
+        .filter_map(|bookmark| {
+            Some((
+                project.absolute_path(&bookmark.path, cx)?,
+                bookmark.row,
+                bookmark.label.map(|label| label.to_string()),
+            ))
+        })
+        .collect();
+    cx.background_executor()
+        .spawn(DB.save_bookmarks(workspace_id, bookmarks))
+        .detach_and_log_err(cx);
+}
+
+impl Editor {
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, _: &mut Window, cx: &mut Context<Self>) {
+        let cursor = self.selections.newest::<Point>(cx).head();
+        self.toggle_bookmark_at(cursor, cx);
+    }
+
+    fn toggle_bookmark_at(&mut self, position: Point, cx: &mut Context<Self>) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        project
+            .read(cx)
+            .bookmark_store()
+            .clone()
+            .update(cx, |bookmark_store, cx| {
+                bookmark_store.toggle_bookmark(buffer, position, None, cx);
+            });
+    }
+
+    /// The bookmark on the line of the newest cursor, with its label.
+    pub fn bookmark_at_cursor(&self, cx: &App) -> Option<Option<SharedString>> {
+        let project = self.project.as_ref()?;
+        let head = self.selections.newest_anchor().head();
+        let (buffer, position) = self.buffer.read(cx).text_anchor_for_position(head, cx)?;
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        project
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .bookmark_rows(snapshot.remote_id(), &snapshot)
+            .into_iter()
+            .find_map(|(bookmarked_row, label)| (bookmarked_row == row).then_some(label))
+    }
+
+    /// Bookmarks the line of the newest cursor with the given label, replacing the label of
+    /// the bookmark already there.
+    pub fn set_bookmark_at_cursor(&mut self, label: Option<SharedString>, cx: &mut Context<Self>) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+        project
+            .read(cx)
+            .bookmark_store()
+            .clone()
+            .update(cx, |bookmark_store, cx| {
+                bookmark_store.set_bookmark(buffer, position, label, cx);
+            });
+    }
+
+    /// Display rows within `range` that have a bookmark, with the label of each.
+    pub(crate) fn bookmark_rows(
+        &self,
+        range: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &App,
+    ) -> HashMap<DisplayRow, Option<SharedString>> {
+        let mut rows = HashMap::default();
+        let Some(project) = self
+            .project
+            .as_ref()
+            .filter(|_| self.mode == EditorMode::Full)
+        else {
+            return rows;
+        };
+        let bookmark_store = project.read(cx).bookmark_store().read(cx);
+        let start = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left);
+        let end = snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+        for (buffer_snapshot, buffer_range, excerpt_id) in
+            snapshot.buffer_snapshot.range_to_buffer_ranges(start..end)
+        {
+            let start_row = buffer_snapshot.offset_to_point(buffer_range.start).row;
+            let end_row = buffer_snapshot.offset_to_point(buffer_range.end).row;
+            for (row, label) in
+                bookmark_store.bookmark_rows(buffer_snapshot.remote_id(), buffer_snapshot)
+            {
+                if row < start_row || row > end_row {
+                    continue;
+                }
+                let anchor = buffer_snapshot.anchor_before(Point::new(row, 0));
+                if let Some(anchor) = snapshot
+                    .buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, anchor)
+                {
+                    rows.insert(anchor.to_display_point(snapshot).row(), label);
+                }
+            }
+        }
+        rows
+    }
+
+    pub(crate) fn render_bookmark_indicator(
+        &self,
+        row: DisplayRow,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let tooltip = match label {
+            Some(label) => SharedString::from(format!("Remove Bookmark: {label}")),
+            None => SharedString::from("Remove Bookmark"),
+        };
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .shape(IconButtonShape::Square)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Accent)
+            .tooltip(Tooltip::text(tooltip))
+            .on_click(cx.listener(move |editor, _e, window, cx| {
+                window.focus(&editor.focus_handle(cx));
+                let snapshot = editor.snapshot(window, cx);
+                let position = DisplayPoint::new(row, 0).to_point(&snapshot.display_snapshot);
+                editor.toggle_bookmark_at(position, cx);
+            }))
+    }
+}
@@ -0,0 +1,372 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use clock::Global;
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{Context, Entity, WeakEntity, Window};
+use language::{Buffer, OffsetRangeExt as _, Point, ToPoint as _};
+use project::CodeLens;
+use settings::Settings as _;
+use ui::{prelude::*, Button, ButtonStyle, Label, LabelSize};
+use util::ResultExt as _;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    Editor, EditorMode, EditorSettings,
+};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The code lenses of a buffer, and the blocks showing them in the editor.
+#[derive(Default)]
+pub(super) struct BufferCodeLens {
+    /// The version of the buffer the lenses were requested for, `None` if they are out of date.
+    version: Option<Global>,
+    lenses: Vec<CodeLens>,
+    blocks: HashSet<CustomBlockId>,
+}
+
+/// Requests the code lenses of the visible buffers whose lenses are out of date, resolves
+/// their commands and shows them above the lines they refer to.
+pub(super) fn refresh_code_lens(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) -> Option<()> {
+    if editor.mode != EditorMode::Full {
+        return None;
+    }
+    if !EditorSettings::get_global(cx).code_lens {
+        clear_code_lens(editor, cx);
+        return None;
+    }
+    let provider = editor.semantics_provider.clone()?;
+
+    editor.code_lens_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+        }
+
+        let requests = editor
+            .update(&mut cx, |editor, cx| {
+                let mut buffers = HashMap::default();
+                for (buffer, _, _) in editor
+                    .excerpts_for_inlay_hints_query(None, cx)
+                    .into_values()
+                {
+                    buffers.insert(buffer.read(cx).remote_id(), buffer);
+                }
+                buffers
+                    .into_values()
+                    .filter_map(|buffer| {
+                        let version = buffer.read(cx).version();
+                        let buffer_id = buffer.read(cx).remote_id();
+                        let up_to_date =
+                            editor.code_lens.get(&buffer_id).map_or(false, |code_lens| {
+                                code_lens.version.as_ref() == Some(&version)
+                            });
+                        if up_to_date {
+                            return None;
+                        }
+                        let request = provider.code_lens(&buffer, cx)?;
+                        Some(async move { (buffer, version, request.await) })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let responses = join_all(requests).await;
+
+        let resolve_requests = editor
+            .update(&mut cx, |_, cx| {
+                responses
+                    .into_iter()
+                    .filter_map(|(buffer, version, response)| {
+                        let lenses = match response {
+                            Ok(lenses) => lenses,
+                            Err(error) => {
+                                log::error!("failed to fetch code lenses: {error:#}");
+                                return None;
+                            }
+                        };
+                        let requests = lenses
+                            .into_iter()
+                            .filter_map(|lens| provider.resolve_code_lens(&buffer, lens, cx))
+                            .collect::<Vec<_>>();
+                        Some(async move { (buffer, version, join_all(requests).await) })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let resolved = join_all(resolve_requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer, version, lenses) in resolved {
+                    let code_lens = editor
+                        .code_lens
+                        .entry(buffer.read(cx).remote_id())
+                        .or_default();
+                    code_lens.version = Some(version);
+                    code_lens.lenses = lenses
+                        .into_iter()
+                        .filter_map(|lens| lens.log_err())
+                        .filter(|lens| lens.is_resolved())
+                        .collect();
+                }
+                update_code_lens_blocks(editor, cx);
+            })
+            .ok()
+    }));
+    None
+}
+
+/// Requests the code lenses of all visible buffers again, as the language server asked for.
+pub(super) fn invalidate_code_lens(editor: &mut Editor, cx: &mut Context<Editor>) {
+    for code_lens in editor.code_lens.values_mut() {
+        code_lens.version = None;
+    }
+    refresh_code_lens(editor, false, cx);
+}
+
+fn clear_code_lens(editor: &mut Editor, cx: &mut Context<Editor>) {
+    editor.code_lens_task = None;
+    if editor.code_lens.is_empty() {
+        return;
+    }
+    let blocks = editor
+        .code_lens
+        .drain()
+        .flat_map(|(_, code_lens)| code_lens.blocks)
+        .collect();
+    editor.remove_blocks(blocks, None, cx);
+}
+
+/// Replaces the blocks showing code lenses with ones for the current excerpts.
+pub(super) fn update_code_lens_blocks(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let mut old_blocks = HashSet::default();
+    for code_lens in editor.code_lens.values_mut() {
+        old_blocks.extend(code_lens.blocks.drain());
+    }
+    let multi_buffer = editor.buffer.read(cx);
+    editor
+        .code_lens
+        .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+    let snapshot = multi_buffer.snapshot(cx);
+    let editor_handle = cx.entity().downgrade();
+    let mut blocks_by_buffer = Vec::new();
+    for (buffer_id, code_lens) in &editor.code_lens {
+        let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+            continue;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let mut lenses_by_row = BTreeMap::<u32, Vec<CodeLens>>::new();
+        for lens in &code_lens.lenses {
+            lenses_by_row
+                .entry(lens.range.start.to_point(&buffer_snapshot).row)
+                .or_default()
+                .push(lens.clone());
+        }
+
+        let mut blocks = Vec::new();
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+            let context = excerpt_range.context.to_point(&buffer_snapshot);
+            for (row, lenses) in lenses_by_row.range(context.start.row..=context.end.row) {
+                let indent = buffer_snapshot.indent_size_for_line(*row).len;
+                let Some(position) = snapshot.anchor_in_excerpt(
+                    excerpt_id,
+                    buffer_snapshot.anchor_after(Point::new(*row, indent)),
+                ) else {
+                    continue;
+                };
+                blocks.push(BlockProperties {
+                    placement: BlockPlacement::Above(position),
+                    height: 1,
+                    style: BlockStyle::Flex,
+                    render: render_code_lens(editor_handle.clone(), buffer.clone(), lenses.clone()),
+                    priority: 0,
+                });
+            }
+        }
+        blocks_by_buffer.push((*buffer_id, blocks));
+    }
+
+    editor.remove_blocks(old_blocks, None, cx);
+    for (buffer_id, blocks) in blocks_by_buffer {
+        let block_ids = editor.insert_blocks(blocks, None, cx);
+        if let Some(code_lens) = editor.code_lens.get_mut(&buffer_id) {
+            code_lens.blocks = block_ids.into_iter().collect();
+        }
+    }
+}
+
+fn render_code_lens(
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    lenses: Vec<CodeLens>,
+) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let mut children = Vec::new();
+        for (ix, lens) in lenses.iter().enumerate() {
+            if ix > 0 {
+                children.push(
+                    Label::new("|")
+                        .size(LabelSize::XSmall)
+                        .color(Color::Disabled)
+                        .into_any_element(),
+                );
+            }
+            let title = lens.title().unwrap_or_default().to_string();
+            children.push(
+                Button::new(ix, title)
+                    .label_size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .style(ButtonStyle::Transparent)
+                    .on_click({
+                        let editor = editor.clone();
+                        let buffer = buffer.clone();
+                        let lens = lens.clone();
+                        move |_, window, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.apply_code_lens(buffer.clone(), lens.clone(), window, cx)
+                                })
+                                .ok();
+                        }
+                    })
+                    .into_any_element(),
+            );
+        }
+
+        h_flex()
+            .id(cx.block_id)
+            .block_mouse_down()
+            .h(cx.line_height)
+            .pl(cx.anchor_x)
+            .gap_1()
+            .children(children)
+            .into_any_element()
+    })
+}
+
+impl Editor {
+    /// Runs the command of a code lens, opening the buffers it edited if there are several.
+    fn apply_code_lens(
+        &mut self,
+        buffer: Entity<Buffer>,
+        lens: CodeLens,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let title = lens.title().unwrap_or_default().to_string();
+        let Some(apply) = provider.apply_code_lens(buffer, lens, cx) else {
+            return;
+        };
+        cx.spawn_in(window, |editor, cx| async move {
+            let project_transaction = apply.await?;
+            Self::open_project_transaction(
+                &editor,
+                workspace.downgrade(),
+                project_transaction,
+                title,
+                cx,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt as _;
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_code_lens_resolution(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.set_state(indoc! {"
+            fn main() {
+                ˇlet x = 1;
+            }
+            fn other() {}
+        "});
+
+        let mut code_lens_requests =
+            cx.handle_request::<lsp::request::CodeLensRequest, _, _>(|_, _, _| async move {
+                Ok(Some(vec![
+                    lsp::CodeLens {
+                        range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                        command: Some(lsp::Command {
+                            title: "Run".into(),
+                            command: "run".into(),
+                            arguments: None,
+                        }),
+                        data: None,
+                    },
+                    lsp::CodeLens {
+                        range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                        command: None,
+                        data: Some(serde_json::json!("debug")),
+                    },
+                    lsp::CodeLens {
+                        range: lsp::Range::new(lsp::Position::new(3, 3), lsp::Position::new(3, 8)),
+                        command: None,
+                        data: None,
+                    },
+                ]))
+            });
+        let mut resolve_requests =
+            cx.handle_request::<lsp::request::CodeLensResolve, _, _>(|_, lens, _| async move {
+                let command =
+                    (lens.data == Some(serde_json::json!("debug"))).then(|| lsp::Command {
+                        title: "Debug".into(),
+                        command: "debug".into(),
+                        arguments: None,
+                    });
+                Ok(lsp::CodeLens { command, ..lens })
+            });
+
+        cx.update_editor(|editor, _, cx| {
+            refresh_code_lens(editor, false, cx);
+        });
+        code_lens_requests.next().await;
+        resolve_requests.next().await;
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, _, _| {
+            let code_lens = editor.code_lens.values().next().unwrap();
+            // Lenses that are still missing a command after resolving them are not shown.
+            assert_eq!(
+                code_lens
+                    .lenses
+                    .iter()
+                    .filter_map(|lens| lens.title())
+                    .collect::<Vec<_>>(),
+                vec!["Run", "Debug"]
+            );
+            // Lenses on the same line share a block.
+            assert_eq!(code_lens.blocks.len(), 1);
+        });
+    }
+}
@@ -81,6 +81,7 @@ pub trait ToDisplayPoint {
 }
 
 type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
+type SemanticTokenHighlights = TreeMap<BufferId, Arc<[(Range<Anchor>, HighlightStyle)]>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
@@ -105,6 +106,10 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Styles of the semantic tokens reported by language servers, by buffer.
+    semantic_token_highlights: SemanticTokenHighlights,
+    /// The ranges language servers would expand the selections to, around the cursors.
+    selection_ranges: Arc<[Range<Anchor>]>,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -157,6 +162,8 @@ impl DisplayMap {
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
+            selection_ranges: Arc::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -184,6 +191,8 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
+            selection_ranges: self.selection_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -486,6 +495,26 @@ impl DisplayMap {
         let highlights = self.text_highlights.get(&type_id)?;
         Some((highlights.0, &highlights.1))
     }
+    /// Replaces the highlights of semantic tokens, which are expected to be sorted by their start
+    /// within each buffer.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        highlights: impl IntoIterator<Item = (BufferId, Vec<(Range<Anchor>, HighlightStyle)>)>,
+    ) {
+        self.semantic_token_highlights = TreeMap::default();
+        for (buffer_id, highlights) in highlights {
+            if !highlights.is_empty() {
+                self.semantic_token_highlights
+                    .insert(buffer_id, Arc::from(highlights));
+            }
+        }
+    }
+
+    /// Replaces the ranges language servers reported for expanding the selections.
+    pub(crate) fn set_selection_ranges(&mut self, ranges: Vec<Range<Anchor>>) {
+        self.selection_ranges = Arc::from(ranges);
+    }
+
     pub fn clear_highlights(&mut self, type_id: TypeId) -> bool {
         let mut cleared = self.text_highlights.remove(&type_id).is_some();
         cleared |= self.inlay_highlights.remove(&type_id).is_some();
@@ -558,6 +587,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -692,6 +722,8 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
+    selection_ranges: Arc<[Range<Anchor>]>,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -707,6 +739,20 @@ impl DisplaySnapshot {
         self.buffer_snapshot.len() == 0
     }
 
+    /// Returns the smallest range reported by a language server that strictly contains the
+    /// given one, if the language server was asked about the selections around it.
+    pub fn larger_selection_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        self.selection_ranges
+            .iter()
+            .map(|selection_range| selection_range.to_offset(&self.buffer_snapshot))
+            .filter(|selection_range| {
+                selection_range.start <= range.start
+                    && selection_range.end >= range.end
+                    && *selection_range != range
+            })
+            .min_by_key(|selection_range| selection_range.len())
+    }
+
     pub fn row_infos(&self, start_row: DisplayRow) -> impl Iterator<Item = RowInfo> + '_ {
         self.block_snapshot.row_infos(BlockRow(start_row.0))
     }
@@ -874,6 +920,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -7,10 +7,10 @@ use std::{
     cmp,
     iter::{self, Peekable},
     ops::Range,
-    sync::Arc,
     vec,
 };
-use sum_tree::TreeMap;
+
+use super::{SemanticTokenHighlights, TextHighlights};
 
 pub struct CustomHighlightsChunks<'a> {
     buffer_chunks: MultiBufferChunks<'a>,
@@ -19,23 +19,33 @@ pub struct CustomHighlightsChunks<'a> {
     multibuffer_snapshot: &'a MultiBufferSnapshot,
 
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<TypeId, HighlightStyle>,
-    text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    active_highlights: BTreeMap<HighlightKey, HighlightStyle>,
+    text_highlights: Option<&'a TextHighlights>,
+    semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct HighlightEndpoint {
     offset: usize,
     is_start: bool,
-    tag: TypeId,
+    tag: HighlightKey,
     style: HighlightStyle,
 }
 
+/// Identifies an active highlight. Semantic tokens are ordered before text highlights, so that
+/// the latter are applied on top of them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightKey {
+    SemanticToken(usize),
+    Text(TypeId),
+}
+
 impl<'a> CustomHighlightsChunks<'a> {
     pub fn new(
         range: Range<usize>,
         language_aware: bool,
-        text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+        text_highlights: Option<&'a TextHighlights>,
+        semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
         Self {
@@ -44,9 +54,11 @@ impl<'a> CustomHighlightsChunks<'a> {
             offset: range.start,
 
             text_highlights,
+            semantic_token_highlights,
             highlight_endpoints: create_highlight_endpoints(
                 &range,
                 text_highlights,
+                semantic_token_highlights,
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
@@ -55,8 +67,12 @@ impl<'a> CustomHighlightsChunks<'a> {
     }
 
     pub fn seek(&mut self, new_range: Range<usize>) {
-        self.highlight_endpoints =
-            create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.highlight_endpoints = create_highlight_endpoints(
+            &new_range,
+            self.text_highlights,
+            self.semantic_token_highlights,
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
@@ -66,52 +82,80 @@ impl<'a> CustomHighlightsChunks<'a> {
 
 fn create_highlight_endpoints(
     range: &Range<usize>,
-    text_highlights: Option<&TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    text_highlights: Option<&TextHighlights>,
+    semantic_token_highlights: Option<&SemanticTokenHighlights>,
     buffer: &MultiBufferSnapshot,
 ) -> iter::Peekable<vec::IntoIter<HighlightEndpoint>> {
     let mut highlight_endpoints = Vec::new();
+    let start = buffer.anchor_after(range.start);
+    let end = buffer.anchor_after(range.end);
+    let mut push_endpoints = |range: &Range<Anchor>, tag: HighlightKey, style: HighlightStyle| {
+        highlight_endpoints.push(HighlightEndpoint {
+            offset: range.start.to_offset(buffer),
+            is_start: true,
+            tag,
+            style,
+        });
+        highlight_endpoints.push(HighlightEndpoint {
+            offset: range.end.to_offset(buffer),
+            is_start: false,
+            tag,
+            style,
+        });
+    };
+
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        // Every token gets its own key, so that adjacent tokens don't end each other.
+        let mut token_ix = 0;
+        for tokens in semantic_token_highlights.values() {
+            for (token_range, style) in
+                ranges_intersecting(tokens, |(range, _)| range, &start, &end, buffer)
+            {
+                push_endpoints(token_range, HighlightKey::SemanticToken(token_ix), *style);
+                token_ix += 1;
+            }
+        }
+    }
+
     if let Some(text_highlights) = text_highlights {
-        let start = buffer.anchor_after(range.start);
-        let end = buffer.anchor_after(range.end);
         for (&tag, text_highlights) in text_highlights.iter() {
             let style = text_highlights.0;
-            let ranges = &text_highlights.1;
-
-            let start_ix = match ranges.binary_search_by(|probe| {
-                let cmp = probe.end.cmp(&start, &buffer);
-                if cmp.is_gt() {
-                    cmp::Ordering::Greater
-                } else {
-                    cmp::Ordering::Less
-                }
-            }) {
-                Ok(i) | Err(i) => i,
-            };
-
-            for range in &ranges[start_ix..] {
-                if range.start.cmp(&end, &buffer).is_ge() {
-                    break;
-                }
-
-                highlight_endpoints.push(HighlightEndpoint {
-                    offset: range.start.to_offset(&buffer),
-                    is_start: true,
-                    tag,
-                    style,
-                });
-                highlight_endpoints.push(HighlightEndpoint {
-                    offset: range.end.to_offset(&buffer),
-                    is_start: false,
-                    tag,
-                    style,
-                });
+            for range in
+                ranges_intersecting(&text_highlights.1, |range| range, &start, &end, buffer)
+            {
+                push_endpoints(range, HighlightKey::Text(tag), style);
             }
         }
-        highlight_endpoints.sort();
     }
+
+    highlight_endpoints.sort();
     highlight_endpoints.into_iter().peekable()
 }
 
+/// Returns the items of a slice sorted by range that intersect `start..end`.
+fn ranges_intersecting<'a, T>(
+    items: &'a [T],
+    range: impl Fn(&T) -> &Range<Anchor> + 'a,
+    start: &'a Anchor,
+    end: &'a Anchor,
+    buffer: &'a MultiBufferSnapshot,
+) -> impl Iterator<Item = &'a T> + 'a {
+    let start_ix = match items.binary_search_by(|probe| {
+        let cmp = range(probe).end.cmp(start, buffer);
+        if cmp.is_gt() {
+            cmp::Ordering::Greater
+        } else {
+            cmp::Ordering::Less
+        }
+    }) {
+        Ok(i) | Err(i) => i,
+    };
+
+    items[start_ix..]
+        .iter()
+        .take_while(move |item| range(item).start.cmp(end, buffer).is_lt())
+}
+
 impl<'a> Iterator for CustomHighlightsChunks<'a> {
     type Item = Chunk<'a>;
 
@@ -150,7 +194,12 @@ impl<'a> Iterator for CustomHighlightsChunks<'a> {
         };
         if !self.active_highlights.is_empty() {
             let mut highlight_style = HighlightStyle::default();
-            for active_highlight in self.active_highlights.values() {
+            for (key, active_highlight) in &self.active_highlights {
+                // Semantic tokens that have a color of their own replace the syntax highlighting.
+                if matches!(key, HighlightKey::SemanticToken(_)) && active_highlight.color.is_some()
+                {
+                    prefix.syntax_highlight_id = None;
+                }
                 highlight_style.highlight(*active_highlight);
             }
             prefix.highlight_style = Some(highlight_style);
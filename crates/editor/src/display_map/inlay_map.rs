@@ -64,6 +64,22 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn inline_value<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::InlineValue(id),
+            position,
+            text: text.into(),
+        }
+    }
+
+    pub fn color_swatch<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::ColorSwatch(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -286,7 +302,9 @@ impl<'a> Iterator for InlayChunks<'a> {
                             }
                         })
                     }
-                    InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Hint(_) | InlayId::InlineValue(_) => self.highlight_styles.inlay_hint,
+                    // Swatches are colored with the color they show, as an inlay highlight.
+                    InlayId::ColorSwatch(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -998,6 +1016,7 @@ impl InlaySnapshot {
             buffer_range,
             language_aware,
             highlights.text_highlights,
+            highlights.semantic_token_highlights,
             &self.buffer,
         );
 
@@ -0,0 +1,453 @@
+use std::{cell::Cell, mem, rc::Rc, time::Duration};
+
+use clock::Global;
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{Context, Entity, HighlightStyle, Pixels, Point, Window};
+use language::{Bias, Buffer, OffsetRangeExt as _, ToOffset as _};
+use project::{ColorPresentation, DocumentColor};
+use settings::Settings as _;
+use text::BufferId;
+use ui::{prelude::*, ColorPicker, ContextMenu};
+use util::post_inc;
+
+use crate::{
+    display_map::Inlay, hover_links::InlayHighlight, mouse_context_menu::MouseContextMenu, Editor,
+    EditorMode, EditorSettings, EditorSnapshot, PointForPosition,
+};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(500);
+const SWATCH: &str = "■";
+
+/// The colors language servers found in a buffer.
+#[derive(Default)]
+pub(super) struct BufferDocumentColors {
+    /// The version of the buffer the colors were requested for, `None` if they are out of date.
+    version: Option<Global>,
+    colors: Vec<DocumentColor>,
+}
+
+/// The swatch shown before a color in the editor.
+#[derive(Clone, Debug)]
+pub(super) struct ColorSwatch {
+    inlay: Inlay,
+    buffer_id: BufferId,
+    color: DocumentColor,
+}
+
+/// Requests the colors of the visible buffers whose colors are out of date, and shows a swatch
+/// before each of them.
+pub(super) fn refresh_document_colors(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) -> Option<()> {
+    if editor.mode != EditorMode::Full {
+        return None;
+    }
+    if !EditorSettings::get_global(cx).document_colors {
+        editor.document_colors_task = None;
+        editor.document_colors.clear();
+        update_color_swatches(editor, cx);
+        return None;
+    }
+    let provider = editor.semantics_provider.clone()?;
+
+    editor.document_colors_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+        }
+
+        let requests = editor
+            .update(&mut cx, |editor, cx| {
+                let mut buffers = HashMap::default();
+                for (buffer, _, _) in editor
+                    .excerpts_for_inlay_hints_query(None, cx)
+                    .into_values()
+                {
+                    buffers.insert(buffer.read(cx).remote_id(), buffer);
+                }
+                buffers
+                    .into_values()
+                    .filter_map(|buffer| {
+                        let version = buffer.read(cx).version();
+                        let buffer_id = buffer.read(cx).remote_id();
+                        let up_to_date = editor
+                            .document_colors
+                            .get(&buffer_id)
+                            .map_or(false, |colors| colors.version.as_ref() == Some(&version));
+                        if up_to_date {
+                            return None;
+                        }
+                        let request = provider.document_colors(&buffer, cx)?;
+                        Some(async move { (buffer_id, version, request.await) })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let responses = join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, version, colors) in responses {
+                    editor.document_colors.insert(
+                        buffer_id,
+                        BufferDocumentColors {
+                            version: Some(version),
+                            colors,
+                        },
+                    );
+                }
+                update_color_swatches(editor, cx);
+            })
+            .ok()
+    }));
+    None
+}
+
+/// Replaces the swatches shown before colors with ones for the current excerpts.
+pub(super) fn update_color_swatches(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let to_remove = mem::take(&mut editor.color_swatches)
+        .into_iter()
+        .map(|swatch| swatch.inlay.id)
+        .collect::<Vec<_>>();
+    let mut swatches = Vec::new();
+    if EditorSettings::get_global(cx).document_colors {
+        let multi_buffer = editor.buffer.read(cx);
+        editor
+            .document_colors
+            .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+        let snapshot = multi_buffer.snapshot(cx);
+        for (buffer_id, colors) in &editor.document_colors {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                let context = excerpt_range.context.to_offset(&buffer_snapshot);
+                for color in &colors.colors {
+                    let start = color.range.start.to_offset(&buffer_snapshot);
+                    if !context.contains(&start) {
+                        continue;
+                    }
+                    // Biased to the right so that the swatch stays before the color.
+                    let Some(position) =
+                        snapshot.anchor_in_excerpt(excerpt_id, buffer_snapshot.anchor_after(start))
+                    else {
+                        continue;
+                    };
+                    swatches.push(ColorSwatch {
+                        inlay: Inlay::color_swatch(
+                            post_inc(&mut editor.next_inlay_id),
+                            position,
+                            format!("{SWATCH} "),
+                        ),
+                        buffer_id: *buffer_id,
+                        color: color.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    if to_remove.is_empty() && swatches.is_empty() {
+        return;
+    }
+    editor.clear_highlights::<ColorSwatch>(cx);
+    let to_insert = swatches.iter().map(|swatch| swatch.inlay.clone()).collect();
+    editor.splice_inlays(&to_remove, to_insert, cx);
+    for swatch in &swatches {
+        editor.highlight_inlays::<ColorSwatch>(
+            vec![InlayHighlight {
+                inlay: swatch.inlay.id,
+                inlay_position: swatch.inlay.position,
+                range: 0..SWATCH.len(),
+            }],
+            HighlightStyle {
+                color: Some(swatch.color.color),
+                ..HighlightStyle::default()
+            },
+            cx,
+        );
+    }
+    editor.color_swatches = swatches;
+}
+
+/// The swatch at a position in the editor, if any.
+pub(super) fn color_swatch_at(
+    editor: &Editor,
+    snapshot: &EditorSnapshot,
+    point_for_position: PointForPosition,
+) -> Option<ColorSwatch> {
+    if point_for_position.column_overshoot_after_line_end != 0 {
+        return None;
+    }
+    let offset =
+        snapshot.display_point_to_inlay_offset(point_for_position.exact_unclipped, Bias::Left);
+    editor
+        .color_swatches
+        .iter()
+        .find(|swatch| {
+            let start = snapshot.anchor_to_inlay_offset(swatch.inlay.position);
+            start <= offset && offset.0 < start.0 + SWATCH.len()
+        })
+        .cloned()
+}
+
+/// The presentation to write a picked color with: the first one in the same format as the
+/// color's current text, such as `#` or `rgb`, or the first one the language server proposes.
+fn preferred_presentation(
+    current_text: &str,
+    presentations: Vec<ColorPresentation>,
+) -> Option<ColorPresentation> {
+    fn format(text: &str) -> &str {
+        if text.starts_with('#') {
+            "#"
+        } else {
+            text.split('(').next().unwrap_or(text).trim()
+        }
+    }
+
+    let current_format = format(current_text);
+    let ix = presentations
+        .iter()
+        .position(|presentation| {
+            let text = presentation
+                .edits
+                .first()
+                .map_or(presentation.label.as_str(), |(_, text)| text.as_str());
+            format(text) == current_format
+        })
+        .unwrap_or(0);
+    presentations.into_iter().nth(ix)
+}
+
+impl Editor {
+    /// Opens a color picker to replace the color a swatch shows.
+    pub(crate) fn open_color_picker(
+        &mut self,
+        swatch: ColorSwatch,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(buffer) = self.buffer.read(cx).buffer(swatch.buffer_id) else {
+            return;
+        };
+        let original_color = swatch.color.color;
+        let picked_color = Rc::new(Cell::new(original_color));
+        let editor = cx.entity().downgrade();
+        let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            menu.custom_row({
+                let picked_color = picked_color.clone();
+                move |_, _| {
+                    let picked_color = picked_color.clone();
+                    ColorPicker::new("color-picker", picked_color.get())
+                        .original_color(original_color)
+                        .on_change(move |color, window, _| {
+                            picked_color.set(color);
+                            window.refresh();
+                        })
+                        .into_any_element()
+                }
+            })
+            .separator()
+            .entry("Save Color", None, {
+                let color = swatch.color.clone();
+                move |_, cx| {
+                    let color = DocumentColor {
+                        color: picked_color.get(),
+                        ..color.clone()
+                    };
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.save_color(buffer.clone(), color, cx);
+                        })
+                        .ok();
+                }
+            })
+        });
+        self.mouse_context_menu = MouseContextMenu::pinned_to_editor(
+            self,
+            swatch.inlay.position,
+            position,
+            context_menu,
+            window,
+            cx,
+        );
+        cx.notify();
+    }
+
+    /// Writes a color over the one found at its range, the way the language server that found
+    /// it proposes.
+    fn save_color(&mut self, buffer: Entity<Buffer>, color: DocumentColor, cx: &mut Context<Self>) {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return;
+        };
+        let current_text = buffer
+            .read(cx)
+            .text_for_range(color.range.clone())
+            .collect::<String>();
+        let Some(presentations) = provider.color_presentations(&buffer, color, cx) else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let Some(presentation) = preferred_presentation(&current_text, presentations.await?)
+            else {
+                return Ok(());
+            };
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.edit(presentation.edits, None, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt as _;
+    use indoc::indoc;
+    use text::Buffer;
+
+    #[gpui::test]
+    async fn test_document_colors(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.set_state(indoc! {r#"
+            ˇlet color = "#ff0000";
+            let other = "red";
+        "#});
+
+        let red = lsp::Color {
+            red: 1.,
+            green: 0.,
+            blue: 0.,
+            alpha: 1.,
+        };
+        let mut color_requests =
+            cx.handle_request::<lsp::request::DocumentColor, _, _>(move |_, _, _| async move {
+                Ok(vec![
+                    lsp::ColorInformation {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, 13),
+                            lsp::Position::new(0, 20),
+                        ),
+                        color: red,
+                    },
+                    lsp::ColorInformation {
+                        range: lsp::Range::new(
+                            lsp::Position::new(1, 13),
+                            lsp::Position::new(1, 16),
+                        ),
+                        color: red,
+                    },
+                ])
+            });
+        let _presentation_requests = cx
+            .handle_request::<lsp::request::ColorPresentationRequest, _, _>(
+                |_, params, _| async move {
+                    if params.range.start.line == 1 {
+                        // Without an edit, the label replaces the color.
+                        return Ok(vec![lsp::ColorPresentation {
+                            label: "blue".into(),
+                            text_edit: None,
+                            additional_text_edits: None,
+                        }]);
+                    }
+                    let presentation = |text: &str| lsp::ColorPresentation {
+                        label: text.into(),
+                        text_edit: Some(lsp::TextEdit::new(params.range, text.into())),
+                        additional_text_edits: None,
+                    };
+                    Ok(vec![
+                        presentation("rgb(0, 0, 255)"),
+                        presentation("#0000ff"),
+                    ])
+                },
+            );
+
+        cx.update_editor(|editor, _, cx| {
+            refresh_document_colors(editor, false, cx);
+        });
+        color_requests.next().await;
+        cx.run_until_parked();
+
+        let swatches = cx.update_editor(|editor, window, cx| {
+            assert_eq!(
+                editor.display_text(cx),
+                indoc! {r#"
+                    let color = "■ #ff0000";
+                    let other = "■ red";
+                "#}
+            );
+            // Each swatch is drawn in its color.
+            let snapshot = editor.snapshot(window, cx);
+            let highlights = snapshot.inlay_highlights::<ColorSwatch>().unwrap();
+            for swatch in &editor.color_swatches {
+                let (style, highlight) = highlights.get(&swatch.inlay.id).unwrap();
+                assert_eq!(style.color, Some(swatch.color.color));
+                assert_eq!(highlight.range, 0..SWATCH.len());
+            }
+            editor.color_swatches.clone()
+        });
+        assert_eq!(swatches.len(), 2);
+
+        // The presentation in the same format as the color's text is written with its edits.
+        for swatch in swatches {
+            cx.update_editor(|editor, _, cx| {
+                let buffer = editor.buffer().read(cx).buffer(swatch.buffer_id).unwrap();
+                let color = DocumentColor {
+                    color: gpui::blue(),
+                    ..swatch.color
+                };
+                editor.save_color(buffer, color, cx);
+            });
+            cx.run_until_parked();
+        }
+        cx.assert_editor_state(indoc! {r#"
+            ˇlet color = "#0000ff";
+            let other = "blue";
+        "#});
+    }
+
+    #[test]
+    fn test_preferred_presentation() {
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), "color: #ff0000;".to_string());
+        let snapshot = buffer.snapshot();
+        let range = snapshot.anchor_before(7)..snapshot.anchor_after(14);
+        let presentation = |label: &str| ColorPresentation {
+            label: label.to_string(),
+            edits: vec![(range.clone(), label.to_string())],
+        };
+        let presentations = vec![
+            presentation("rgb(0, 255, 0)"),
+            presentation("#00ff00"),
+            presentation("hsl(120, 100%, 50%)"),
+        ];
+
+        assert_eq!(
+            preferred_presentation("#ff0000", presentations.clone()),
+            Some(presentation("#00ff00"))
+        );
+        assert_eq!(
+            preferred_presentation("hsl(0, 100%, 50%)", presentations.clone()),
+            Some(presentation("hsl(120, 100%, 50%)"))
+        );
+        assert_eq!(
+            preferred_presentation("Color(0xFFFF0000)", presentations.clone()),
+            Some(presentation("rgb(0, 255, 0)"))
+        );
+        assert_eq!(preferred_presentation("#ff0000", Vec::new()), None);
+    }
+}
@@ -14,19 +14,24 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 mod blink_manager;
+pub mod bookmarks;
 mod clangd_ext;
 mod code_context_menus;
+mod code_lens;
 pub mod commit_tooltip;
 pub mod display_map;
+mod document_colors;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
 mod indent_guides;
 mod inlay_hint_cache;
+mod inline_values;
 pub mod items;
 mod linked_editing_ranges;
 mod lsp_ext;
@@ -36,7 +41,9 @@ mod persistence;
 mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
@@ -63,7 +70,7 @@ pub use editor_settings::{
     CurrentLineHighlight, EditorSettings, ScrollBeyondLastLine, SearchSettings, ShowScrollbar,
 };
 pub use editor_settings_controls::*;
-use element::{AcceptEditPredictionBinding, LineWithInvisibles, PositionMap};
+use element::{AcceptEditPredictionBinding, LineWithInvisibles, PositionMap, StickyScopesCache};
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
 };
@@ -78,6 +85,8 @@ use code_context_menus::{
     CompletionsMenu, ContextMenuOrigin,
 };
 use git::blame::GitBlame;
+use git::conflicts::ConflictState;
+pub use git::conflicts::{parse_conflicts, ConflictRegion, ConflictResolution};
 use gpui::{
     div, impl_actions, point, prelude::*, pulsating_between, px, relative, size, Action, Animation,
     AnimationExt, AnyElement, App, AsyncWindowContext, AvailableSpace, Bounds, ClipboardEntry,
@@ -131,10 +140,16 @@ use multi_buffer::{
     ToOffsetUtf16,
 };
 use project::{
+    dap_store::DapStoreEvent,
+    git::GitEvent,
+    lsp_command::SemanticTokens,
     lsp_store::{FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
-    CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, Location, LocationLink,
+    test_store::{TestStatus, TestStoreEvent},
+    CodeAction, CodeLens, ColorPresentation, Completion, CompletionIntent, DocumentColor,
+    DocumentHighlight, DocumentLink, InlayHint, InlineValue, Location, LocationLink,
     PrepareRenameResponse, Project, ProjectItem, ProjectTransaction, TaskSourceKind,
+    WorkspaceEditPreview,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
@@ -269,6 +284,8 @@ pub fn render_parsed_markdown(
 pub enum InlayId {
     InlineCompletion(usize),
     Hint(usize),
+    InlineValue(usize),
+    ColorSwatch(usize),
 }
 
 impl InlayId {
@@ -276,6 +293,8 @@ impl InlayId {
         match self {
             Self::InlineCompletion(id) => *id,
             Self::Hint(id) => *id,
+            Self::InlineValue(id) => *id,
+            Self::ColorSwatch(id) => *id,
         }
     }
 }
@@ -312,11 +331,13 @@ pub fn init(cx: &mut App) {
     workspace::register_serializable_item::<Editor>(cx);
 
     cx.observe_new(
-        |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
+        |workspace: &mut Workspace, _: Option<&mut Window>, cx: &mut Context<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_vertical);
             workspace.register_action(Editor::new_file_horizontal);
             workspace.register_action(Editor::cancel_language_server_work);
+            bookmarks::register(workspace);
+            bookmarks::restore_bookmarks(workspace, cx);
         },
     )
     .detach();
@@ -657,6 +678,7 @@ pub struct Editor {
     show_breadcrumbs: bool,
     show_gutter: bool,
     show_scrollbars: bool,
+    show_minimap: bool,
     show_line_numbers: Option<bool>,
     use_relative_line_numbers: Option<bool>,
     show_git_diff_gutter: Option<bool>,
@@ -684,6 +706,19 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    semantic_tokens_task: Option<Task<Option<()>>>,
+    semantic_tokens: HashMap<BufferId, SemanticTokens>,
+    code_lens_task: Option<Task<Option<()>>>,
+    code_lens: HashMap<BufferId, code_lens::BufferCodeLens>,
+    folding_ranges_task: Option<Task<Option<()>>>,
+    folding_ranges: HashMap<BufferId, folding_ranges::BufferFoldingRanges>,
+    inline_values_task: Option<Task<Option<()>>>,
+    inline_values: HashMap<BufferId, inline_values::BufferInlineValues>,
+    inline_value_inlays: Vec<InlayId>,
+    document_colors_task: Option<Task<Option<()>>>,
+    document_colors: HashMap<BufferId, document_colors::BufferDocumentColors>,
+    color_swatches: Vec<document_colors::ColorSwatch>,
+    selection_ranges_task: Option<Task<Option<()>>>,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
@@ -699,6 +734,8 @@ pub struct Editor {
     hover_state: HoverState,
     pending_mouse_down: Option<Rc<RefCell<Option<MouseDownEvent>>>>,
     gutter_hovered: bool,
+    /// The gutter row under the mouse, where clicking adds a breakpoint.
+    gutter_breakpoint_indicator: Option<DisplayRow>,
     hovered_link_state: Option<HoveredLinkState>,
     edit_prediction_provider: Option<RegisteredInlineCompletionProvider>,
     code_action_providers: Vec<Rc<dyn CodeActionProvider>>,
@@ -734,6 +771,7 @@ pub struct Editor {
     show_selection_menu: Option<bool>,
     blame: Option<Entity<GitBlame>>,
     blame_subscription: Option<Subscription>,
+    conflicts: ConflictState,
     custom_context_menu: Option<
         Box<
             dyn 'static
@@ -747,6 +785,7 @@ pub struct Editor {
     >,
     last_bounds: Option<Bounds<Pixels>>,
     last_position_map: Option<Rc<PositionMap>>,
+    sticky_scopes_cache: StickyScopesCache,
     expect_bounds_change: Option<Bounds<Pixels>>,
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
@@ -1254,6 +1293,16 @@ impl Editor {
                         if let project::Event::RefreshInlayHints = event {
                             editor
                                 .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        } else if let project::Event::RefreshSemanticTokens = event {
+                            semantic_tokens::refresh_semantic_tokens(editor, false, cx);
+                        } else if let project::Event::RefreshCodeLens = event {
+                            code_lens::invalidate_code_lens(editor, cx);
+                        } else if let project::Event::LanguageServerAdded(..) = event {
+                            folding_ranges::invalidate_folding_ranges(editor, cx);
+                        } else if let project::Event::TaskOutputValuesChanged(buffer_id) = event {
+                            if editor.buffer.read(cx).buffer(*buffer_id).is_some() {
+                                inline_values::update_inline_value_inlays(editor, cx);
+                            }
                         } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                             if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                                 let focus_handle = editor.focus_handle(cx);
@@ -1276,6 +1325,33 @@ impl Editor {
                         }
                     },
                 ));
+                project_subscriptions
+                    .push(cx.observe(project.read(cx).bookmark_store(), |_, _, cx| cx.notify()));
+                project_subscriptions.push(cx.subscribe_in(
+                    project.read(cx).git_store(),
+                    window,
+                    |editor, _, event, window, cx| {
+                        if let GitEvent::GitStateUpdated = event {
+                            editor.refresh_conflicts(window, cx);
+                        }
+                    },
+                ));
+                project_subscriptions.push(cx.subscribe(
+                    project.read(cx).dap_store(),
+                    |_, _, event, cx| {
+                        if let DapStoreEvent::BreakpointsChanged(_) = event {
+                            cx.notify();
+                        }
+                    },
+                ));
+                project_subscriptions.push(cx.subscribe(
+                    project.read(cx).test_store(),
+                    |_, _, event, cx| {
+                        if let TestStoreEvent::StatusesChanged(_) = event {
+                            cx.notify();
+                        }
+                    },
+                ));
                 if let Some(task_inventory) = project
                     .read(cx)
                     .task_store()
@@ -1355,6 +1431,7 @@ impl Editor {
             blink_manager: blink_manager.clone(),
             show_local_selections: true,
             show_scrollbars: true,
+            show_minimap: true,
             mode,
             show_breadcrumbs: EditorSettings::get_global(cx).toolbar.breadcrumbs,
             show_gutter: mode == EditorMode::Full,
@@ -1386,6 +1463,19 @@ impl Editor {
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
             linked_editing_range_task: Default::default(),
+            semantic_tokens_task: Default::default(),
+            semantic_tokens: Default::default(),
+            code_lens_task: Default::default(),
+            code_lens: Default::default(),
+            folding_ranges_task: Default::default(),
+            folding_ranges: Default::default(),
+            inline_values_task: Default::default(),
+            inline_values: Default::default(),
+            inline_value_inlays: Default::default(),
+            document_colors_task: Default::default(),
+            document_colors: Default::default(),
+            color_swatches: Default::default(),
+            selection_ranges_task: Default::default(),
             pending_rename: Default::default(),
             searchable: true,
             cursor_shape: EditorSettings::get_global(cx)
@@ -1413,9 +1503,11 @@ impl Editor {
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
 
             gutter_hovered: false,
+            gutter_breakpoint_indicator: None,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
             last_position_map: None,
+            sticky_scopes_cache: StickyScopesCache::default(),
             expect_bounds_change: None,
             gutter_dimensions: GutterDimensions::default(),
             style: None,
@@ -1441,6 +1533,7 @@ impl Editor {
                 .restore_unsaved_buffers,
             blame: None,
             blame_subscription: None,
+            conflicts: ConflictState::default(),
             tasks: Default::default(),
             _subscriptions: vec![
                 cx.observe(&buffer, Self::on_buffer_changed),
@@ -1476,6 +1569,7 @@ impl Editor {
             load_diff_task: load_uncommitted_diff,
         };
         this.tasks_update_task = Some(this.refresh_runnables(window, cx));
+        this.refresh_conflicts(window, cx);
         this._subscriptions.extend(project_subscriptions);
 
         this.end_selection(window, cx);
@@ -5629,12 +5723,19 @@ impl Editor {
         _style: &EditorStyle,
         is_active: bool,
         row: DisplayRow,
+        test_status: Option<TestStatus>,
         cx: &mut Context<Self>,
     ) -> IconButton {
-        IconButton::new(("run_indicator", row.0 as usize), ui::IconName::Play)
+        let (icon, color) = match test_status {
+            Some(TestStatus::Passed) => (ui::IconName::Check, Color::Success),
+            Some(TestStatus::Failed) => (ui::IconName::XCircle, Color::Error),
+            Some(TestStatus::Running) => (ui::IconName::Play, Color::Accent),
+            Some(TestStatus::Skipped) | None => (ui::IconName::Play, Color::Muted),
+        };
+        IconButton::new(("run_indicator", row.0 as usize), icon)
             .shape(ui::IconButtonShape::Square)
             .icon_size(IconSize::XSmall)
-            .icon_color(Color::Muted)
+            .icon_color(color)
             .toggle_state(is_active)
             .on_click(cx.listener(move |editor, _e, window, cx| {
                 window.focus(&editor.focus_handle(cx));
@@ -5648,6 +5749,116 @@ impl Editor {
             }))
     }
 
+    pub fn toggle_breakpoint(
+        &mut self,
+        _: &ToggleBreakpoint,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cursor = self.selections.newest::<Point>(cx).head();
+        self.toggle_breakpoint_at(cursor, cx);
+    }
+
+    fn toggle_breakpoint_at(&mut self, position: Point, cx: &mut Context<Self>) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        project
+            .read(cx)
+            .dap_store()
+            .clone()
+            .update(cx, |dap_store, cx| {
+                dap_store.toggle_breakpoint(buffer, position, cx);
+            });
+    }
+
+    /// The status of the test on the given buffer row, if it has been run.
+    fn test_status(
+        &self,
+        buffer_snapshot: &BufferSnapshot,
+        row: u32,
+        cx: &App,
+    ) -> Option<TestStatus> {
+        let project = self.project.as_ref()?;
+        project
+            .read(cx)
+            .test_store()
+            .read(cx)
+            .test_status_rows(buffer_snapshot.remote_id(), buffer_snapshot)
+            .into_iter()
+            .find_map(|(status_row, status)| (status_row == row).then_some(status))
+    }
+
+    /// Display rows within `range` that have a breakpoint.
+    fn breakpoint_rows(
+        &self,
+        range: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &App,
+    ) -> HashSet<DisplayRow> {
+        let mut rows = HashSet::default();
+        let Some(project) = self
+            .project
+            .as_ref()
+            .filter(|_| self.mode == EditorMode::Full)
+        else {
+            return rows;
+        };
+        let dap_store = project.read(cx).dap_store().read(cx);
+        let start = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left);
+        let end = snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+        for (buffer_snapshot, buffer_range, excerpt_id) in
+            snapshot.buffer_snapshot.range_to_buffer_ranges(start..end)
+        {
+            let start_row = buffer_snapshot.offset_to_point(buffer_range.start).row;
+            let end_row = buffer_snapshot.offset_to_point(buffer_range.end).row;
+            for row in dap_store.breakpoint_rows(buffer_snapshot.remote_id(), buffer_snapshot) {
+                if row < start_row || row > end_row {
+                    continue;
+                }
+                let anchor = buffer_snapshot.anchor_before(Point::new(row, 0));
+                if let Some(anchor) = snapshot
+                    .buffer_snapshot
+                    .anchor_in_excerpt(excerpt_id, anchor)
+                {
+                    rows.insert(anchor.to_display_point(snapshot).row());
+                }
+            }
+        }
+        rows
+    }
+
+    fn render_breakpoint_indicator(
+        &self,
+        row: DisplayRow,
+        has_breakpoint: bool,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let (color, tooltip) = if has_breakpoint {
+            (Color::Error, "Remove Breakpoint")
+        } else {
+            (Color::Hint, "Add Breakpoint")
+        };
+        IconButton::new(
+            ("breakpoint_indicator", row.0 as usize),
+            ui::IconName::Circle,
+        )
+        .shape(ui::IconButtonShape::Square)
+        .icon_size(IconSize::XSmall)
+        .icon_color(color)
+        .tooltip(Tooltip::text(tooltip))
+        .on_click(cx.listener(move |editor, _e, window, cx| {
+            window.focus(&editor.focus_handle(cx));
+            let snapshot = editor.snapshot(window, cx);
+            let position = DisplayPoint::new(row, 0).to_point(&snapshot.display_snapshot);
+            editor.toggle_breakpoint_at(position, cx);
+        }))
+    }
+
     pub fn context_menu_visible(&self) -> bool {
         !self.edit_prediction_preview_is_active()
             && self
@@ -9945,6 +10156,34 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        // Languages that prefer the ranges of their language server wait for them.
+        let editor = cx.entity().downgrade();
+        let fetching = self.fetch_selection_ranges(window, cx, move |window, cx| {
+            editor
+                .update(cx, |editor, cx| {
+                    editor.expand_to_larger_syntax_node(window, cx)
+                })
+                .ok();
+        });
+        if !fetching {
+            self.expand_to_larger_syntax_node(window, cx);
+        }
+    }
+
+    /// Asks the language servers of the buffers under the cursors for the ranges the
+    /// selections could be expanded to, when their language prefers these over its syntax
+    /// tree, and runs `then` once they arrive. Returns `false`, without running `then`, when
+    /// there is nothing to ask for.
+    pub fn fetch_selection_ranges(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        then: impl 'static + FnOnce(&mut Window, &mut App),
+    ) -> bool {
+        selection_ranges::fetch_selection_ranges(self, window, cx, then)
+    }
+
+    fn expand_to_larger_syntax_node(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
         let old_selections = self.selections.all::<usize>(cx).into_boxed_slice();
@@ -9957,10 +10196,19 @@ impl Editor {
                 let old_range = selection.start..selection.end;
                 let mut new_range = old_range.clone();
                 let mut new_node = None;
-                while let Some((node, containing_range)) = buffer.syntax_ancestor(new_range.clone())
-                {
-                    new_node = Some(node);
-                    new_range = containing_range;
+                loop {
+                    // Ranges from a language server are only known for languages that prefer
+                    // them, and the syntax tree takes over past the largest of them.
+                    if let Some(range) = display_map.larger_selection_range(new_range.clone()) {
+                        new_range = range;
+                    } else if let Some((node, containing_range)) =
+                        buffer.syntax_ancestor(new_range.clone())
+                    {
+                        new_node = Some(node);
+                        new_range = containing_range;
+                    } else {
+                        break;
+                    }
                     if !display_map.intersects_fold(new_range.start)
                         && !display_map.intersects_fold(new_range.end)
                     {
@@ -11432,6 +11680,58 @@ impl Editor {
         }))
     }
 
+    pub fn preview_rename(
+        &mut self,
+        _: &PreviewRename,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let rename = self.take_rename(false, window, cx)?;
+        let workspace = self.workspace()?.downgrade();
+        let (buffer, start) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(rename.range.start, cx)?;
+        let (end_buffer, _) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(rename.range.end, cx)?;
+        if buffer != end_buffer {
+            return None;
+        }
+
+        let old_name = rename.old_name;
+        let new_name = rename.editor.read(cx).text(cx);
+
+        let rename_edits =
+            self.semantics_provider
+                .as_ref()?
+                .rename_edits(&buffer, start, new_name.clone(), cx)?;
+
+        let project = self.project.clone();
+        Some(cx.spawn_in(window, |_, mut cx| async move {
+            let preview = rename_edits.await?;
+            if preview.buffer_edits.is_empty() && preview.file_operations.is_empty() {
+                return Ok(());
+            }
+
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                let editor = cx.new(|cx| {
+                    ProposedChangesEditor::for_workspace_edit(
+                        format!("Rename: {} → {}", old_name, new_name),
+                        preview,
+                        project,
+                        window,
+                        cx,
+                    )
+                });
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.add_item(Box::new(editor), true, true, None, window, cx);
+                });
+            })
+        }))
+    }
+
     fn take_rename(
         &mut self,
         moving_cursor: bool,
@@ -12616,9 +12916,19 @@ impl Editor {
         _: &ApplyDiffHunk,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) {
+        let ranges = self.selections.ranges(cx);
+        self.apply_diff_hunks_in_ranges(ranges.into_iter(), window, cx);
+    }
+
+    fn apply_diff_hunks_in_ranges(
+        &mut self,
+        ranges: impl Iterator<Item = Range<Point>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         let snapshot = self.snapshot(window, cx);
-        let hunks = snapshot.hunks_for_ranges(self.selections.ranges(cx).into_iter());
+        let hunks = snapshot.hunks_for_ranges(ranges);
         let mut ranges_by_buffer = HashMap::default();
         self.transact(window, cx, |editor, _window, cx| {
             for hunk in hunks {
@@ -12649,6 +12959,18 @@ impl Editor {
         }
     }
 
+    pub(crate) fn set_gutter_breakpoint_indicator(
+        &mut self,
+        row: Option<DisplayRow>,
+        cx: &mut Context<Self>,
+    ) {
+        let row = row.filter(|_| self.project.is_some() && self.mode == EditorMode::Full);
+        if row != self.gutter_breakpoint_indicator {
+            self.gutter_breakpoint_indicator = row;
+            cx.notify();
+        }
+    }
+
     pub fn insert_blocks(
         &mut self,
         blocks: impl IntoIterator<Item = BlockProperties<Anchor>>,
@@ -12968,6 +13290,11 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn set_show_minimap(&mut self, show_minimap: bool, cx: &mut Context<Self>) {
+        self.show_minimap = show_minimap;
+        cx.notify();
+    }
+
     pub fn set_show_line_numbers(&mut self, show_line_numbers: bool, cx: &mut Context<Self>) {
         self.show_line_numbers = Some(show_line_numbers);
         cx.notify();
@@ -14042,6 +14369,7 @@ impl Editor {
                 self.active_indent_guides_state.dirty = true;
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(window, cx);
+                self.refresh_conflicts(window, cx);
                 if self.has_active_inline_completion() {
                     self.update_visible_inline_completion(window, cx);
                 }
@@ -14060,6 +14388,7 @@ impl Editor {
                 }
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                selection_ranges::buffer_edited(self, cx);
                 if *singleton_buffer_edited {
                     if let Some(project) = &self.project {
                         #[allow(clippy::mutable_key_type)]
@@ -14096,6 +14425,11 @@ impl Editor {
                     (telemetry, is_via_ssh)
                 };
                 refresh_linked_ranges(self, window, cx);
+                semantic_tokens::refresh_semantic_tokens(self, true, cx);
+                code_lens::refresh_code_lens(self, true, cx);
+                folding_ranges::refresh_folding_ranges(self, true, cx);
+                inline_values::refresh_inline_values(self, true, cx);
+                document_colors::refresh_document_colors(self, true, cx);
                 telemetry.log_edit_event("editor", is_via_ssh);
             }
             multi_buffer::Event::ExcerptsAdded {
@@ -14104,6 +14438,7 @@ impl Editor {
                 excerpts,
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                self.refresh_conflicts(window, cx);
                 let buffer_id = buffer.read(cx).remote_id();
                 if self.buffer.read(cx).diff_for(buffer_id).is_none() {
                     if let Some(project) = &self.project {
@@ -14122,12 +14457,27 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                semantic_tokens::refresh_semantic_tokens(self, false, cx);
+                code_lens::update_code_lens_blocks(self, cx);
+                code_lens::refresh_code_lens(self, false, cx);
+                folding_ranges::update_folding_creases(self, cx);
+                folding_ranges::refresh_folding_ranges(self, false, cx);
+                inline_values::update_inline_value_inlays(self, cx);
+                inline_values::refresh_inline_values(self, false, cx);
+                document_colors::update_color_swatches(self, cx);
+                document_colors::refresh_document_colors(self, false, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_conflicts(window, cx);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                semantic_tokens::highlight_semantic_tokens(self, cx);
+                code_lens::update_code_lens_blocks(self, cx);
+                folding_ranges::update_folding_creases(self, cx);
+                inline_values::update_inline_value_inlays(self, cx);
+                document_colors::update_color_swatches(self, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -14135,10 +14485,20 @@ impl Editor {
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                semantic_tokens::refresh_semantic_tokens(self, false, cx);
+                code_lens::update_code_lens_blocks(self, cx);
+                code_lens::refresh_code_lens(self, false, cx);
+                folding_ranges::update_folding_creases(self, cx);
+                folding_ranges::refresh_folding_ranges(self, false, cx);
+                inline_values::update_inline_value_inlays(self, cx);
+                inline_values::refresh_inline_values(self, false, cx);
+                document_colors::update_color_swatches(self, cx);
+                document_colors::refresh_document_colors(self, false, cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                folding_ranges::buffer_reparsed(self, *buffer_id, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
             }
@@ -14147,6 +14507,11 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                semantic_tokens::refresh_semantic_tokens(self, false, cx);
+                code_lens::refresh_code_lens(self, false, cx);
+                folding_ranges::refresh_folding_ranges(self, false, cx);
+                inline_values::refresh_inline_values(self, false, cx);
+                document_colors::refresh_document_colors(self, false, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -14190,6 +14555,11 @@ impl Editor {
             )),
             cx,
         );
+        semantic_tokens::highlight_semantic_tokens(self, cx);
+        code_lens::refresh_code_lens(self, false, cx);
+        folding_ranges::refresh_folding_ranges(self, false, cx);
+        inline_values::refresh_inline_values(self, false, cx);
+        document_colors::refresh_document_colors(self, false, cx);
 
         let old_cursor_shape = self.cursor_shape;
 
@@ -15232,6 +15602,71 @@ pub trait SemanticsProvider {
 
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
 
+    fn semantic_tokens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<SemanticTokens>>>>;
+
+    fn code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<CodeLens>>>>;
+
+    fn resolve_code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        lens: CodeLens,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<CodeLens>>>;
+
+    fn apply_code_lens(
+        &self,
+        buffer: Entity<Buffer>,
+        lens: CodeLens,
+        cx: &mut App,
+    ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Range<text::Anchor>>>>>;
+
+    fn inline_values(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<InlineValue>>>>;
+
+    fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Vec<DocumentColor>>>;
+
+    fn color_presentations(
+        &self,
+        buffer: &Entity<Buffer>,
+        color: DocumentColor,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<ColorPresentation>>>>;
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Range<text::Anchor>>>>>;
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -15261,6 +15696,14 @@ pub trait SemanticsProvider {
         new_name: String,
         cx: &mut App,
     ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    fn rename_edits(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        new_name: String,
+        cx: &mut App,
+    ) -> Option<Task<Result<WorkspaceEditPreview>>>;
 }
 
 pub trait CompletionProvider {
@@ -15656,6 +16099,97 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn semantic_tokens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<SemanticTokens>>>> {
+        Some(self.update(cx, |project, cx| project.semantic_tokens(buffer, cx)))
+    }
+
+    fn code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<CodeLens>>>> {
+        Some(self.update(cx, |project, cx| project.code_lens(buffer, cx)))
+    }
+
+    fn resolve_code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        lens: CodeLens,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<CodeLens>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_code_lens(buffer, lens, cx)
+        }))
+    }
+
+    fn apply_code_lens(
+        &self,
+        buffer: Entity<Buffer>,
+        lens: CodeLens,
+        cx: &mut App,
+    ) -> Option<Task<Result<ProjectTransaction>>> {
+        Some(self.update(cx, |project, cx| project.apply_code_lens(buffer, lens, cx)))
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Range<text::Anchor>>>>> {
+        Some(self.update(cx, |project, cx| project.folding_ranges(buffer, cx)))
+    }
+
+    fn inline_values(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<InlineValue>>>> {
+        Some(self.update(cx, |project, cx| project.inline_values(buffer, range, cx)))
+    }
+
+    fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Vec<DocumentColor>>> {
+        Some(self.update(cx, |project, cx| project.document_colors(buffer, cx)))
+    }
+
+    fn color_presentations(
+        &self,
+        buffer: &Entity<Buffer>,
+        color: DocumentColor,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<ColorPresentation>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.color_presentations(buffer, color, cx)
+        }))
+    }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Range<text::Anchor>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        }))
+    }
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -15699,6 +16233,18 @@ impl SemanticsProvider for Entity<Project> {
             project.perform_rename(buffer.clone(), position, new_name, cx)
         }))
     }
+
+    fn rename_edits(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        new_name: String,
+        cx: &mut App,
+    ) -> Option<Task<Result<WorkspaceEditPreview>>> {
+        Some(self.update(cx, |project, cx| {
+            project.rename_edits(buffer.clone(), position, new_name, cx)
+        }))
+    }
 }
 
 fn inlay_hint_settings(
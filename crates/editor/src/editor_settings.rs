@@ -12,8 +12,13 @@ pub struct EditorSettings {
     pub lsp_highlight_debounce: u64,
     pub hover_popover_enabled: bool,
     pub hover_popover_delay: u64,
+    pub code_lens: bool,
+    pub inline_values: bool,
+    pub document_colors: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
+    pub sticky_scroll: StickyScroll,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
@@ -109,6 +114,20 @@ pub struct Scrollbar {
     pub axes: ScrollbarAxes,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+    pub side: MinimapSide,
+    pub width: f32,
+    pub render: MinimapRender,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_depth: usize,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
@@ -134,6 +153,43 @@ pub enum ShowScrollbar {
     Never,
 }
 
+/// When to show the minimap in the editor.
+///
+/// Default: never
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Always show the minimap, next to the text.
+    Always,
+    /// Show the minimap while the mouse is over its column next to the text, which stays
+    /// empty otherwise.
+    Hover,
+    /// Never show the minimap.
+    Never,
+}
+
+/// Which side of the editor to show the minimap on.
+///
+/// Default: right
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimapSide {
+    Left,
+    Right,
+}
+
+/// How to draw the lines in the minimap.
+///
+/// Default: characters
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MinimapRender {
+    /// Draw the text itself, scaled down.
+    Characters,
+    /// Draw a block for each word.
+    Blocks,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -285,10 +341,29 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 350
     pub hover_popover_delay: Option<u64>,
+    /// Whether to show the code lenses provided by language servers
+    /// above the lines they refer to.
+    ///
+    /// Default: true
+    pub code_lens: Option<bool>,
+    /// Whether to show values next to the lines they belong to, such as those
+    /// printed by tasks and the ones language servers provide.
+    ///
+    /// Default: true
+    pub inline_values: Option<bool>,
+    /// Whether to show a swatch before the colors language servers find,
+    /// which opens a color picker when clicked.
+    ///
+    /// Default: true
+    pub document_colors: Option<bool>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -434,6 +509,41 @@ pub struct ScrollbarAxesContent {
     vertical: Option<bool>,
 }
 
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: never
+    pub show: Option<ShowMinimap>,
+    /// Which side of the editor to show the minimap on.
+    ///
+    /// Default: right
+    pub side: Option<MinimapSide>,
+    /// The width of the minimap, in pixels.
+    ///
+    /// Default: 120
+    pub width: Option<f32>,
+    /// Whether to draw the text in the minimap, or a block for each word.
+    ///
+    /// Default: characters
+    pub render: Option<MinimapRender>,
+}
+
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the lines starting the scopes that enclose the top of the
+    /// viewport, such as functions and impl blocks, to the top of the editor.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The maximum number of enclosing scopes to pin.
+    ///
+    /// Default: 5
+    pub max_depth: Option<usize>,
+}
+
 /// Gutter related settings
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct GutterContent {
@@ -27,11 +27,11 @@ use language_settings::{Formatter, FormatterList, IndentGuideSettings};
 use multi_buffer::IndentGuide;
 use parking_lot::Mutex;
 use pretty_assertions::{assert_eq, assert_ne};
-use project::FakeFs;
 use project::{
     lsp_command::SIGNATURE_HELP_HIGHLIGHT_CURRENT,
     project_settings::{LspSettings, ProjectSettings},
 };
+use project::{FakeFs, ProjectPath};
 use serde_json::{self, json};
 use std::{cell::RefCell, future::Future, rc::Rc, time::Instant};
 use std::{
@@ -15832,6 +15832,323 @@ async fn test_rename_without_prepare(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_toggle_breakpoint(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), "one\ntwo\nthree\n".into())
+        .await;
+    let project = Project::test(fs, [path!("/file.rs").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), multi_buffer, window, cx)
+    });
+    let breakpoint_rows = |cx: &mut VisualTestContext| {
+        project.read_with(cx, |project, cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            project
+                .dap_store()
+                .read(cx)
+                .breakpoint_rows(snapshot.remote_id(), &snapshot)
+        })
+    };
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(1, 2)..Point::new(1, 2)])
+        });
+        editor.toggle_breakpoint(&ToggleBreakpoint, window, cx);
+    });
+    assert_eq!(breakpoint_rows(cx), vec![1]);
+
+    // The breakpoint follows its line when lines are inserted above it.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", window, cx);
+    });
+    assert_eq!(breakpoint_rows(cx), vec![2]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(2, 3)..Point::new(2, 3)])
+        });
+        editor.toggle_breakpoint(&ToggleBreakpoint, window, cx);
+    });
+    assert!(breakpoint_rows(cx).is_empty());
+}
+
+#[gpui::test]
+async fn test_toggle_bookmark(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), "one\ntwo\nthree\n".into())
+        .await;
+    let project = Project::test(fs, [path!("/file.rs").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), multi_buffer, window, cx)
+    });
+    let bookmark_rows = |cx: &mut VisualTestContext| {
+        project.read_with(cx, |project, cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            project
+                .bookmark_store()
+                .read(cx)
+                .bookmark_rows(snapshot.remote_id(), &snapshot)
+        })
+    };
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(1, 2)..Point::new(1, 2)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), vec![(1, None)]);
+
+    // The bookmark follows its line when lines are inserted above it.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), vec![(2, None)]);
+
+    // Labeling a bookmarked line replaces its bookmark.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(2, 3)..Point::new(2, 3)])
+        });
+        assert_eq!(editor.bookmark_at_cursor(cx), Some(None));
+        editor.set_bookmark_at_cursor(Some("Two".into()), cx);
+        assert_eq!(editor.bookmark_at_cursor(cx), Some(Some("Two".into())));
+    });
+    assert_eq!(bookmark_rows(cx), vec![(2, Some("Two".into()))]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_bookmark(&ToggleBookmark, window, cx);
+        assert_eq!(editor.bookmark_at_cursor(cx), None);
+    });
+    assert!(bookmark_rows(cx).is_empty());
+}
+
+#[gpui::test]
+async fn test_go_to_bookmark_across_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "one.rs": "a\nb\nc\n",
+            "two.rs": "d\ne\nf\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+
+    let mut buffers = Vec::new();
+    for path in [path!("/a/one.rs"), path!("/a/two.rs")] {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffers.push(buffer);
+    }
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store().clone());
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        for (buffer, row) in [(&buffers[1], 1), (&buffers[0], 2), (&buffers[0], 0)] {
+            let position = buffer.read(cx).anchor_before(Point::new(row, 0));
+            bookmark_store.set_bookmark(buffer.clone(), position, None, cx);
+        }
+    });
+
+    let editor = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_path((worktree_id, "one.rs"), None, true, window, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+        });
+    });
+
+    let active_location = |cx: &mut VisualTestContext| {
+        workspace
+            .update(cx, |workspace, _, cx| {
+                let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+                let editor = editor.read(cx);
+                let (_, buffer, _) = editor.active_excerpt(cx).unwrap();
+                let path = buffer.read(cx).file().unwrap().path().to_string_lossy();
+                let row = editor.selections.newest::<Point>(cx).head().row;
+                (path.to_string(), row)
+            })
+            .unwrap()
+    };
+
+    cx.dispatch_action(GoToNextBookmark);
+    cx.run_until_parked();
+    assert_eq!(active_location(cx), ("one.rs".to_string(), 2));
+    cx.dispatch_action(GoToNextBookmark);
+    cx.run_until_parked();
+    assert_eq!(active_location(cx), ("two.rs".to_string(), 1));
+    // Going past the last bookmark wraps around to the first one.
+    cx.dispatch_action(GoToNextBookmark);
+    cx.run_until_parked();
+    assert_eq!(active_location(cx), ("one.rs".to_string(), 0));
+    cx.dispatch_action(GoToPreviousBookmark);
+    cx.run_until_parked();
+    assert_eq!(active_location(cx), ("two.rs".to_string(), 1));
+    cx.dispatch_action(GoToPreviousBookmark);
+    cx.run_until_parked();
+    assert_eq!(active_location(cx), ("one.rs".to_string(), 2));
+}
+
+#[gpui::test]
+async fn test_bookmarks_restored_after_restart(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let app_state = cx.update(workspace::AppState::test);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(path!("/a"), json!({ "one.rs": "a\nb\nc\n" }))
+        .await;
+    let abs_path = std::path::PathBuf::from(path!("/a/one.rs"));
+    let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+    async fn open_workspace(
+        app_state: Arc<workspace::AppState>,
+        workspace_id: workspace::WorkspaceId,
+        cx: &mut gpui::TestAppContext,
+    ) -> (
+        Entity<Project>,
+        gpui::WindowHandle<Workspace>,
+        ProjectPath,
+        VisualTestContext,
+    ) {
+        let project = Project::test(app_state.fs.clone(), [path!("/a").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| {
+            Workspace::new(Some(workspace_id), project.clone(), app_state, window, cx)
+        });
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let cx = VisualTestContext::from_window(*window, cx);
+        (
+            project,
+            window,
+            ProjectPath::from((worktree_id, "one.rs")),
+            cx,
+        )
+    }
+    let open_editor =
+        |window: gpui::WindowHandle<Workspace>, path: ProjectPath, cx: &mut VisualTestContext| {
+            window
+                .update(cx, |workspace, window, cx| {
+                    workspace.open_path(path, None, true, window, cx)
+                })
+                .unwrap()
+        };
+
+    let (project, window, path, mut cx) = open_workspace(app_state.clone(), workspace_id, cx).await;
+    let editor = open_editor(window, path, &mut cx)
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    editor.update_in(&mut cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+        });
+        editor.toggle_bookmark(&ToggleBookmark, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        persistence::DB.get_bookmarks(workspace_id).unwrap(),
+        vec![(abs_path.clone(), 1, None)]
+    );
+
+    // Rows moved by edits are saved once the edits stop.
+    editor.update_in(&mut cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("zero\n", window, cx);
+    });
+    cx.executor()
+        .advance_clock(workspace::SERIALIZATION_THROTTLE_TIME);
+    cx.run_until_parked();
+    assert_eq!(
+        persistence::DB.get_bookmarks(workspace_id).unwrap(),
+        vec![(abs_path.clone(), 2, None)]
+    );
+
+    let buffer = editor.read_with(&cx, |editor, cx| {
+        editor.buffer().read(cx).as_singleton().unwrap()
+    });
+    project
+        .update(&mut cx, |project, cx| project.save_buffer(buffer, cx))
+        .await
+        .unwrap();
+    cx.update(|window, _| window.remove_window());
+    cx.run_until_parked();
+    drop((project, editor));
+
+    // Restoring the bookmarks doesn't open their files.
+    let (project, window, path, mut cx) = open_workspace(app_state, workspace_id, &mut cx).await;
+    cx.run_until_parked();
+    cx.update(|_, cx| {
+        assert!(project.read(cx).get_open_buffer(&path, cx).is_none());
+        let bookmarks = bookmarks::sorted_bookmarks(&project, cx);
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!((&bookmarks[0].path, bookmarks[0].row), (&path, 2));
+    });
+
+    // Opening the file anchors its bookmarks, so they keep following edits.
+    let editor = open_editor(window, path, &mut cx)
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    editor.update_in(&mut cx, |editor, window, cx| {
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.handle_input("start\n", window, cx);
+        editor.change_selections(None, window, cx, |s| {
+            s.select_ranges([Point::new(3, 0)..Point::new(3, 0)])
+        });
+        assert_eq!(editor.bookmark_at_cursor(cx), Some(None));
+    });
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(DisplayRow(row as u32), column as u32);
     point..point
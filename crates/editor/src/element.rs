@@ -4,9 +4,11 @@ use crate::{
     display_map::{
         Block, BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint,
     },
+    document_colors,
     editor_settings::{
-        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ScrollBeyondLastLine,
-        ScrollbarDiagnostics, ShowScrollbar,
+        CurrentLineHighlight, DoubleClickInMultibuffer, Minimap, MinimapRender, MinimapSide,
+        MultiCursorModifier, ScrollBeyondLastLine, ScrollbarDiagnostics, ShowMinimap,
+        ShowScrollbar,
     },
     git::blame::GitBlame,
     hover_popover::{
@@ -14,8 +16,8 @@ use crate::{
     },
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition, MouseContextMenu},
-    scroll::{axis_pair, scroll_amount::ScrollAmount, AxisPair},
-    BlockId, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
+    scroll::{axis_pair, scroll_amount::ScrollAmount, Autoscroll, AxisPair},
+    ApplyDiffHunk, BlockId, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
     DocumentHighlightRead, DocumentHighlightWrite, EditDisplayMode, Editor, EditorMode,
     EditorSettings, EditorSnapshot, EditorStyle, ExpandExcerpts, FocusedBlock, GoToHunk,
     GutterDimensions, HalfPageDown, HalfPageUp, HandleInput, HoveredCursor, InlineCompletion,
@@ -34,11 +36,11 @@ use gpui::{
     point, px, quad, relative, size, solid_color, svg, transparent_black, Action, AnyElement, App,
     AvailableSpace, Axis, Bounds, ClickEvent, ClipboardItem, ContentMask, Context, Corner, Corners,
     CursorStyle, DispatchPhase, Edges, Element, ElementInputHandler, Entity, Focusable, FontId,
-    GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, Keystroke, Length,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
-    ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size,
-    StatefulInteractiveElement, Style, Styled, Subscription, TextRun, TextStyleRefinement,
-    WeakEntity, Window,
+    GlobalElementId, HighlightStyle, Hitbox, Hsla, InteractiveElement, IntoElement, Keystroke,
+    Length, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine,
+    SharedString, Size, StatefulInteractiveElement, Style, Styled, StyledText, Subscription,
+    TextRun, TextStyleRefinement, WeakEntity, Window,
 };
 use itertools::Itertools;
 use language::{
@@ -51,7 +53,7 @@ use language::{
 use lsp::DiagnosticSeverity;
 use multi_buffer::{
     Anchor, ExcerptId, ExcerptInfo, ExpandExcerptDirection, MultiBufferPoint, MultiBufferRow,
-    RowInfo, ToOffset,
+    MultiBufferSnapshot, RowInfo, ToOffset,
 };
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use settings::Settings;
@@ -404,6 +406,8 @@ impl EditorElement {
         register_action(editor, window, Editor::toggle_line_numbers);
         register_action(editor, window, Editor::toggle_relative_line_numbers);
         register_action(editor, window, Editor::toggle_indent_guides);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::toggle_breakpoint);
         register_action(editor, window, Editor::toggle_inlay_hints);
         register_action(editor, window, Editor::toggle_inline_completions);
         register_action(editor, window, hover_popover::hover);
@@ -473,6 +477,13 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(editor, window, |editor, action, window, cx| {
+            if let Some(task) = editor.preview_rename(action, window, cx) {
+                task.detach_and_notify_err(window, cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(editor, window, |editor, action, window, cx| {
             if let Some(task) = editor.find_all_references(action, window, cx) {
                 task.detach_and_log_err(cx);
@@ -500,6 +511,7 @@ impl EditorElement {
         register_action(editor, window, Editor::open_active_item_in_terminal);
         register_action(editor, window, Editor::reload_file);
         register_action(editor, window, Editor::spawn_nearest_task);
+        register_action(editor, window, Editor::clear_inline_values);
         register_action(editor, window, Editor::insert_uuid_v4);
         register_action(editor, window, Editor::insert_uuid_v7);
         register_action(editor, window, Editor::open_selections_in_multibuffer);
@@ -595,6 +607,14 @@ impl EditorElement {
         }
 
         let point_for_position = position_map.point_for_position(event.position);
+        if click_count == 1 && modifiers == Modifiers::default() && text_hitbox.is_hovered(window) {
+            if let Some(swatch) =
+                document_colors::color_swatch_at(editor, &position_map.snapshot, point_for_position)
+            {
+                editor.open_color_picker(swatch, event.position, window, cx);
+                return;
+            }
+        }
         let position = point_for_position.previous_valid;
         if modifiers.shift && modifiers.alt {
             editor.select(
@@ -848,6 +868,12 @@ impl EditorElement {
         let modifiers = event.modifiers;
         let gutter_hovered = gutter_hitbox.is_hovered(window);
         editor.set_gutter_hovered(gutter_hovered, cx);
+        let breakpoint_indicator_row = gutter_hovered.then(|| {
+            let y =
+                event.position.y - gutter_hitbox.origin.y + position_map.scroll_pixel_position.y;
+            DisplayRow((y / position_map.line_height) as u32)
+        });
+        editor.set_gutter_breakpoint_indicator(breakpoint_indicator_row, cx);
 
         // Don't trigger hover popover if mouse is hovering over context menu
         if text_hitbox.is_hovered(window) {
@@ -1451,6 +1477,292 @@ impl EditorElement {
         axis_pair(horizontal_scrollbar, vertical_scrollbar)
     }
 
+    /// The minimap settings, if this editor can show a minimap at all.
+    fn minimap_settings(&self, cx: &App) -> Option<Minimap> {
+        let editor = self.editor.read(cx);
+        let settings = EditorSettings::get_global(cx).minimap;
+        (editor.mode == EditorMode::Full
+            && editor.show_minimap
+            && settings.show != ShowMinimap::Never)
+            .then_some(settings)
+    }
+
+    /// The bounds of the column reserved for the minimap next to the text, along with whether
+    /// the minimap is shown in it.
+    fn minimap_bounds(
+        &self,
+        bounds: Bounds<Pixels>,
+        window: &Window,
+        cx: &App,
+    ) -> Option<(Bounds<Pixels>, bool)> {
+        let settings = self.minimap_settings(cx)?;
+        let width = px(settings.width.max(0.)).min(bounds.size.width / 2.);
+        let origin = match settings.side {
+            MinimapSide::Left => bounds.origin,
+            MinimapSide::Right => point(bounds.right() - width, bounds.top()),
+        };
+        let minimap_bounds = Bounds {
+            origin,
+            size: size(width, bounds.size.height),
+        };
+
+        let is_shown = match settings.show {
+            ShowMinimap::Always => true,
+            ShowMinimap::Hover => {
+                self.editor.read(cx).scroll_manager.is_dragging_minimap()
+                    || minimap_bounds.contains(&window.mouse_position())
+            }
+            ShowMinimap::Never => return None,
+        };
+        Some((minimap_bounds, is_shown))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_minimap(
+        &self,
+        bounds: Bounds<Pixels>,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        visible_row_count: f32,
+        line_height: Pixels,
+        font_size: Pixels,
+        em_width: Pixels,
+        window: &mut Window,
+        cx: &App,
+    ) -> MinimapLayout {
+        let settings = EditorSettings::get_global(cx).minimap;
+        let minimap_line_height = line_height * MinimapLayout::SCALE;
+        let minimap_em_width = em_width * MinimapLayout::SCALE;
+
+        // When the buffer doesn't fit in the minimap, the minimap scrolls along with the
+        // editor, so that both reach the end of the buffer together.
+        let row_count = snapshot.max_point().row().next_row().as_f32();
+        let minimap_row_count = bounds.size.height / minimap_line_height;
+        let max_scroll_top = (row_count - visible_row_count).max(0.);
+        let minimap_max_scroll_top = (row_count - minimap_row_count).max(0.);
+        let scroll_ratio = if max_scroll_top > 0. {
+            (minimap_max_scroll_top / max_scroll_top).min(1.)
+        } else {
+            0.
+        };
+        let scroll_top = (scroll_position.y * scroll_ratio).min(minimap_max_scroll_top);
+        let row_top = |row: f32| bounds.top() + minimap_line_height * (row - scroll_top);
+
+        let start_row = DisplayRow(scroll_top.floor() as u32);
+        let end_row =
+            DisplayRow(((scroll_top + minimap_row_count).ceil() as u32).min(row_count as u32));
+        let last_row = end_row.previous_row();
+
+        let display_snapshot = &snapshot.display_snapshot;
+        let buffer_snapshot = &snapshot.buffer_snapshot;
+        let start = DisplayPoint::new(start_row, 0).to_point(display_snapshot);
+        let end = if end_row > snapshot.max_point().row() {
+            buffer_snapshot.max_point()
+        } else {
+            DisplayPoint::new(end_row, 0).to_point(display_snapshot)
+        };
+
+        let range_quads = |range: Range<DisplayPoint>, color: Hsla| {
+            let first_row = range.start.row().max(start_row);
+            let last_row = range.end.row().min(last_row);
+            (first_row.0..=last_row.0).map(move |row| {
+                let row = DisplayRow(row);
+                let start_column = if row == range.start.row() {
+                    range.start.column()
+                } else {
+                    0
+                };
+                let end_column = if row == range.end.row() {
+                    range.end.column()
+                } else {
+                    snapshot.line_len(row)
+                };
+                let columns = end_column.saturating_sub(start_column).max(1);
+                fill(
+                    Bounds {
+                        origin: point(
+                            bounds.left() + minimap_em_width * start_column as f32,
+                            row_top(row.as_f32()),
+                        ),
+                        size: size(minimap_em_width * columns as f32, minimap_line_height),
+                    },
+                    color,
+                )
+            })
+        };
+
+        let mut markers = Vec::new();
+
+        let theme = cx.theme();
+        let editor = self.editor.read(cx);
+        if let Some((color_fetcher, ranges)) = editor
+            .background_highlights
+            .get(&TypeId::of::<BufferSearchHighlights>())
+        {
+            let color = color_fetcher(theme.colors());
+            let start_anchor = buffer_snapshot.anchor_before(start);
+            let end_anchor = buffer_snapshot.anchor_after(end);
+            let start_ix = ranges
+                .partition_point(|range| range.end.cmp(&start_anchor, buffer_snapshot).is_lt());
+            for range in &ranges[start_ix..] {
+                if range.start.cmp(&end_anchor, buffer_snapshot).is_gt() {
+                    break;
+                }
+                let range = range.start.to_display_point(display_snapshot)
+                    ..range.end.to_display_point(display_snapshot);
+                markers.extend(range_quads(range, color));
+            }
+        }
+
+        // Paint the most severe diagnostics last.
+        let diagnostics = buffer_snapshot
+            .diagnostics_in_range::<Point>(start..end)
+            .sorted_by_key(|diagnostic| cmp::Reverse(diagnostic.diagnostic.severity));
+        for diagnostic in diagnostics {
+            let color = match diagnostic.diagnostic.severity {
+                DiagnosticSeverity::ERROR => theme.status().error,
+                DiagnosticSeverity::WARNING => theme.status().warning,
+                DiagnosticSeverity::INFORMATION => theme.status().info,
+                _ => theme.status().hint,
+            };
+            let range = diagnostic.range.start.to_display_point(display_snapshot)
+                ..diagnostic.range.end.to_display_point(display_snapshot);
+            markers.extend(range_quads(range, color.opacity(0.5)));
+        }
+
+        for hunk in buffer_snapshot.diff_hunks_in_range(start..end) {
+            let hunk_start_row = MultiBufferPoint::new(hunk.row_range.start.0, 0)
+                .to_display_point(display_snapshot)
+                .row();
+            let mut hunk_end_row = MultiBufferPoint::new(hunk.row_range.end.0, 0)
+                .to_display_point(display_snapshot)
+                .row();
+            if hunk_end_row != hunk_start_row {
+                hunk_end_row.0 -= 1;
+            }
+            let color = match hunk.status() {
+                DiffHunkStatus::Added(_) => theme.colors().version_control_added,
+                DiffHunkStatus::Modified(_) => theme.colors().version_control_modified,
+                DiffHunkStatus::Removed(_) => theme.colors().version_control_deleted,
+            };
+            markers.push(fill(
+                Bounds::from_corners(
+                    point(bounds.left(), row_top(hunk_start_row.as_f32())),
+                    point(
+                        bounds.left() + MinimapLayout::GIT_MARKER_WIDTH,
+                        row_top(hunk_end_row.as_f32()) + minimap_line_height,
+                    ),
+                ),
+                color,
+            ));
+        }
+
+        let max_columns = (bounds.size.width / minimap_em_width).ceil() as usize;
+        let rows = minimap_rows(snapshot, start_row..end_row, &self.style, max_columns);
+        let lines = match settings.render {
+            MinimapRender::Characters => {
+                let font = self.style.text.font();
+                let font_size = font_size * MinimapLayout::SCALE;
+                MinimapLines::Characters(
+                    rows.into_iter()
+                        .zip(start_row.0..end_row.0)
+                        .filter_map(|(row, display_row)| {
+                            if row.text.is_empty() {
+                                return None;
+                            }
+                            let runs = row
+                                .runs
+                                .iter()
+                                .map(|(len, color)| TextRun {
+                                    len: *len,
+                                    font: font.clone(),
+                                    color: *color,
+                                    background_color: None,
+                                    underline: None,
+                                    strikethrough: None,
+                                })
+                                .collect::<Vec<_>>();
+                            let line = window
+                                .text_system()
+                                .shape_line(row.text.into(), font_size, &runs)
+                                .log_err()?;
+                            let origin = point(bounds.left(), row_top(display_row as f32));
+                            Some((origin, line))
+                        })
+                        .collect(),
+                )
+            }
+            MinimapRender::Blocks => {
+                // Blocks are a bit shorter than the rows, so that consecutive lines stay apart.
+                let block_height = minimap_line_height * 0.7;
+                let mut blocks = Vec::new();
+                for (row, display_row) in rows.iter().zip(start_row.0..end_row.0) {
+                    let top =
+                        row_top(display_row as f32) + (minimap_line_height - block_height) / 2.;
+                    let mut push_block = |start_column: usize, end_column: usize, color: Hsla| {
+                        blocks.push(fill(
+                            Bounds {
+                                origin: point(
+                                    bounds.left() + minimap_em_width * start_column as f32,
+                                    top,
+                                ),
+                                size: size(
+                                    minimap_em_width * (end_column - start_column) as f32,
+                                    block_height,
+                                ),
+                            },
+                            color.opacity(0.6),
+                        ))
+                    };
+
+                    let mut column = 0;
+                    let mut offset = 0;
+                    let mut block: Option<(usize, Hsla)> = None;
+                    for (len, color) in &row.runs {
+                        for character in row.text[offset..offset + len].chars() {
+                            if character.is_whitespace() {
+                                if let Some((start_column, color)) = block.take() {
+                                    push_block(start_column, column, color);
+                                }
+                            } else if block.map_or(true, |(_, block_color)| block_color != *color) {
+                                if let Some((start_column, color)) = block.take() {
+                                    push_block(start_column, column, color);
+                                }
+                                block = Some((column, *color));
+                            }
+                            column += 1;
+                        }
+                        offset += len;
+                    }
+                    if let Some((start_column, color)) = block {
+                        push_block(start_column, column, color);
+                    }
+                }
+                MinimapLines::Blocks(blocks)
+            }
+        };
+
+        let thumb_bounds = Bounds::from_corners(
+            point(bounds.left(), row_top(scroll_position.y).max(bounds.top())),
+            point(
+                bounds.right(),
+                row_top(scroll_position.y + visible_row_count).min(bounds.bottom()),
+            ),
+        );
+
+        MinimapLayout {
+            hitbox: window.insert_hitbox(bounds, true),
+            side: settings.side,
+            line_height: minimap_line_height,
+            lines,
+            markers,
+            thumb_bounds,
+            scroll_top,
+            visible_row_count,
+            thumb_pixels_per_row: minimap_line_height * (1. - scroll_ratio).max(0.1),
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_crease_toggles(
         &self,
@@ -1951,6 +2263,7 @@ impl EditorElement {
         &self,
         line_height: Pixels,
         range: Range<DisplayRow>,
+        breakpoint_rows: &HashSet<DisplayRow>,
         scroll_pixel_position: gpui::Point<Pixels>,
         gutter_dimensions: &GutterDimensions,
         gutter_hitbox: &Hitbox,
@@ -1993,9 +2306,11 @@ impl EditorElement {
                     let multibuffer_point =
                         multi_buffer::ToPoint::to_point(&tasks.offset.0, &snapshot.buffer_snapshot);
                     let multibuffer_row = MultiBufferRow(multibuffer_point.row);
-                    let buffer_folded = snapshot
+                    let buffer_line = snapshot
                         .buffer_snapshot
-                        .buffer_line_for_row(multibuffer_row)
+                        .buffer_line_for_row(multibuffer_row);
+                    let buffer_folded = buffer_line
+                        .as_ref()
                         .map(|(buffer_snapshot, _)| buffer_snapshot.remote_id())
                         .map(|buffer_id| editor.is_buffer_folded(buffer_id, cx))
                         .unwrap_or(false);
@@ -2016,10 +2331,17 @@ impl EditorElement {
                         }
                     }
                     let display_row = multibuffer_point.to_display_point(snapshot).row();
+                    if breakpoint_rows.contains(&display_row) {
+                        return None;
+                    }
+                    let test_status = buffer_line.and_then(|(buffer_snapshot, range)| {
+                        editor.test_status(buffer_snapshot, range.start.row, cx)
+                    });
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
                         display_row,
+                        test_status,
                         cx,
                     );
 
@@ -2040,6 +2362,117 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_breakpoint_indicators(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        breakpoint_rows: &HashSet<DisplayRow>,
+        bookmark_rows: &HashMap<DisplayRow, Option<SharedString>>,
+        code_actions_row: Option<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        rows_with_hunk_bounds: &HashMap<DisplayRow, Bounds<Pixels>>,
+        snapshot: &EditorSnapshot,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            // Offer to add a breakpoint on the hovered row, unless another indicator is there.
+            let hovered_row = editor.gutter_breakpoint_indicator.filter(|row| {
+                if !range.contains(row)
+                    || breakpoint_rows.contains(row)
+                    || bookmark_rows.contains_key(row)
+                    || code_actions_row == Some(*row)
+                {
+                    return false;
+                }
+                let point = DisplayPoint::new(*row, 0).to_point(snapshot);
+                snapshot
+                    .buffer_snapshot
+                    .buffer_line_for_row(MultiBufferRow(point.row))
+                    .map_or(false, |(buffer, range)| {
+                        !editor
+                            .tasks
+                            .contains_key(&(buffer.remote_id(), range.start.row))
+                    })
+            });
+
+            breakpoint_rows
+                .iter()
+                .map(|row| (*row, true))
+                .chain(hovered_row.map(|row| (row, false)))
+                .map(|(row, has_breakpoint)| {
+                    let button = editor.render_breakpoint_indicator(row, has_breakpoint, cx);
+                    prepaint_gutter_button(
+                        button,
+                        row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        rows_with_hunk_bounds,
+                        window,
+                        cx,
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_bookmark_indicators(
+        &self,
+        line_height: Pixels,
+        bookmark_rows: &HashMap<DisplayRow, Option<SharedString>>,
+        breakpoint_rows: &HashSet<DisplayRow>,
+        show_runnables: bool,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        rows_with_hunk_bounds: &HashMap<DisplayRow, Bounds<Pixels>>,
+        snapshot: &EditorSnapshot,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            bookmark_rows
+                .iter()
+                .filter(|(row, _)| {
+                    // Breakpoints and run indicators take precedence over bookmarks.
+                    if breakpoint_rows.contains(*row) {
+                        return false;
+                    }
+                    let point = DisplayPoint::new(**row, 0).to_point(snapshot);
+                    !show_runnables
+                        || snapshot
+                            .buffer_snapshot
+                            .buffer_line_for_row(MultiBufferRow(point.row))
+                            .map_or(true, |(buffer, range)| {
+                                !editor
+                                    .tasks
+                                    .contains_key(&(buffer.remote_id(), range.start.row))
+                            })
+                })
+                .map(|(row, label)| {
+                    let button = editor.render_bookmark_indicator(*row, label.clone(), cx);
+                    prepaint_gutter_button(
+                        button,
+                        *row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        rows_with_hunk_bounds,
+                        window,
+                        cx,
+                    )
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_code_actions_indicator(
         &self,
@@ -3145,6 +3578,142 @@ impl EditorElement {
         header
     }
 
+    /// Lays out the first line of each scope enclosing the top of the viewport, pinned below
+    /// the `top_rows` taken by the sticky buffer header, if any.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        top_rows: u32,
+        line_height: Pixels,
+        em_width: Pixels,
+        hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        line_number_right: Option<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<StickyScopeLayout> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled || snapshot.mode != EditorMode::Full {
+            return Vec::new();
+        }
+
+        let scopes = self.editor.update(cx, |editor, _| {
+            sticky_scopes(
+                snapshot,
+                scroll_position.y,
+                top_rows,
+                settings.max_depth,
+                &mut editor.sticky_scopes_cache,
+            )
+        });
+        let colors = cx.theme().colors();
+        let (background_color, border_color, line_number_color) = (
+            colors.editor_background,
+            colors.border_variant,
+            colors.editor_line_number,
+        );
+        let text_left = content_origin.x - hitbox.origin.x;
+        let scroll_left = scroll_position.x * em_width;
+        let width = text_hitbox.right() - hitbox.left();
+        let scope_count = scopes.len();
+
+        scopes
+            .into_iter()
+            .enumerate()
+            .map(|(ix, scope)| {
+                let (text, highlights) = match scope.header_row {
+                    Some(header_row) => highlighted_line(snapshot, header_row, &self.style),
+                    None => highlighted_buffer_line(
+                        snapshot,
+                        scope.anchor.excerpt_id,
+                        scope.buffer_row,
+                        &self.style,
+                    ),
+                };
+                let styled_text =
+                    StyledText::new(text.clone()).with_highlights(&self.style.text, highlights);
+                let line_number = line_number_right.map(|right| {
+                    div()
+                        .absolute()
+                        .left_0()
+                        .w(right)
+                        .h_full()
+                        .flex()
+                        .justify_end()
+                        .text_color(line_number_color)
+                        .child((scope.buffer_row + 1).to_string())
+                });
+
+                // Jumping puts the header where its pinned line was, so that the scopes
+                // enclosing it stay pinned above it.
+                let lines_from_top = top_rows as usize + ix;
+                let anchor = scope.anchor;
+                let editor = self.editor.clone();
+                let mut element = div()
+                    .id(("sticky-scope", ix))
+                    .relative()
+                    .w(width)
+                    .h(line_height)
+                    .overflow_hidden()
+                    .bg(background_color)
+                    .when(ix + 1 == scope_count, |line| {
+                        line.border_b_1().border_color(border_color)
+                    })
+                    .font(self.style.text.font())
+                    .text_size(self.style.text.font_size)
+                    .line_height(line_height)
+                    .cursor_pointer()
+                    .occlude()
+                    .on_click(move |_, window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.change_selections(
+                                Some(Autoscroll::top_relative(lines_from_top)),
+                                window,
+                                cx,
+                                |selections| selections.select_anchor_ranges([anchor..anchor]),
+                            );
+                        });
+                    })
+                    .children(line_number)
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left(text_left)
+                            .right_0()
+                            .h_full()
+                            .overflow_hidden()
+                            .child(div().absolute().left(-scroll_left).child(styled_text)),
+                    )
+                    .into_any_element();
+
+                // Each line stays pinned until the end of its scope scrolls up to it, and is
+                // then pushed up along with the scope.
+                let top =
+                    ((top_rows + ix as u32) as f32).min(scope.end_row.as_f32() - scroll_position.y);
+                let origin = hitbox.origin + point(Pixels::ZERO, line_height * top);
+                element.prepaint_as_root(
+                    origin,
+                    size(
+                        AvailableSpace::Definite(width),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+
+                StickyScopeLayout {
+                    buffer_row: scope.buffer_row,
+                    text,
+                    element,
+                }
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_cursor_popovers(
         &self,
@@ -4474,7 +5043,8 @@ impl EditorElement {
                     };
 
                     if x < layout.position_map.text_hitbox.origin.x
-                        || (show_scrollbars && x > self.scrollbar_left(&layout.hitbox.bounds))
+                        || (show_scrollbars
+                            && x > self.scrollbar_left(&layout.position_map.text_hitbox.bounds))
                     {
                         continue;
                     }
@@ -4815,6 +5385,14 @@ impl EditorElement {
                 test_indicator.paint(window, cx);
             }
 
+            for breakpoint_indicator in layout.breakpoint_indicators.iter_mut() {
+                breakpoint_indicator.paint(window, cx);
+            }
+
+            for bookmark_indicator in layout.bookmark_indicators.iter_mut() {
+                bookmark_indicator.paint(window, cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(window, cx);
             }
@@ -5547,34 +6125,189 @@ impl EditorElement {
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn paint_highlighted_range(
-        &self,
-        range: Range<DisplayPoint>,
-        color: Hsla,
-        corner_radius: Pixels,
-        line_end_overshoot: Pixels,
+    fn paint_minimap(
+        &mut self,
+        bounds: Bounds<Pixels>,
         layout: &EditorLayout,
         window: &mut Window,
+        cx: &mut App,
     ) {
-        let start_row = layout.visible_display_row_range.start;
-        let end_row = layout.visible_display_row_range.end;
-        if range.start != range.end {
-            let row_range = if range.end.column() == 0 {
-                cmp::max(range.start.row(), start_row)..cmp::min(range.end.row(), end_row)
-            } else {
-                cmp::max(range.start.row(), start_row)
-                    ..cmp::min(range.end.row().next_row(), end_row)
-            };
+        // A minimap shown on hover appears and disappears as the mouse enters and leaves
+        // its column.
+        let is_hover = self
+            .minimap_settings(cx)
+            .map_or(false, |settings| settings.show == ShowMinimap::Hover);
+        if let Some((minimap_bounds, _)) =
+            self.minimap_bounds(bounds, window, cx).filter(|_| is_hover)
+        {
+            let is_shown = layout.minimap.is_some();
+            window.on_mouse_event(move |event: &MouseMoveEvent, phase, window, _| {
+                if phase == DispatchPhase::Bubble
+                    && minimap_bounds.contains(&event.position) != is_shown
+                {
+                    window.refresh();
+                }
+            });
+        }
 
-            let highlighted_range = HighlightedRange {
-                color,
-                line_height: layout.position_map.line_height,
-                corner_radius,
-                start_y: layout.content_origin.y
-                    + row_range.start.as_f32() * layout.position_map.line_height
-                    - layout.position_map.scroll_pixel_position.y,
-                lines: row_range
-                    .iter_rows()
+        let Some(minimap) = layout.minimap.as_ref() else {
+            return;
+        };
+        let hitbox = minimap.hitbox.clone();
+        let colors = cx.theme().colors();
+        let background = colors.editor_background;
+        let border_color = colors.border_variant;
+        let thumb_color = colors.scrollbar_thumb_background;
+        let border_widths = match minimap.side {
+            MinimapSide::Left => Edges {
+                right: ScrollbarLayout::BORDER_WIDTH,
+                ..Default::default()
+            },
+            MinimapSide::Right => Edges {
+                left: ScrollbarLayout::BORDER_WIDTH,
+                ..Default::default()
+            },
+        };
+
+        window.paint_layer(hitbox.bounds, |window| {
+            window.with_content_mask(
+                Some(ContentMask {
+                    bounds: hitbox.bounds,
+                }),
+                |window| {
+                    window.paint_quad(quad(
+                        hitbox.bounds,
+                        Corners::default(),
+                        background,
+                        border_widths,
+                        border_color,
+                    ));
+                    for marker in &minimap.markers {
+                        window.paint_quad(marker.clone());
+                    }
+                    match &minimap.lines {
+                        MinimapLines::Characters(lines) => {
+                            for (origin, line) in lines {
+                                line.paint(*origin, minimap.line_height, window, cx)
+                                    .log_err();
+                            }
+                        }
+                        MinimapLines::Blocks(blocks) => {
+                            for block in blocks {
+                                window.paint_quad(block.clone());
+                            }
+                        }
+                    }
+                    window.paint_quad(fill(minimap.thumb_bounds, thumb_color));
+                },
+            )
+        });
+
+        window.set_cursor_style(CursorStyle::Arrow, &hitbox);
+
+        let thumb_bounds = minimap.thumb_bounds;
+        let scroll_top = minimap.scroll_top;
+        let line_height = minimap.line_height;
+        let visible_row_count = minimap.visible_row_count;
+        let thumb_pixels_per_row = minimap.thumb_pixels_per_row;
+
+        window.on_mouse_event({
+            let editor = self.editor.clone();
+            let mut mouse_position = window.mouse_position();
+            move |event: &MouseMoveEvent, phase, window, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if editor.scroll_manager.is_dragging_minimap() {
+                        if event.pressed_button == Some(MouseButton::Left) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y +=
+                                (event.position.y - mouse_position.y) / thumb_pixels_per_row;
+                            position.y = position.y.max(0.);
+                            editor.set_scroll_position(position, window, cx);
+                        } else {
+                            editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        }
+                    }
+                    mouse_position = event.position;
+                })
+            }
+        });
+
+        if self.editor.read(cx).scroll_manager.is_dragging_minimap() {
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, _, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            window.on_mouse_event({
+                let editor = self.editor.clone();
+                move |event: &MouseDownEvent, phase, window, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(window)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(true, cx);
+
+                        // Clicking outside of the thumb centers the editor on the clicked row.
+                        let y = event.position.y;
+                        if y < thumb_bounds.top() || thumb_bounds.bottom() < y {
+                            let row = scroll_top + (y - hitbox.top()) / line_height;
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (row - visible_row_count / 2.).max(0.);
+                            editor.set_scroll_position(position, window, cx);
+                        }
+
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
+    fn paint_highlighted_range(
+        &self,
+        range: Range<DisplayPoint>,
+        color: Hsla,
+        corner_radius: Pixels,
+        line_end_overshoot: Pixels,
+        layout: &EditorLayout,
+        window: &mut Window,
+    ) {
+        let start_row = layout.visible_display_row_range.start;
+        let end_row = layout.visible_display_row_range.end;
+        if range.start != range.end {
+            let row_range = if range.end.column() == 0 {
+                cmp::max(range.start.row(), start_row)..cmp::min(range.end.row(), end_row)
+            } else {
+                cmp::max(range.start.row(), start_row)
+                    ..cmp::min(range.end.row().next_row(), end_row)
+            };
+
+            let highlighted_range = HighlightedRange {
+                color,
+                line_height: layout.position_map.line_height,
+                corner_radius,
+                start_y: layout.content_origin.y
+                    + row_range.start.as_f32() * layout.position_map.line_height
+                    - layout.position_map.scroll_pixel_position.y,
+                lines: row_range
+                    .iter_rows()
                     .map(|row| {
                         let line_layout =
                             &layout.position_map.line_layouts[row.minus(start_row) as usize];
@@ -5661,6 +6394,10 @@ impl EditorElement {
             let position_map = layout.position_map.clone();
             let editor = self.editor.clone();
             let hitbox = layout.hitbox.clone();
+            let minimap_hitbox = layout
+                .minimap
+                .as_ref()
+                .map(|minimap| minimap.hitbox.clone());
             let mut delta = ScrollDelta::default();
 
             // Set a minimum scroll_sensitivity of 0.01 to make sure the user doesn't
@@ -5668,7 +6405,11 @@ impl EditorElement {
             let scroll_sensitivity = EditorSettings::get_global(cx).scroll_sensitivity.max(0.01);
 
             move |event: &ScrollWheelEvent, phase, window, cx| {
-                if phase == DispatchPhase::Bubble && hitbox.is_hovered(window) {
+                let is_hovered = hitbox.is_hovered(window)
+                    || minimap_hitbox
+                        .as_ref()
+                        .map_or(false, |hitbox| hitbox.is_hovered(window));
+                if phase == DispatchPhase::Bubble && is_hovered {
                     delta = delta.coalesce(event.delta);
                     editor.update(cx, |editor, cx| {
                         let position_map: &PositionMap = &position_map;
@@ -6860,6 +7601,21 @@ impl Element for EditorElement {
 
                     let letter_size = size(em_width, line_height);
 
+                    let minimap_bounds = self.minimap_bounds(bounds, window, cx);
+                    // The minimap takes its width from the text, even while a minimap shown on
+                    // hover is hidden, so that it never covers the text.
+                    let editor_bounds = match minimap_bounds {
+                        Some((minimap_bounds, _)) => {
+                            let mut editor_bounds = bounds;
+                            editor_bounds.size.width -= minimap_bounds.size.width;
+                            if minimap_bounds.left() == bounds.left() {
+                                editor_bounds.origin.x += minimap_bounds.size.width;
+                            }
+                            editor_bounds
+                        }
+                        None => bounds,
+                    };
+
                     let gutter_dimensions = snapshot
                         .gutter_dimensions(
                             font_id,
@@ -6868,13 +7624,13 @@ impl Element for EditorElement {
                             cx,
                         )
                         .unwrap_or_default();
-                    let text_width = bounds.size.width - gutter_dimensions.width;
+                    let text_width = editor_bounds.size.width - gutter_dimensions.width;
 
                     let editor_width =
                         text_width - gutter_dimensions.margin - em_width - style.scrollbar_width;
 
                     snapshot = self.editor.update(cx, |editor, cx| {
-                        editor.last_bounds = Some(bounds);
+                        editor.last_bounds = Some(editor_bounds);
                         editor.gutter_dimensions = gutter_dimensions;
                         editor.set_visible_line_count(bounds.size.height / line_height, window, cx);
 
@@ -6907,9 +7663,9 @@ impl Element for EditorElement {
                         .map(|(guide, active)| (self.column_pixels(*guide, window, cx), *active))
                         .collect::<SmallVec<[_; 2]>>();
 
-                    let hitbox = window.insert_hitbox(bounds, false);
-                    let gutter_hitbox =
-                        window.insert_hitbox(gutter_bounds(bounds, gutter_dimensions), false);
+                    let hitbox = window.insert_hitbox(editor_bounds, false);
+                    let gutter_hitbox = window
+                        .insert_hitbox(gutter_bounds(editor_bounds, gutter_dimensions), false);
                     let text_hitbox = window.insert_hitbox(
                         Bounds {
                             origin: gutter_hitbox.top_right(),
@@ -6923,7 +7679,7 @@ impl Element for EditorElement {
                         text_hitbox.origin + point(gutter_dimensions.margin, Pixels::ZERO);
 
                     let scrollbar_bounds =
-                        Bounds::from_corners(content_origin, bounds.bottom_right());
+                        Bounds::from_corners(content_origin, editor_bounds.bottom_right());
 
                     let height_in_lines = scrollbar_bounds.size.height / line_height;
 
@@ -6955,7 +7711,7 @@ impl Element for EditorElement {
                             autoscroll_request.is_some() || editor.has_pending_selection();
                         // TODO: Is this horizontal or vertical?!
                         autoscroll_horizontally = editor.autoscroll_vertically(
-                            bounds,
+                            editor_bounds,
                             line_height,
                             max_scroll_top,
                             window,
@@ -7230,6 +7986,30 @@ impl Element for EditorElement {
                         }
                     };
 
+                    // Prepainted before the sticky buffer header, which is drawn over them.
+                    let sticky_scopes = window.with_element_namespace("sticky_scopes", |window| {
+                        let top_rows = if sticky_header_excerpt.is_some() {
+                            FILE_HEADER_HEIGHT
+                        } else {
+                            0
+                        };
+                        let line_number_right = (!line_numbers.is_empty())
+                            .then(|| gutter_hitbox.size.width - gutter_dimensions.right_padding);
+                        self.layout_sticky_scopes(
+                            &snapshot,
+                            scroll_position,
+                            top_rows,
+                            line_height,
+                            em_width,
+                            &hitbox,
+                            &text_hitbox,
+                            content_origin,
+                            line_number_right,
+                            window,
+                            cx,
+                        )
+                    });
+
                     let sticky_buffer_header = sticky_header_excerpt.map(|sticky_header_excerpt| {
                         window.with_element_namespace("blocks", |window| {
                             self.layout_sticky_buffer_header(
@@ -7425,6 +8205,22 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let minimap = minimap_bounds.filter(|(_, is_shown)| *is_shown).map(
+                        |(minimap_bounds, _)| {
+                            self.layout_minimap(
+                                minimap_bounds,
+                                &snapshot,
+                                scroll_position,
+                                height_in_lines,
+                                line_height,
+                                font_size,
+                                em_width,
+                                window,
+                                cx,
+                            )
+                        },
+                    );
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let rows_with_hunk_bounds = display_hunks
@@ -7448,6 +8244,14 @@ impl Element for EditorElement {
                                 rows_with_hunk_bounds
                             },
                         );
+                    let breakpoint_rows =
+                        self.editor
+                            .read(cx)
+                            .breakpoint_rows(start_row..end_row, &snapshot, cx);
+                    let bookmark_rows =
+                        self.editor
+                            .read(cx)
+                            .bookmark_rows(start_row..end_row, &snapshot, cx);
                     let mut code_actions_indicator = None;
                     if let Some(newest_selection_head) = newest_selection_head {
                         let newest_selection_point =
@@ -7489,7 +8293,12 @@ impl Element for EditorElement {
                                             .tasks
                                             .contains_key(&(buffer_id, row));
 
-                                        if !has_test_indicator {
+                                        if !has_test_indicator
+                                            && !breakpoint_rows
+                                                .contains(&newest_selection_head.row())
+                                            && !bookmark_rows
+                                                .contains_key(&newest_selection_head.row())
+                                        {
                                             code_actions_indicator = self
                                                 .layout_code_actions_indicator(
                                                     line_height,
@@ -7522,6 +8331,7 @@ impl Element for EditorElement {
                         self.layout_run_indicators(
                             line_height,
                             start_row..end_row,
+                            &breakpoint_rows,
                             scroll_pixel_position,
                             &gutter_dimensions,
                             &gutter_hitbox,
@@ -7534,6 +8344,38 @@ impl Element for EditorElement {
                         Vec::new()
                     };
 
+                    let breakpoint_indicators = self.layout_breakpoint_indicators(
+                        line_height,
+                        start_row..end_row,
+                        &breakpoint_rows,
+                        &bookmark_rows,
+                        code_actions_indicator
+                            .as_ref()
+                            .and(newest_selection_head)
+                            .map(|head| head.row()),
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &rows_with_hunk_bounds,
+                        &snapshot,
+                        window,
+                        cx,
+                    );
+
+                    let bookmark_indicators = self.layout_bookmark_indicators(
+                        line_height,
+                        &bookmark_rows,
+                        &breakpoint_rows,
+                        gutter_settings.runnables,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &rows_with_hunk_bounds,
+                        &snapshot,
+                        window,
+                        cx,
+                    );
+
                     self.layout_signature_help(
                         &hitbox,
                         content_origin,
@@ -7636,7 +8478,7 @@ impl Element for EditorElement {
                     let mode = snapshot.mode;
 
                     let position_map = Rc::new(PositionMap {
-                        size: bounds.size,
+                        size: editor_bounds.size,
                         visible_row_range,
                         scroll_pixel_position,
                         scroll_max,
@@ -7678,6 +8520,7 @@ impl Element for EditorElement {
                         display_hunks,
                         content_origin,
                         scrollbars_layout,
+                        minimap,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -7695,12 +8538,15 @@ impl Element for EditorElement {
                         diff_hunk_controls: hunk_controls,
                         mouse_context_menu,
                         test_indicators,
+                        breakpoint_indicators,
+                        bookmark_indicators,
                         code_actions_indicator,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                     }
                 })
             })
@@ -7761,6 +8607,14 @@ impl Element for EditorElement {
                         });
                     }
 
+                    // Inner scopes are drawn first, so that outer ones cover them as they're
+                    // pushed up.
+                    window.with_element_namespace("sticky_scopes", |window| {
+                        for scope in layout.sticky_scopes.iter_mut().rev() {
+                            scope.element.paint(window, cx);
+                        }
+                    });
+
                     window.with_element_namespace("blocks", |window| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(window, cx)
@@ -7768,6 +8622,7 @@ impl Element for EditorElement {
                     });
 
                     self.paint_scrollbars(layout, window, cx);
+                    self.paint_minimap(bounds, layout, window, cx);
                     self.paint_inline_completion_popover(layout, window, cx);
                     self.paint_mouse_context_menu(layout, window, cx);
                 });
@@ -7853,6 +8708,7 @@ pub struct EditorLayout {
     gutter_hitbox: Hitbox,
     content_origin: gpui::Point<Pixels>,
     scrollbars_layout: AxisPair<Option<ScrollbarLayout>>,
+    minimap: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -7873,6 +8729,8 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    breakpoint_indicators: Vec<AnyElement>,
+    bookmark_indicators: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     diff_hunk_controls: Vec<AnyElement>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
@@ -7881,6 +8739,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Vec<StickyScopeLayout>,
 }
 
 impl EditorLayout {
@@ -7889,6 +8748,12 @@ impl EditorLayout {
     }
 }
 
+struct StickyScopeLayout {
+    buffer_row: u32,
+    text: String,
+    element: AnyElement,
+}
+
 struct LineNumberLayout {
     shaped_line: ShapedLine,
     hitbox: Option<Hitbox>,
@@ -8024,6 +8889,81 @@ impl ScrollbarLayout {
     }
 }
 
+struct MinimapLayout {
+    hitbox: Hitbox,
+    side: MinimapSide,
+    line_height: Pixels,
+    lines: MinimapLines,
+    /// Search results, diagnostics and git hunks.
+    markers: Vec<PaintQuad>,
+    /// The part of the buffer the editor shows.
+    thumb_bounds: Bounds<Pixels>,
+    /// The row at the top of the minimap, which scrolls along with the editor.
+    scroll_top: f32,
+    visible_row_count: f32,
+    /// How far the thumb moves when the editor scrolls by a row.
+    thumb_pixels_per_row: Pixels,
+}
+
+impl MinimapLayout {
+    /// How much smaller than the editor's the minimap's text is.
+    const SCALE: f32 = 0.15;
+    const GIT_MARKER_WIDTH: Pixels = px(2.0);
+}
+
+enum MinimapLines {
+    Characters(Vec<(gpui::Point<Pixels>, ShapedLine)>),
+    Blocks(Vec<PaintQuad>),
+}
+
+/// A row of text in the minimap, cut off at the minimap's width.
+#[derive(Default)]
+struct MinimapRow {
+    text: String,
+    columns: usize,
+    /// The length and color of each run of the text.
+    runs: Vec<(usize, Hsla)>,
+}
+
+fn minimap_rows(
+    snapshot: &EditorSnapshot,
+    rows: Range<DisplayRow>,
+    style: &EditorStyle,
+    max_columns: usize,
+) -> Vec<MinimapRow> {
+    let mut minimap_rows = vec![MinimapRow::default()];
+    for chunk in snapshot.highlighted_chunks(rows, true, style) {
+        let color = chunk
+            .style
+            .and_then(|highlight| highlight.color)
+            .unwrap_or(style.text.color);
+        for (ix, text) in chunk.text.split('\n').enumerate() {
+            if ix > 0 {
+                minimap_rows.push(MinimapRow::default());
+            }
+            let Some(row) = minimap_rows.last_mut() else {
+                continue;
+            };
+            let available_columns = max_columns.saturating_sub(row.columns);
+            let text = match text.char_indices().nth(available_columns) {
+                Some((ix, _)) => &text[..ix],
+                None => text,
+            };
+            if text.is_empty() {
+                continue;
+            }
+
+            row.columns += text.chars().count();
+            row.text.push_str(text);
+            match row.runs.last_mut() {
+                Some((len, run_color)) if *run_color == color => *len += text.len(),
+                _ => row.runs.push((text.len(), color)),
+            }
+        }
+    }
+    minimap_rows
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -8131,6 +9071,183 @@ fn layout_line(
     .unwrap()
 }
 
+/// The text of a display row, along with the highlights of its syntax and diagnostics.
+fn highlighted_line(
+    snapshot: &EditorSnapshot,
+    row: DisplayRow,
+    style: &EditorStyle,
+) -> (String, Vec<(Range<usize>, HighlightStyle)>) {
+    let mut text = String::new();
+    let mut highlights = Vec::new();
+    for chunk in snapshot.highlighted_chunks(row..row.next_row(), true, style) {
+        let chunk_text = match &chunk.replacement {
+            Some(ChunkReplacement::Str(replacement)) => replacement.as_ref(),
+            Some(ChunkReplacement::Renderer(_)) => "⋯",
+            None => chunk.text,
+        };
+        let (line, line_ended) = match chunk_text.split_once('\n') {
+            Some((line, _)) => (line, true),
+            None => (chunk_text, false),
+        };
+        if let Some(highlight) = chunk.style {
+            highlights.push((text.len()..text.len() + line.len(), highlight));
+        }
+        text.push_str(line);
+        if line_ended {
+            break;
+        }
+    }
+    (text, highlights)
+}
+
+/// The text of a row in the buffer of an excerpt, along with the highlights of its syntax, for
+/// rows outside of the excerpt.
+fn highlighted_buffer_line(
+    snapshot: &EditorSnapshot,
+    excerpt_id: ExcerptId,
+    row: u32,
+    style: &EditorStyle,
+) -> (String, Vec<(Range<usize>, HighlightStyle)>) {
+    let Some(buffer) = snapshot.buffer_snapshot.buffer_for_excerpt(excerpt_id) else {
+        return Default::default();
+    };
+    let range = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+    let text = buffer.highlighted_text_for_range(range, None, &style.syntax);
+    (text.text.to_string(), text.highlights)
+}
+
+/// A scope enclosing the top of the viewport, whose first line is pinned there.
+#[derive(Clone, Debug, PartialEq)]
+struct StickyScope {
+    /// The row of the header, `None` if it is before the start of the scope's excerpt.
+    header_row: Option<DisplayRow>,
+    /// The last row of the scope, or of its excerpt if the scope continues past it.
+    end_row: DisplayRow,
+    /// The row of the header in its buffer, for its line number.
+    buffer_row: u32,
+    /// The header, or the start of the excerpt if the header is before it.
+    anchor: Anchor,
+}
+
+/// The outline items containing rows of the multibuffer, outermost first. Finding them is too
+/// slow to repeat on every frame, so the items of the rows the last frame used are kept until
+/// the buffers or their syntax trees change.
+#[derive(Default)]
+pub(crate) struct StickyScopesCache {
+    version: (usize, usize),
+    items: HashMap<MultiBufferRow, Arc<[Range<text::Anchor>]>>,
+    previous_items: HashMap<MultiBufferRow, Arc<[Range<text::Anchor>]>>,
+}
+
+impl StickyScopesCache {
+    fn start_frame(&mut self, snapshot: &MultiBufferSnapshot) {
+        let version = (
+            snapshot.edit_count(),
+            snapshot.non_text_state_update_count(),
+        );
+        self.previous_items = mem::take(&mut self.items);
+        if version != self.version {
+            self.version = version;
+            self.previous_items.clear();
+        }
+    }
+
+    fn items_containing(
+        &mut self,
+        snapshot: &MultiBufferSnapshot,
+        row: MultiBufferRow,
+    ) -> Arc<[Range<text::Anchor>]> {
+        if let Some(items) = self.items.get(&row) {
+            return items.clone();
+        }
+        let items = self.previous_items.remove(&row).unwrap_or_else(|| {
+            let anchor = snapshot.anchor_before(Point::new(row.0, 0));
+            snapshot
+                .buffer_for_excerpt(anchor.excerpt_id)
+                .and_then(|buffer| buffer.symbols_containing(anchor.text_anchor, None))
+                .map(|items| items.into_iter().map(|item| item.range).collect::<Vec<_>>())
+                .unwrap_or_default()
+                .into()
+        });
+        self.items.insert(row, items.clone());
+        items
+    }
+}
+
+/// The outline items containing a display row, outermost first. In a multibuffer, the items
+/// whose first line is before the row's excerpt start where the excerpt does.
+fn scopes_containing(
+    snapshot: &EditorSnapshot,
+    row: DisplayRow,
+    cache: &mut StickyScopesCache,
+) -> Vec<StickyScope> {
+    let multi_buffer = &snapshot.buffer_snapshot;
+    let position = DisplayPoint::new(row, 0).to_point(&snapshot.display_snapshot);
+    let excerpt_id = multi_buffer.anchor_before(position).excerpt_id;
+    let (Some(buffer), Some(context)) = (
+        multi_buffer.buffer_for_excerpt(excerpt_id),
+        multi_buffer.context_range_for_excerpt(excerpt_id),
+    ) else {
+        return Vec::new();
+    };
+
+    cache
+        .items_containing(multi_buffer, MultiBufferRow(position.row))
+        .iter()
+        .filter_map(|range| {
+            let start = multi_buffer.anchor_in_excerpt(excerpt_id, range.start)?;
+            let end = multi_buffer.anchor_in_excerpt(excerpt_id, range.end)?;
+            let header_row = range
+                .start
+                .cmp(&context.start, buffer)
+                .is_ge()
+                .then(|| start.to_display_point(&snapshot.display_snapshot).row());
+            Some(StickyScope {
+                header_row,
+                end_row: end.to_display_point(&snapshot.display_snapshot).row(),
+                buffer_row: language::ToPoint::to_point(&range.start, buffer).row,
+                anchor: start,
+            })
+        })
+        .filter(|scope| scope.end_row >= row)
+        .collect()
+}
+
+/// The scopes whose first lines to pin to the top of the viewport, outermost first. A scope
+/// is pinned when its first line has scrolled above the pinned line that would show it.
+fn sticky_scopes(
+    snapshot: &EditorSnapshot,
+    scroll_top: f32,
+    top_rows: u32,
+    max_depth: usize,
+    cache: &mut StickyScopesCache,
+) -> Vec<StickyScope> {
+    cache.start_frame(&snapshot.buffer_snapshot);
+    let top_row = scroll_top as u32 + top_rows;
+    let mut scopes = Vec::new();
+    // Each pinned line covers another row, which may be inside more nested scopes.
+    loop {
+        let row = DisplayRow(top_row + scopes.len() as u32);
+        let mut containing = scopes_containing(snapshot, row, cache);
+        // Headers before the start of the excerpt are always above the viewport.
+        let pinned_count = containing
+            .iter()
+            .enumerate()
+            .take_while(|(ix, scope)| {
+                scope
+                    .header_row
+                    .map_or(true, |header_row| header_row.0 < top_row + *ix as u32)
+            })
+            .count()
+            .min(max_depth);
+        if pinned_count <= scopes.len() {
+            return scopes;
+        }
+        containing.truncate(pinned_count);
+        scopes = containing;
+    }
+}
+
 #[derive(Debug)]
 pub struct IndentGuideLayout {
     origin: gpui::Point<Pixels>,
@@ -8493,12 +9610,15 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockPlacement, BlockProperties},
+        editor_settings::{MinimapContent, StickyScrollContent},
         editor_tests::{init_test, update_test_language_settings},
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
-    use language::language_settings;
+    use language::{language_settings, Buffer, Capability, Language, LanguageConfig};
     use log::info;
+    use multi_buffer::ExcerptRange;
+    use settings::SettingsStore;
     use similar::DiffableStr;
     use std::num::NonZeroU32;
     use util::test::sample_text;
@@ -8804,6 +9924,361 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_minimap_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        side: Some(MinimapSide::Left),
+                        width: Some(100.),
+                        render: Some(MinimapRender::Blocks),
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(6, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style),
+        );
+        let minimap = state.minimap.as_ref().unwrap();
+        // The minimap takes its width from the text, rather than being drawn over it.
+        assert_eq!(
+            minimap.hitbox.bounds,
+            Bounds {
+                origin: point(px(500.), px(500.)),
+                size: size(px(100.), px(500.)),
+            }
+        );
+        assert_eq!(
+            state.hitbox.bounds,
+            Bounds {
+                origin: point(px(600.), px(500.)),
+                size: size(px(400.), px(500.)),
+            }
+        );
+        assert_eq!(minimap.scroll_top, 0.);
+        // Each line is a single word, drawn as a single block.
+        let MinimapLines::Blocks(blocks) = &minimap.lines else {
+            panic!("expected the minimap to draw blocks");
+        };
+        assert_eq!(blocks.len(), 6);
+    }
+
+    #[gpui::test]
+    fn test_minimap_shown_on_hover(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Hover),
+                        side: Some(MinimapSide::Right),
+                        width: Some(100.),
+                        render: Some(MinimapRender::Blocks),
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(6, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+        let text_bounds = Bounds {
+            origin: point(px(500.), px(500.)),
+            size: size(px(400.), px(500.)),
+        };
+
+        // While the mouse is over the text, the minimap is hidden, but its column is still
+        // reserved so that showing it never covers the text.
+        cx.simulate_mouse_move(point(px(700.), px(600.)), None, Modifiers::none());
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style.clone()),
+        );
+        assert!(state.minimap.is_none());
+        assert_eq!(state.hitbox.bounds, text_bounds);
+
+        cx.simulate_mouse_move(point(px(950.), px(600.)), None, Modifiers::none());
+        let (_, state) = cx.draw(
+            point(px(500.), px(500.)),
+            size(px(500.), px(500.)),
+            |_, _| EditorElement::new(&editor, style.clone()),
+        );
+        assert_eq!(
+            state.minimap.as_ref().unwrap().hitbox.bounds,
+            Bounds {
+                origin: point(px(900.), px(500.)),
+                size: size(px(100.), px(500.)),
+            }
+        );
+        assert_eq!(state.hitbox.bounds, text_bounds);
+    }
+
+    #[gpui::test]
+    fn test_minimap_mouse_scrolling(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        side: Some(MinimapSide::Right),
+                        width: Some(100.),
+                        render: Some(MinimapRender::Blocks),
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple(&"line\n".repeat(1000), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, window, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        cx.simulate_resize(size(px(500.), px(500.)));
+        cx.run_until_parked();
+        let editor = window.root(cx).unwrap();
+        let (text_bounds, line_height, visible_row_count) =
+            editor.update_in(cx, |editor, window, _| {
+                (
+                    editor.last_bounds.unwrap(),
+                    editor
+                        .style()
+                        .unwrap()
+                        .text
+                        .line_height_in_pixels(window.rem_size()),
+                    editor.visible_line_count().unwrap(),
+                )
+            });
+        let minimap_x = text_bounds.right() + px(50.);
+        let minimap_line_height = line_height * MinimapLayout::SCALE;
+        let scroll_top = |cx: &mut VisualTestContext| {
+            editor.update(cx, |editor, cx| editor.scroll_position(cx).y)
+        };
+
+        // Clicking below the thumb centers the editor on the clicked row.
+        let position = point(minimap_x, text_bounds.top() + minimap_line_height * 50.);
+        cx.simulate_mouse_down(position, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(position, MouseButton::Left, Modifiers::none());
+        assert!((scroll_top(cx) - (50. - visible_row_count / 2.)).abs() < 0.01);
+
+        // Dragging the thumb scrolls the editor, even once the mouse leaves the minimap.
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_scroll_position(point(0., 0.), window, cx);
+        });
+        let position = point(minimap_x, text_bounds.top() + minimap_line_height);
+        cx.simulate_mouse_down(position, MouseButton::Left, Modifiers::none());
+        assert_eq!(scroll_top(cx), 0.);
+
+        let position = point(minimap_x, position.y + px(10.));
+        cx.simulate_mouse_move(position, MouseButton::Left, Modifiers::none());
+        let dragged_scroll_top = scroll_top(cx);
+        assert!(dragged_scroll_top > 0.);
+
+        let position = point(text_bounds.left() + px(100.), position.y + px(10.));
+        cx.simulate_mouse_move(position, MouseButton::Left, Modifiers::none());
+        assert!(scroll_top(cx) > dragged_scroll_top);
+
+        // Once the mouse is released, moving it no longer scrolls.
+        cx.simulate_mouse_up(position, MouseButton::Left, Modifiers::none());
+        let released_scroll_top = scroll_top(cx);
+        cx.simulate_mouse_move(
+            point(minimap_x, position.y + px(10.)),
+            None,
+            Modifiers::none(),
+        );
+        assert_eq!(scroll_top(cx), released_scroll_top);
+    }
+
+    #[gpui::test]
+    fn test_sticky_scopes_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig::default(),
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (impl_item "impl" @context type: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let body = "        let x = 1;\n".repeat(20);
+        let text = format!("impl Foo {{\n    fn bar() {{\n{body}    }}\n}}\n");
+
+        let window = cx.add_window(|window, cx| {
+            let buffer = MultiBuffer::build_simple(&text, cx);
+            buffer.update(cx, |buffer, cx| {
+                buffer.as_singleton().unwrap().update(cx, |buffer, cx| {
+                    buffer.set_language(Some(language), cx);
+                });
+            });
+            Editor::new(EditorMode::Full, buffer, None, true, window, cx)
+        });
+        cx.executor().run_until_parked();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+        let draw_at = |scroll_top: f32, cx: &mut VisualTestContext| {
+            window
+                .update(cx, |editor, window, cx| {
+                    editor.set_scroll_position(point(0., scroll_top), window, cx);
+                })
+                .unwrap();
+            let (_, state) = cx.draw(
+                point(px(500.), px(500.)),
+                size(px(500.), px(500.)),
+                |_, _| EditorElement::new(&editor, style.clone()),
+            );
+            state
+                .sticky_scopes
+                .iter()
+                .map(|scope| scope.buffer_row)
+                .collect::<Vec<_>>()
+        };
+
+        // Headers that are still visible aren't pinned, unless a pinned line covers them.
+        assert_eq!(draw_at(0., cx), Vec::<u32>::new());
+        assert_eq!(draw_at(1., cx), vec![0, 1]);
+        assert_eq!(draw_at(5., cx), vec![0, 1]);
+
+        cx.update(|_, cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.sticky_scroll = Some(StickyScrollContent {
+                        enabled: None,
+                        max_depth: Some(1),
+                    });
+                });
+            });
+        });
+        assert_eq!(draw_at(5., cx), vec![0]);
+    }
+
+    #[gpui::test]
+    fn test_sticky_scopes_in_multibuffer(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig::default(),
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (impl_item "impl" @context type: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let body = "        let x = 1;\n".repeat(20);
+        let text = format!("impl Foo {{\n    fn bar() {{\n{body}    }}\n}}\n");
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+        // The second excerpt starts in the middle of the function, below the first lines of
+        // its scopes.
+        let multi_buffer = cx.new(|cx| {
+            let mut multi_buffer = MultiBuffer::new(Capability::ReadWrite);
+            multi_buffer.push_excerpts(
+                buffer,
+                [
+                    ExcerptRange {
+                        context: Point::new(0, 0)..Point::new(10, 0),
+                        primary: None,
+                    },
+                    ExcerptRange {
+                        context: Point::new(14, 0)..Point::new(24, 0),
+                        primary: None,
+                    },
+                ],
+                cx,
+            );
+            multi_buffer
+        });
+
+        let window = cx.add_window(|window, cx| {
+            Editor::new(EditorMode::Full, multi_buffer, None, true, window, cx)
+        });
+        cx.executor().run_until_parked();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|_, cx| editor.read(cx).style().unwrap().clone());
+        // The display row of a multibuffer row, below the headers of the excerpts.
+        let display_row = |row: u32, cx: &mut VisualTestContext| {
+            window
+                .update(cx, |editor, window, cx| {
+                    Point::new(row, 0)
+                        .to_display_point(&editor.snapshot(window, cx).display_snapshot)
+                        .row()
+                })
+                .unwrap()
+        };
+        let draw_with_top_row = |top_row: DisplayRow, cx: &mut VisualTestContext| {
+            window
+                .update(cx, |editor, window, cx| {
+                    // The top rows are taken by the sticky buffer header.
+                    let scroll_top = top_row.0 as f32 - FILE_HEADER_HEIGHT as f32;
+                    editor.set_scroll_position(point(0., scroll_top), window, cx);
+                })
+                .unwrap();
+            let (_, state) = cx.draw(
+                point(px(500.), px(500.)),
+                size(px(500.), px(500.)),
+                |_, _| EditorElement::new(&editor, style.clone()),
+            );
+            state
+                .sticky_scopes
+                .iter()
+                .map(|scope| (scope.buffer_row, scope.text.clone()))
+                .collect::<Vec<_>>()
+        };
+        let headers = vec![
+            (0, "impl Foo {".to_string()),
+            (1, "    fn bar() {".to_string()),
+        ];
+
+        assert_eq!(draw_with_top_row(display_row(5, cx), cx), headers);
+
+        // The first lines of the scopes enclosing the second excerpt are outside of it, so
+        // they are read from the buffer.
+        assert_eq!(draw_with_top_row(display_row(14, cx), cx), headers);
+
+        // The scopes found for the rows shown are only reused until the buffer changes.
+        window
+            .update(cx, |editor, _, cx| {
+                editor.edit([(Point::new(1, 7)..Point::new(1, 10), "baz")], cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            draw_with_top_row(display_row(14, cx), cx),
+            vec![
+                (0, "impl Foo {".to_string()),
+                (1, "    fn baz() {".to_string()),
+            ]
+        );
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
@@ -9029,6 +10504,11 @@ fn diff_hunk_controls(
         let snapshot = editor.buffer.read(cx).snapshot(cx);
         editor.has_stageable_diff_hunks_in_ranges(&[hunk_range.start..hunk_range.start], &snapshot)
     });
+    let is_branch_buffer = hunk_range
+        .start
+        .buffer_id
+        .and_then(|buffer_id| editor.read(cx).buffer.read(cx).buffer(buffer_id))
+        .map_or(false, |buffer| buffer.read(cx).base_buffer().is_some());
     h_flex()
         .h(line_height)
         .mr_1()
@@ -9088,33 +10568,69 @@ fn diff_hunk_controls(
                     }
                 }),
         )
-        .child(
-            Button::new(
-                ("stage-unstage-hunk", row as u64),
-                if stage { "Stage" } else { "Unstage" },
+        .when(is_branch_buffer, |el| {
+            el.child(
+                Button::new(("apply-hunk", row as u64), "Apply")
+                    .label_size(LabelSize::Small)
+                    .tooltip({
+                        let focus_handle = editor.focus_handle(cx);
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Apply Hunk",
+                                &ApplyDiffHunk,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click({
+                        let editor = editor.clone();
+                        move |_event, window, cx| {
+                            editor.update(cx, |editor, cx| {
+                                let snapshot = editor.snapshot(window, cx);
+                                let point = hunk_range.start.to_point(&snapshot.buffer_snapshot);
+                                editor.apply_diff_hunks_in_ranges(
+                                    [point..point].into_iter(),
+                                    window,
+                                    cx,
+                                );
+                            });
+                        }
+                    }),
             )
-            .label_size(LabelSize::Small)
-            .tooltip({
-                let focus_handle = editor.focus_handle(cx);
-                move |window, cx| {
-                    Tooltip::for_action_in(
-                        if stage { "Stage Hunk" } else { "Unstage Hunk" },
-                        &ToggleStagedSelectedDiffHunks,
-                        &focus_handle,
-                        window,
-                        cx,
-                    )
-                }
-            })
-            .on_click({
-                let editor = editor.clone();
-                move |_event, _window, cx| {
-                    editor.update(cx, |editor, cx| {
-                        editor
-                            .stage_or_unstage_diff_hunks(&[hunk_range.start..hunk_range.start], cx);
-                    });
-                }
-            }),
-        )
+        })
+        .when(!is_branch_buffer, |el| {
+            el.child(
+                Button::new(
+                    ("stage-unstage-hunk", row as u64),
+                    if stage { "Stage" } else { "Unstage" },
+                )
+                .label_size(LabelSize::Small)
+                .tooltip({
+                    let focus_handle = editor.focus_handle(cx);
+                    move |window, cx| {
+                        Tooltip::for_action_in(
+                            if stage { "Stage Hunk" } else { "Unstage Hunk" },
+                            &ToggleStagedSelectedDiffHunks,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    }
+                })
+                .on_click({
+                    let editor = editor.clone();
+                    move |_event, _window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.stage_or_unstage_diff_hunks(
+                                &[hunk_range.start..hunk_range.start],
+                                cx,
+                            );
+                        });
+                    }
+                }),
+            )
+        })
         .into_any_element()
 }
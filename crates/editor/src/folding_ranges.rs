@@ -0,0 +1,296 @@
+use std::{cmp::Reverse, ops::Range, time::Duration};
+
+use clock::Global;
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{Context, Task};
+use language::{
+    language_settings::{language_settings, FoldingRangesProvider},
+    BufferSnapshot, OffsetRangeExt as _,
+};
+use text::BufferId;
+
+use crate::{
+    display_map::{Crease, CreaseId},
+    Editor, EditorMode,
+};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The ranges of a buffer that can be folded, and the creases the editor has for them.
+#[derive(Default)]
+pub(super) struct BufferFoldingRanges {
+    /// The version of the buffer the ranges were computed for, `None` if they are out of date.
+    version: Option<Global>,
+    provider: FoldingRangesProvider,
+    ranges: Vec<Range<text::Anchor>>,
+    creases: Vec<CreaseId>,
+}
+
+/// Computes the folding ranges of the visible buffers whose ranges are out of date, using the
+/// provider configured for their language, and replaces the creases made for them.
+///
+/// Rows that aren't covered by any of these creases can still be folded by their indentation.
+pub(super) fn refresh_folding_ranges(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) -> Option<()> {
+    if editor.mode != EditorMode::Full {
+        return None;
+    }
+    let semantics_provider = editor.semantics_provider.clone();
+
+    editor.folding_ranges_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+        }
+
+        let requests = editor
+            .update(&mut cx, |editor, cx| {
+                let mut buffers = HashMap::default();
+                for (buffer, _, _) in editor
+                    .excerpts_for_inlay_hints_query(None, cx)
+                    .into_values()
+                {
+                    buffers.insert(buffer.read(cx).remote_id(), buffer);
+                }
+                buffers
+                    .into_iter()
+                    .filter_map(|(buffer_id, buffer)| {
+                        let buffer_ref = buffer.read(cx);
+                        let version = buffer_ref.version();
+                        let provider = language_settings(
+                            buffer_ref.language().map(|language| language.name()),
+                            buffer_ref.file(),
+                            cx,
+                        )
+                        .folding_ranges;
+                        let up_to_date =
+                            editor
+                                .folding_ranges
+                                .get(&buffer_id)
+                                .map_or(false, |folding_ranges| {
+                                    folding_ranges.provider == provider
+                                        && folding_ranges.version.as_ref() == Some(&version)
+                                });
+                        if up_to_date {
+                            return None;
+                        }
+
+                        let request: Task<anyhow::Result<Vec<Range<text::Anchor>>>> = match provider
+                        {
+                            FoldingRangesProvider::LanguageServer => {
+                                semantics_provider.as_ref()?.folding_ranges(&buffer, cx)?
+                            }
+                            FoldingRangesProvider::TreeSitter => {
+                                let snapshot = buffer_ref.snapshot();
+                                cx.background_executor()
+                                    .spawn(async move { Ok(tree_sitter_folding_ranges(&snapshot)) })
+                            }
+                            FoldingRangesProvider::Indent => Task::ready(Ok(Vec::new())),
+                        };
+                        Some(async move { (buffer_id, version, provider, request.await) })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let responses = join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, version, provider, response) in responses {
+                    let ranges = match response {
+                        Ok(ranges) => ranges,
+                        Err(error) => {
+                            log::error!("failed to fetch folding ranges: {error:#}");
+                            continue;
+                        }
+                    };
+                    let folding_ranges = editor.folding_ranges.entry(buffer_id).or_default();
+                    folding_ranges.version = Some(version);
+                    folding_ranges.provider = provider;
+                    folding_ranges.ranges = ranges;
+                }
+                update_folding_creases(editor, cx);
+            })
+            .ok()
+    }));
+    None
+}
+
+/// Computes the folding ranges of all visible buffers again, as a language server started.
+pub(super) fn invalidate_folding_ranges(editor: &mut Editor, cx: &mut Context<Editor>) {
+    for folding_ranges in editor.folding_ranges.values_mut() {
+        folding_ranges.version = None;
+    }
+    refresh_folding_ranges(editor, false, cx);
+}
+
+/// Computes the folding ranges of a buffer again if they come from its syntax tree, which
+/// changed.
+pub(super) fn buffer_reparsed(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+    let Some(folding_ranges) = editor.folding_ranges.get_mut(&buffer_id) else {
+        return;
+    };
+    if folding_ranges.provider == FoldingRangesProvider::TreeSitter {
+        folding_ranges.version = None;
+        refresh_folding_ranges(editor, false, cx);
+    }
+}
+
+/// Replaces the creases made for folding ranges with ones for the current excerpts.
+pub(super) fn update_folding_creases(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let mut old_creases = Vec::new();
+    for folding_ranges in editor.folding_ranges.values_mut() {
+        old_creases.append(&mut folding_ranges.creases);
+    }
+    let multi_buffer = editor.buffer.read(cx);
+    editor
+        .folding_ranges
+        .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+    let snapshot = multi_buffer.snapshot(cx);
+    let placeholder = editor.display_map.read(cx).fold_placeholder.clone();
+    let mut creases_by_buffer = Vec::new();
+    for (buffer_id, folding_ranges) in &editor.folding_ranges {
+        let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+            continue;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let mut creases = Vec::new();
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+            let context = excerpt_range.context.to_point(&buffer_snapshot);
+            let mut last_row = None;
+            for range in &folding_ranges.ranges {
+                let point_range = range.to_point(&buffer_snapshot);
+                // A row can only have one crease, so keep the outermost range starting on it.
+                if point_range.start < context.start
+                    || point_range.end > context.end
+                    || point_range.end.row <= point_range.start.row
+                    || last_row == Some(point_range.start.row)
+                {
+                    continue;
+                }
+                let (Some(start), Some(end)) = (
+                    snapshot.anchor_in_excerpt(excerpt_id, range.start),
+                    snapshot.anchor_in_excerpt(excerpt_id, range.end),
+                ) else {
+                    continue;
+                };
+                last_row = Some(point_range.start.row);
+                creases.push(Crease::simple(start..end, placeholder.clone()));
+            }
+        }
+        creases_by_buffer.push((*buffer_id, creases));
+    }
+
+    editor.remove_creases(old_creases, cx);
+    for (buffer_id, creases) in creases_by_buffer {
+        let crease_ids = editor.insert_creases(creases, cx);
+        if let Some(folding_ranges) = editor.folding_ranges.get_mut(&buffer_id) {
+            folding_ranges.creases = crease_ids;
+        }
+    }
+}
+
+/// Returns the contents of the bracket pairs spanning several lines, sorted by their start and
+/// with outer pairs first.
+fn tree_sitter_folding_ranges(snapshot: &BufferSnapshot) -> Vec<Range<text::Anchor>> {
+    let mut ranges = snapshot
+        .bracket_ranges(0..snapshot.len())
+        .map(|(open, close)| open.end..close.start)
+        .filter(|range| {
+            snapshot.offset_to_point(range.start).row < snapshot.offset_to_point(range.end).row
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+    ranges.dedup();
+    ranges
+        .into_iter()
+        .map(|range| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::{init_test, update_test_language_settings},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use futures::StreamExt as _;
+    use indoc::indoc;
+    use multi_buffer::MultiBufferRow;
+    use text::Point;
+
+    #[gpui::test]
+    async fn test_language_server_folding_ranges(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.folding_ranges = Some(FoldingRangesProvider::LanguageServer);
+        });
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.set_state(indoc! {"
+            ˇ// one
+            // two
+            fn main() {
+                let x = 1;
+            }
+        "});
+
+        let mut requests =
+            cx.handle_request::<lsp::request::FoldingRangeRequest, _, _>(|_, _, _| async move {
+                Ok(Some(vec![
+                    lsp::FoldingRange {
+                        start_line: 0,
+                        start_character: None,
+                        end_line: 1,
+                        end_character: None,
+                        kind: Some(lsp::FoldingRangeKind::Comment),
+                        collapsed_text: None,
+                    },
+                    lsp::FoldingRange {
+                        start_line: 2,
+                        start_character: Some(11),
+                        end_line: 4,
+                        end_character: Some(0),
+                        kind: None,
+                        collapsed_text: None,
+                    },
+                ]))
+            });
+        cx.update_editor(|editor, _, cx| {
+            refresh_folding_ranges(editor, false, cx);
+        });
+        requests.next().await;
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            let crease_range = |row| {
+                snapshot
+                    .crease_for_buffer_row(MultiBufferRow(row))
+                    .map(|crease| crease.range().clone())
+            };
+            // The comment lines have the same indentation, so only the server can fold them.
+            assert_eq!(crease_range(0), Some(Point::new(0, 6)..Point::new(1, 6)));
+            assert_eq!(crease_range(2), Some(Point::new(2, 11)..Point::new(4, 0)));
+        });
+
+        update_test_language_settings(&mut cx, |settings| {
+            settings.defaults.folding_ranges = Some(FoldingRangesProvider::Indent);
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            assert!(snapshot.crease_for_buffer_row(MultiBufferRow(0)).is_none());
+        });
+    }
+}
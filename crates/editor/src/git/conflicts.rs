@@ -0,0 +1,592 @@
+use collections::{HashMap, HashSet};
+use gpui::{App, Context, Entity, MouseButton, Task, Window};
+use language::{BufferId, Point};
+use multi_buffer::{Anchor, MultiBufferRow, MultiBufferSnapshot, ToOffset as _, ToPoint as _};
+use std::{ops::Range, sync::Arc};
+use text::{BufferSnapshot, ToPoint as _};
+use ui::{prelude::*, ButtonLike, ElevationIndex, Tooltip};
+
+use crate::{
+    display_map::{Crease, CreaseId},
+    BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, Editor, EditorMode,
+    FoldPlaceholder, UPDATE_DEBOUNCE,
+};
+
+const START_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const END_MARKER: &str = ">>>>>>>";
+
+/// A region of a buffer delimited by git conflict markers:
+///
+/// ```text
+/// <<<<<<< ours_label
+/// ours
+/// ||||||| base (only with `merge.conflictStyle = diff3`)
+/// base
+/// =======
+/// theirs
+/// >>>>>>> theirs_label
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictRegion<T> {
+    /// The whole region, including the marker lines.
+    pub range: Range<T>,
+    pub ours: Range<T>,
+    pub base: Option<Range<T>>,
+    pub theirs: Range<T>,
+    pub ours_label: SharedString,
+    pub theirs_label: SharedString,
+}
+
+impl<T> ConflictRegion<T> {
+    fn try_map<U>(&self, mut f: impl FnMut(&T) -> Option<U>) -> Option<ConflictRegion<U>> {
+        let mut map_range = |range: &Range<T>| Some(f(&range.start)?..f(&range.end)?);
+        Some(ConflictRegion {
+            range: map_range(&self.range)?,
+            ours: map_range(&self.ours)?,
+            base: match &self.base {
+                Some(base) => Some(map_range(base)?),
+                None => None,
+            },
+            theirs: map_range(&self.theirs)?,
+            ours_label: self.ours_label.clone(),
+            theirs_label: self.theirs_label.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+/// Finds all conflict regions in the given buffer. Unterminated or malformed regions are ignored.
+pub fn parse_conflicts(snapshot: &BufferSnapshot) -> Vec<ConflictRegion<Point>> {
+    #[derive(Default)]
+    struct PendingRegion {
+        start_row: u32,
+        ours_label: SharedString,
+        base_row: Option<u32>,
+        separator_row: Option<u32>,
+    }
+
+    let max_point = snapshot.max_point();
+    let mut regions = Vec::new();
+    let mut pending: Option<PendingRegion> = None;
+    let mut line = String::new();
+    for row in 0..=max_point.row {
+        let line_start = Point::new(row, 0);
+        let line_end = Point::new(row, snapshot.line_len(row));
+        line.clear();
+        line.extend(snapshot.text_for_range(line_start..line_end));
+
+        if let Some(label) = marker_label(&line, START_MARKER) {
+            pending = Some(PendingRegion {
+                start_row: row,
+                ours_label: label.into(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(region) = pending.as_mut() else {
+            continue;
+        };
+        if marker_label(&line, BASE_MARKER).is_some() {
+            if region.base_row.is_none() && region.separator_row.is_none() {
+                region.base_row = Some(row);
+            }
+        } else if line.trim_end() == SEPARATOR_MARKER {
+            if region.separator_row.is_none() {
+                region.separator_row = Some(row);
+            }
+        } else if let Some(label) = marker_label(&line, END_MARKER) {
+            let region = pending.take().unwrap();
+            let Some(separator_row) = region.separator_row else {
+                continue;
+            };
+            let ours_end = region.base_row.unwrap_or(separator_row);
+            let end = if row < max_point.row {
+                Point::new(row + 1, 0)
+            } else {
+                line_end
+            };
+            regions.push(ConflictRegion {
+                range: Point::new(region.start_row, 0)..end,
+                ours: Point::new(region.start_row + 1, 0)..Point::new(ours_end, 0),
+                base: region
+                    .base_row
+                    .map(|base_row| Point::new(base_row + 1, 0)..Point::new(separator_row, 0)),
+                theirs: Point::new(separator_row + 1, 0)..Point::new(row, 0),
+                ours_label: region.ours_label,
+                theirs_label: label.into(),
+            });
+        }
+    }
+    regions
+}
+
+/// Returns the text following a conflict marker if the line starts with exactly that marker.
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Finds the conflict regions of the given buffers in the multibuffer that are fully contained in
+/// one of its excerpts.
+fn conflicts_in_multibuffer(
+    snapshot: &MultiBufferSnapshot,
+    conflicted_buffers: &HashSet<BufferId>,
+) -> Vec<ConflictRegion<Anchor>> {
+    let mut regions_by_buffer = HashMap::<BufferId, Vec<ConflictRegion<Point>>>::default();
+    let mut regions = Vec::new();
+    for (excerpt_id, buffer, excerpt_range) in snapshot.excerpts() {
+        if !conflicted_buffers.contains(&buffer.remote_id()) {
+            continue;
+        }
+        let buffer_regions = regions_by_buffer
+            .entry(buffer.remote_id())
+            .or_insert_with(|| parse_conflicts(buffer));
+        let context_start = excerpt_range.context.start.to_point(buffer);
+        let context_end = excerpt_range.context.end.to_point(buffer);
+        for region in buffer_regions.iter() {
+            if region.range.start < context_start || region.theirs.end > context_end {
+                continue;
+            }
+            let region = region.try_map(|point| {
+                let anchor = buffer.anchor_before((*point).min(context_end));
+                snapshot.anchor_in_excerpt(excerpt_id, anchor)
+            });
+            regions.extend(region);
+        }
+    }
+    regions
+}
+
+/// Conflict regions currently displayed by an editor, along with the blocks and creases
+/// created for them.
+pub(crate) struct ConflictState {
+    regions: Vec<ConflictRegion<Anchor>>,
+    blocks: HashSet<CustomBlockId>,
+    creases: Vec<CreaseId>,
+    refresh_task: Task<()>,
+}
+
+impl Default for ConflictState {
+    fn default() -> Self {
+        Self {
+            regions: Vec::new(),
+            blocks: HashSet::default(),
+            creases: Vec::new(),
+            refresh_task: Task::ready(()),
+        }
+    }
+}
+
+struct ConflictOursHighlight;
+struct ConflictBaseHighlight;
+struct ConflictTheirsHighlight;
+
+impl Editor {
+    pub(crate) fn refresh_conflicts(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let conflicted_buffers = self.conflicted_buffers(cx);
+        if conflicted_buffers.is_empty() {
+            self.conflicts.refresh_task = Task::ready(());
+            if !self.conflicts.regions.is_empty() {
+                self.set_conflicts(Vec::new(), cx);
+            }
+            return;
+        }
+        self.conflicts.refresh_task = cx.spawn_in(window, |this, mut cx| async move {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let Ok(snapshot) = this.update(&mut cx, |this, cx| this.buffer.read(cx).snapshot(cx))
+            else {
+                return;
+            };
+            let regions = cx
+                .background_executor()
+                .spawn(async move { conflicts_in_multibuffer(&snapshot, &conflicted_buffers) })
+                .await;
+            this.update(&mut cx, |this, cx| this.set_conflicts(regions, cx))
+                .ok();
+        });
+    }
+
+    /// The buffers in this editor whose files git reports as conflicted. Only those are searched
+    /// for conflict markers, so files that merely contain marker-like lines are left alone.
+    fn conflicted_buffers(&self, cx: &App) -> HashSet<BufferId> {
+        let Some(project) = self.project.as_ref() else {
+            return HashSet::default();
+        };
+        let project = project.read(cx);
+        self.buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter_map(|buffer| {
+                let buffer_id = buffer.read(cx).remote_id();
+                let (repository, repo_path) =
+                    project.repository_and_path_for_buffer_id(buffer_id, cx)?;
+                let status = repository
+                    .read(cx)
+                    .repository_entry
+                    .status_for_path(&repo_path)?;
+                status.status.is_conflicted().then_some(buffer_id)
+            })
+            .collect()
+    }
+
+    /// The conflict regions currently shown in this editor, in multibuffer order.
+    pub fn conflict_regions(&self) -> &[ConflictRegion<Anchor>] {
+        &self.conflicts.regions
+    }
+
+    /// Replaces the conflict region, markers included, with the side(s) picked by `resolution`.
+    pub fn resolve_conflict(
+        &mut self,
+        region: &ConflictRegion<Anchor>,
+        resolution: ConflictResolution,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut text = String::new();
+        if matches!(
+            resolution,
+            ConflictResolution::Ours | ConflictResolution::Both
+        ) {
+            text.extend(snapshot.text_for_range(region.ours.clone()));
+        }
+        if matches!(
+            resolution,
+            ConflictResolution::Theirs | ConflictResolution::Both
+        ) {
+            text.extend(snapshot.text_for_range(region.theirs.clone()));
+        }
+        self.transact(window, cx, |editor, _, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(region.range.clone(), text)], None, cx);
+            });
+        });
+    }
+
+    fn set_conflicts(&mut self, regions: Vec<ConflictRegion<Anchor>>, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let offsets = |regions: &[ConflictRegion<Anchor>]| {
+            regions
+                .iter()
+                .map(|region| {
+                    region.range.start.to_offset(&snapshot)..region.range.end.to_offset(&snapshot)
+                })
+                .collect::<Vec<_>>()
+        };
+        if offsets(&self.conflicts.regions) == offsets(&regions) {
+            return;
+        }
+
+        let blocks = std::mem::take(&mut self.conflicts.blocks);
+        if !blocks.is_empty() {
+            self.remove_blocks(blocks, None, cx);
+        }
+        let creases = std::mem::take(&mut self.conflicts.creases);
+        self.remove_creases(creases, cx);
+        self.clear_row_highlights::<ConflictOursHighlight>();
+        self.clear_row_highlights::<ConflictBaseHighlight>();
+        self.clear_row_highlights::<ConflictTheirsHighlight>();
+
+        let colors = cx.theme().colors();
+        let ours_color = colors.version_control_added_background;
+        let base_color = colors.version_control_conflict_background;
+        let theirs_color = colors.version_control_modified_background;
+        for region in &regions {
+            if let Some(rows) = highlight_rows(&region.ours, &snapshot) {
+                self.highlight_rows::<ConflictOursHighlight>(rows, ours_color, false, cx);
+            }
+            if let Some(rows) = region
+                .base
+                .as_ref()
+                .and_then(|base| highlight_rows(base, &snapshot))
+            {
+                self.highlight_rows::<ConflictBaseHighlight>(rows, base_color, false, cx);
+            }
+            if let Some(rows) = highlight_rows(&region.theirs, &snapshot) {
+                self.highlight_rows::<ConflictTheirsHighlight>(rows, theirs_color, false, cx);
+            }
+        }
+
+        let editor = cx.entity();
+        let new_blocks = regions
+            .iter()
+            .map(|region| conflict_header_block(region, editor.clone()))
+            .collect::<Vec<_>>();
+        self.conflicts.blocks = self
+            .insert_blocks(new_blocks, None, cx)
+            .into_iter()
+            .collect();
+
+        let new_creases = regions
+            .iter()
+            .map(|region| {
+                Crease::simple(region.range.clone(), conflict_placeholder(editor.clone()))
+            })
+            .collect::<Vec<_>>();
+        self.conflicts.creases = self.insert_creases(new_creases, cx);
+
+        self.conflicts.regions = regions;
+        cx.notify();
+    }
+}
+
+/// Converts a range ending at the start of a line into the rows it covers, for row highlights.
+fn highlight_rows(range: &Range<Anchor>, snapshot: &MultiBufferSnapshot) -> Option<Range<Anchor>> {
+    let start = range.start.to_point(snapshot);
+    let end = range.end.to_point(snapshot);
+    if end.row <= start.row {
+        return None;
+    }
+    let last_row = MultiBufferRow(end.row - 1);
+    let end = Point::new(last_row.0, snapshot.line_len(last_row));
+    Some(range.start..snapshot.anchor_before(end))
+}
+
+fn conflict_header_block(
+    region: &ConflictRegion<Anchor>,
+    editor: Entity<Editor>,
+) -> BlockProperties<Anchor> {
+    let region = region.clone();
+    BlockProperties {
+        placement: BlockPlacement::Above(region.range.start),
+        height: 1,
+        style: BlockStyle::Sticky,
+        priority: 0,
+        render: Arc::new(move |cx| {
+            let resolve_button = |id: &'static str, label: SharedString, resolution| {
+                let editor = editor.clone();
+                let region = region.clone();
+                Button::new(id, label)
+                    .label_size(LabelSize::Small)
+                    .on_click(move |_, window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.resolve_conflict(&region, resolution, window, cx);
+                        });
+                    })
+            };
+            let side_label = |side: &str, label: &SharedString| -> SharedString {
+                if label.is_empty() {
+                    format!("Accept {side}").into()
+                } else {
+                    format!("Accept {side} ({label})").into()
+                }
+            };
+
+            h_flex()
+                .id(cx.block_id)
+                .block_mouse_down()
+                .h(cx.window.line_height())
+                .pl(cx.anchor_x)
+                .gap_1()
+                .child(
+                    Label::new("Merge conflict")
+                        .size(LabelSize::Small)
+                        .color(Color::Conflict),
+                )
+                .child(resolve_button(
+                    "accept-ours",
+                    side_label("Ours", &region.ours_label),
+                    ConflictResolution::Ours,
+                ))
+                .child(resolve_button(
+                    "accept-theirs",
+                    side_label("Theirs", &region.theirs_label),
+                    ConflictResolution::Theirs,
+                ))
+                .child(resolve_button(
+                    "accept-both",
+                    "Accept Both".into(),
+                    ConflictResolution::Both,
+                ))
+                .into_any_element()
+        }),
+    }
+}
+
+fn conflict_placeholder(editor: Entity<Editor>) -> FoldPlaceholder {
+    FoldPlaceholder {
+        render: Arc::new(move |fold_id, fold_range, _, _| {
+            let editor = editor.clone();
+            ButtonLike::new(fold_id)
+                .style(ButtonStyle::Filled)
+                .layer(ElevationIndex::ElevatedSurface)
+                .child(Icon::new(IconName::Warning).color(Color::Conflict))
+                .child(Label::new("Merge conflict").color(Color::Conflict))
+                .tooltip(Tooltip::text("Unfold to resolve the conflict"))
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .on_click(move |_, _, cx| {
+                    editor.update(cx, |editor, cx| {
+                        editor.unfold_ranges(&[fold_range.start..fold_range.end], true, false, cx);
+                    });
+                })
+                .into_any_element()
+        }),
+        constrain_width: false,
+        merge_adjacent: false,
+        type_tag: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use ::git::status::{FileStatus, UnmergedStatus, UnmergedStatusCode};
+    use fs::FakeFs;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Buffer;
+    use project::Project;
+    use serde_json::json;
+    use std::path::Path;
+    use unindent::Unindent as _;
+    use util::path;
+
+    #[gpui::test]
+    fn test_parse_conflicts(cx: &mut TestAppContext) {
+        let text = "
+            one
+            <<<<<<< HEAD
+            two
+            =======
+            three
+            >>>>>>> feature
+            four
+            <<<<<<< ours
+            five
+            ||||||| base
+            six
+            =======
+            >>>>>>> theirs
+            <<<<<<< unterminated
+            seven
+        "
+        .unindent();
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let regions = buffer.read_with(cx, |buffer, _| parse_conflicts(&buffer.snapshot()));
+
+        assert_eq!(
+            regions,
+            vec![
+                ConflictRegion {
+                    range: Point::new(1, 0)..Point::new(6, 0),
+                    ours: Point::new(2, 0)..Point::new(3, 0),
+                    base: None,
+                    theirs: Point::new(4, 0)..Point::new(5, 0),
+                    ours_label: "HEAD".into(),
+                    theirs_label: "feature".into(),
+                },
+                ConflictRegion {
+                    range: Point::new(7, 0)..Point::new(13, 0),
+                    ours: Point::new(8, 0)..Point::new(9, 0),
+                    base: Some(Point::new(10, 0)..Point::new(11, 0)),
+                    theirs: Point::new(12, 0)..Point::new(12, 0),
+                    ours_label: "ours".into(),
+                    theirs_label: "theirs".into(),
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_parse_conflicts_ignores_lookalike_lines(cx: &mut TestAppContext) {
+        let text = "
+            <<<<<<<< not a marker
+            ========
+            >>>>>>>>
+        "
+        .unindent();
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let regions = buffer.read_with(cx, |buffer, _| parse_conflicts(&buffer.snapshot()));
+        assert_eq!(regions, Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_conflicts_only_shown_for_conflicted_files(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        let text = "
+            <<<<<<< HEAD
+            ours
+            =======
+            theirs
+            >>>>>>> feature
+        "
+        .unindent();
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "conflicted.txt": text,
+                "clean.txt": text,
+            }),
+        )
+        .await;
+        fs.set_status_for_repo_via_git_operation(
+            Path::new(path!("/project/.git")),
+            &[(
+                Path::new("conflicted.txt"),
+                FileStatus::Unmerged(UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                }),
+            )],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+
+        let mut editors = Vec::new();
+        for path in [
+            path!("/project/conflicted.txt"),
+            path!("/project/clean.txt"),
+        ] {
+            let buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+            let project = project.clone();
+            editors.push(
+                cx.add_window(|window, cx| Editor::for_buffer(buffer, Some(project), window, cx)),
+            );
+        }
+        let [conflicted_editor, clean_editor] = [editors[0], editors[1]];
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+
+        conflicted_editor
+            .update(cx, |editor, _, _| {
+                assert_eq!(editor.conflict_regions().len(), 1);
+            })
+            .unwrap();
+        clean_editor
+            .update(cx, |editor, _, _| {
+                assert_eq!(editor.conflict_regions().len(), 0);
+            })
+            .unwrap();
+
+        // Once the file is no longer conflicted, e.g. after staging it, its markers are ignored.
+        fs.set_status_for_repo_via_git_operation(Path::new(path!("/project/.git")), &[]);
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+        conflicted_editor
+            .update(cx, |editor, _, _| {
+                assert_eq!(editor.conflict_regions().len(), 0);
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+use std::{collections::BTreeMap, mem, time::Duration};
+
+use clock::Global;
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{Context, Window};
+use language::{OffsetRangeExt as _, Point, ToPoint as _};
+use project::{InlineValue, InlineValueKind, TaskOutputValue};
+use settings::Settings as _;
+use util::{post_inc, truncate_and_trailoff};
+
+use crate::{actions::ClearInlineValues, display_map::Inlay, Editor, EditorMode, EditorSettings};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(500);
+const MAX_VALUE_LENGTH: usize = 80;
+
+/// The inline values a language server provided for a buffer.
+#[derive(Default)]
+pub(super) struct BufferInlineValues {
+    /// The version of the buffer the values were requested for, `None` if they are out of date.
+    version: Option<Global>,
+    values: Vec<InlineValue>,
+}
+
+/// Requests the inline values of the visible buffers whose values are out of date, and shows
+/// them along with the values tasks printed for those buffers.
+pub(super) fn refresh_inline_values(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) -> Option<()> {
+    if editor.mode != EditorMode::Full {
+        return None;
+    }
+    if !EditorSettings::get_global(cx).inline_values {
+        editor.inline_values_task = None;
+        editor.inline_values.clear();
+        update_inline_value_inlays(editor, cx);
+        return None;
+    }
+    let provider = editor.semantics_provider.clone()?;
+
+    editor.inline_values_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+        }
+
+        let requests = editor
+            .update(&mut cx, |editor, cx| {
+                let mut buffers = HashMap::default();
+                for (buffer, _, _) in editor
+                    .excerpts_for_inlay_hints_query(None, cx)
+                    .into_values()
+                {
+                    buffers.insert(buffer.read(cx).remote_id(), buffer);
+                }
+                buffers
+                    .into_values()
+                    .filter_map(|buffer| {
+                        let version = buffer.read(cx).version();
+                        let buffer_id = buffer.read(cx).remote_id();
+                        let up_to_date = editor
+                            .inline_values
+                            .get(&buffer_id)
+                            .map_or(false, |values| values.version.as_ref() == Some(&version));
+                        if up_to_date {
+                            return None;
+                        }
+                        let range = {
+                            let buffer = buffer.read(cx);
+                            buffer.anchor_before(0)..buffer.anchor_after(buffer.len())
+                        };
+                        let request = provider.inline_values(&buffer, range, cx)?;
+                        Some(async move { (buffer_id, version, request.await) })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let responses = join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, version, response) in responses {
+                    let values = match response {
+                        Ok(values) => values,
+                        Err(error) => {
+                            log::error!("failed to fetch inline values: {error:#}");
+                            continue;
+                        }
+                    };
+                    editor.inline_values.insert(
+                        buffer_id,
+                        BufferInlineValues {
+                            version: Some(version),
+                            values,
+                        },
+                    );
+                }
+                update_inline_value_inlays(editor, cx);
+            })
+            .ok()
+    }));
+    None
+}
+
+/// Replaces the inlays showing inline values with ones for the current excerpts.
+pub(super) fn update_inline_value_inlays(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let to_remove = mem::take(&mut editor.inline_value_inlays);
+    let mut to_insert = Vec::new();
+    let project = editor.project.clone();
+    if let Some(project) = project.filter(|_| EditorSettings::get_global(cx).inline_values) {
+        let multi_buffer = editor.buffer.read(cx);
+        editor
+            .inline_values
+            .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+        let snapshot = multi_buffer.snapshot(cx);
+        for buffer in multi_buffer.all_buffers() {
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let buffer_id = buffer_snapshot.remote_id();
+            let lsp_values = editor
+                .inline_values
+                .get(&buffer_id)
+                .map(|values| values.values.as_slice())
+                .unwrap_or_default();
+            let texts_by_row = inline_value_texts(
+                &buffer_snapshot,
+                project.read(cx).task_output_values(buffer_id),
+                lsp_values,
+            );
+            if texts_by_row.is_empty() {
+                continue;
+            }
+
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(buffer_id, cx) {
+                let context = excerpt_range.context.to_point(&buffer_snapshot);
+                for (row, texts) in texts_by_row.range(context.start.row..=context.end.row) {
+                    let line_end = Point::new(*row, buffer_snapshot.line_len(*row));
+                    let Some(position) = snapshot
+                        .anchor_in_excerpt(excerpt_id, buffer_snapshot.anchor_after(line_end))
+                    else {
+                        continue;
+                    };
+                    to_insert.push(Inlay::inline_value(
+                        post_inc(&mut editor.next_inlay_id),
+                        position,
+                        format!("  {}", texts.join(", ")),
+                    ));
+                }
+            }
+        }
+    }
+
+    if to_remove.is_empty() && to_insert.is_empty() {
+        return;
+    }
+    editor.inline_value_inlays = to_insert.iter().map(|inlay| inlay.id).collect();
+    editor.splice_inlays(&to_remove, to_insert, cx);
+}
+
+/// The texts to show at the end of each row of a buffer: the values tasks printed for the row,
+/// followed by the language server's values for it. The variables and expressions the language
+/// server asks for are looked up in what the tasks printed, as there is nothing to evaluate them with.
+fn inline_value_texts(
+    snapshot: &text::BufferSnapshot,
+    task_values: &[TaskOutputValue],
+    lsp_values: &[InlineValue],
+) -> BTreeMap<u32, Vec<String>> {
+    let mut texts_by_row = BTreeMap::<u32, Vec<String>>::new();
+    for value in task_values {
+        let text = match &value.expression {
+            Some(expression) => format!("{expression} = {}", value.value),
+            None => value.value.clone(),
+        };
+        texts_by_row
+            .entry(value.position.to_point(snapshot).row)
+            .or_default()
+            .push(truncate_and_trailoff(&text, MAX_VALUE_LENGTH));
+    }
+
+    for value in lsp_values {
+        let range = value.range.to_point(snapshot);
+        let range_text = || snapshot.text_for_range(range.clone()).collect::<String>();
+        let text = match &value.kind {
+            InlineValueKind::Text(text) => text.clone(),
+            InlineValueKind::VariableLookup {
+                variable_name,
+                case_sensitive,
+            } => {
+                let name = variable_name.clone().unwrap_or_else(range_text);
+                let Some(value) = printed_value(task_values, &name, *case_sensitive) else {
+                    continue;
+                };
+                format!("{name} = {value}")
+            }
+            InlineValueKind::EvaluatableExpression { expression } => {
+                let expression = expression.clone().unwrap_or_else(range_text);
+                let Some(value) = printed_value(task_values, &expression, true) else {
+                    continue;
+                };
+                format!("{expression} = {value}")
+            }
+        };
+        let text = truncate_and_trailoff(&text, MAX_VALUE_LENGTH);
+        let row_texts = texts_by_row.entry(range.end.row).or_default();
+        if !row_texts.contains(&text) {
+            row_texts.push(text);
+        }
+    }
+    texts_by_row
+}
+
+/// The value a task printed last for an expression, ignoring references taken to print it.
+fn printed_value<'a>(
+    task_values: &'a [TaskOutputValue],
+    expression: &str,
+    case_sensitive: bool,
+) -> Option<&'a str> {
+    task_values
+        .iter()
+        .rev()
+        .find(|value| {
+            value.expression.as_deref().map_or(false, |printed| {
+                let printed = printed.trim_start_matches('&');
+                if case_sensitive {
+                    printed == expression
+                } else {
+                    printed.eq_ignore_ascii_case(expression)
+                }
+            })
+        })
+        .map(|value| value.value.as_str())
+}
+
+impl Editor {
+    /// Stops showing the values tasks printed for the buffers with a selection.
+    pub fn clear_inline_values(
+        &mut self,
+        _: &ClearInlineValues,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let buffer_ids = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .filter_map(|selection| selection.head().buffer_id)
+            .collect::<HashSet<_>>();
+        project.update(cx, |project, cx| {
+            for buffer_id in buffer_ids {
+                project.clear_task_output_values(buffer_id, cx);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId};
+
+    #[test]
+    fn test_inline_value_texts() {
+        let buffer = Buffer::new(
+            0,
+            BufferId::new(1).unwrap(),
+            "fn main() {\n    let point = Point::new(1, 2);\n    dbg!(&point);\n    let Total = point.x + point.y;\n}\n"
+                .to_string(),
+        );
+        let snapshot = buffer.snapshot();
+        let task_values = vec![
+            TaskOutputValue {
+                position: snapshot.anchor_after(Point::new(2, 0)),
+                expression: Some("&point".to_string()),
+                value: "Point { x: 1, y: 2 }".to_string(),
+            },
+            TaskOutputValue {
+                position: snapshot.anchor_after(Point::new(3, 0)),
+                expression: Some("total".to_string()),
+                value: "3".to_string(),
+            },
+        ];
+        let lsp_value = |start: Point, end: Point, kind: InlineValueKind| InlineValue {
+            range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+            kind,
+        };
+        let lsp_values = vec![
+            lsp_value(
+                Point::new(1, 8),
+                Point::new(1, 13),
+                InlineValueKind::VariableLookup {
+                    variable_name: None,
+                    case_sensitive: true,
+                },
+            ),
+            lsp_value(
+                Point::new(3, 8),
+                Point::new(3, 13),
+                InlineValueKind::VariableLookup {
+                    variable_name: None,
+                    case_sensitive: false,
+                },
+            ),
+            // Nothing printed this expression, so there is no value to show for it.
+            lsp_value(
+                Point::new(3, 16),
+                Point::new(3, 23),
+                InlineValueKind::EvaluatableExpression { expression: None },
+            ),
+            lsp_value(
+                Point::new(0, 3),
+                Point::new(0, 7),
+                InlineValueKind::Text("entry point".to_string()),
+            ),
+        ];
+
+        assert_eq!(
+            inline_value_texts(&snapshot, &task_values, &lsp_values)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![
+                (0, vec!["entry point".to_string()]),
+                (1, vec!["point = Point { x: 1, y: 2 }".to_string()]),
+                (2, vec!["&point = Point { x: 1, y: 2 }".to_string()]),
+                (3, vec!["total = 3".to_string(), "Total = 3".to_string()]),
+            ]
+        );
+    }
+}
@@ -211,6 +211,9 @@ pub fn deploy_context_menu(
                     } else {
                         builder.disabled_action(COPY_PERMALINK_LABEL, Box::new(CopyPermalinkToLine))
                     }
+                })
+                .when(has_git_repo, |builder| {
+                    builder.action("File History", Box::new(git::FileHistory))
                 });
             match focus {
                 Some(focus) => builder.context(focus),
@@ -87,6 +87,13 @@ define_connection!(
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
     // )
+    //
+    // bookmarks(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   buffer_row: u32,
+    //   label: Option<String>,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> = &[
         sql! (
             CREATE TABLE editors(
@@ -134,6 +141,18 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN mtime_seconds INTEGER DEFAULT NULL;
             ALTER TABLE editors ADD COLUMN mtime_nanos INTEGER DEFAULT NULL;
         ),
+        sql! (
+            CREATE TABLE bookmarks(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                buffer_row INTEGER NOT NULL,
+                label TEXT,
+                PRIMARY KEY(workspace_id, path, buffer_row),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
     ];
 );
 
@@ -188,6 +207,36 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_bookmarks(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+            SELECT path, buffer_row, label
+            FROM bookmarks
+            WHERE workspace_id = ?
+            ORDER BY path, buffer_row
+        }
+    }
+
+    /// Replaces the bookmarks of a workspace.
+    pub async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(DELETE FROM bookmarks WHERE workspace_id = ?))?(workspace_id)?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO bookmarks(workspace_id, path, buffer_row, label) VALUES (?, ?, ?, ?)
+                ))?;
+                for (path, row, label) in bookmarks {
+                    insert((workspace_id, path, row, label))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
     pub async fn delete_unloaded_items(
         &self,
         workspace: WorkspaceId,
@@ -294,4 +343,42 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_bookmarks() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        assert_eq!(DB.get_bookmarks(workspace_id).unwrap(), Vec::new());
+
+        let bookmarks = vec![
+            (PathBuf::from("/root/b.rs"), 3, None),
+            (
+                PathBuf::from("/root/a.rs"),
+                10,
+                Some("Entry point".to_owned()),
+            ),
+            (PathBuf::from("/root/a.rs"), 2, None),
+        ];
+        DB.save_bookmarks(workspace_id, bookmarks).await.unwrap();
+        assert_eq!(
+            DB.get_bookmarks(workspace_id).unwrap(),
+            vec![
+                (PathBuf::from("/root/a.rs"), 2, None),
+                (
+                    PathBuf::from("/root/a.rs"),
+                    10,
+                    Some("Entry point".to_owned())
+                ),
+                (PathBuf::from("/root/b.rs"), 3, None),
+            ]
+        );
+
+        // Saving replaces the bookmarks saved before.
+        DB.save_bookmarks(workspace_id, vec![(PathBuf::from("/root/b.rs"), 4, None)])
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_bookmarks(workspace_id).unwrap(),
+            vec![(PathBuf::from("/root/b.rs"), 4, None)]
+        );
+    }
 }
@@ -5,11 +5,11 @@ use futures::{channel::mpsc, future::join_all};
 use gpui::{App, Entity, EventEmitter, Focusable, Render, Subscription, Task};
 use language::{Buffer, BufferEvent, Capability};
 use multi_buffer::{ExcerptRange, MultiBuffer};
-use project::Project;
+use project::{lsp_command::SemanticTokens, Project, WorkspaceEditPreview, WorkspaceFileOperation};
 use smol::stream::StreamExt;
-use std::{any::TypeId, ops::Range, rc::Rc, time::Duration};
-use text::ToOffset;
-use ui::{prelude::*, ButtonLike, KeyBinding};
+use std::{any::TypeId, mem, ops::Range, path::Path, rc::Rc, time::Duration};
+use text::{Point, ToOffset, ToPoint};
+use ui::{prelude::*, ButtonLike, Checkbox, KeyBinding};
 use workspace::{
     searchable::SearchableItemHandle, Item, ItemHandle as _, ToolbarItemEvent, ToolbarItemLocation,
     ToolbarItemView, Workspace,
@@ -20,6 +20,9 @@ pub struct ProposedChangesEditor {
     multibuffer: Entity<MultiBuffer>,
     title: SharedString,
     buffer_entries: Vec<BufferEntry>,
+    project: Option<Entity<Project>>,
+    file_edits: Vec<ProposedFileEdit>,
+    file_operations: Vec<ProposedFileOperation>,
     _recalculate_diffs_task: Task<Option<()>>,
     recalculate_diffs_tx: mpsc::UnboundedSender<RecalculateDiff>,
 }
@@ -35,6 +38,18 @@ struct BufferEntry {
     _subscription: Subscription,
 }
 
+/// The edits a workspace edit makes to one file, which can be rejected as a whole.
+struct ProposedFileEdit {
+    base: Entity<Buffer>,
+    edits: Vec<(Range<text::Anchor>, String)>,
+    accepted: bool,
+}
+
+struct ProposedFileOperation {
+    operation: WorkspaceFileOperation,
+    accepted: bool,
+}
+
 pub struct ProposedChangesEditorToolbar {
     current_editor: Option<Entity<ProposedChangesEditor>>,
 }
@@ -63,7 +78,7 @@ impl ProposedChangesEditor {
         let mut this = Self {
             editor: cx.new(|cx| {
                 let mut editor =
-                    Editor::for_multibuffer(multibuffer.clone(), project, true, window, cx);
+                    Editor::for_multibuffer(multibuffer.clone(), project.clone(), true, window, cx);
                 editor.set_expand_all_diff_hunks(cx);
                 editor.set_completion_provider(None);
                 editor.clear_code_action_providers();
@@ -77,6 +92,9 @@ impl ProposedChangesEditor {
             multibuffer,
             title: title.into(),
             buffer_entries: Vec::new(),
+            project,
+            file_edits: Vec::new(),
+            file_operations: Vec::new(),
             recalculate_diffs_tx,
             _recalculate_diffs_task: cx.spawn_in(window, |this, mut cx| async move {
                 let mut buffers_to_diff = HashSet::default();
@@ -126,6 +144,72 @@ impl ProposedChangesEditor {
         this
     }
 
+    /// Creates an editor that previews a workspace edit, such as a rename, without applying it.
+    ///
+    /// Each file's edits and each file operation can be rejected before the
+    /// accepted ones are applied with [`ApplyAllDiffHunks`].
+    pub fn for_workspace_edit(
+        title: impl Into<SharedString>,
+        preview: WorkspaceEditPreview,
+        project: Option<Entity<Project>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let locations = preview
+            .buffer_edits
+            .iter()
+            .map(|(buffer, edits)| {
+                let snapshot = buffer.read(cx).text_snapshot();
+                let mut line_ranges = edits
+                    .iter()
+                    .map(|(range, _)| {
+                        let start = range.start.to_point(&snapshot);
+                        let end = range.end.to_point(&snapshot);
+                        Point::new(start.row, 0)..Point::new(end.row, snapshot.line_len(end.row))
+                    })
+                    .collect::<Vec<_>>();
+                line_ranges.sort_by_key(|range| range.start);
+
+                let mut ranges: Vec<Range<Point>> = Vec::new();
+                for range in line_ranges {
+                    match ranges.last_mut() {
+                        Some(last) if last.end.row + 1 >= range.start.row => {
+                            last.end = last.end.max(range.end);
+                        }
+                        _ => ranges.push(range),
+                    }
+                }
+                ProposedChangeLocation {
+                    buffer: buffer.clone(),
+                    ranges,
+                }
+            })
+            .collect();
+
+        let mut this = Self::new(title, locations, project, window, cx);
+        this.file_edits = preview
+            .buffer_edits
+            .into_iter()
+            .map(|(base, edits)| ProposedFileEdit {
+                base,
+                edits,
+                accepted: true,
+            })
+            .collect();
+        this.file_operations = preview
+            .file_operations
+            .into_iter()
+            .map(|operation| ProposedFileOperation {
+                operation,
+                accepted: true,
+            })
+            .collect();
+        for ix in 0..this.file_edits.len() {
+            this.apply_file_edit(ix, cx);
+        }
+        this
+    }
+
     pub fn branch_buffer_for_base(&self, base_buffer: &Entity<Buffer>) -> Option<Entity<Buffer>> {
         self.buffer_entries.iter().find_map(|entry| {
             if &entry.base == base_buffer {
@@ -149,21 +233,7 @@ impl ProposedChangesEditor {
     ) {
         // Undo all branch changes
         for entry in &self.buffer_entries {
-            let base_version = entry.base.read(cx).version();
-            entry.branch.update(cx, |buffer, cx| {
-                let undo_counts = buffer
-                    .operations()
-                    .iter()
-                    .filter_map(|(timestamp, _)| {
-                        if !base_version.observed(*timestamp) {
-                            Some((*timestamp, u32::MAX))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                buffer.undo_operations(undo_counts, cx);
-            });
+            undo_branch_changes(&entry.base, &entry.branch, cx);
         }
 
         self.multibuffer.update(cx, |multibuffer, cx| {
@@ -223,6 +293,152 @@ impl ProposedChangesEditor {
         });
     }
 
+    fn apply_file_edit(&self, ix: usize, cx: &mut App) {
+        let file_edit = &self.file_edits[ix];
+        if let Some(branch) = self.branch_buffer_for_base(&file_edit.base) {
+            let edits = file_edit.edits.clone();
+            branch.update(cx, |branch, cx| branch.edit(edits, None, cx));
+        }
+    }
+
+    fn toggle_file_edit(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let file_edit = &mut self.file_edits[ix];
+        file_edit.accepted = !file_edit.accepted;
+        if file_edit.accepted {
+            self.apply_file_edit(ix, cx);
+        } else {
+            let base = file_edit.base.clone();
+            if let Some(branch) = self.branch_buffer_for_base(&base) {
+                undo_branch_changes(&base, &branch, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn toggle_file_operation(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let file_operation = &mut self.file_operations[ix];
+        file_operation.accepted = !file_operation.accepted;
+        cx.notify();
+    }
+
+    /// Writes the accepted part of a previewed workspace edit: merges the
+    /// accepted files into their buffers, saves them, and then performs the
+    /// accepted file operations.
+    fn apply_workspace_edit(
+        &mut self,
+        _: &ApplyAllDiffHunks,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.file_edits.is_empty() && self.file_operations.is_empty() {
+            return;
+        }
+        cx.stop_propagation();
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let mut buffers_to_save = HashSet::default();
+        for file_edit in mem::take(&mut self.file_edits) {
+            if !file_edit.accepted {
+                continue;
+            }
+            if let Some(branch) = self.branch_buffer_for_base(&file_edit.base) {
+                branch.update(cx, |branch, cx| branch.merge_into_base(Vec::new(), cx));
+                buffers_to_save.insert(file_edit.base);
+            }
+        }
+        let operations = mem::take(&mut self.file_operations)
+            .into_iter()
+            .filter(|file_operation| file_operation.accepted)
+            .map(|file_operation| file_operation.operation)
+            .collect::<Vec<_>>();
+        cx.notify();
+
+        let save = project.update(cx, |project, cx| project.save_buffers(buffers_to_save, cx));
+        cx.spawn(|_, mut cx| async move {
+            save.await?;
+            for operation in operations {
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.apply_file_operation(operation, cx)
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_workspace_edit(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        let file_edits = self
+            .file_edits
+            .iter()
+            .enumerate()
+            .map(|(ix, file_edit)| {
+                let path = file_edit
+                    .base
+                    .read(cx)
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "untitled".to_string());
+                let this = this.clone();
+                Checkbox::new(("file-edit", ix), file_edit.accepted.into())
+                    .label(format!("Edit {path}"))
+                    .on_click(move |_, _, cx| {
+                        this.update(cx, |this, cx| this.toggle_file_edit(ix, cx))
+                            .ok();
+                    })
+            })
+            .collect::<Vec<_>>();
+        let file_operations = self
+            .file_operations
+            .iter()
+            .enumerate()
+            .map(|(ix, file_operation)| {
+                let label = match &file_operation.operation {
+                    WorkspaceFileOperation::Create { path, .. } => {
+                        format!("Create {}", self.display_path(path, cx))
+                    }
+                    WorkspaceFileOperation::Rename { old_path, new_path } => format!(
+                        "Rename {} to {}",
+                        self.display_path(old_path, cx),
+                        self.display_path(new_path, cx)
+                    ),
+                    WorkspaceFileOperation::Delete { path } => {
+                        format!("Delete {}", self.display_path(path, cx))
+                    }
+                };
+                let this = this.clone();
+                Checkbox::new(("file-operation", ix), file_operation.accepted.into())
+                    .label(label)
+                    .on_click(move |_, _, cx| {
+                        this.update(cx, |this, cx| this.toggle_file_operation(ix, cx))
+                            .ok();
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .p_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(file_edits)
+            .children(file_operations)
+    }
+
+    fn display_path(&self, abs_path: &Path, cx: &App) -> String {
+        self.project
+            .as_ref()
+            .and_then(|project| project.read(cx).find_worktree(abs_path, cx))
+            .map_or_else(
+                || abs_path.to_string_lossy().into_owned(),
+                |(_, path)| path.to_string_lossy().into_owned(),
+            )
+    }
+
     pub fn recalculate_all_buffer_diffs(&self) {
         for (ix, entry) in self.buffer_entries.iter().enumerate().rev() {
             self.recalculate_diffs_tx
@@ -262,12 +478,36 @@ impl ProposedChangesEditor {
     }
 }
 
+/// Undoes the changes to a branch buffer that its base buffer doesn't know about.
+fn undo_branch_changes(base: &Entity<Buffer>, branch: &Entity<Buffer>, cx: &mut App) {
+    let base_version = base.read(cx).version();
+    branch.update(cx, |buffer, cx| {
+        let undo_counts = buffer
+            .operations()
+            .iter()
+            .filter_map(|(timestamp, _)| {
+                if !base_version.observed(*timestamp) {
+                    Some((*timestamp, u32::MAX))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        buffer.undo_operations(undo_counts, cx);
+    });
+}
+
 impl Render for ProposedChangesEditor {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
-        div()
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_workspace_edit = !self.file_edits.is_empty() || !self.file_operations.is_empty();
+        v_flex()
             .size_full()
             .key_context("ProposedChangesEditor")
-            .child(self.editor.clone())
+            .capture_action(cx.listener(Self::apply_workspace_edit))
+            .when(has_workspace_edit, |this| {
+                this.child(self.render_workspace_edit(cx))
+            })
+            .child(div().flex_1().child(self.editor.clone()))
     }
 }
 
@@ -467,6 +707,92 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         self.0.resolve_inlay_hint(hint, buffer, server_id, cx)
     }
 
+    fn semantic_tokens(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<SemanticTokens>>>> {
+        // The language server only knows about the base buffer, whose tokens don't match the branch.
+        None
+    }
+
+    fn code_lens(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::CodeLens>>>> {
+        None
+    }
+
+    fn resolve_code_lens(
+        &self,
+        _: &Entity<Buffer>,
+        _: project::CodeLens,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<project::CodeLens>>> {
+        None
+    }
+
+    fn apply_code_lens(
+        &self,
+        _: Entity<Buffer>,
+        _: project::CodeLens,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<project::ProjectTransaction>>> {
+        None
+    }
+
+    fn document_links(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::DocumentLink>>>> {
+        None
+    }
+
+    fn folding_ranges(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<Range<text::Anchor>>>>> {
+        None
+    }
+
+    fn inline_values(
+        &self,
+        _: &Entity<Buffer>,
+        _: Range<text::Anchor>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::InlineValue>>>> {
+        None
+    }
+
+    fn document_colors(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<Vec<project::DocumentColor>>> {
+        None
+    }
+
+    fn color_presentations(
+        &self,
+        _: &Entity<Buffer>,
+        _: project::DocumentColor,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::ColorPresentation>>>> {
+        None
+    }
+
+    fn selection_ranges(
+        &self,
+        _: &Entity<Buffer>,
+        _: Vec<text::Anchor>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<Range<text::Anchor>>>>> {
+        None
+    }
+
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
         if let Some(buffer) = self.to_base(&buffer, &[], cx) {
             self.0.supports_inlay_hints(&buffer, cx)
@@ -514,4 +840,14 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
     ) -> Option<Task<gpui::Result<project::ProjectTransaction>>> {
         None
     }
+
+    fn rename_edits(
+        &self,
+        _: &Entity<Buffer>,
+        _: text::Anchor,
+        _: String,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<project::WorkspaceEditPreview>>> {
+        None
+    }
 }
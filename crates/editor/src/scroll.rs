@@ -5,11 +5,13 @@ pub(crate) mod scroll_amount;
 use crate::editor_settings::{ScrollBeyondLastLine, ScrollbarAxes};
 use crate::EditPredictionPreview;
 use crate::{
+    code_lens,
     display_map::{DisplaySnapshot, ToDisplayPoint},
+    folding_ranges,
     hover_popover::hide_hover,
     persistence::DB,
-    Anchor, DisplayPoint, DisplayRow, Editor, EditorEvent, EditorMode, EditorSettings,
-    InlayHintRefreshReason, MultiBufferSnapshot, RowExt, ToPoint,
+    semantic_tokens, Anchor, DisplayPoint, DisplayRow, Editor, EditorEvent, EditorMode,
+    EditorSettings, InlayHintRefreshReason, MultiBufferSnapshot, RowExt, ToPoint,
 };
 pub use autoscroll::{Autoscroll, AutoscrollStrategy};
 use core::fmt::Debug;
@@ -182,6 +184,7 @@ pub struct ScrollManager {
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
     dragging_scrollbar: AxisPair<bool>,
+    dragging_minimap: bool,
     visible_line_count: Option<f32>,
     forbid_vertical_scroll: bool,
 }
@@ -196,6 +199,7 @@ impl ScrollManager {
             show_scrollbars: true,
             hide_scrollbar_task: None,
             dragging_scrollbar: axis_pair(false, false),
+            dragging_minimap: false,
             last_autoscroll: None,
             visible_line_count: None,
             forbid_vertical_scroll: false,
@@ -382,6 +386,15 @@ impl ScrollManager {
         cx.notify();
     }
 
+    pub fn is_dragging_minimap(&self) -> bool {
+        self.dragging_minimap
+    }
+
+    pub fn set_is_dragging_minimap(&mut self, dragging: bool, cx: &mut Context<Editor>) {
+        self.dragging_minimap = dragging;
+        cx.notify();
+    }
+
     pub fn clamp_scroll_left(&mut self, max: f32) -> bool {
         if max < self.anchor.offset.x {
             self.anchor.offset.x = max;
@@ -431,7 +444,10 @@ impl Editor {
             cx.spawn_in(window, |editor, mut cx| async move {
                 editor
                     .update(&mut cx, |editor, cx| {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
+                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                        semantic_tokens::refresh_semantic_tokens(editor, false, cx);
+                        code_lens::refresh_code_lens(editor, false, cx);
+                        folding_ranges::refresh_folding_ranges(editor, false, cx);
                     })
                     .ok()
             })
@@ -516,6 +532,9 @@ impl Editor {
         );
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+        semantic_tokens::refresh_semantic_tokens(self, true, cx);
+        code_lens::refresh_code_lens(self, true, cx);
+        folding_ranges::refresh_folding_ranges(self, true, cx);
     }
 
     pub fn scroll_position(&self, cx: &mut Context<Self>) -> gpui::Point<f32> {
@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{App, Context, Entity, Window};
+use language::{
+    language_settings::{language_settings, SelectionRangesProvider},
+    Buffer,
+};
+use multi_buffer::{Anchor, ExcerptId};
+use text::BufferId;
+
+use crate::{Editor, EditorMode};
+
+/// Asks the language servers of the buffers under the cursors for the ranges the selections
+/// could be expanded to, when their language prefers these over its syntax tree, and runs
+/// `then` once they are in the display map, for [`Editor::select_larger_syntax_node`] and
+/// Vim's selection range object.
+///
+/// Returns `false` without running `then` when no language server is asked, in which case the
+/// ranges of the syntax tree are used right away. Requests made while another one is pending
+/// wait for it, so that each of them expands the selections left by the previous one.
+pub(super) fn fetch_selection_ranges(
+    editor: &mut Editor,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+    then: impl 'static + FnOnce(&mut Window, &mut App),
+) -> bool {
+    if editor.mode != EditorMode::Full
+        || editor.semantics_provider.is_none()
+        || positions_by_buffer(editor, cx).is_empty()
+    {
+        return false;
+    }
+
+    let previous_task = editor.selection_ranges_task.take();
+    editor.selection_ranges_task = Some(cx.spawn_in(window, |editor, mut cx| async move {
+        if let Some(previous_task) = previous_task {
+            previous_task.await;
+        }
+
+        let requests = editor
+            .update(&mut cx, |editor, cx| {
+                let provider = editor.semantics_provider.clone()?;
+                Some(
+                    positions_by_buffer(editor, cx)
+                        .into_values()
+                        .filter_map(|(buffer, excerpt_ids, positions)| {
+                            let request = provider.selection_ranges(&buffer, positions, cx)?;
+                            Some(async move { (excerpt_ids, request.await) })
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .ok()?
+            .unwrap_or_default();
+        let responses = join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer.read(cx).snapshot(cx);
+                let mut ranges = Vec::<Range<Anchor>>::new();
+                for (excerpt_ids, response) in responses {
+                    let buffer_ranges = match response {
+                        Ok(ranges) => ranges,
+                        Err(error) => {
+                            log::error!("failed to fetch selection ranges: {error:#}");
+                            continue;
+                        }
+                    };
+                    for excerpt_id in excerpt_ids {
+                        ranges.extend(buffer_ranges.iter().filter_map(|range| {
+                            Some(
+                                snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                                    ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                            )
+                        }));
+                    }
+                }
+                editor.display_map.update(cx, |display_map, _| {
+                    display_map.set_selection_ranges(ranges)
+                });
+            })
+            .ok()?;
+        cx.update(then).ok()
+    }));
+    true
+}
+
+/// The positions of the cursors in the buffers that prefer the ranges of their language server.
+fn positions_by_buffer(
+    editor: &Editor,
+    cx: &App,
+) -> HashMap<BufferId, (Entity<Buffer>, HashSet<ExcerptId>, Vec<text::Anchor>)> {
+    let mut positions_by_buffer =
+        HashMap::<BufferId, (Entity<Buffer>, HashSet<ExcerptId>, Vec<text::Anchor>)>::default();
+    let multi_buffer = editor.buffer.read(cx);
+    for selection in editor.selections.disjoint_anchors().iter() {
+        let head = selection.head();
+        let Some(buffer) = head
+            .buffer_id
+            .and_then(|buffer_id| multi_buffer.buffer(buffer_id))
+        else {
+            continue;
+        };
+        let buffer_ref = buffer.read(cx);
+        let preferred = language_settings(
+            buffer_ref.language().map(|language| language.name()),
+            buffer_ref.file(),
+            cx,
+        )
+        .selection_ranges;
+        if preferred != SelectionRangesProvider::LanguageServer {
+            continue;
+        }
+        let (_, excerpt_ids, positions) = positions_by_buffer
+            .entry(buffer_ref.remote_id())
+            .or_insert_with(|| (buffer.clone(), HashSet::default(), Vec::new()));
+        excerpt_ids.insert(head.excerpt_id);
+        positions.push(head.text_anchor);
+    }
+    positions_by_buffer
+}
+
+/// Forgets the selection ranges, which might not match the edited buffers anymore.
+pub(super) fn buffer_edited(editor: &mut Editor, cx: &mut Context<Editor>) {
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_selection_ranges(Vec::new())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext,
+        SelectLargerSyntaxNode, SelectSmallerSyntaxNode,
+    };
+    use indoc::indoc;
+    use language::language_settings::SelectionRangesProvider;
+
+    #[gpui::test]
+    async fn test_select_larger_language_server_range(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.selection_ranges = Some(SelectionRangesProvider::LanguageServer);
+        });
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let _requests = cx.handle_request::<lsp::request::SelectionRangeRequest, _, _>(
+            |_, params, _| async move {
+                let range = |start, end| {
+                    lsp::Range::new(lsp::Position::new(1, start), lsp::Position::new(1, end))
+                };
+                // The syntax tree has no node for the assignment without its `let`, nor for
+                // the sum without its last operand.
+                let (word, expression) = if params.positions[0].character < 18 {
+                    (range(12, 15), range(8, 19))
+                } else {
+                    (range(18, 19), range(12, 19))
+                };
+                Ok(Some(vec![lsp::SelectionRange {
+                    range: word,
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: expression,
+                        parent: None,
+                    })),
+                }]))
+            },
+        );
+        cx.set_state(indoc! {"
+            fn main() {
+                let x = aˇbc + 1;
+            }
+        "});
+
+        // The ranges are requested when expanding the selection, and awaited.
+        cx.update_editor(|editor, window, cx| {
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            fn main() {
+                let x = «abcˇ» + 1;
+            }
+        "});
+        // Expanding twice in a row expands the selection left by the first expansion.
+        cx.update_editor(|editor, window, cx| {
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        });
+        cx.run_until_parked();
+        // Past the largest range reported by the server, selections follow the syntax tree.
+        cx.assert_editor_state(indoc! {"
+            fn main() {
+                «let x = abc + 1;ˇ»
+            }
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+        });
+        cx.assert_editor_state(indoc! {"
+            fn main() {
+                let «x = abc + 1ˇ»;
+            }
+        "});
+
+        // Right after moving the cursor, the ranges around its new position are used.
+        cx.set_state(indoc! {"
+            fn main() {
+                let x = abc + ˇ1;
+            }
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            fn main() {
+                let x = «abc + 1ˇ»;
+            }
+        "});
+
+        // Edits make the ranges out of date.
+        cx.simulate_keystroke("x");
+        cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            assert_eq!(
+                snapshot.display_snapshot.larger_selection_range(25..25),
+                None
+            );
+        });
+    }
+}
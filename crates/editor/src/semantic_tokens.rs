@@ -0,0 +1,131 @@
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{Context, HighlightStyle};
+use project::lsp_command::{SemanticToken, SemanticTokens};
+use std::time::Duration;
+use theme::{ActiveTheme as _, Theme};
+
+use crate::{Editor, EditorMode};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Requests the semantic tokens of the visible buffers whose tokens are out of date, and
+/// highlights them once they arrive.
+pub(super) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) -> Option<()> {
+    if editor.mode != EditorMode::Full {
+        return None;
+    }
+    let provider = editor.semantics_provider.clone()?;
+
+    editor.semantic_tokens_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+        }
+
+        let requests = editor
+            .update(&mut cx, |editor, cx| {
+                let mut buffers = HashMap::default();
+                for (buffer, _, _) in editor
+                    .excerpts_for_inlay_hints_query(None, cx)
+                    .into_values()
+                {
+                    buffers.insert(buffer.read(cx).remote_id(), buffer);
+                }
+                buffers
+                    .into_iter()
+                    .filter_map(|(buffer_id, buffer)| {
+                        let request = provider.semantic_tokens(&buffer, cx)?;
+                        Some(async move { (buffer_id, request.await) })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .ok()?;
+        let responses = join_all(requests).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, response) in responses {
+                    match response {
+                        Ok(Some(tokens)) => {
+                            editor.semantic_tokens.insert(buffer_id, tokens);
+                        }
+                        Ok(None) => {
+                            editor.semantic_tokens.remove(&buffer_id);
+                        }
+                        Err(error) => {
+                            log::error!("failed to fetch semantic tokens: {error:#}");
+                        }
+                    }
+                }
+                highlight_semantic_tokens(editor, cx);
+            })
+            .ok()
+    }));
+    None
+}
+
+/// Styles the known semantic tokens with the current theme and hands them to the display map.
+pub(super) fn highlight_semantic_tokens(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let multi_buffer = editor.buffer.read(cx);
+    editor
+        .semantic_tokens
+        .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+    let theme = cx.theme().clone();
+    let snapshot = multi_buffer.snapshot(cx);
+    let mut highlights_by_buffer = Vec::new();
+    for (buffer_id, tokens) in &editor.semantic_tokens {
+        let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+            continue;
+        };
+        let buffer = buffer.read(cx);
+        let mut styles = HashMap::default();
+        let mut highlights = Vec::new();
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+            let context = excerpt_range.context;
+            for token in tokens.tokens.iter() {
+                if token.range.end.cmp(&context.start, buffer).is_lt()
+                    || token.range.start.cmp(&context.end, buffer).is_gt()
+                {
+                    continue;
+                }
+                let style = *styles
+                    .entry((token.token_type, token.modifiers))
+                    .or_insert_with(|| token_style(tokens, token, &theme));
+                let Some(style) = style else {
+                    continue;
+                };
+                let (Some(start), Some(end)) = (
+                    snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                    snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+                ) else {
+                    continue;
+                };
+                highlights.push((start..end, style));
+            }
+        }
+        highlights.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start, &snapshot));
+        highlights_by_buffer.push((*buffer_id, highlights));
+    }
+
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_semantic_token_highlights(highlights_by_buffer);
+    });
+    cx.notify();
+}
+
+fn token_style(
+    tokens: &SemanticTokens,
+    token: &SemanticToken,
+    theme: &Theme,
+) -> Option<HighlightStyle> {
+    theme.semantic_tokens().style(
+        tokens.token_type(token)?,
+        tokens.modifiers(token),
+        theme.syntax(),
+    )
+}
@@ -0,0 +1,73 @@
+use editor::Editor;
+use gpui::{Action as _, Corner, Entity, Subscription};
+use language::Encoding;
+use ui::{prelude::*, ContextMenu, PopoverMenu, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+use crate::{ReopenWithEncoding, SaveWithEncoding};
+
+/// Shows the encoding of the active buffer's file in the status bar, with a menu to reopen
+/// or save the file with another encoding.
+#[derive(Default)]
+pub struct ActiveBufferEncoding {
+    active_encoding: Option<Encoding>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl ActiveBufferEncoding {
+    fn update_encoding(&mut self, editor: Entity<Editor>, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_encoding = None;
+
+        let editor = editor.read(cx);
+        if let Some((_, buffer, _)) = editor.active_excerpt(cx) {
+            let buffer = buffer.read(cx);
+            if buffer.file().is_some() {
+                self.active_encoding = Some(buffer.encoding());
+            }
+        }
+
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferEncoding {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().when_some(self.active_encoding, |el, encoding| {
+            el.child(
+                PopoverMenu::new("encoding-menu")
+                    .trigger_with_tooltip(
+                        Button::new("change-encoding", encoding.to_string())
+                            .label_size(LabelSize::Small),
+                        Tooltip::text("Change Encoding"),
+                    )
+                    .anchor(Corner::BottomRight)
+                    .menu(|window, cx| {
+                        Some(ContextMenu::build(window, cx, |menu, _, _| {
+                            menu.action("Reopen with Encoding", ReopenWithEncoding.boxed_clone())
+                                .action("Save with Encoding", SaveWithEncoding.boxed_clone())
+                        }))
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferEncoding {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor =
+                Some(cx.observe_in(&editor, window, Self::update_encoding));
+            self.update_encoding(editor, window, cx);
+        } else {
+            self.active_encoding = None;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
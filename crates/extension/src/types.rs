@@ -18,6 +18,20 @@ pub struct Command {
     pub args: Vec<String>,
     /// The environment variables to set for the command.
     pub env: EnvVars,
+    /// How to communicate with the process started by the command.
+    pub transport: Transport,
+}
+
+/// How to communicate with a process started by a [`Command`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// Over the standard input and output of the process.
+    #[default]
+    Stdio,
+    /// Over a connection to a TCP socket.
+    Tcp { host: String, port: u16 },
+    /// Over a connection to a Unix domain socket.
+    UnixSocket { path: String },
 }
 
 /// A label containing some code.
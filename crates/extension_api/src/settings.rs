@@ -1,6 +1,6 @@
 //! Provides access to Zed settings.
 
-#[path = "../wit/since_v0.2.0/settings.rs"]
+#[path = "../wit/since_v0.3.0/settings.rs"]
 mod types;
 
 use crate::{wit, Project, Result, SettingsLocation, Worktree};
@@ -63,6 +63,7 @@ impl From<Command> for latest::Command {
             command: value.command,
             args: value.args,
             env: value.env,
+            transport: latest::Transport::Stdio,
         }
     }
 }
@@ -0,0 +1,395 @@
+use std::{borrow::Cow, fmt};
+
+use anyhow::{anyhow, Result};
+use chardetng::EncodingDetector;
+use encoding_rs::EncoderResult;
+
+/// The character encoding of a text file, along with whether the file starts with a byte
+/// order mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::new(encoding_rs::UTF_8)
+    }
+}
+
+/// The error returned when a file's contents aren't text in any known encoding, as with
+/// binary files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidTextError;
+
+impl fmt::Display for InvalidTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("file is not valid text in any known encoding")
+    }
+}
+
+impl std::error::Error for InvalidTextError {}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.bom {
+            write!(f, "{} with BOM", self.name())
+        } else {
+            f.write_str(self.name())
+        }
+    }
+}
+
+impl Encoding {
+    fn new(encoding: &'static encoding_rs::Encoding) -> Self {
+        Self {
+            encoding,
+            bom: false,
+        }
+    }
+
+    /// Looks up an encoding by one of its labels, such as `utf-8`, `shift_jis` or `latin1`.
+    pub fn for_label(label: &str) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())?;
+        // Some labels map to encodings that can only be read, not written.
+        let can_encode = encoding == encoding.output_encoding()
+            || encoding == encoding_rs::UTF_16LE
+            || encoding == encoding_rs::UTF_16BE;
+        can_encode.then(|| Self::new(encoding))
+    }
+
+    /// The encodings to pick from when reopening or saving a file with an encoding of the
+    /// user's choice.
+    pub fn all() -> Vec<Self> {
+        let mut encodings = Vec::new();
+        for encoding in [
+            encoding_rs::UTF_8,
+            encoding_rs::UTF_16LE,
+            encoding_rs::UTF_16BE,
+        ] {
+            encodings.push(Self::new(encoding));
+            encodings.push(Self::new(encoding).with_bom(true));
+        }
+        encodings.extend(
+            [
+                encoding_rs::WINDOWS_1252,
+                encoding_rs::ISO_8859_15,
+                encoding_rs::WINDOWS_1250,
+                encoding_rs::ISO_8859_2,
+                encoding_rs::WINDOWS_1251,
+                encoding_rs::KOI8_R,
+                encoding_rs::KOI8_U,
+                encoding_rs::IBM866,
+                encoding_rs::WINDOWS_1253,
+                encoding_rs::WINDOWS_1254,
+                encoding_rs::WINDOWS_1255,
+                encoding_rs::WINDOWS_1256,
+                encoding_rs::WINDOWS_1257,
+                encoding_rs::WINDOWS_1258,
+                encoding_rs::WINDOWS_874,
+                encoding_rs::MACINTOSH,
+                encoding_rs::SHIFT_JIS,
+                encoding_rs::EUC_JP,
+                encoding_rs::ISO_2022_JP,
+                encoding_rs::GBK,
+                encoding_rs::GB18030,
+                encoding_rs::BIG5,
+                encoding_rs::EUC_KR,
+            ]
+            .into_iter()
+            .map(Self::new),
+        );
+        encodings
+    }
+
+    /// Detects the encoding of a file's contents. A byte order mark takes precedence over the
+    /// `configured` encoding, which in turn takes precedence over guessing the encoding from
+    /// the contents, preferring UTF-8 whenever they are valid UTF-8. The configured encoding
+    /// is only used for contents that are valid in it and don't look binary.
+    pub fn detect(bytes: &[u8], configured: Option<Self>) -> Result<Self> {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Ok(Self::new(encoding).with_bom(true));
+        }
+        if let Some(configured) = configured {
+            // Only UTF-16 text has zero bytes, so they are checked for below otherwise.
+            if (configured.is_utf16() || !bytes.contains(&0)) && configured.is_valid(bytes) {
+                return Ok(configured.with_bom(false));
+            }
+        }
+        if let Some(encoding) = detect_utf16(bytes) {
+            return Ok(Self::new(encoding));
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Ok(Self::default());
+        }
+        if bytes.contains(&0) {
+            return Err(InvalidTextError.into());
+        }
+
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        Ok(Self::new(detector.guess(None, false)))
+    }
+
+    /// The name of the encoding, such as `UTF-8` or `Shift_JIS`.
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    /// Whether the encoding is UTF-8, with or without a byte order mark.
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    fn is_utf16(&self) -> bool {
+        self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE
+    }
+
+    /// Whether the bytes, without a byte order mark, are free of malformed sequences in this
+    /// encoding.
+    fn is_valid(&self, bytes: &[u8]) -> bool {
+        if self.is_utf8() {
+            std::str::from_utf8(bytes).is_ok()
+        } else {
+            self.encoding
+                .decode_without_bom_handling_and_without_replacement(bytes)
+                .is_some()
+        }
+    }
+
+    /// Whether files in this encoding start with a byte order mark.
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Returns this encoding with or without a byte order mark. Encodings other than UTF-8 and
+    /// UTF-16 never have one.
+    pub fn with_bom(self, bom: bool) -> Self {
+        Self {
+            bom: bom && !self.encoding_bom().is_empty(),
+            ..self
+        }
+    }
+
+    /// The bytes files in this encoding start with, empty if they don't have a byte order mark.
+    pub fn byte_order_mark(&self) -> &'static [u8] {
+        if self.bom {
+            self.encoding_bom()
+        } else {
+            &[]
+        }
+    }
+
+    fn encoding_bom(&self) -> &'static [u8] {
+        if self.encoding == encoding_rs::UTF_8 {
+            b"\xEF\xBB\xBF"
+        } else if self.encoding == encoding_rs::UTF_16LE {
+            b"\xFF\xFE"
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            b"\xFE\xFF"
+        } else {
+            &[]
+        }
+    }
+
+    /// Decodes a file's contents. Returns the text along with this encoding, recording whether
+    /// the contents started with its byte order mark.
+    ///
+    /// Fails with an [`InvalidTextError`] if the contents have malformed sequences, as
+    /// replacing them would corrupt the file when it is saved.
+    pub fn decode(self, mut bytes: Vec<u8>) -> Result<(String, Self)> {
+        let bom = self.encoding_bom();
+        let has_bom = !bom.is_empty() && bytes.starts_with(bom);
+        if has_bom {
+            bytes.drain(..bom.len());
+        }
+
+        let text = if self.is_utf8() {
+            String::from_utf8(bytes).ok()
+        } else {
+            self.encoding
+                .decode_without_bom_handling_and_without_replacement(&bytes)
+                .map(Cow::into_owned)
+        };
+        let text = text.ok_or_else(|| {
+            anyhow::Error::new(InvalidTextError)
+                .context(format!("file is not valid {}", self.name()))
+        })?;
+        Ok((text, self.with_bom(has_bom)))
+    }
+
+    /// Encodes text, without the byte order mark, failing if the text contains characters
+    /// the encoding can't represent.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        if self.is_utf8() {
+            return Ok(Cow::Borrowed(text.as_bytes()));
+        }
+        // encoding_rs only encodes UTF-16 as UTF-8, following the WHATWG Encoding Standard.
+        if self.encoding == encoding_rs::UTF_16LE {
+            return Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect());
+        }
+        if self.encoding == encoding_rs::UTF_16BE {
+            return Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect());
+        }
+
+        let mut encoder = self.encoding.new_encoder();
+        let capacity = encoder
+            .max_buffer_length_from_utf8_without_replacement(text.len())
+            .ok_or_else(|| anyhow!("text is too long to encode"))?;
+        let mut bytes = Vec::with_capacity(capacity);
+        let (result, _) =
+            encoder.encode_from_utf8_to_vec_without_replacement(text, &mut bytes, true);
+        match result {
+            EncoderResult::InputEmpty => Ok(Cow::Owned(bytes)),
+            EncoderResult::Unmappable(character) => {
+                Err(anyhow!("{character:?} can't be encoded in {}", self.name()))
+            }
+            EncoderResult::OutputFull => Err(anyhow!("failed to encode text in {}", self.name())),
+        }
+    }
+}
+
+/// Recognizes UTF-16 without a byte order mark by the zero bytes that ASCII characters leave
+/// in every other position.
+fn detect_utf16(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+
+    let mut zero_high_bytes_le = 0;
+    let mut zero_high_bytes_be = 0;
+    for unit in bytes.chunks_exact(2) {
+        match (unit[0] == 0, unit[1] == 0) {
+            (true, true) => return None,
+            (false, true) => zero_high_bytes_le += 1,
+            (true, false) => zero_high_bytes_be += 1,
+            (false, false) => {}
+        }
+    }
+
+    let units = bytes.len() / 2;
+    if zero_high_bytes_be == 0 && zero_high_bytes_le * 2 > units {
+        Some(encoding_rs::UTF_16LE)
+    } else if zero_high_bytes_le == 0 && zero_high_bytes_be * 2 > units {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoding(label: &str) -> Encoding {
+        Encoding::for_label(label).unwrap()
+    }
+
+    #[test]
+    fn test_detect() {
+        let shift_jis = encoding("shift_jis");
+
+        assert_eq!(
+            Encoding::detect(b"fn main() {}\n", None).unwrap(),
+            Encoding::default()
+        );
+        assert_eq!(
+            Encoding::detect("caf\u{e9}\n".as_bytes(), None).unwrap(),
+            Encoding::default()
+        );
+        assert_eq!(
+            Encoding::detect(b"\xEF\xBB\xBFabc", Some(shift_jis)).unwrap(),
+            Encoding::default().with_bom(true)
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFF\xFEa\0b\0", None).unwrap(),
+            encoding("utf-16le").with_bom(true)
+        );
+        assert_eq!(
+            Encoding::detect(b"abc", Some(shift_jis)).unwrap(),
+            shift_jis
+        );
+        assert_eq!(
+            Encoding::detect(b"a\0b\0\n\0", None).unwrap(),
+            encoding("utf-16le")
+        );
+        assert_eq!(
+            Encoding::detect(b"\0a\0b\0\n", None).unwrap(),
+            encoding("utf-16be")
+        );
+        assert!(Encoding::detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff", None)
+            .unwrap_err()
+            .is::<InvalidTextError>());
+    }
+
+    #[test]
+    fn test_detect_with_invalid_configured_encoding() {
+        // Binary files aren't read in the configured encoding.
+        for configured in [Encoding::default(), encoding("shift_jis")] {
+            assert!(
+                Encoding::detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff", Some(configured))
+                    .unwrap_err()
+                    .is::<InvalidTextError>(),
+                "{configured}"
+            );
+        }
+
+        // Contents that aren't valid in the configured encoding are detected instead.
+        let detected = Encoding::detect(b"caf\xE9\n", Some(Encoding::default())).unwrap();
+        assert!(!detected.is_utf8());
+        assert_eq!(
+            detected.decode(b"caf\xE9\n".to_vec()).unwrap().0,
+            "caf\u{e9}\n"
+        );
+
+        // Decoding them anyway fails rather than replacing the malformed bytes.
+        assert!(Encoding::default()
+            .decode(b"caf\xE9\n".to_vec())
+            .unwrap_err()
+            .is::<InvalidTextError>());
+        assert!(encoding("shift_jis")
+            .decode(b"\x82".to_vec())
+            .unwrap_err()
+            .is::<InvalidTextError>());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "let greeting = \"\u{3053}\u{3093}\u{306b}\u{3061}\u{306f}\";\n";
+        for encoding in [
+            Encoding::default(),
+            Encoding::default().with_bom(true),
+            encoding("utf-16le").with_bom(true),
+            encoding("utf-16be"),
+            encoding("shift_jis"),
+            encoding("euc-jp"),
+            encoding("gb18030"),
+        ] {
+            let mut bytes = encoding.byte_order_mark().to_vec();
+            bytes.extend_from_slice(&encoding.encode(text).unwrap());
+            assert_eq!(
+                Encoding::detect(&bytes, Some(encoding)).unwrap(),
+                encoding,
+                "{encoding}"
+            );
+            assert_eq!(
+                encoding.decode(bytes).unwrap(),
+                (text.to_string(), encoding)
+            );
+        }
+
+        let windows_1252 = encoding("latin1");
+        assert_eq!(windows_1252.name(), "windows-1252");
+        assert_eq!(
+            windows_1252.encode("caf\u{e9}").unwrap().as_ref(),
+            b"caf\xE9"
+        );
+        assert!(windows_1252.encode("\u{3053}").is_err());
+        assert_eq!(
+            windows_1252.decode(b"caf\xE9".to_vec()).unwrap(),
+            ("caf\u{e9}".to_string(), windows_1252)
+        );
+        assert_eq!(Encoding::for_label("iso-2022-kr"), None);
+    }
+}
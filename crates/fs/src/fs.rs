@@ -4,6 +4,10 @@ mod mac_watcher;
 #[cfg(not(target_os = "macos"))]
 pub mod fs_watcher;
 
+mod encoding;
+
+pub use encoding::{Encoding, InvalidTextError};
+
 use anyhow::{anyhow, Context as _, Result};
 use git::GitHostingProviderRegistry;
 #[cfg(any(test, feature = "test-support"))]
@@ -106,8 +110,32 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads a text file in the given encoding, returning its text along with the encoding,
+    /// which records whether the file starts with a byte order mark. Fails if the file isn't
+    /// valid in the encoding.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Encoding,
+    ) -> Result<(String, Encoding)> {
+        encoding.decode(self.load_bytes(path).await?)
+    }
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    /// Replaces the contents of a file with the given bytes, as they are. The bytes are written
+    /// to a temporary file first, which then replaces the file, so that it is never left
+    /// half-written.
+    async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
+    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+        self.save_with_encoding(path, text, line_ending, Encoding::default())
+            .await
+    }
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -300,6 +328,28 @@ impl RealFs {
             git_binary_path,
         }
     }
+
+    /// Creates a temporary file that can replace the file at `path` once it is written.
+    fn temp_file_for(path: &Path) -> io::Result<NamedTempFile> {
+        if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+            // Use the directory of the destination as temp dir to avoid
+            // invalid cross-device link error, and XDG_CACHE_DIR for fallback.
+            // See https://github.com/zed-industries/zed/pull/8437 for more details.
+            NamedTempFile::new_in(path.parent().unwrap_or(paths::temp_dir()))
+        } else if cfg!(target_os = "windows") {
+            // If temp dir is set to a different drive than the destination,
+            // we receive error:
+            //
+            // failed to persist temporary file:
+            // The system cannot move the file to a different disk drive. (os error 17)
+            //
+            // So we use the directory of the destination as a temp dir to avoid it.
+            // https://github.com/zed-industries/zed/issues/16571
+            NamedTempFile::new_in(path.parent().unwrap_or(paths::temp_dir()))
+        } else {
+            NamedTempFile::new()
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -520,24 +570,7 @@ impl Fs for RealFs {
 
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
-            let mut tmp_file = if cfg!(any(target_os = "linux", target_os = "freebsd")) {
-                // Use the directory of the destination as temp dir to avoid
-                // invalid cross-device link error, and XDG_CACHE_DIR for fallback.
-                // See https://github.com/zed-industries/zed/pull/8437 for more details.
-                NamedTempFile::new_in(path.parent().unwrap_or(paths::temp_dir()))
-            } else if cfg!(target_os = "windows") {
-                // If temp dir is set to a different drive than the destination,
-                // we receive error:
-                //
-                // failed to persist temporary file:
-                // The system cannot move the file to a different disk drive. (os error 17)
-                //
-                // So we use the directory of the destination as a temp dir to avoid it.
-                // https://github.com/zed-industries/zed/issues/16571
-                NamedTempFile::new_in(path.parent().unwrap_or(paths::temp_dir()))
-            } else {
-                NamedTempFile::new()
-            }?;
+            let mut tmp_file = Self::temp_file_for(&path)?;
             tmp_file.write_all(data.as_bytes())?;
             tmp_file.persist(path)?;
             Ok::<(), anyhow::Error>(())
@@ -547,20 +580,53 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        // Encode the text before truncating the file, so that text the encoding can't
+        // represent doesn't leave the file half-written.
+        let chunks = chunks(text, line_ending)
+            .map(|chunk| encoding.encode(chunk))
+            .collect::<Result<Vec<_>>>()?;
         let buffer_size = text.summary().len.min(10 * 1024);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
-        for chunk in chunks(text, line_ending) {
-            writer.write_all(chunk.as_bytes()).await?;
+        writer.write_all(encoding.byte_order_mark()).await?;
+        for chunk in chunks {
+            writer.write_all(&chunk).await?;
         }
         writer.flush().await?;
         Ok(())
     }
 
+    async fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+        if let Some(path) = path.parent() {
+            self.create_dir(path).await?;
+        }
+        let path = path.to_path_buf();
+        let content = content.to_vec();
+        smol::unblock(move || {
+            // Replace the target of a symlink rather than the symlink itself.
+            let path = std::fs::canonicalize(&path).unwrap_or(path);
+            let mut tmp_file = Self::temp_file_for(&path)?;
+            tmp_file.write_all(&content)?;
+            // Keep the permissions of the replaced file, such as whether it is executable.
+            if let Ok(metadata) = std::fs::metadata(&path) {
+                tmp_file.as_file().set_permissions(metadata.permissions())?;
+            }
+            tmp_file.persist(path)?;
+            Ok::<(), anyhow::Error>(())
+        })
+        .await
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(smol::fs::canonicalize(path).await?)
     }
@@ -1787,14 +1853,33 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        self.simulate_random_delay().await;
+        let path = normalize_path(path);
+        let mut content = encoding.byte_order_mark().to_vec();
+        for chunk in chunks(text, line_ending) {
+            content.extend_from_slice(&encoding.encode(chunk)?);
+        }
+        if let Some(path) = path.parent() {
+            self.create_dir(path).await?;
+        }
+        self.write_file_internal(path, content)?;
+        Ok(())
+    }
+
+    async fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
-        let content = chunks(text, line_ending).collect::<String>();
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes())?;
+        self.write_file_internal(path, content.to_vec())?;
         Ok(())
     }
 
@@ -2095,6 +2180,31 @@ mod tests {
     use gpui::BackgroundExecutor;
     use serde_json::json;
 
+    #[gpui::test]
+    async fn test_real_fs_write(executor: BackgroundExecutor) {
+        executor.allow_parking();
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("file.bin");
+        std::fs::write(&path, b"\x7fELF").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let fs = RealFs::new(Default::default(), None);
+        fs.write(&path, b"\x7fELF\0\x01").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x7fELF\0\x01");
+        // The temporary file replaced the file, with its permissions.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+
     #[gpui::test]
     async fn test_fake_fs(executor: BackgroundExecutor) {
         let fs = FakeFs::new(executor.clone());
@@ -41,7 +41,15 @@ actions!(
         TrashUntrackedFiles,
         Uncommit,
         Commit,
-        ClearCommitMessage
+        ClearCommitMessage,
+        Fetch,
+        Pull,
+        Push,
+        ForcePush,
+        StashAll,
+        StashFile,
+        StashPop,
+        FileHistory,
     ]
 );
 
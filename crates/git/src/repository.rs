@@ -2,17 +2,19 @@ use crate::status::FileStatus;
 use crate::GitHostingProviderRegistry;
 use crate::{blame::Blame, status::GitStatus};
 use anyhow::{anyhow, Context, Result};
+use askpass::AskPassSession;
 use collections::{HashMap, HashSet};
 use git2::BranchType;
 use gpui::SharedString;
 use parking_lot::Mutex;
 use rope::Rope;
 use std::borrow::Borrow;
-use std::io::Write as _;
+use std::io::{Read, Write as _};
 use std::process::Stdio;
 use std::sync::LazyLock;
 use std::{
     cmp::Ordering,
+    hash::{Hash as _, Hasher as _},
     path::{Component, Path, PathBuf},
     sync::Arc,
 };
@@ -68,6 +70,136 @@ pub struct CommitDetails {
     pub committer_name: SharedString,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Remote {
+    pub name: SharedString,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum PushOptions {
+    SetUpstream,
+    Force,
+}
+
+/// The output of a git command that talks to a remote, which git uses to
+/// report progress and a summary of what happened.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteCommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl RemoteCommandOutput {
+    pub fn is_empty(&self) -> bool {
+        self.stdout.is_empty() && self.stderr.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the entry in the stash, as in `stash@{index}`.
+    pub index: usize,
+    pub sha: SharedString,
+    pub message: SharedString,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+}
+
+/// The contents of a file before and after the changes recorded in a commit or stash entry.
+/// A missing side means the file was created or deleted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFileChange {
+    pub path: RepoPath,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+}
+
+/// A commit in the repository's history, as returned by [`GitRepository::log`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    pub parent_shas: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+}
+
+/// Which page of the history to return from [`GitRepository::log`], and how to filter it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// The number of matching commits to skip.
+    pub skip: usize,
+    /// The maximum number of commits to return.
+    pub limit: usize,
+    /// Only include commits that touched this path.
+    pub path: Option<RepoPath>,
+    /// Only include commits whose author's name or email contains this text, ignoring case.
+    pub author: Option<String>,
+    /// Only include commits whose message contains this text, ignoring case.
+    pub message: Option<String>,
+}
+
+/// What to do with a commit during an interactive rebase, as in a line of git's todo list.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Edit => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    /// Parses a todo list command, including git's single letter abbreviations.
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "p" | "pick" => Some(RebaseAction::Pick),
+            "r" | "reword" => Some(RebaseAction::Reword),
+            "e" | "edit" => Some(RebaseAction::Edit),
+            "s" | "squash" => Some(RebaseAction::Squash),
+            "f" | "fixup" => Some(RebaseAction::Fixup),
+            "d" | "drop" => Some(RebaseAction::Drop),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of an entry to reword. Without one, the commit keeps its message.
+    pub message: Option<SharedString>,
+}
+
+/// An interactive rebase that git has stopped partway through.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseStatus {
+    /// The commit that the branch is being rebased onto.
+    pub onto: SharedString,
+    /// The todo entry that git stopped at, if any.
+    pub stopped_at: Option<RebaseTodoEntry>,
+    /// The entries that are yet to be applied.
+    pub remaining: Vec<RebaseTodoEntry>,
+    /// Whether git stopped because applying a commit caused conflicts.
+    pub has_conflicts: bool,
+}
+
 pub enum ResetMode {
     // reset the branch pointer, leave index and worktree unchanged
     // (this will make it look like things that were committed are now
@@ -115,6 +247,14 @@ pub trait GitRepository: Send + Sync {
 
     fn show(&self, commit: &str) -> Result<CommitDetails>;
 
+    /// Returns a page of the history reachable from HEAD, newest first, with parents always
+    /// listed after their children.
+    fn log(&self, options: &LogOptions) -> Result<Vec<LogEntry>>;
+
+    /// Returns the files changed by a commit, relative to its first parent.
+    /// Binary files are not included.
+    fn commit_changes(&self, commit: &str) -> Result<Vec<CommitFileChange>>;
+
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -139,6 +279,75 @@ pub trait GitRepository: Send + Sync {
     fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
 
     fn commit(&self, message: &str, name_and_email: Option<(&str, &str)>) -> Result<()>;
+
+    /// Returns the remotes of the repository. If a branch name is given and that branch tracks a
+    /// remote, only that remote is returned.
+    fn get_remotes(&self, branch_name: Option<&str>) -> Result<Vec<Remote>>;
+
+    /// Fetches from all remotes. Like [`Self::pull`] and [`Self::push`], this reports each line
+    /// of progress git prints to `progress` as it arrives.
+    fn fetch(
+        &self,
+        askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput>;
+
+    fn pull(
+        &self,
+        branch_name: &str,
+        remote_name: &str,
+        askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput>;
+
+    fn push(
+        &self,
+        branch_name: &str,
+        remote_name: &str,
+        options: Option<PushOptions>,
+        askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput>;
+
+    /// Stashes the changes to the given paths, or all changes if `paths` is empty.
+    fn stash_paths(&self, message: Option<&str>, paths: &[RepoPath]) -> Result<()>;
+
+    /// Returns the stash entries, most recent first.
+    fn stash_entries(&self) -> Result<Vec<StashEntry>>;
+
+    /// Applies the stash entry with the given sha. Entries are identified by sha rather than by
+    /// index, since the indices shift whenever the stash changes.
+    fn stash_apply(&self, sha: &str) -> Result<()>;
+
+    /// Applies the stash entry with the given sha and removes it from the stash.
+    fn stash_pop(&self, sha: &str) -> Result<()>;
+
+    /// Removes the stash entry with the given sha from the stash.
+    fn stash_drop(&self, sha: &str) -> Result<()>;
+
+    /// Returns the files changed by the stash entry with the given sha, relative to the commit it
+    /// was created on. Untracked and binary files are not included.
+    fn stash_changes(&self, sha: &str) -> Result<Vec<CommitFileChange>>;
+
+    /// Returns the todo list for interactively rebasing the current branch onto `base`,
+    /// picking every commit, oldest first.
+    fn rebase_todo(&self, base: &str) -> Result<Vec<RebaseTodoEntry>>;
+
+    /// Rebases the current branch onto `base`, following the given todo list. Entries to
+    /// reword are given their new messages without stopping. Stopping, whether to edit or
+    /// because of conflicts, is not an error.
+    fn rebase_interactive(&self, base: &str, todo: &[RebaseTodoEntry]) -> Result<()>;
+
+    /// Continues a stopped rebase, until it completes or stops again.
+    fn rebase_continue(&self) -> Result<()>;
+
+    fn rebase_abort(&self) -> Result<()>;
+
+    /// Returns the state of the rebase in progress, if any.
+    fn rebase_status(&self) -> Result<Option<RebaseStatus>>;
+
+    /// Replaces the message of the HEAD commit, leaving its contents unchanged.
+    fn amend_commit_message(&self, message: &str) -> Result<()>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -165,11 +374,150 @@ impl RealGitRepository {
             hosting_provider_registry,
         }
     }
+
+    /// Finds the `stash@{n}` reference of the stash entry with the given sha, so that an operation
+    /// fails instead of acting on another entry if the stash changed since it was listed.
+    fn stash_reference(&self, sha: &str) -> Result<String> {
+        let index = self
+            .stash_entries()?
+            .into_iter()
+            .position(|entry| entry.sha.as_ref() == sha)
+            .with_context(|| format!("stash entry {sha} no longer exists"))?;
+        Ok(format!("stash@{{{index}}}"))
+    }
+
+    fn run_git_command(&self, args: &[&str], operation: &str) -> Result<String> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        let output = new_std_command(&self.git_binary_path)
+            .current_dir(&working_directory)
+            .args(args)
+            .output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to {operation}:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Runs a rebase command without letting git open an editor. The todo list, if given,
+    /// replaces the one git generates, and commits it rewords are given their new messages.
+    /// Other commit messages are accepted as git proposes them.
+    fn run_rebase_command(
+        &self,
+        args: &[&str],
+        todo: Option<&[RebaseTodoEntry]>,
+        operation: &str,
+    ) -> Result<()> {
+        let (working_directory, git_directory) = {
+            let repo = self.repository.lock();
+            let working_directory = repo
+                .workdir()
+                .context("failed to read git work directory")?
+                .to_path_buf();
+            (working_directory, repo.path().to_path_buf())
+        };
+
+        let mut command = new_std_command(&self.git_binary_path);
+        command
+            .current_dir(&working_directory)
+            .args(args)
+            .stdin(Stdio::null());
+
+        // Git runs editors through its shell, with the path of the file to edit appended. Paths
+        // are passed in the environment, so that they never need quoting.
+        let rebase_directory = git_directory.join(REBASE_DIRECTORY_NAME);
+        let todo_path = rebase_directory.join("todo");
+        let editor_path = rebase_directory.join("editor.sh");
+        let messages_path = rebase_directory.join("messages");
+        if let Some(todo) = todo {
+            std::fs::create_dir_all(&messages_path)?;
+            std::fs::write(&todo_path, format_rebase_todo(todo))?;
+            std::fs::write(&editor_path, REWORD_EDITOR_SCRIPT)?;
+            for entry in todo {
+                if let (RebaseAction::Reword, Some(message)) = (entry.action, &entry.message) {
+                    std::fs::write(messages_path.join(entry.sha.as_ref()), message.as_bytes())?;
+                }
+            }
+            command
+                .env("GIT_SEQUENCE_EDITOR", "cp \"$ZED_REBASE_TODO\"")
+                .env("ZED_REBASE_TODO", &todo_path);
+        }
+        // Rewording may happen when continuing, after the rebase stopped for conflicts. Rebases
+        // started outside of Zed have no messages to give.
+        if editor_path.exists() {
+            command
+                .env("GIT_EDITOR", "sh \"$ZED_REBASE_EDITOR\"")
+                .env("ZED_REBASE_EDITOR", &editor_path)
+                .env("ZED_REBASE_MESSAGES", &messages_path)
+                .env(
+                    "ZED_REBASE_DONE",
+                    git_directory.join("rebase-merge").join("done"),
+                );
+        } else {
+            command.env("GIT_EDITOR", "true");
+        }
+        let output = command.output();
+        if !self.rebase_in_progress() {
+            self.remove_rebase_files();
+        }
+        let output = output?;
+
+        // Git exits with an error when it stops for conflicts, which is expected here.
+        if !output.status.success() && !self.rebase_in_progress() {
+            return Err(anyhow!(
+                "Failed to {operation}:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Removes the files that Zed keeps for the rebase in progress.
+    fn remove_rebase_files(&self) {
+        let rebase_directory = self.repository.lock().path().join(REBASE_DIRECTORY_NAME);
+        if rebase_directory.exists() {
+            std::fs::remove_dir_all(rebase_directory).log_err();
+        }
+    }
+
+    fn rebase_in_progress(&self) -> bool {
+        matches!(
+            self.repository.lock().state(),
+            git2::RepositoryState::RebaseInteractive | git2::RepositoryState::RebaseMerge
+        )
+    }
 }
 
 // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
 const GIT_MODE_SYMLINK: u32 = 0o120000;
 
+/// The directory within `.git` holding the todo list and reword messages of a rebase.
+const REBASE_DIRECTORY_NAME: &str = "zed-rebase";
+
+/// Used as `GIT_EDITOR` during a rebase. When git asks for the message of a commit being
+/// reworded, this writes the message chosen for it, if any. The last entry that git marked as
+/// done is the one being applied, and it may name the commit by an abbreviated sha.
+const REWORD_EDITOR_SCRIPT: &str = r#"entry=$(tail -n 1 "$ZED_REBASE_DONE")
+case "$entry" in
+    "r "* | "reword "*) ;;
+    *) exit 0 ;;
+esac
+sha=$(echo "$entry" | cut -d " " -f 2)
+for message in "$ZED_REBASE_MESSAGES/$sha"*; do
+    if [ -f "$message" ]; then
+        cp "$message" "$1"
+    fi
+done
+"#;
+
 impl GitRepository for RealGitRepository {
     fn reload_index(&self) {
         if let Ok(mut index) = self.repository.lock().index() {
@@ -208,6 +556,42 @@ impl GitRepository for RealGitRepository {
         Ok(details)
     }
 
+    fn log(&self, options: &LogOptions) -> Result<Vec<LogEntry>> {
+        let skip = format!("--skip={}", options.skip);
+        let max_count = format!("--max-count={}", options.limit);
+        let mut args = vec![
+            "log".to_string(),
+            "--topo-order".to_string(),
+            "--format=%H%x00%P%x00%an%x00%ae%x00%ct%x00%s".to_string(),
+            skip,
+            max_count,
+        ];
+        if let Some(author) = &options.author {
+            args.push(format!("--author={author}"));
+        }
+        if let Some(message) = &options.message {
+            args.push(format!("--grep={message}"));
+        }
+        if options.author.is_some() || options.message.is_some() {
+            // Filters are plain text typed by the user, rather than regular expressions.
+            args.push("--fixed-strings".to_string());
+            args.push("--regexp-ignore-case".to_string());
+        }
+        args.push("--".to_string());
+        if let Some(path) = &options.path {
+            args.push(path.to_string_lossy().into_owned());
+        }
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let output = self.run_git_command(&args, "load commit history")?;
+        parse_log_output(&output)
+    }
+
+    fn commit_changes(&self, commit: &str) -> Result<Vec<CommitFileChange>> {
+        let repo = self.repository.lock();
+        let commit = repo.revparse_single(commit)?.peel_to_commit()?;
+        changes_for_commit(&repo, &commit)
+    }
+
     fn reset(&self, commit: &str, mode: ResetMode) -> Result<()> {
         let working_directory = self
             .repository
@@ -556,6 +940,413 @@ impl GitRepository for RealGitRepository {
         }
         Ok(())
     }
+
+    fn get_remotes(&self, branch_name: Option<&str>) -> Result<Vec<Remote>> {
+        let repo = self.repository.lock();
+        if let Some(branch_name) = branch_name {
+            if let Ok(remote_name) =
+                repo.branch_upstream_remote(&format!("refs/heads/{branch_name}"))
+            {
+                if let Some(remote_name) = remote_name.as_str() {
+                    return Ok(vec![Remote {
+                        name: remote_name.to_string().into(),
+                    }]);
+                }
+            }
+        }
+
+        Ok(repo
+            .remotes()?
+            .iter()
+            .flatten()
+            .map(|name| Remote {
+                name: name.to_string().into(),
+            })
+            .collect())
+    }
+
+    fn fetch(
+        &self,
+        askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        run_remote_command(
+            &self.git_binary_path,
+            &working_directory,
+            &["fetch", "--all", "--progress"],
+            askpass,
+            progress,
+            "fetch",
+        )
+    }
+
+    fn pull(
+        &self,
+        branch_name: &str,
+        remote_name: &str,
+        askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        run_remote_command(
+            &self.git_binary_path,
+            &working_directory,
+            &["pull", "--progress", remote_name, branch_name],
+            askpass,
+            progress,
+            "pull",
+        )
+    }
+
+    fn push(
+        &self,
+        branch_name: &str,
+        remote_name: &str,
+        options: Option<PushOptions>,
+        askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        let refspec = format!("{branch_name}:{branch_name}");
+        let mut args = vec!["push", "--progress"];
+        match options {
+            Some(PushOptions::SetUpstream) => args.push("--set-upstream"),
+            Some(PushOptions::Force) => args.push("--force-with-lease"),
+            None => {}
+        }
+        args.push(remote_name);
+        args.push(&refspec);
+
+        run_remote_command(
+            &self.git_binary_path,
+            &working_directory,
+            &args,
+            askpass,
+            progress,
+            "push",
+        )
+    }
+
+    fn stash_paths(&self, message: Option<&str>, paths: &[RepoPath]) -> Result<()> {
+        let mut args = vec!["stash", "push", "--quiet"];
+        if let Some(message) = message {
+            args.push("--message");
+            args.push(message);
+        }
+        args.push("--");
+        let paths = paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        args.extend(paths.iter().map(String::as_str));
+        self.run_git_command(&args, "stash changes")?;
+        Ok(())
+    }
+
+    fn stash_entries(&self) -> Result<Vec<StashEntry>> {
+        let output = self.run_git_command(
+            &["stash", "list", "--format=%H%x00%ct%x00%gs"],
+            "list stash entries",
+        )?;
+        parse_stash_list(&output)
+    }
+
+    fn stash_apply(&self, sha: &str) -> Result<()> {
+        let stash = self.stash_reference(sha)?;
+        self.run_git_command(&["stash", "apply", "--quiet", &stash], "apply stash")?;
+        Ok(())
+    }
+
+    fn stash_pop(&self, sha: &str) -> Result<()> {
+        let stash = self.stash_reference(sha)?;
+        self.run_git_command(&["stash", "pop", "--quiet", &stash], "pop stash")?;
+        Ok(())
+    }
+
+    fn stash_drop(&self, sha: &str) -> Result<()> {
+        let stash = self.stash_reference(sha)?;
+        self.run_git_command(&["stash", "drop", "--quiet", &stash], "drop stash")?;
+        Ok(())
+    }
+
+    fn stash_changes(&self, sha: &str) -> Result<Vec<CommitFileChange>> {
+        // Make sure the sha is still in the stash, rather than showing any commit.
+        self.stash_reference(sha)?;
+        let repo = self.repository.lock();
+        let stash = repo.find_commit(git2::Oid::from_str(sha)?)?;
+        changes_for_commit(&repo, &stash)
+    }
+
+    fn rebase_todo(&self, base: &str) -> Result<Vec<RebaseTodoEntry>> {
+        let range = format!("{base}..HEAD");
+        let output = self.run_git_command(
+            &[
+                "log",
+                "--reverse",
+                "--no-merges",
+                "--format=%H%x00%s",
+                &range,
+            ],
+            "list commits to rebase",
+        )?;
+        output
+            .lines()
+            .map(|line| {
+                let (sha, subject) = line
+                    .split_once('\0')
+                    .with_context(|| format!("unexpected git log output: {line:?}"))?;
+                Ok(RebaseTodoEntry {
+                    action: RebaseAction::Pick,
+                    sha: sha.to_string().into(),
+                    subject: subject.to_string().into(),
+                    message: None,
+                })
+            })
+            .collect()
+    }
+
+    fn rebase_interactive(&self, base: &str, todo: &[RebaseTodoEntry]) -> Result<()> {
+        if self.rebase_in_progress() {
+            anyhow::bail!("a rebase is already in progress");
+        }
+        self.run_rebase_command(&["rebase", "--interactive", base], Some(todo), "rebase")
+    }
+
+    fn rebase_continue(&self) -> Result<()> {
+        if self.repository.lock().index()?.has_conflicts() {
+            anyhow::bail!("Resolve all conflicts and stage the result before continuing");
+        }
+        self.run_rebase_command(&["rebase", "--continue"], None, "continue rebase")
+    }
+
+    fn rebase_abort(&self) -> Result<()> {
+        self.run_git_command(&["rebase", "--abort"], "abort rebase")?;
+        self.remove_rebase_files();
+        Ok(())
+    }
+
+    fn rebase_status(&self) -> Result<Option<RebaseStatus>> {
+        if !self.rebase_in_progress() {
+            return Ok(None);
+        }
+        let repo = self.repository.lock();
+        let rebase_directory = repo.path().join("rebase-merge");
+        let read = |name: &str| std::fs::read_to_string(rebase_directory.join(name));
+
+        let onto = read("onto")?.trim().to_string().into();
+        let stopped_at = read("done")
+            .map(|done| parse_rebase_todo(&done).pop())
+            .unwrap_or_default()
+            // Git records the last entry as done before applying it, so it is only where the
+            // rebase stopped if git says it stopped.
+            .filter(|_| rebase_directory.join("stopped-sha").exists());
+        let remaining = read("git-rebase-todo")
+            .map(|todo| parse_rebase_todo(&todo))
+            .unwrap_or_default();
+        let has_conflicts = repo.index()?.has_conflicts();
+
+        Ok(Some(RebaseStatus {
+            onto,
+            stopped_at,
+            remaining,
+            has_conflicts,
+        }))
+    }
+
+    fn amend_commit_message(&self, message: &str) -> Result<()> {
+        self.run_git_command(
+            &[
+                "commit",
+                "--quiet",
+                "--amend",
+                "--only",
+                "--cleanup=strip",
+                "-m",
+                message,
+            ],
+            "amend commit message",
+        )?;
+        Ok(())
+    }
+}
+
+fn format_rebase_todo(todo: &[RebaseTodoEntry]) -> String {
+    let mut output = String::new();
+    for entry in todo {
+        output.push_str(entry.action.as_str());
+        output.push(' ');
+        output.push_str(&entry.sha);
+        output.push(' ');
+        output.push_str(&entry.subject);
+        output.push('\n');
+    }
+    output
+}
+
+fn parse_rebase_todo(input: &str) -> Vec<RebaseTodoEntry> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().splitn(3, ' ');
+            let action = RebaseAction::from_command(fields.next()?)?;
+            let sha = fields.next()?.to_string().into();
+            let subject = fields.next().unwrap_or_default().to_string().into();
+            Some(RebaseTodoEntry {
+                action,
+                sha,
+                subject,
+                message: None,
+            })
+        })
+        .collect()
+}
+
+/// Loads the contents of every file changed by `commit`, relative to its first parent.
+fn changes_for_commit(
+    repo: &git2::Repository,
+    commit: &git2::Commit,
+) -> Result<Vec<CommitFileChange>> {
+    let old_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let new_tree = commit.tree()?;
+    let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+
+    // Resolves to `None` for binary files, which can't be previewed.
+    let load_text = |oid: git2::Oid| -> Result<Option<Option<String>>> {
+        if oid.is_zero() {
+            return Ok(Some(None));
+        }
+        let blob = repo.find_blob(oid)?;
+        if blob.is_binary() {
+            return Ok(None);
+        }
+        Ok(Some(Some(String::from_utf8(blob.content().to_owned())?)))
+    };
+
+    let mut changes = Vec::new();
+    for delta in diff.deltas() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let (Some(old_text), Some(new_text)) = (
+            load_text(delta.old_file().id())?,
+            load_text(delta.new_file().id())?,
+        ) else {
+            continue;
+        };
+        changes.push(CommitFileChange {
+            path: RepoPath::new(path.to_path_buf()),
+            old_text,
+            new_text,
+        });
+    }
+    Ok(changes)
+}
+
+fn run_remote_command(
+    git_binary_path: &Path,
+    working_directory: &Path,
+    args: &[&str],
+    askpass: &AskPassSession,
+    progress: &dyn Fn(&str),
+    operation: &str,
+) -> Result<RemoteCommandOutput> {
+    let mut command = new_std_command(git_binary_path);
+    command
+        .current_dir(working_directory)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(askpass_script) = askpass.script_path() {
+        command
+            .env("GIT_ASKPASS", askpass_script)
+            .env("SSH_ASKPASS", askpass_script)
+            .env("SSH_ASKPASS_REQUIRE", "force");
+    }
+
+    let mut child = command.spawn()?;
+    let mut stdout = child.stdout.take().context("failed to read git's output")?;
+    let stderr = child.stderr.take().context("failed to read git's output")?;
+    // Git reports progress on stderr, so stdout is read on another thread to keep it from
+    // filling up and blocking git in the meantime.
+    let stdout = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let stderr = read_progress(stderr, progress)?;
+    let stdout = stdout
+        .join()
+        .map_err(|_| anyhow!("failed to read git's output"))??;
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(anyhow!("Failed to {}:\n{}", operation, stderr));
+    }
+    Ok(RemoteCommandOutput {
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr,
+    })
+}
+
+/// Reads git's stderr until it's closed, reporting each line of it to `progress` as it arrives.
+/// Git rewrites progress lines in place by ending them with a carriage return, so those lines
+/// are reported but left out of the returned output.
+fn read_progress(mut reader: impl Read, progress: &dyn Fn(&str)) -> Result<String> {
+    let mut output = String::new();
+    let mut pending = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        pending.extend_from_slice(&buffer[..len]);
+        while let Some(line_end) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+            let line = String::from_utf8_lossy(&pending[..line_end]);
+            let line = line.trim();
+            if !line.is_empty() {
+                progress(line);
+                if pending[line_end] == b'\n' {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+            pending.drain(..=line_end);
+        }
+    }
+
+    let line = String::from_utf8_lossy(&pending);
+    let line = line.trim();
+    if !line.is_empty() {
+        progress(line);
+        output.push_str(line);
+    }
+    Ok(output)
 }
 
 #[derive(Debug, Clone)]
@@ -573,6 +1364,11 @@ pub struct FakeGitRepositoryState {
     pub statuses: HashMap<RepoPath, FileStatus>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    pub remotes: HashSet<String>,
+    pub stash_entries: Vec<StashEntry>,
+    pub log_entries: Vec<LogEntry>,
+    /// The lines of progress that fetching, pulling and pushing report.
+    pub remote_progress: Vec<String>,
 }
 
 impl FakeGitRepository {
@@ -582,6 +1378,23 @@ impl FakeGitRepository {
 }
 
 impl FakeGitRepositoryState {
+    fn stash_index(&self, sha: &str) -> Result<usize> {
+        self.stash_entries
+            .iter()
+            .position(|entry| entry.sha.as_ref() == sha)
+            .with_context(|| format!("no such stash entry: {sha}"))
+    }
+
+    fn report_remote_progress(&self, progress: &dyn Fn(&str)) -> RemoteCommandOutput {
+        for line in &self.remote_progress {
+            progress(line);
+        }
+        RemoteCommandOutput {
+            stdout: String::new(),
+            stderr: self.remote_progress.join("\n"),
+        }
+    }
+
     pub fn new(path: PathBuf, event_emitter: smol::channel::Sender<PathBuf>) -> Self {
         FakeGitRepositoryState {
             path,
@@ -592,6 +1405,10 @@ impl FakeGitRepositoryState {
             statuses: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            remotes: Default::default(),
+            stash_entries: Default::default(),
+            log_entries: Default::default(),
+            remote_progress: Default::default(),
         }
     }
 }
@@ -639,6 +1456,31 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn log(&self, options: &LogOptions) -> Result<Vec<LogEntry>> {
+        let state = self.state.lock();
+        let contains =
+            |text: &str, filter: &str| text.to_lowercase().contains(&filter.to_lowercase());
+        Ok(state
+            .log_entries
+            .iter()
+            .filter(|entry| {
+                options.author.as_ref().map_or(true, |author| {
+                    contains(&entry.author_name, author) || contains(&entry.author_email, author)
+                }) && options
+                    .message
+                    .as_ref()
+                    .map_or(true, |message| contains(&entry.subject, message))
+            })
+            .skip(options.skip)
+            .take(options.limit)
+            .cloned()
+            .collect())
+    }
+
+    fn commit_changes(&self, _: &str) -> Result<Vec<CommitFileChange>> {
+        unimplemented!()
+    }
+
     fn reset(&self, _: &str, _: ResetMode) -> Result<()> {
         unimplemented!()
     }
@@ -740,6 +1582,139 @@ impl GitRepository for FakeGitRepository {
     fn commit(&self, _message: &str, _name_and_email: Option<(&str, &str)>) -> Result<()> {
         unimplemented!()
     }
+
+    fn get_remotes(&self, _branch_name: Option<&str>) -> Result<Vec<Remote>> {
+        let state = self.state.lock();
+        let mut remotes = state
+            .remotes
+            .iter()
+            .map(|name| Remote {
+                name: name.clone().into(),
+            })
+            .collect::<Vec<_>>();
+        remotes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(remotes)
+    }
+
+    fn fetch(
+        &self,
+        _askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput> {
+        let state = self.state.lock();
+        Ok(state.report_remote_progress(progress))
+    }
+
+    fn pull(
+        &self,
+        _branch_name: &str,
+        remote_name: &str,
+        _askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput> {
+        let state = self.state.lock();
+        if !state.remotes.contains(remote_name) {
+            anyhow::bail!("no such remote: {remote_name}");
+        }
+        Ok(state.report_remote_progress(progress))
+    }
+
+    fn push(
+        &self,
+        branch_name: &str,
+        remote_name: &str,
+        _options: Option<PushOptions>,
+        _askpass: &AskPassSession,
+        progress: &dyn Fn(&str),
+    ) -> Result<RemoteCommandOutput> {
+        let state = self.state.lock();
+        if !state.remotes.contains(remote_name) {
+            anyhow::bail!("no such remote: {remote_name}");
+        }
+        if !state.branches.contains(branch_name) {
+            anyhow::bail!("no such branch: {branch_name}");
+        }
+        Ok(state.report_remote_progress(progress))
+    }
+    fn stash_paths(&self, message: Option<&str>, _paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        let branch = state.current_branch_name.clone().unwrap_or_default();
+        let message = match message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {branch}"),
+        };
+        // Derive a sha that differs from those of the other entries.
+        let mut hasher = std::hash::DefaultHasher::new();
+        for entry in &state.stash_entries {
+            entry.sha.hash(&mut hasher);
+        }
+        message.hash(&mut hasher);
+        let sha = format!("{:040x}", hasher.finish());
+        state.stash_entries.insert(
+            0,
+            StashEntry {
+                index: 0,
+                sha: sha.into(),
+                message: message.into(),
+                timestamp: 0,
+            },
+        );
+        for (index, entry) in state.stash_entries.iter_mut().enumerate() {
+            entry.index = index;
+        }
+        Ok(())
+    }
+
+    fn stash_entries(&self) -> Result<Vec<StashEntry>> {
+        Ok(self.state.lock().stash_entries.clone())
+    }
+
+    fn stash_apply(&self, sha: &str) -> Result<()> {
+        self.state.lock().stash_index(sha)?;
+        Ok(())
+    }
+
+    fn stash_pop(&self, sha: &str) -> Result<()> {
+        self.stash_drop(sha)
+    }
+
+    fn stash_drop(&self, sha: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        let index = state.stash_index(sha)?;
+        state.stash_entries.remove(index);
+        for (index, entry) in state.stash_entries.iter_mut().enumerate() {
+            entry.index = index;
+        }
+        Ok(())
+    }
+
+    fn stash_changes(&self, _sha: &str) -> Result<Vec<CommitFileChange>> {
+        unimplemented!()
+    }
+
+    fn rebase_todo(&self, _base: &str) -> Result<Vec<RebaseTodoEntry>> {
+        unimplemented!()
+    }
+
+    fn rebase_interactive(&self, _base: &str, _todo: &[RebaseTodoEntry]) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn rebase_continue(&self) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn rebase_abort(&self) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn rebase_status(&self) -> Result<Option<RebaseStatus>> {
+        Ok(None)
+    }
+
+    fn amend_commit_message(&self, _message: &str) -> Result<()> {
+        unimplemented!()
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -908,6 +1883,60 @@ fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+fn parse_log_output(input: &str) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for line in input.split('\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\x00');
+        let sha: SharedString = fields.next().context("no commit hash")?.to_string().into();
+        let parent_shas = fields
+            .next()
+            .context("no parent hashes")?
+            .split_whitespace()
+            .map(|sha| SharedString::from(sha.to_string()))
+            .collect();
+        let author_name: SharedString = fields.next().context("no author name")?.to_string().into();
+        let author_email: SharedString =
+            fields.next().context("no author email")?.to_string().into();
+        let commit_timestamp = fields.next().context("no committer date")?.parse::<i64>()?;
+        let subject: SharedString = fields.next().context("no subject")?.to_string().into();
+
+        entries.push(LogEntry {
+            sha,
+            parent_shas,
+            subject,
+            author_name,
+            author_email,
+            commit_timestamp,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn parse_stash_list(output: &str) -> Result<Vec<StashEntry>> {
+    output
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut fields = line.splitn(3, '\0');
+            let (Some(sha), Some(timestamp), Some(message)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!("unexpected stash list output: {line:?}"));
+            };
+            Ok(StashEntry {
+                index,
+                sha: sha.to_string().into(),
+                message: message.to_string().into(),
+                timestamp: timestamp.parse()?,
+            })
+        })
+        .collect()
+}
+
 fn parse_upstream_track(upstream_track: &str) -> Result<Option<UpstreamTracking>> {
     if upstream_track == "" {
         return Ok(Some(UpstreamTracking {
@@ -963,3 +1992,263 @@ fn test_branches_parsing() {
         }]
     )
 }
+
+#[test]
+fn test_progress_reading() {
+    let lines = std::cell::RefCell::new(Vec::new());
+    let stderr = "Enumerating objects: 5, done.\nWriting objects:  50% (1/2)\rWriting objects: 100% (2/2), done.\nTo github.com:zed-industries/zed.git\n   060964d..0123456  main -> main";
+    let output = read_progress(stderr.as_bytes(), &|line| {
+        lines.borrow_mut().push(line.to_string())
+    })
+    .unwrap();
+    assert_eq!(
+        lines.into_inner(),
+        [
+            "Enumerating objects: 5, done.",
+            "Writing objects:  50% (1/2)",
+            "Writing objects: 100% (2/2), done.",
+            "To github.com:zed-industries/zed.git",
+            "060964d..0123456  main -> main",
+        ]
+    );
+    // Lines that were rewritten in place are left out of the output.
+    assert_eq!(
+        output,
+        "Enumerating objects: 5, done.\nWriting objects: 100% (2/2), done.\nTo github.com:zed-industries/zed.git\n060964d..0123456  main -> main"
+    );
+}
+
+#[test]
+fn test_log_parsing() {
+    let input = "060964da10574cd9bf06463a53bf6e0769c5c45e\x00a1b2c3d4e5f60718293a4b5c6d7e8f9012345678 0123456789abcdef0123456789abcdef01234567\x00Jane Doe\x00jane@example.com\x001733187470\x00Merge branch 'main'\n0123456789abcdef0123456789abcdef01234567\x00\x00John Doe\x00john@example.com\x001733187000\x00Initial commit\n";
+    assert_eq!(
+        parse_log_output(input).unwrap(),
+        vec![
+            LogEntry {
+                sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                parent_shas: vec![
+                    "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678".into(),
+                    "0123456789abcdef0123456789abcdef01234567".into(),
+                ],
+                subject: "Merge branch 'main'".into(),
+                author_name: "Jane Doe".into(),
+                author_email: "jane@example.com".into(),
+                commit_timestamp: 1733187470,
+            },
+            LogEntry {
+                sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                parent_shas: vec![],
+                subject: "Initial commit".into(),
+                author_name: "John Doe".into(),
+                author_email: "john@example.com".into(),
+                commit_timestamp: 1733187000,
+            },
+        ]
+    )
+}
+
+#[test]
+fn test_stash_list_parsing() {
+    let input = "060964da10574cd9bf06463a53bf6e0769c5c45e\x001733187470\x00WIP on main: 0123456 Initial commit\n0123456789abcdef0123456789abcdef01234567\x001733187000\x00On main: message with\x00a nul\n";
+    assert_eq!(
+        parse_stash_list(input).unwrap(),
+        vec![
+            StashEntry {
+                index: 0,
+                sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                message: "WIP on main: 0123456 Initial commit".into(),
+                timestamp: 1733187470,
+            },
+            StashEntry {
+                index: 1,
+                sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                message: "On main: message with\x00a nul".into(),
+                timestamp: 1733187000,
+            },
+        ]
+    );
+    assert!(parse_stash_list("").unwrap().is_empty());
+    assert!(parse_stash_list("060964da10574cd9bf06463a53bf6e0769c5c45e\n").is_err());
+}
+
+#[test]
+fn test_rebase_todo_parsing() {
+    let input = "pick 0123456 First commit\n# A comment\nfixup 89abcde Fix typo\nexec make test\nr fedcba9 Reword me\n";
+    assert_eq!(
+        parse_rebase_todo(input),
+        vec![
+            RebaseTodoEntry {
+                action: RebaseAction::Pick,
+                sha: "0123456".into(),
+                subject: "First commit".into(),
+                message: None,
+            },
+            RebaseTodoEntry {
+                action: RebaseAction::Fixup,
+                sha: "89abcde".into(),
+                subject: "Fix typo".into(),
+                message: None,
+            },
+            RebaseTodoEntry {
+                action: RebaseAction::Reword,
+                sha: "fedcba9".into(),
+                subject: "Reword me".into(),
+                message: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_rebase_todo_round_trip() {
+    let todo = [
+        RebaseAction::Pick,
+        RebaseAction::Reword,
+        RebaseAction::Edit,
+        RebaseAction::Squash,
+        RebaseAction::Fixup,
+        RebaseAction::Drop,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(ix, action)| RebaseTodoEntry {
+        action,
+        sha: format!("{ix:040x}").into(),
+        subject: format!("Commit {ix}: with spaces").into(),
+        message: None,
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(parse_rebase_todo(&format_rebase_todo(&todo)), todo);
+}
+
+#[test]
+fn test_interactive_rebase() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Zed").unwrap();
+    config.set_str("user.email", "zed@example.com").unwrap();
+    let commit = |path: &str, contents: &str, message: &str| -> SharedString {
+        std::fs::write(dir.path().join(path), contents).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = repo.signature().unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            parent.as_ref().as_slice(),
+        )
+        .unwrap()
+        .to_string()
+        .into()
+    };
+    let base = commit("a.txt", "a\n", "Add a");
+    let add_b = commit("b.txt", "one\n", "Add b");
+    let change_b = commit("b.txt", "two\n", "Change b");
+
+    let repository = RealGitRepository::new(
+        git2::Repository::open(dir.path()).unwrap(),
+        None,
+        Arc::new(GitHostingProviderRegistry::new()),
+    );
+    let head = || repo.head().unwrap().peel_to_commit().unwrap();
+    let mut todo = repository.rebase_todo(&base).unwrap();
+    assert_eq!(
+        todo.iter().map(|entry| &entry.sha).collect::<Vec<_>>(),
+        [&add_b, &change_b]
+    );
+
+    // Changing b before adding it stops the rebase with conflicts, until it is aborted.
+    todo.swap(0, 1);
+    repository.rebase_interactive(&base, &todo).unwrap();
+    let status = repository.rebase_status().unwrap().unwrap();
+    assert!(status.has_conflicts);
+    assert_eq!(status.onto, base);
+    assert_eq!(
+        status.stopped_at.map(|entry| entry.sha),
+        Some(change_b.clone())
+    );
+    assert_eq!(
+        status
+            .remaining
+            .iter()
+            .map(|entry| &entry.sha)
+            .collect::<Vec<_>>(),
+        [&add_b]
+    );
+    repository.rebase_abort().unwrap();
+    assert_eq!(repository.rebase_status().unwrap(), None);
+    assert_eq!(head().id().to_string(), change_b.as_ref());
+    assert!(!repo.path().join(REBASE_DIRECTORY_NAME).exists());
+
+    // Rewording gives the commit its new message without stopping.
+    todo.swap(0, 1);
+    todo[1].action = RebaseAction::Reword;
+    todo[1].message = Some("Change b to two\n\nWith a body.\n".into());
+    repository.rebase_interactive(&base, &todo).unwrap();
+    assert_eq!(repository.rebase_status().unwrap(), None);
+    let head = head();
+    assert_eq!(head.message(), Some("Change b to two\n\nWith a body.\n"));
+    assert_eq!(head.parent(0).unwrap().id().to_string(), add_b.as_ref());
+    assert!(!repo.path().join(REBASE_DIRECTORY_NAME).exists());
+}
+
+#[test]
+fn test_log_filters() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(dir.path()).unwrap();
+    let commit = |name: &str, email: &str, message: &str| {
+        std::fs::write(dir.path().join("a.txt"), message).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now(name, email).unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            parent.as_ref().as_slice(),
+        )
+        .unwrap();
+    };
+    commit("Ada", "ada@example.com", "Fix parse(x)");
+    commit("Bob", "bob@example.org", "Add a.b");
+    commit("Abe", "abe@example.com", "Add axb");
+
+    let repository = RealGitRepository::new(
+        git2::Repository::open(dir.path()).unwrap(),
+        None,
+        Arc::new(GitHostingProviderRegistry::new()),
+    );
+    let subjects = |author: Option<&str>, message: Option<&str>| {
+        repository
+            .log(&LogOptions {
+                limit: 10,
+                author: author.map(Into::into),
+                message: message.map(Into::into),
+                ..Default::default()
+            })
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.subject.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Filters contain the text as it is typed, ignoring case, rather than being patterns.
+    assert_eq!(subjects(Some("ADA"), None), ["Fix parse(x)"]);
+    assert_eq!(subjects(Some("example.org"), None), ["Add a.b"]);
+    assert_eq!(subjects(Some("a.e"), None), Vec::<String>::new());
+    assert_eq!(subjects(None, Some("a.B")), ["Add a.b"]);
+    assert_eq!(subjects(None, Some("parse(")), ["Fix parse(x)"]);
+    assert_eq!(subjects(Some("a"), Some("add")), ["Add axb", "Add a.b"]);
+}
@@ -0,0 +1,204 @@
+use git::repository::LogEntry;
+use gpui::SharedString;
+
+/// The part of the commit graph drawn alongside a single row of the history view.
+///
+/// Each row is split into a top half, which connects the lanes of the row above to this row's
+/// commit, and a bottom half, which connects the commit to the lanes of the row below.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GraphRow {
+    /// The lane in which the commit is drawn.
+    pub lane: usize,
+    /// Segments in the top half of the row, from a lane at the top edge to a lane at the middle.
+    pub incoming: Vec<(usize, usize)>,
+    /// Segments in the bottom half of the row, from a lane at the middle to a lane at the bottom
+    /// edge.
+    pub outgoing: Vec<(usize, usize)>,
+}
+
+impl GraphRow {
+    /// The number of lanes this row needs to be drawn.
+    pub fn lane_count(&self) -> usize {
+        self.incoming
+            .iter()
+            .chain(&self.outgoing)
+            .flat_map(|(from, to)| [*from, *to])
+            .chain([self.lane])
+            .max()
+            .map_or(0, |lane| lane + 1)
+    }
+}
+
+/// Assigns commits to lanes as they are loaded, one page at a time.
+///
+/// Commits must be pushed in topological order, children before their parents.
+#[derive(Default)]
+pub(crate) struct CommitGraph {
+    /// The commit that each lane is waiting for, if any.
+    lanes: Vec<Option<SharedString>>,
+    rows: Vec<GraphRow>,
+}
+
+impl CommitGraph {
+    pub fn rows(&self) -> &[GraphRow] {
+        &self.rows
+    }
+
+    pub fn push(&mut self, entry: &LogEntry) {
+        let mut row = GraphRow::default();
+
+        let lane = self
+            .lanes
+            .iter()
+            .position(|sha| sha.as_ref() == Some(&entry.sha))
+            .unwrap_or_else(|| self.allocate_lane());
+        row.lane = lane;
+
+        for (ix, sha) in self.lanes.iter_mut().enumerate() {
+            let Some(expected) = sha.as_ref() else {
+                continue;
+            };
+            if *expected == entry.sha {
+                row.incoming.push((ix, lane));
+                // Branches that converge on this commit end here.
+                if ix != lane {
+                    *sha = None;
+                }
+            } else {
+                row.incoming.push((ix, ix));
+            }
+        }
+
+        let mut parent_lanes = Vec::new();
+        let mut parents = entry.parent_shas.iter();
+        self.lanes[lane] = parents.next().cloned();
+        if self.lanes[lane].is_some() {
+            parent_lanes.push(lane);
+        }
+        for parent in parents {
+            let parent_lane = match self
+                .lanes
+                .iter()
+                .position(|sha| sha.as_ref() == Some(parent))
+            {
+                Some(parent_lane) => parent_lane,
+                None => {
+                    let parent_lane = self.allocate_lane();
+                    self.lanes[parent_lane] = Some(parent.clone());
+                    parent_lanes.push(parent_lane);
+                    parent_lane
+                }
+            };
+            row.outgoing.push((lane, parent_lane));
+        }
+
+        for (ix, sha) in self.lanes.iter().enumerate() {
+            if sha.is_none() {
+                continue;
+            }
+            if parent_lanes.contains(&ix) {
+                row.outgoing.push((lane, ix));
+            } else {
+                row.outgoing.push((ix, ix));
+            }
+        }
+        row.outgoing.sort_unstable();
+        row.outgoing.dedup();
+
+        while self.lanes.last().is_some_and(|sha| sha.is_none()) {
+            self.lanes.pop();
+        }
+        self.rows.push(row);
+    }
+
+    fn allocate_lane(&mut self) -> usize {
+        if let Some(ix) = self.lanes.iter().position(|sha| sha.is_none()) {
+            ix
+        } else {
+            self.lanes.push(None);
+            self.lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parent_shas: parents.iter().map(|sha| sha.to_string().into()).collect(),
+            subject: Default::default(),
+            author_name: Default::default(),
+            author_email: Default::default(),
+            commit_timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_linear_history() {
+        let mut graph = CommitGraph::default();
+        graph.push(&entry("c", &["b"]));
+        graph.push(&entry("b", &["a"]));
+        graph.push(&entry("a", &[]));
+
+        assert_eq!(
+            graph.rows(),
+            &[
+                GraphRow {
+                    lane: 0,
+                    incoming: vec![],
+                    outgoing: vec![(0, 0)],
+                },
+                GraphRow {
+                    lane: 0,
+                    incoming: vec![(0, 0)],
+                    outgoing: vec![(0, 0)],
+                },
+                GraphRow {
+                    lane: 0,
+                    incoming: vec![(0, 0)],
+                    outgoing: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        // d merges c into b; both branch off a.
+        let mut graph = CommitGraph::default();
+        graph.push(&entry("d", &["b", "c"]));
+        graph.push(&entry("c", &["a"]));
+        graph.push(&entry("b", &["a"]));
+        graph.push(&entry("a", &[]));
+
+        assert_eq!(
+            graph.rows(),
+            &[
+                GraphRow {
+                    lane: 0,
+                    incoming: vec![],
+                    outgoing: vec![(0, 0), (0, 1)],
+                },
+                GraphRow {
+                    lane: 1,
+                    incoming: vec![(0, 0), (1, 1)],
+                    outgoing: vec![(0, 0), (1, 1)],
+                },
+                GraphRow {
+                    lane: 0,
+                    incoming: vec![(0, 0), (1, 1)],
+                    outgoing: vec![(0, 0), (1, 1)],
+                },
+                GraphRow {
+                    lane: 0,
+                    incoming: vec![(0, 0), (1, 0)],
+                    outgoing: vec![],
+                },
+            ]
+        );
+        assert_eq!(graph.rows()[0].lane_count(), 2);
+    }
+}
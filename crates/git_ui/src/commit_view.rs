@@ -0,0 +1,361 @@
+use std::{
+    any::{Any, TypeId},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use editor::{Editor, EditorEvent};
+use git::repository::{CommitFileChange, RepoPath};
+use gpui::{
+    AnyElement, AnyView, App, AppContext, Entity, EventEmitter, FocusHandle, Focusable, Render,
+    Task, WeakEntity,
+};
+use language::{Buffer, Capability, DiskState, Language, LanguageRegistry, LocalFile, TextBuffer};
+use multi_buffer::{MultiBuffer, PathKey};
+use project::{git::Repository, Project, WorktreeId};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+    ToolbarItemLocation, Workspace,
+};
+
+/// A read-only multibuffer showing the changes recorded in a single commit or stash entry,
+/// relative to its first parent.
+pub(crate) struct CommitView {
+    sha: SharedString,
+    title: SharedString,
+    description: SharedString,
+    multibuffer: Entity<MultiBuffer>,
+    editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    _load_task: Task<Result<()>>,
+}
+
+/// The file backing one side of a committed change. Committed contents only live in the git
+/// object database, so these buffers are never saved to disk.
+struct CommittedFile {
+    path: Arc<Path>,
+    worktree_id: WorktreeId,
+    is_deleted: bool,
+}
+
+impl CommitView {
+    /// Opens the changes made by the commit `sha`, reusing an existing view of it if there is
+    /// one. `title` labels the tab and `description` is shown as its tooltip.
+    pub(crate) fn open(
+        repository: Entity<Repository>,
+        sha: SharedString,
+        title: SharedString,
+        description: SharedString,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        workspace
+            .update(cx, |workspace, cx| {
+                let existing = workspace
+                    .items_of_type::<Self>(cx)
+                    .find(|view| view.read(cx).sha == sha);
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, true, true, window, cx);
+                } else {
+                    let project = workspace.project().clone();
+                    let commit_view = cx.new(|cx| {
+                        Self::new(repository, sha, title, description, project, window, cx)
+                    });
+                    workspace.add_item_to_active_pane(
+                        Box::new(commit_view),
+                        None,
+                        true,
+                        window,
+                        cx,
+                    );
+                }
+            })
+            .ok();
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        sha: SharedString,
+        title: SharedString,
+        description: SharedString,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(
+                multibuffer.clone(),
+                Some(project.clone()),
+                true,
+                window,
+                cx,
+            );
+            editor.set_expand_all_diff_hunks(cx);
+            editor.set_read_only(true);
+            editor
+        });
+
+        let worktree_id = repository.read(cx).worktree_id;
+        let load_changes =
+            repository.update(cx, |repository, cx| repository.commit_changes(&sha, cx));
+        let language_registry = project.read(cx).languages().clone();
+        let load_task = cx.spawn(|this, mut cx| async move {
+            let changes = load_changes.await?;
+            for change in changes {
+                let language = language_registry
+                    .language_for_file_path(&change.path)
+                    .await
+                    .ok();
+                this.update(&mut cx, |this, cx| {
+                    this.add_change(change, worktree_id, language, &language_registry, cx)
+                })?;
+            }
+            Ok(())
+        });
+
+        Self {
+            sha,
+            title,
+            description,
+            multibuffer,
+            editor,
+            focus_handle,
+            _load_task: load_task,
+        }
+    }
+
+    fn add_change(
+        &mut self,
+        change: CommitFileChange,
+        worktree_id: WorktreeId,
+        language: Option<Arc<Language>>,
+        language_registry: &Arc<LanguageRegistry>,
+        cx: &mut Context<Self>,
+    ) {
+        let path_key = PathKey::namespaced("", change.path.0.clone());
+        let is_deleted = change.new_text.is_none();
+        let buffer = committed_buffer(
+            &change.path,
+            change.new_text.unwrap_or_default(),
+            worktree_id,
+            is_deleted,
+            language.clone(),
+            language_registry,
+            cx,
+        );
+        let base_buffer = committed_buffer(
+            &change.path,
+            change.old_text.unwrap_or_default(),
+            worktree_id,
+            false,
+            language,
+            language_registry,
+            cx,
+        );
+
+        let snapshot = buffer.read(cx).text_snapshot();
+        let diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&snapshot);
+            let _ = diff.set_base_text(base_buffer, snapshot.clone(), cx);
+            diff
+        });
+
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            let max_point = snapshot.max_point();
+            multibuffer.set_excerpts_for_path(
+                path_key,
+                buffer,
+                vec![language::Point::zero()..max_point],
+                0,
+                cx,
+            );
+            multibuffer.add_diff(diff, cx);
+        });
+    }
+}
+
+fn committed_buffer(
+    path: &RepoPath,
+    text: String,
+    worktree_id: WorktreeId,
+    is_deleted: bool,
+    language: Option<Arc<Language>>,
+    language_registry: &Arc<LanguageRegistry>,
+    cx: &mut App,
+) -> Entity<Buffer> {
+    let file = Arc::new(CommittedFile {
+        path: path.0.clone(),
+        worktree_id,
+        is_deleted,
+    });
+    cx.new(|cx| {
+        let mut buffer = Buffer::build(
+            TextBuffer::new(0, cx.entity_id().as_non_zero_u64().into(), text),
+            Some(file),
+            Capability::ReadOnly,
+        );
+        buffer.set_language_registry(language_registry.clone());
+        buffer.set_language(language, cx);
+        buffer
+    })
+}
+
+impl language::File for CommittedFile {
+    fn as_local(&self) -> Option<&dyn LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        if self.is_deleted {
+            DiskState::Deleted
+        } else {
+            DiskState::New
+        }
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &App) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a App) -> &'a OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    fn worktree_id(&self, _: &App) -> WorktreeId {
+        self.worktree_id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self, _: &App) -> proto::File {
+        proto::File {
+            worktree_id: self.worktree_id.to_proto(),
+            entry_id: None,
+            path: self.path.to_string_lossy().to_string(),
+            mtime: None,
+            is_deleted: self.is_deleted,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
+
+impl EventEmitter<EditorEvent> for CommitView {}
+
+impl Focusable for CommitView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        if self.multibuffer.read(cx).is_empty() {
+            self.focus_handle.clone()
+        } else {
+            self.editor.focus_handle(cx)
+        }
+    }
+}
+
+impl Item for CommitView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.description.clone())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Commit View Opened")
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for CommitView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_empty = self.multibuffer.read(cx).is_empty();
+
+        div()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .flex()
+            .items_center()
+            .justify_center()
+            .size_full()
+            .when(is_empty, |el| {
+                el.child(Label::new("No changes in this commit"))
+            })
+            .when(!is_empty, |el| el.child(self.editor.clone()))
+    }
+}
@@ -1,9 +1,12 @@
+use crate::askpass_modal::AskPassModal;
+use crate::commit_view::CommitView;
 use crate::git_panel_settings::StatusStyle;
 use crate::repository_selector::RepositorySelectorPopoverMenu;
 use crate::{
     git_panel_settings::GitPanelSettings, git_status_icon, repository_selector::RepositorySelector,
 };
-use crate::{project_diff, ProjectDiff};
+use crate::{history_view, project_conflicts, project_diff, ProjectDiff};
+use askpass::AskPassDelegate;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
 use editor::commit_tooltip::CommitTooltip;
@@ -11,15 +14,28 @@ use editor::{
     scroll::ScrollbarAutoHide, Editor, EditorElement, EditorMode, EditorSettings, MultiBuffer,
     ShowScrollbar,
 };
-use git::repository::{CommitDetails, ResetMode};
-use git::{repository::RepoPath, status::FileStatus, Commit, ToggleStaged};
-use git::{DiscardTrackedChanges, StageAll, TrashUntrackedFiles, UnstageAll};
+use futures::channel::mpsc;
+use futures::StreamExt as _;
+use git::repository::{
+    Branch, CommitDetails, PushOptions, Remote, RemoteCommandOutput, ResetMode, StashEntry,
+};
+use git::{
+    repository::RepoPath,
+    status::{FileStatus, GitSummary},
+    Commit, ToggleStaged,
+};
+use git::{
+    DiscardTrackedChanges, Fetch, ForcePush, Pull, Push, StageAll, StashAll, StashFile, StashPop,
+    TrashUntrackedFiles, UnstageAll,
+};
 use gpui::*;
 use itertools::Itertools;
 use language::{markdown, Buffer, File, ParsedMarkdown};
 use menu::{Confirm, SecondaryConfirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
 use multi_buffer::ExcerptInfo;
-use panel::{panel_editor_container, panel_editor_style, panel_filled_button, PanelHeader};
+use panel::{
+    panel_button, panel_editor_container, panel_editor_style, panel_filled_button, PanelHeader,
+};
 use project::{
     git::{GitEvent, Repository},
     Fs, Project, ProjectPath,
@@ -67,6 +83,13 @@ enum TrashCancel {
     Cancel,
 }
 
+#[derive(strum::EnumIter, strum::VariantNames)]
+#[strum(serialize_all = "title_case")]
+enum DropCancel {
+    Drop,
+    Cancel,
+}
+
 const GIT_PANEL_KEY: &str = "GitPanel";
 
 const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
@@ -88,6 +111,36 @@ pub fn init(cx: &mut App) {
                         .focus(window);
                 }
             });
+            workspace.register_action(|workspace, action: &Fetch, window, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.fetch(action, window, cx));
+                }
+            });
+            workspace.register_action(|workspace, action: &Pull, window, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.pull(action, window, cx));
+                }
+            });
+            workspace.register_action(|workspace, action: &Push, window, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.push(action, window, cx));
+                }
+            });
+            workspace.register_action(|workspace, action: &ForcePush, window, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.force_push(action, window, cx));
+                }
+            });
+            workspace.register_action(|workspace, action: &StashAll, window, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.stash_all(action, window, cx));
+                }
+            });
+            workspace.register_action(|workspace, action: &StashPop, window, cx| {
+                if let Some(git_panel) = workspace.panel::<GitPanel>(cx) {
+                    git_panel.update(cx, |git_panel, cx| git_panel.stash_pop(action, window, cx));
+                }
+            });
         },
     )
     .detach();
@@ -158,6 +211,31 @@ pub struct GitStatusEntry {
     pub(crate) is_staged: Option<bool>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RemoteOperation {
+    Fetch,
+    Pull,
+    Push,
+}
+
+impl RemoteOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            RemoteOperation::Fetch => "git fetch",
+            RemoteOperation::Pull => "git pull",
+            RemoteOperation::Push => "git push",
+        }
+    }
+
+    fn progress_label(&self) -> &'static str {
+        match self {
+            RemoteOperation::Fetch => "Fetching…",
+            RemoteOperation::Pull => "Pulling…",
+            RemoteOperation::Push => "Pushing…",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum TargetStatus {
     Staged,
@@ -189,6 +267,9 @@ pub struct GitPanel {
     new_staged_count: usize,
     pending: Vec<PendingOperation>,
     pending_commit: Option<Task<()>>,
+    pending_remote_operation: Option<(RemoteOperation, Task<()>)>,
+    /// The latest progress line git reported for the pending remote operation.
+    remote_operation_progress: Option<SharedString>,
     pending_serialization: Task<Option<()>>,
     project: Entity<Project>,
     repository_selector: Entity<RepositorySelector>,
@@ -196,6 +277,10 @@ pub struct GitPanel {
     scrollbar_state: ScrollbarState,
     selected_entry: Option<usize>,
     show_scrollbar: bool,
+    stash_entries: Vec<StashEntry>,
+    stashes_expanded: bool,
+    reload_stashes_task: Task<()>,
+    stash_fingerprint: Option<StashFingerprint>,
     tracked_count: usize,
     tracked_staged_count: usize,
     update_visible_entries_task: Task<()>,
@@ -204,6 +289,14 @@ pub struct GitPanel {
     context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
 }
 
+/// The parts of a repository's state that change whenever its stash might have.
+#[derive(PartialEq)]
+struct StashFingerprint {
+    repository: EntityId,
+    branch: Option<Branch>,
+    status: GitSummary,
+}
+
 fn commit_message_editor(
     commit_message_buffer: Entity<Buffer>,
     project: Entity<Project>,
@@ -268,6 +361,7 @@ impl GitPanel {
                     }
                     GitEvent::ActiveRepositoryChanged | GitEvent::GitStateUpdated => {
                         this.active_repository = git_store.read(cx).active_repository();
+                        this.reload_stashes_if_changed(window, cx);
                         this.schedule_update(true, window, cx);
                     }
                 },
@@ -296,6 +390,8 @@ impl GitPanel {
                 new_staged_count: 0,
                 pending: Vec::new(),
                 pending_commit: None,
+                pending_remote_operation: None,
+                remote_operation_progress: None,
                 pending_serialization: Task::ready(None),
                 project,
                 repository_selector,
@@ -303,6 +399,10 @@ impl GitPanel {
                 scrollbar_state,
                 selected_entry: None,
                 show_scrollbar: false,
+                stash_entries: Vec::new(),
+                stashes_expanded: false,
+                reload_stashes_task: Task::ready(()),
+                stash_fingerprint: None,
                 tracked_count: 0,
                 tracked_staged_count: 0,
                 update_visible_entries_task: Task::ready(()),
@@ -310,6 +410,7 @@ impl GitPanel {
                 context_menu: None,
                 workspace,
             };
+            git_panel.reload_stashes_if_changed(window, cx);
             git_panel.schedule_update(false, window, cx);
             git_panel.show_scrollbar = git_panel.should_show_scrollbar(cx);
             git_panel
@@ -1132,6 +1233,368 @@ impl GitPanel {
         self.pending_commit = Some(task);
     }
 
+    fn fetch(&mut self, _: &Fetch, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let askpass = self.askpass_delegate(RemoteOperation::Fetch, window, cx);
+        let progress = self.remote_progress(window, cx);
+        let fetch = repo.update(cx, |repo, cx| repo.fetch(askpass, progress, cx));
+        self.run_remote_operation(RemoteOperation::Fetch, fetch, window, cx);
+    }
+
+    fn pull(&mut self, _: &Pull, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(branch) = repo.read(cx).branch().cloned() else {
+            return;
+        };
+        let remote = self.get_current_remote(window, cx);
+        let askpass = self.askpass_delegate(RemoteOperation::Pull, window, cx);
+        let progress = self.remote_progress(window, cx);
+        let pull = cx.spawn(|_, mut cx| async move {
+            let Some(remote) = remote.await? else {
+                return Ok(None);
+            };
+            repo.update(&mut cx, |repo, cx| {
+                repo.pull(branch.name.clone(), remote.name, askpass, progress, cx)
+            })?
+            .await
+            .map(Some)
+        });
+        self.run_remote_operation(RemoteOperation::Pull, pull, window, cx);
+    }
+
+    fn push(&mut self, _: &Push, window: &mut Window, cx: &mut Context<Self>) {
+        self.push_with_options(false, window, cx);
+    }
+
+    fn force_push(&mut self, _: &ForcePush, window: &mut Window, cx: &mut Context<Self>) {
+        self.push_with_options(true, window, cx);
+    }
+
+    fn push_with_options(&mut self, force: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(branch) = repo.read(cx).branch().cloned() else {
+            return;
+        };
+        let options = if force {
+            Some(PushOptions::Force)
+        } else if branch.upstream.is_none() {
+            Some(PushOptions::SetUpstream)
+        } else {
+            None
+        };
+        let remote = self.get_current_remote(window, cx);
+        let askpass = self.askpass_delegate(RemoteOperation::Push, window, cx);
+        let progress = self.remote_progress(window, cx);
+        let push = cx.spawn(|_, mut cx| async move {
+            let Some(remote) = remote.await? else {
+                return Ok(None);
+            };
+            repo.update(&mut cx, |repo, cx| {
+                repo.push(
+                    branch.name.clone(),
+                    remote.name,
+                    options,
+                    askpass,
+                    progress,
+                    cx,
+                )
+            })?
+            .await
+            .map(Some)
+        });
+        self.run_remote_operation(RemoteOperation::Push, push, window, cx);
+    }
+
+    fn stash_all(&mut self, _: &StashAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.perform_stash(Vec::new(), window, cx);
+    }
+
+    fn stash_file(&mut self, _: &StashFile, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = maybe!({ self.entries.get(self.selected_entry?)?.status_entry() }) else {
+            return;
+        };
+        let repo_paths = vec![entry.repo_path.clone()];
+        self.perform_stash(repo_paths, window, cx);
+    }
+
+    /// Stashes the given paths, or every change in the working tree if `repo_paths` is empty.
+    fn perform_stash(
+        &mut self,
+        repo_paths: Vec<RepoPath>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let stash = repo.read(cx).stash_paths(None, repo_paths);
+        self.run_stash_operation(stash, window, cx);
+    }
+
+    fn stash_pop(&mut self, _: &StashPop, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry) = self.stash_entries.first().cloned() {
+            self.pop_stash(&entry, window, cx);
+        }
+    }
+
+    fn apply_stash(&mut self, entry: &StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let apply = repo.read(cx).stash_apply(entry.sha.clone());
+        self.run_stash_operation(apply, window, cx);
+    }
+
+    fn pop_stash(&mut self, entry: &StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let pop = repo.read(cx).stash_pop(entry.sha.clone());
+        self.run_stash_operation(pop, window, cx);
+    }
+
+    fn drop_stash(&mut self, entry: &StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let sha = entry.sha.clone();
+        let prompt = prompt("Drop this stash?", Some(entry.message.as_ref()), window, cx);
+        cx.spawn_in(window, |this, mut cx| async move {
+            match prompt.await? {
+                DropCancel::Drop => {}
+                DropCancel::Cancel => return Ok(()),
+            }
+            let drop = repo.update(&mut cx, |repo, _| repo.stash_drop(sha))?;
+            this.update_in(&mut cx, |this, window, cx| {
+                this.run_stash_operation(drop, window, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn run_stash_operation(
+        &mut self,
+        operation: futures::channel::oneshot::Receiver<Result<()>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, |this, mut cx| async move {
+            let result = operation.await;
+            this.update_in(&mut cx, |this, window, cx| {
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => this.show_err_toast(e, cx),
+                    Err(e) => this.show_err_toast(e.into(), cx),
+                }
+                this.reload_stashes(window, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn reload_stashes(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            self.stash_entries.clear();
+            return;
+        };
+        let stash_entries = repo.update(cx, |repo, cx| repo.stash_entries(cx));
+        self.reload_stashes_task = cx.spawn_in(window, |this, mut cx| async move {
+            let stash_entries = stash_entries.await.log_err().unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.stash_entries = stash_entries;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Reloads the stash list when the active repository's refs or status
+    /// have changed, since stashing, applying or popping changes at least
+    /// one of them. A stash dropped outside of Zed is picked up along with
+    /// the next such change.
+    fn reload_stashes_if_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let fingerprint = self.active_repository.as_ref().map(|repo| {
+            let repository = repo.entity_id();
+            let repo = repo.read(cx);
+            StashFingerprint {
+                repository,
+                branch: repo.branch().cloned(),
+                status: repo.repository_entry.status_summary(),
+            }
+        });
+        if fingerprint.is_some() && fingerprint == self.stash_fingerprint {
+            return;
+        }
+        self.stash_fingerprint = fingerprint;
+        self.reload_stashes(window, cx);
+    }
+
+    fn open_stash(&mut self, entry: &StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let entry = entry.clone();
+        CommitView::open(
+            repo,
+            entry.sha,
+            format!("stash@{{{}}}", entry.index).into(),
+            entry.message,
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn run_remote_operation<T>(
+        &mut self,
+        operation: RemoteOperation,
+        task: Task<Result<T>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) where
+        T: Into<Option<RemoteCommandOutput>> + 'static,
+    {
+        let task = cx.spawn_in(window, |this, mut cx| async move {
+            let result = task.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_remote_operation.take();
+                this.remote_operation_progress.take();
+                match result.map(Into::<Option<RemoteCommandOutput>>::into) {
+                    Ok(Some(output)) => this.show_remote_output(operation, output, cx),
+                    Ok(None) => {}
+                    Err(e) => this.show_err_toast(e, cx),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+        self.pending_remote_operation = Some((operation, task));
+        cx.notify();
+    }
+
+    /// Creates a channel for the progress of a remote operation, showing its latest line next
+    /// to the remote button while the operation is pending.
+    fn remote_progress(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> mpsc::UnboundedSender<String> {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded::<String>();
+        cx.spawn_in(window, |this, mut cx| async move {
+            while let Some(line) = progress_rx.next().await {
+                this.update(&mut cx, |this, cx| {
+                    if this.pending_remote_operation.is_some() {
+                        this.remote_operation_progress = Some(line.into());
+                        cx.notify();
+                    }
+                })
+                .ok();
+            }
+        })
+        .detach();
+        progress_tx
+    }
+
+    /// Resolves the remote to use for the active branch: its upstream remote if it tracks one,
+    /// otherwise the only remote, otherwise the one the user picks. Resolves to `None` if the
+    /// user cancels the choice.
+    fn get_current_remote(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Remote>>> {
+        let Some(repo) = self.active_repository.clone() else {
+            return Task::ready(Err(anyhow::anyhow!("no active repo")));
+        };
+        let branch_name = repo.read(cx).branch().map(|branch| branch.name.to_string());
+        let remotes = repo.update(cx, |repo, cx| repo.get_remotes(branch_name, cx));
+
+        cx.spawn_in(window, |_, mut cx| async move {
+            let mut remotes = remotes.await?;
+            match remotes.len() {
+                0 => Err(anyhow::anyhow!("No remotes configured for this repository")),
+                1 => Ok(remotes.pop()),
+                _ => {
+                    let mut answers = remotes
+                        .iter()
+                        .map(|remote| remote.name.to_string())
+                        .collect::<Vec<_>>();
+                    answers.push("Cancel".to_string());
+                    let selection = cx
+                        .update(|window, cx| {
+                            let answers = answers.iter().map(String::as_str).collect::<Vec<_>>();
+                            window.prompt(PromptLevel::Info, "Choose a remote", None, &answers, cx)
+                        })?
+                        .await?;
+                    Ok((selection < remotes.len()).then(|| remotes.swap_remove(selection)))
+                }
+            }
+        })
+    }
+
+    fn askpass_delegate(
+        &self,
+        operation: RemoteOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AskPassDelegate {
+        let workspace = self.workspace.clone();
+        let window_handle = window.window_handle();
+        AskPassDelegate::new(&mut cx.to_async(), move |prompt, tx, cx| {
+            window_handle
+                .update(cx, |_, window, cx| {
+                    workspace.update(cx, |workspace, cx| {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            AskPassModal::new(
+                                operation.name().into(),
+                                prompt.into(),
+                                tx,
+                                window,
+                                cx,
+                            )
+                        });
+                    })
+                })
+                .ok();
+        })
+    }
+
+    fn show_remote_output(
+        &self,
+        operation: RemoteOperation,
+        output: RemoteCommandOutput,
+        cx: &mut App,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let notif_id = NotificationId::Named("git-remote-operation".into());
+
+        // Git reports progress and its summary on stderr, so the last line of
+        // output is the most useful thing to show.
+        let summary = output
+            .stderr
+            .lines()
+            .chain(output.stdout.lines())
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .last();
+        let message = match summary {
+            Some(summary) => format!("{}: {}", operation.name(), summary),
+            None => format!("{} finished", operation.name()),
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(Toast::new(notif_id, message), cx);
+        });
+    }
+
     fn potential_co_authors(&self, cx: &App) -> Vec<(String, String)> {
         let mut new_co_authors = Vec::new();
         let project = self.project.read(cx);
@@ -1555,16 +2018,89 @@ impl GitPanel {
                 .is_above_project()
         });
 
-        self.panel_header_container(window, cx).when(
-            all_repositories.len() > 1 || has_repo_above,
-            |el| {
+        self.panel_header_container(window, cx)
+            .when(all_repositories.len() > 1 || has_repo_above, |el| {
                 el.child(
                     Label::new("Repository")
                         .size(LabelSize::Small)
                         .color(Color::Muted),
                 )
                 .child(self.render_repository_selector(cx))
-            },
+            })
+            .child(div().flex_grow())
+            .children(self.render_remote_button(cx))
+    }
+
+    fn render_remote_button(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let branch = self.active_repository.as_ref()?.read(cx).branch()?.clone();
+        if !self.has_write_access(cx) {
+            return None;
+        }
+
+        if let Some((operation, _)) = self.pending_remote_operation.as_ref() {
+            return Some(
+                h_flex()
+                    .min_w_0()
+                    .gap_1()
+                    .children(self.remote_operation_progress.clone().map(|progress| {
+                        Label::new(progress)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .text_ellipsis()
+                    }))
+                    .child(
+                        panel_button(operation.progress_label())
+                            .icon(IconName::ArrowCircle)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .icon_position(IconPosition::Start)
+                            .disabled(true),
+                    )
+                    .into_any_element(),
+            );
+        }
+
+        let tracking = branch
+            .upstream
+            .as_ref()
+            .and_then(|upstream| upstream.tracking.clone());
+        let (label, icon, tooltip, action): (SharedString, _, _, Box<dyn Action>) =
+            match (&branch.upstream, tracking) {
+                (None, _) => (
+                    "Publish".into(),
+                    IconName::ArrowUp,
+                    "git push --set-upstream",
+                    Push.boxed_clone(),
+                ),
+                (Some(_), Some(tracking)) if tracking.behind > 0 => (
+                    format!("Pull ({})", tracking.behind).into(),
+                    IconName::ArrowDown,
+                    "git pull",
+                    Pull.boxed_clone(),
+                ),
+                (Some(_), Some(tracking)) if tracking.ahead > 0 => (
+                    format!("Push ({})", tracking.ahead).into(),
+                    IconName::ArrowUp,
+                    "git push",
+                    Push.boxed_clone(),
+                ),
+                _ => (
+                    "Fetch".into(),
+                    IconName::ArrowCircle,
+                    "git fetch",
+                    Fetch.boxed_clone(),
+                ),
+            };
+
+        Some(
+            panel_button(label)
+                .icon(icon)
+                .icon_size(IconSize::Small)
+                .icon_color(Color::Muted)
+                .icon_position(IconPosition::Start)
+                .tooltip(Tooltip::for_action_title(tooltip, action.as_ref()))
+                .on_click(move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx))
+                .into_any_element(),
         )
     }
 
@@ -1716,6 +2252,93 @@ impl GitPanel {
             )
     }
 
+    fn render_stashes(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.stash_entries.is_empty() {
+            return None;
+        }
+        let expanded = self.stashes_expanded;
+        Some(
+            v_flex()
+                .flex_none()
+                .max_h(px(200.))
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    ListItem::new("stashes-header")
+                        .spacing(ListItemSpacing::Sparse)
+                        .toggle(expanded)
+                        .on_toggle(cx.listener(|this, _, _, cx| {
+                            this.stashes_expanded = !this.stashes_expanded;
+                            cx.notify();
+                        }))
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Label::new("Stashes")
+                                        .color(Color::Muted)
+                                        .size(LabelSize::Small),
+                                )
+                                .child(
+                                    Label::new(self.stash_entries.len().to_string())
+                                        .color(Color::Placeholder)
+                                        .size(LabelSize::Small),
+                                ),
+                        ),
+                )
+                .when(expanded, |this| {
+                    this.child(
+                        v_flex().id("stash-entries").overflow_y_scroll().children(
+                            self.stash_entries
+                                .iter()
+                                .map(|entry| self.render_stash_entry(entry, cx)),
+                        ),
+                    )
+                }),
+        )
+    }
+
+    fn render_stash_entry(&self, entry: &StashEntry, cx: &mut Context<Self>) -> AnyElement {
+        let index = entry.index;
+        let on_click = |action: fn(&mut Self, &StashEntry, &mut Window, &mut Context<Self>)| {
+            let entry = entry.clone();
+            cx.listener(move |this, _, window, cx| action(this, &entry, window, cx))
+        };
+        ListItem::new(("stash-entry", index))
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(1)
+            .on_click(on_click(Self::open_stash))
+            .child(
+                Label::new(entry.message.clone())
+                    .size(LabelSize::Small)
+                    .single_line()
+                    .text_ellipsis(),
+            )
+            .end_hover_slot(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new(("apply-stash", index), IconName::Check)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Apply Stash"))
+                            .on_click(on_click(Self::apply_stash)),
+                    )
+                    .child(
+                        IconButton::new(("pop-stash", index), IconName::Undo)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Pop Stash"))
+                            .on_click(on_click(Self::pop_stash)),
+                    )
+                    .child(
+                        IconButton::new(("drop-stash", index), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Drop Stash"))
+                            .on_click(on_click(Self::drop_stash)),
+                    ),
+            )
+            .into_any_element()
+    }
+
     fn render_previous_commit(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
         let active_repository = self.active_repository.as_ref()?;
         let branch = active_repository.read(cx).branch()?;
@@ -2016,6 +2639,7 @@ impl GitPanel {
             context_menu
                 .action("Stage File", ToggleStaged.boxed_clone())
                 .action(revert_title, editor::actions::RevertFile.boxed_clone())
+                .action("Stash File", StashFile.boxed_clone())
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
@@ -2030,17 +2654,38 @@ impl GitPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        let has_conflicts = self.conflicted_count > 0;
+        let has_stashes = !self.stash_entries.is_empty();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
-            context_menu
+            let mut context_menu = context_menu
                 .action("Stage All", StageAll.boxed_clone())
                 .action("Unstage All", UnstageAll.boxed_clone())
                 .action("Open Diff", project_diff::Diff.boxed_clone())
+                .action("View History", history_view::ShowHistory.boxed_clone());
+            if has_conflicts {
+                context_menu = context_menu.action(
+                    "Show Merge Conflicts",
+                    project_conflicts::ShowConflicts.boxed_clone(),
+                );
+            }
+            context_menu = context_menu
                 .separator()
                 .action(
                     "Discard Tracked Changes",
                     DiscardTrackedChanges.boxed_clone(),
                 )
                 .action("Trash Untracked Files", TrashUntrackedFiles.boxed_clone())
+                .separator()
+                .action("Stash All", StashAll.boxed_clone());
+            if has_stashes {
+                context_menu = context_menu.action("Pop Stash", StashPop.boxed_clone());
+            }
+            context_menu
+                .separator()
+                .action("Fetch", Fetch.boxed_clone())
+                .action("Pull", Pull.boxed_clone())
+                .action("Push", Push.boxed_clone())
+                .action("Force Push", ForcePush.boxed_clone())
         });
         self.set_context_menu(context_menu, position, window, cx);
     }
@@ -2245,6 +2890,9 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::unstage_all))
             .on_action(cx.listener(Self::discard_tracked_changes))
             .on_action(cx.listener(Self::clean_all))
+            .on_action(cx.listener(Self::stash_all))
+            .on_action(cx.listener(Self::stash_file))
+            .on_action(cx.listener(Self::stash_pop))
             .when(has_write_access && has_co_authors, |git_panel| {
                 git_panel.on_action(cx.listener(Self::toggle_fill_co_authors))
             })
@@ -2268,6 +2916,7 @@ impl Render for GitPanel {
             } else {
                 self.render_empty_state(cx).into_any_element()
             })
+            .children(self.render_stashes(cx))
             .children(self.render_previous_commit(cx))
             .child(self.render_commit_editor(window, cx))
             .children(self.context_menu.as_ref().map(|(menu, position, _)| {
@@ -2,13 +2,22 @@ use ::settings::Settings;
 use git::status::FileStatus;
 use git_panel_settings::GitPanelSettings;
 use gpui::App;
+use history_view::HistoryView;
+use project_conflicts::ProjectConflicts;
 use project_diff::ProjectDiff;
+use rebase_view::RebaseView;
 use ui::{ActiveTheme, Color, Icon, IconName, IntoElement};
 
+mod askpass_modal;
 pub mod branch_picker;
+mod commit_graph;
+mod commit_view;
 pub mod git_panel;
 mod git_panel_settings;
+mod history_view;
+pub mod project_conflicts;
 pub mod project_diff;
+mod rebase_view;
 // mod quick_commit;
 pub mod repository_selector;
 
@@ -16,6 +25,9 @@ pub fn init(cx: &mut App) {
     GitPanelSettings::register(cx);
     branch_picker::init(cx);
     cx.observe_new(ProjectDiff::register).detach();
+    cx.observe_new(ProjectConflicts::register).detach();
+    cx.observe_new(HistoryView::register).detach();
+    cx.observe_new(RebaseView::register).detach();
     // quick_commit::init(cx);
 }
 
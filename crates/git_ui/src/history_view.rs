@@ -0,0 +1,599 @@
+use std::time::Duration;
+
+use editor::{Editor, EditorEvent};
+use feature_flags::FeatureFlagViewExt;
+use git::repository::{LogEntry, LogOptions, RepoPath};
+use git::{FileHistory, SHORT_SHA_LENGTH};
+use gpui::{
+    actions, anchored, canvas, deferred, point, px, uniform_list, AnyElement, App, AppContext,
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, KeyContext, ListSizingBehavior,
+    MouseDownEvent, PathBuilder, Pixels, Point, Render, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity,
+};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::git::Repository;
+use theme::ActiveTheme;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ContextMenu};
+use util::ResultExt as _;
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    Workspace,
+};
+
+use crate::commit_graph::{CommitGraph, GraphRow};
+use crate::commit_view::CommitView;
+use crate::rebase_view::RebaseView;
+
+actions!(git, [ShowHistory]);
+
+/// The number of commits loaded at a time.
+const PAGE_SIZE: usize = 200;
+
+/// How many rows before the end of the loaded history to start loading the next page.
+const LOAD_MORE_THRESHOLD: usize = 50;
+
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(300);
+
+const ROW_HEIGHT: Pixels = px(28.);
+const LANE_WIDTH: Pixels = px(12.);
+const MAX_GRAPH_LANES: usize = 16;
+
+/// Lists the history of a repository, or of a single file within it.
+pub(crate) struct HistoryView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    path: Option<RepoPath>,
+    filter_editor: Entity<Editor>,
+    entries: Vec<LogEntry>,
+    graph: CommitGraph,
+    selected_entry: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    /// Whether there may be commits beyond the ones loaded so far.
+    has_more: bool,
+    load_task: Option<Task<()>>,
+    filter_task: Task<()>,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    _subscription: Subscription,
+}
+
+impl HistoryView {
+    pub(crate) fn register(
+        _: &mut Workspace,
+        window: Option<&mut Window>,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(window) = window else { return };
+        cx.when_flag_enabled::<feature_flags::GitUiFeatureFlag>(window, |workspace, _, _cx| {
+            workspace.register_action(Self::show_history);
+            workspace.register_action(Self::file_history);
+        });
+    }
+
+    fn show_history(
+        workspace: &mut Workspace,
+        _: &ShowHistory,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        Self::deploy(workspace, repository, None, window, cx);
+    }
+
+    fn file_history(
+        workspace: &mut Workspace,
+        _: &FileHistory,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx) else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some((repository, repo_path)) = workspace
+            .project()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            return;
+        };
+        Self::deploy(workspace, repository, Some(repo_path), window, cx);
+    }
+
+    fn deploy(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.items_of_type::<Self>(cx).find(|view| {
+            let view = view.read(cx);
+            view.repository == repository && view.path == path
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+        } else {
+            let workspace_handle = cx.entity().downgrade();
+            let history_view =
+                cx.new(|cx| Self::new(repository, path, workspace_handle, window, cx));
+            workspace.add_item_to_active_pane(Box::new(history_view), None, true, window, cx);
+        }
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter by message, or author:<name>", cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &filter_editor,
+            window,
+            |this, _, event: &EditorEvent, window, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.filter_task = cx.spawn_in(window, |this, mut cx| async move {
+                        cx.background_executor().timer(FILTER_DEBOUNCE).await;
+                        this.update(&mut cx, |this, cx| this.reload(cx)).ok();
+                    });
+                }
+            },
+        );
+
+        let mut this = Self {
+            repository,
+            workspace,
+            path,
+            filter_editor,
+            entries: Vec::new(),
+            graph: CommitGraph::default(),
+            selected_entry: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            has_more: true,
+            load_task: None,
+            filter_task: Task::ready(()),
+            context_menu: None,
+            _subscription: subscription,
+        };
+        this.load_more(cx);
+        this
+    }
+
+    fn log_options(&self, cx: &App) -> LogOptions {
+        let filter = self.filter_editor.read(cx).text(cx);
+        let filter = filter.trim();
+        let (author, message) = match filter.strip_prefix("author:") {
+            Some(author) => (Some(author.trim().to_string()), None),
+            None if filter.is_empty() => (None, None),
+            None => (None, Some(filter.to_string())),
+        };
+        LogOptions {
+            skip: self.entries.len(),
+            limit: PAGE_SIZE,
+            path: self.path.clone(),
+            author,
+            message,
+        }
+    }
+
+    /// The graph is only meaningful when every commit is listed, since a filtered history skips
+    /// the commits that connect the ones shown.
+    fn shows_graph(&self, cx: &App) -> bool {
+        self.path.is_none() && self.filter_editor.read(cx).text(cx).trim().is_empty()
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.graph = CommitGraph::default();
+        self.selected_entry = None;
+        self.has_more = true;
+        self.load_task = None;
+        self.load_more(cx);
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if !self.has_more || self.load_task.is_some() {
+            return;
+        }
+        let options = self.log_options(cx);
+        let log = self
+            .repository
+            .update(cx, |repository, cx| repository.log(options, cx));
+        self.load_task = Some(cx.spawn(|this, mut cx| async move {
+            let entries = log.await.log_err().unwrap_or_default();
+            this.update(&mut cx, |this, cx| {
+                this.has_more = entries.len() == PAGE_SIZE;
+                for entry in &entries {
+                    this.graph.push(entry);
+                }
+                this.entries.extend(entries);
+                this.load_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn dispatch_context(&self, window: &Window) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("GitHistory");
+        if self.focus_handle.is_focused(window) {
+            dispatch_context.add("menu");
+        }
+        dispatch_context
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_entry(0, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_entry(self.entries.len() - 1, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let next = self.selected_entry.map_or(0, |ix| ix + 1);
+        if next < self.entries.len() {
+            self.select_entry(next, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(prev) = self.selected_entry.and_then(|ix| ix.checked_sub(1)) {
+            self.select_entry(prev, cx);
+        }
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_entry = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.open_commit(ix, window, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let short_sha = entry.sha.chars().take(SHORT_SHA_LENGTH).collect::<String>();
+        CommitView::open(
+            self.repository.clone(),
+            entry.sha.clone(),
+            short_sha.into(),
+            entry.subject.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn rebase_onto(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let base = entry.sha.clone();
+        let repository = self.repository.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                RebaseView::deploy(workspace, repository, Some(base), window, cx)
+            })
+            .ok();
+    }
+
+    fn deploy_entry_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let this = cx.entity().downgrade();
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .entry("Open Commit", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.open_commit(ix, window, cx))
+                            .ok();
+                    }
+                })
+                .separator()
+                .entry("Rebase Interactively onto This Commit", None, {
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.rebase_onto(ix, window, cx))
+                            .ok();
+                    }
+                })
+        });
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.selected_entry = Some(ix);
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        graph_lanes: Option<usize>,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let entry = &self.entries[ix];
+        let selected = self.selected_entry == Some(ix);
+        let short_sha = entry.sha.chars().take(SHORT_SHA_LENGTH).collect::<String>();
+        let timestamp = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    OffsetDateTime::now_utc(),
+                    UtcOffset::UTC,
+                    time_format::TimestampFormat::Relative,
+                )
+            })
+            .unwrap_or_default();
+        let graph = graph_lanes.and_then(|lane_count| {
+            let row = self.graph.rows().get(ix)?.clone();
+            Some(render_graph_row(row, lane_count, cx))
+        });
+
+        // Rows are laid out by hand rather than as list items so that the graph can span their
+        // full height and connect with the rows around them.
+        h_flex()
+            .id(("history-entry", ix))
+            .w_full()
+            .h(ROW_HEIGHT)
+            .px_2()
+            .gap_2()
+            .when(selected, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .when(selected && self.focus_handle.is_focused(window), |this| {
+                this.border_1()
+                    .border_color(cx.theme().colors().border_focused)
+            })
+            .hover(|this| this.bg(cx.theme().colors().element_hover))
+            .cursor_pointer()
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.selected_entry = Some(ix);
+                this.open_commit(ix, window, cx);
+                cx.notify();
+            }))
+            .on_secondary_mouse_down(cx.listener(
+                move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_entry_context_menu(event.position, ix, window, cx);
+                    cx.stop_propagation();
+                },
+            ))
+            .children(graph)
+            .child(
+                Label::new(short_sha)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    Label::new(entry.subject.clone())
+                        .size(LabelSize::Small)
+                        .single_line()
+                        .text_ellipsis(),
+                ),
+            )
+            .child(
+                Label::new(entry.author_name.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .child(
+                Label::new(timestamp)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .into_any_element()
+    }
+}
+
+/// Draws the lanes of the commit graph for one row, with a dot for the row's commit.
+fn render_graph_row(row: GraphRow, lane_count: usize, cx: &App) -> impl IntoElement {
+    let accents = cx.theme().accents().clone();
+    let node_color = cx.theme().colors().text;
+    canvas(
+        |_, _, _| {},
+        move |bounds, _, window, _| {
+            let lane_x = |lane: usize| bounds.origin.x + LANE_WIDTH * (lane as f32 + 0.5);
+            let top = bounds.origin.y;
+            let middle = bounds.center().y;
+            let bottom = bounds.bottom_left().y;
+            let visible = |lane: usize| lane < lane_count;
+
+            let halves = [
+                (&row.incoming, top, middle),
+                (&row.outgoing, middle, bottom),
+            ];
+            for (segments, start_y, end_y) in halves {
+                for &(from, to) in segments {
+                    if !visible(from) || !visible(to) {
+                        continue;
+                    }
+                    let mut path = PathBuilder::stroke(px(1.5));
+                    path.move_to(point(lane_x(from), start_y));
+                    path.line_to(point(lane_x(to), end_y));
+                    if let Ok(path) = path.build() {
+                        // Forks and merges take the color of the other lane.
+                        let lane = if from == row.lane { to } else { from };
+                        window.paint_path(path, accents.color_for_index(lane as u32));
+                    }
+                }
+            }
+
+            if visible(row.lane) {
+                let radius = px(3.);
+                window.paint_quad(
+                    gpui::fill(
+                        gpui::Bounds::centered_at(
+                            point(lane_x(row.lane), middle),
+                            gpui::size(radius * 2., radius * 2.),
+                        ),
+                        node_color,
+                    )
+                    .corner_radii(radius),
+                );
+            }
+        },
+    )
+    .w(LANE_WIDTH * lane_count as f32)
+    .h_full()
+}
+
+impl EventEmitter<EditorEvent> for HistoryView {}
+
+impl Focusable for HistoryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HistoryView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(match &self.path {
+            Some(path) => format!("History of {}", path.to_string_lossy()).into(),
+            None => "Git History".into(),
+        })
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        let title: SharedString = match self.path.as_ref().and_then(|path| path.file_name()) {
+            Some(file_name) => format!("History: {}", file_name.to_string_lossy()).into(),
+            None => "History".into(),
+        };
+        Label::new(title)
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git History Opened")
+    }
+}
+
+impl Render for HistoryView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let graph_lanes = self.shows_graph(cx).then(|| {
+            self.graph
+                .rows()
+                .iter()
+                .map(GraphRow::lane_count)
+                .max()
+                .unwrap_or(0)
+                .min(MAX_GRAPH_LANES)
+        });
+        let entry_count = self.entries.len();
+        let is_loading = self.load_task.is_some();
+
+        v_flex()
+            .key_context(self.dispatch_context(window))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                    .child(div().flex_1().child(self.filter_editor.clone())),
+            )
+            .when(entry_count == 0, |this| {
+                this.child(
+                    h_flex().size_full().justify_center().child(
+                        Label::new(if is_loading {
+                            "Loading history…"
+                        } else {
+                            "No commits"
+                        })
+                        .color(Color::Muted),
+                    ),
+                )
+            })
+            .when(entry_count > 0, |this| {
+                this.child(
+                    uniform_list(cx.entity(), "history-entries", entry_count, {
+                        move |this, range, window, cx| {
+                            if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                                this.load_more(cx);
+                            }
+                            range
+                                .map(|ix| this.render_entry(ix, graph_lanes, window, cx))
+                                .collect()
+                        }
+                    })
+                    .size_full()
+                    .with_sizing_behavior(ListSizingBehavior::Infer)
+                    .track_scroll(self.scroll_handle.clone()),
+                )
+            })
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(gpui::Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
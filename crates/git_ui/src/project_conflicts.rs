@@ -0,0 +1,365 @@
+use std::any::{Any, TypeId};
+
+use anyhow::Result;
+use collections::HashSet;
+use editor::{parse_conflicts, Editor, EditorEvent};
+use feature_flags::FeatureFlagViewExt;
+use gpui::{
+    actions, AnyElement, AnyView, App, AppContext, Entity, EventEmitter, FocusHandle, Focusable,
+    Render, Subscription, Task, WeakEntity,
+};
+use language::{Buffer, Capability};
+use multi_buffer::{MultiBuffer, PathKey};
+use project::{git::GitStore, Project, ProjectPath};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+use crate::project_diff::{update_focus, watch_git_status, GitStatusView};
+
+actions!(git, [ShowConflicts]);
+
+/// A multibuffer listing every merge conflict region in the active repository, with the
+/// editor's inline controls for resolving each of them.
+pub(crate) struct ProjectConflicts {
+    multibuffer: Entity<MultiBuffer>,
+    editor: Entity<Editor>,
+    project: Entity<Project>,
+    git_store: Entity<GitStore>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+
+    _task: Task<Result<()>>,
+    _subscription: Subscription,
+}
+
+pub(crate) struct ConflictedBuffer {
+    path_key: PathKey,
+    buffer: Entity<Buffer>,
+}
+
+impl ProjectConflicts {
+    pub(crate) fn register(
+        _: &mut Workspace,
+        window: Option<&mut Window>,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(window) = window else { return };
+        cx.when_flag_enabled::<feature_flags::GitUiFeatureFlag>(window, |workspace, _, _cx| {
+            workspace.register_action(Self::deploy);
+        });
+    }
+
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        _: &ShowConflicts,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(existing) = workspace.item_of_type::<Self>(cx) {
+            workspace.activate_item(&existing, true, true, window, cx);
+        } else {
+            let workspace_handle = cx.entity();
+            let project_conflicts =
+                cx.new(|cx| Self::new(workspace.project().clone(), workspace_handle, window, cx));
+            workspace.add_item_to_active_pane(Box::new(project_conflicts), None, true, window, cx);
+        }
+    }
+
+    fn new(
+        project: Entity<Project>,
+        workspace: Entity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadWrite));
+
+        let editor = cx.new(|cx| {
+            Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), true, window, cx)
+        });
+
+        let git_store = project.read(cx).git_store().clone();
+        let (git_store_subscription, worker) = watch_git_status(&git_store, window, cx);
+
+        Self {
+            project,
+            git_store,
+            workspace: workspace.downgrade(),
+            focus_handle,
+            editor,
+            multibuffer,
+            _task: worker,
+            _subscription: git_store_subscription,
+        }
+    }
+}
+
+impl GitStatusView for ProjectConflicts {
+    type StatusBuffer = ConflictedBuffer;
+
+    fn load_buffers(&mut self, cx: &mut Context<Self>) -> Vec<Task<Result<ConflictedBuffer>>> {
+        let Some(repo) = self.git_store.read(cx).active_repository() else {
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.clear(cx);
+            });
+            return vec![];
+        };
+
+        let mut previous_paths = self.multibuffer.read(cx).paths().collect::<HashSet<_>>();
+
+        let mut result = vec![];
+        repo.update(cx, |repo, cx| {
+            for entry in repo.status() {
+                if !entry.status.is_conflicted() && !repo.has_conflict(&entry.repo_path) {
+                    continue;
+                }
+                let Some(project_path) = repo.repo_path_to_project_path(&entry.repo_path) else {
+                    continue;
+                };
+                let path_key = PathKey::namespaced("", entry.repo_path.0.clone());
+
+                previous_paths.remove(&path_key);
+                let load_buffer = self
+                    .project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx));
+
+                result.push(cx.spawn(|_, _| async move {
+                    let buffer = load_buffer.await?;
+                    Ok(ConflictedBuffer { path_key, buffer })
+                }));
+            }
+        });
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            for path in previous_paths {
+                multibuffer.remove_excerpts_for_path(path, cx);
+            }
+        });
+        result
+    }
+
+    fn register_buffer(
+        &mut self,
+        conflicted_buffer: ConflictedBuffer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path_key = conflicted_buffer.path_key;
+        let buffer = conflicted_buffer.buffer;
+
+        let snapshot = buffer.read(cx).snapshot();
+        let conflict_ranges = parse_conflicts(&snapshot)
+            .into_iter()
+            .map(|region| region.range)
+            .collect::<Vec<_>>();
+
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            if conflict_ranges.is_empty() {
+                multibuffer.remove_excerpts_for_path(path_key, cx);
+            } else {
+                multibuffer.set_excerpts_for_path(
+                    path_key,
+                    buffer,
+                    conflict_ranges,
+                    editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                );
+            }
+        });
+        update_focus(
+            &self.multibuffer,
+            &self.editor,
+            &self.focus_handle,
+            window,
+            cx,
+        );
+    }
+}
+
+impl EventEmitter<EditorEvent> for ProjectConflicts {}
+
+impl Focusable for ProjectConflicts {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        if self.multibuffer.read(cx).is_empty() {
+            self.focus_handle.clone()
+        } else {
+            self.editor.focus_handle(cx)
+        }
+    }
+}
+
+impl Item for ProjectConflicts {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some("Merge Conflicts".into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new("Merge Conflicts")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Project Conflicts Opened")
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        let workspace = self.workspace.upgrade()?;
+        Some(cx.new(|cx| ProjectConflicts::new(self.project.clone(), workspace, window, cx)))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, window, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.reload(project, window, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for ProjectConflicts {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_empty = self.multibuffer.read(cx).is_empty();
+
+        div()
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background)
+            .flex()
+            .items_center()
+            .justify_center()
+            .size_full()
+            .when(is_empty, |el| el.child(Label::new("No merge conflicts")))
+            .when(!is_empty, |el| el.child(self.editor.clone()))
+    }
+}
@@ -12,7 +12,10 @@ use gpui::{
 };
 use language::{Anchor, Buffer, Capability, OffsetRangeExt, Point};
 use multi_buffer::{MultiBuffer, PathKey};
-use project::{git::GitStore, Project, ProjectPath};
+use project::{
+    git::{GitEvent, GitStore},
+    Project, ProjectPath,
+};
 use theme::ActiveTheme;
 use ui::prelude::*;
 use util::ResultExt as _;
@@ -34,14 +37,13 @@ pub(crate) struct ProjectDiff {
     git_store: Entity<GitStore>,
     workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
-    update_needed: postage::watch::Sender<()>,
     pending_scroll: Option<PathKey>,
 
     _task: Task<Result<()>>,
     _subscription: Subscription,
 }
 
-struct DiffBuffer {
+pub(crate) struct DiffBuffer {
     path_key: PathKey,
     buffer: Entity<Buffer>,
     diff: Entity<BufferDiff>,
@@ -138,21 +140,7 @@ impl ProjectDiff {
             .detach();
 
         let git_store = project.read(cx).git_store().clone();
-        let git_store_subscription = cx.subscribe_in(
-            &git_store,
-            window,
-            move |this, _git_store, _event, _window, _cx| {
-                *this.update_needed.borrow_mut() = ();
-            },
-        );
-
-        let (mut send, recv) = postage::watch::channel::<()>();
-        let worker = window.spawn(cx, {
-            let this = cx.weak_entity();
-            |cx| Self::handle_status_updates(this, recv, cx)
-        });
-        // Kick of a refresh immediately
-        *send.borrow_mut() = ();
+        let (git_store_subscription, worker) = watch_git_status(&git_store, window, cx);
 
         Self {
             project,
@@ -163,7 +151,6 @@ impl ProjectDiff {
             editor,
             multibuffer,
             pending_scroll: None,
-            update_needed: send,
             _task: worker,
             _subscription: git_store_subscription,
         }
@@ -244,6 +231,10 @@ impl ProjectDiff {
             _ => {}
         }
     }
+}
+
+impl GitStatusView for ProjectDiff {
+    type StatusBuffer = DiffBuffer;
 
     fn load_buffers(&mut self, cx: &mut Context<Self>) -> Vec<Task<Result<DiffBuffer>>> {
         let Some(repo) = self.git_store.read(cx).active_repository() else {
@@ -331,43 +322,106 @@ impl ProjectDiff {
                 cx,
             );
         });
-        if self.multibuffer.read(cx).is_empty()
-            && self
-                .editor
-                .read(cx)
-                .focus_handle(cx)
-                .contains_focused(window, cx)
-        {
-            self.focus_handle.focus(window);
-        } else if self.focus_handle.is_focused(window) && !self.multibuffer.read(cx).is_empty() {
-            self.editor.update(cx, |editor, cx| {
-                editor.focus_handle(cx).focus(window);
-            });
-        }
+        update_focus(
+            &self.multibuffer,
+            &self.editor,
+            &self.focus_handle,
+            window,
+            cx,
+        );
         if self.pending_scroll.as_ref() == Some(&path_key) {
             self.scroll_to_path(path_key, window, cx);
         }
     }
 
-    pub async fn handle_status_updates(
-        this: WeakEntity<Self>,
-        mut recv: postage::watch::Receiver<()>,
-        mut cx: AsyncWindowContext,
-    ) -> Result<()> {
-        while let Some(_) = recv.next().await {
-            let buffers_to_load = this.update(&mut cx, |this, cx| this.load_buffers(cx))?;
-            for buffer_to_load in buffers_to_load {
-                if let Some(buffer) = buffer_to_load.await.log_err() {
-                    cx.update(|window, cx| {
-                        this.update(cx, |this, cx| this.register_buffer(buffer, window, cx))
-                            .ok();
-                    })?;
-                }
+    fn buffers_loaded(&mut self, _: &mut Context<Self>) {
+        self.pending_scroll.take();
+    }
+}
+
+/// A view of buffers from the active repository's status in a multibuffer, which reloads them
+/// whenever the git state changes. Shared by [`ProjectDiff`] and the merge conflicts view.
+pub(crate) trait GitStatusView: Sized + 'static {
+    type StatusBuffer: 'static;
+
+    /// Starts loading the buffers to show, and removes the excerpts of paths that are gone.
+    fn load_buffers(&mut self, cx: &mut Context<Self>) -> Vec<Task<Result<Self::StatusBuffer>>>;
+
+    fn register_buffer(
+        &mut self,
+        buffer: Self::StatusBuffer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    );
+
+    /// Called once every buffer of a status update has been registered.
+    fn buffers_loaded(&mut self, _: &mut Context<Self>) {}
+}
+
+/// Loads the view's buffers right away, and again whenever the git store changes.
+pub(crate) fn watch_git_status<V: GitStatusView>(
+    git_store: &Entity<GitStore>,
+    window: &mut Window,
+    cx: &mut Context<V>,
+) -> (Subscription, Task<Result<()>>) {
+    let (mut update_needed, recv) = postage::watch::channel::<()>();
+    let worker = window.spawn(cx, {
+        let this = cx.weak_entity();
+        |cx| handle_status_updates(this, recv, cx)
+    });
+    // Kick off a refresh immediately
+    *update_needed.borrow_mut() = ();
+    let subscription = cx.subscribe_in(
+        git_store,
+        window,
+        move |_, _git_store, _event: &GitEvent, _window, _cx| {
+            *update_needed.borrow_mut() = ();
+        },
+    );
+    (subscription, worker)
+}
+
+async fn handle_status_updates<V: GitStatusView>(
+    this: WeakEntity<V>,
+    mut recv: postage::watch::Receiver<()>,
+    mut cx: AsyncWindowContext,
+) -> Result<()> {
+    while let Some(_) = recv.next().await {
+        let buffers_to_load = this.update(&mut cx, |this, cx| this.load_buffers(cx))?;
+        for buffer_to_load in buffers_to_load {
+            if let Some(buffer) = buffer_to_load.await.log_err() {
+                cx.update(|window, cx| {
+                    this.update(cx, |this, cx| this.register_buffer(buffer, window, cx))
+                        .ok();
+                })?;
             }
-            this.update(&mut cx, |this, _| this.pending_scroll.take())?;
         }
+        this.update(&mut cx, |this, cx| this.buffers_loaded(cx))?;
+    }
 
-        Ok(())
+    Ok(())
+}
+
+/// Moves focus to the editor once the multibuffer has excerpts, and back to the view when it
+/// becomes empty.
+pub(crate) fn update_focus(
+    multibuffer: &Entity<MultiBuffer>,
+    editor: &Entity<Editor>,
+    focus_handle: &FocusHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    if multibuffer.read(cx).is_empty()
+        && editor
+            .read(cx)
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+    {
+        focus_handle.focus(window);
+    } else if focus_handle.is_focused(window) && !multibuffer.read(cx).is_empty() {
+        editor.update(cx, |editor, cx| {
+            editor.focus_handle(cx).focus(window);
+        });
     }
 }
 
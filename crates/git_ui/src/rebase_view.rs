@@ -0,0 +1,848 @@
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use feature_flags::FeatureFlagViewExt;
+use git::repository::{RebaseAction, RebaseStatus, RebaseTodoEntry};
+use git::SHORT_SHA_LENGTH;
+use gpui::{
+    actions, AnyElement, App, AppContext, AsyncWindowContext, Entity, EventEmitter, FocusHandle,
+    Focusable, KeyContext, Render, Subscription, Task,
+};
+use menu::{SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::git::Repository;
+use theme::ActiveTheme;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    Workspace,
+};
+
+use crate::project_conflicts::ShowConflicts;
+
+actions!(
+    git,
+    [
+        ShowRebase,
+        MoveRebaseEntryUp,
+        MoveRebaseEntryDown,
+        CycleRebaseAction
+    ]
+);
+
+/// The actions offered when planning a rebase, in the order they are cycled through.
+const PLANNING_ACTIONS: [RebaseAction; 5] = [
+    RebaseAction::Pick,
+    RebaseAction::Reword,
+    RebaseAction::Squash,
+    RebaseAction::Fixup,
+    RebaseAction::Drop,
+];
+
+enum RebaseState {
+    /// Editing the todo list for rebasing onto `base`, before the rebase has started.
+    Planning {
+        base: SharedString,
+        entries: Vec<RebaseTodoEntry>,
+    },
+    /// Git has stopped partway through the rebase.
+    Paused(RebaseStatus),
+    /// No rebase is in progress, either because none was started or because it finished.
+    Idle { finished: bool },
+}
+
+/// Plans an interactive rebase of the current branch, and steps through it when git stops for
+/// conflicts or to edit a commit message.
+pub(crate) struct RebaseView {
+    repository: Entity<Repository>,
+    state: RebaseState,
+    selected_entry: Option<usize>,
+    /// Edits the new message of the selected entry to reword while planning, or the message of
+    /// the commit git stopped at.
+    message_editor: Entity<Editor>,
+    /// The commit whose message is shown in the message editor, and the message as loaded.
+    loaded_message: Option<(SharedString, String)>,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    pending_operation: Option<Task<()>>,
+    load_task: Task<()>,
+    load_message_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RebaseView {
+    pub(crate) fn register(
+        _: &mut Workspace,
+        window: Option<&mut Window>,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(window) = window else { return };
+        cx.when_flag_enabled::<feature_flags::GitUiFeatureFlag>(window, |workspace, _, _cx| {
+            workspace.register_action(Self::show_rebase);
+        });
+    }
+
+    fn show_rebase(
+        workspace: &mut Workspace,
+        _: &ShowRebase,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        Self::deploy(workspace, repository, None, window, cx);
+    }
+
+    /// Opens the rebase view for `repository`, planning a rebase onto `base` if one is given.
+    pub(crate) fn deploy(
+        workspace: &mut Workspace,
+        repository: Entity<Repository>,
+        base: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .find(|view| view.read(cx).repository == repository);
+        if let Some(existing) = existing {
+            if let Some(base) = base {
+                existing.update(cx, |view, cx| view.plan(base, window, cx));
+            }
+            workspace.activate_item(&existing, true, true, window, cx);
+        } else {
+            let rebase_view = cx.new(|cx| Self::new(repository, base, window, cx));
+            workspace.add_item_to_active_pane(Box::new(rebase_view), None, true, window, cx);
+        }
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        base: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(12, window, cx);
+            editor.set_show_gutter(false, cx);
+            editor.set_placeholder_text("Commit message", cx);
+            editor
+        });
+
+        // Rebases can also be continued or aborted from the command line, so keep the status
+        // up to date with the repository.
+        let git_store = repository.read(cx).git_store();
+        let mut subscriptions = Vec::new();
+        if let Some(git_store) = git_store {
+            subscriptions.push(
+                cx.subscribe_in(&git_store, window, |this, _, _, window, cx| {
+                    if this.pending_operation.is_none() && !this.is_planning() {
+                        this.refresh(window, cx);
+                    }
+                }),
+            );
+        }
+        subscriptions.push(
+            cx.subscribe(&message_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_reword_message(cx);
+                }
+            }),
+        );
+
+        let mut this = Self {
+            repository,
+            state: RebaseState::Idle { finished: false },
+            selected_entry: None,
+            message_editor,
+            loaded_message: None,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            pending_operation: None,
+            load_task: Task::ready(()),
+            load_message_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        match base {
+            Some(base) => this.plan(base, window, cx),
+            None => this.refresh(window, cx),
+        }
+        this
+    }
+
+    fn is_planning(&self) -> bool {
+        matches!(self.state, RebaseState::Planning { .. })
+    }
+
+    /// Loads the todo list for rebasing onto `base`, unless a rebase is already in progress.
+    fn plan(&mut self, base: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        self.error = None;
+        let status = self
+            .repository
+            .update(cx, |repository, cx| repository.rebase_status(cx));
+        let todo = self
+            .repository
+            .update(cx, |repository, cx| repository.rebase_todo(&base, cx));
+        self.load_task = cx.spawn_in(window, |this, mut cx| async move {
+            let result = async {
+                match status.await? {
+                    Some(status) => Ok(RebaseState::Paused(status)),
+                    None => Ok(RebaseState::Planning {
+                        base,
+                        entries: todo.await?,
+                    }),
+                }
+            }
+            .await;
+            this.update_in(&mut cx, |this, window, cx| match result {
+                Ok(state) => this.set_state(state, window, cx),
+                Err(error) => this.set_error(error, cx),
+            })
+            .ok();
+        });
+    }
+
+    /// Reloads the status of the rebase in progress, if any.
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let status = self
+            .repository
+            .update(cx, |repository, cx| repository.rebase_status(cx));
+        self.load_task = cx.spawn_in(window, |this, mut cx| async move {
+            let result = status.await;
+            this.update_in(&mut cx, |this, window, cx| match result {
+                Ok(Some(status)) => this.set_state(RebaseState::Paused(status), window, cx),
+                Ok(None) => {
+                    let finished = !matches!(this.state, RebaseState::Idle { finished: false });
+                    this.set_state(RebaseState::Idle { finished }, window, cx);
+                }
+                Err(error) => this.set_error(error, cx),
+            })
+            .ok();
+        });
+    }
+
+    fn set_state(&mut self, state: RebaseState, window: &mut Window, cx: &mut Context<Self>) {
+        if let RebaseState::Paused(status) = &state {
+            self.load_message(status, window, cx);
+        } else {
+            self.loaded_message = None;
+        }
+        let entry_count = match &state {
+            RebaseState::Planning { entries, .. } => entries.len(),
+            RebaseState::Paused(status) => status.remaining.len(),
+            RebaseState::Idle { .. } => 0,
+        };
+        self.selected_entry = self
+            .selected_entry
+            .filter(|ix| *ix < entry_count)
+            .or((entry_count > 0).then_some(0));
+        self.state = state;
+        self.load_reword_message(window, cx);
+        cx.notify();
+    }
+
+    fn set_error(&mut self, error: anyhow::Error, cx: &mut Context<Self>) {
+        self.error = Some(format!("{error:#}").into());
+        cx.notify();
+    }
+
+    /// Fills the message editor with the message of the commit git stopped at, so that it can
+    /// be reworded before continuing.
+    fn load_message(&mut self, status: &RebaseStatus, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(stopped_at) = status.stopped_at.as_ref().filter(|_| !status.has_conflicts) else {
+            self.loaded_message = None;
+            return;
+        };
+        if self
+            .loaded_message
+            .as_ref()
+            .is_some_and(|(sha, _)| *sha == stopped_at.sha)
+        {
+            return;
+        }
+        let sha = stopped_at.sha.clone();
+        let details = self
+            .repository
+            .update(cx, |repository, cx| repository.show("HEAD", cx));
+        cx.spawn_in(window, |this, mut cx| async move {
+            let result = details.await;
+            this.update_in(&mut cx, |this, window, cx| match result {
+                Ok(details) => {
+                    let message = details.message.trim_end().to_string();
+                    this.message_editor.update(cx, |editor, cx| {
+                        editor.set_text(message.clone(), window, cx)
+                    });
+                    this.loaded_message = Some((sha, message));
+                    cx.notify();
+                }
+                Err(error) => this.set_error(error, cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Fills the message editor with the message of the selected entry, if it is to be
+    /// reworded, so that the new message can be given before starting the rebase.
+    fn load_reword_message(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RebaseState::Planning { entries, .. } = &self.state else {
+            return;
+        };
+        let Some(entry) = self
+            .selected_entry
+            .and_then(|ix| entries.get(ix))
+            .filter(|entry| entry.action == RebaseAction::Reword)
+        else {
+            return;
+        };
+        if self
+            .loaded_message
+            .as_ref()
+            .is_some_and(|(sha, _)| *sha == entry.sha)
+        {
+            return;
+        }
+        let sha = entry.sha.clone();
+        let new_message = entry.message.clone();
+        let details = self
+            .repository
+            .update(cx, |repository, cx| repository.show(&sha, cx));
+        self.load_message_task = cx.spawn_in(window, |this, mut cx| async move {
+            let result = details.await;
+            this.update_in(&mut cx, |this, window, cx| match result {
+                Ok(details) => {
+                    let message = details.message.trim_end().to_string();
+                    let text = new_message.map_or_else(|| message.clone(), String::from);
+                    this.loaded_message = Some((sha, message));
+                    this.message_editor
+                        .update(cx, |editor, cx| editor.set_text(text, window, cx));
+                    cx.notify();
+                }
+                Err(error) => this.set_error(error, cx),
+            })
+            .ok();
+        });
+    }
+
+    /// Records the message editor's contents as the new message of the entry being reworded.
+    fn update_reword_message(&mut self, cx: &mut Context<Self>) {
+        let Some((sha, loaded)) = self.loaded_message.clone() else {
+            return;
+        };
+        let message = self.message_editor.read(cx).text(cx);
+        let Some(entry) = self
+            .planned_entries_mut()
+            .and_then(|entries| entries.iter_mut().find(|entry| entry.sha == sha))
+        else {
+            return;
+        };
+        entry.message = (message.trim_end() != loaded).then(|| message.into());
+    }
+
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let RebaseState::Planning { base, entries } = &self.state else {
+            return;
+        };
+        let rebase = self.repository.update(cx, |repository, _| {
+            repository.rebase_interactive(base.clone(), entries.clone())
+        });
+        self.run_operation(|_| async move { rebase.await? }, window, cx);
+    }
+
+    fn continue_rebase(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let message = self.message_editor.read(cx).text(cx);
+        let amend = self
+            .loaded_message
+            .as_ref()
+            .filter(|(_, loaded)| message.trim_end() != loaded)
+            .map(|_| {
+                self.repository.update(cx, |repository, _| {
+                    repository.amend_commit_message(message.into())
+                })
+            });
+        let repository = self.repository.clone();
+        self.run_operation(
+            |mut cx| async move {
+                if let Some(amend) = amend {
+                    amend.await??;
+                }
+                repository
+                    .update(&mut cx, |repository, _| repository.rebase_continue())?
+                    .await?
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn abort(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        let abort = self
+            .repository
+            .update(cx, |repository, _| repository.rebase_abort());
+        let this = cx.entity().downgrade();
+        self.run_operation(
+            |mut cx| async move {
+                abort.await??;
+                // An aborted rebase didn't finish, which the refresh that follows can't tell.
+                this.update(&mut cx, |this, _| {
+                    this.state = RebaseState::Idle { finished: false }
+                })
+            },
+            window,
+            cx,
+        );
+    }
+
+    fn run_operation<F, Fut>(&mut self, operation: F, window: &mut Window, cx: &mut Context<Self>)
+    where
+        F: FnOnce(AsyncWindowContext) -> Fut + 'static,
+        Fut: std::future::Future<Output = Result<()>> + 'static,
+    {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        self.error = None;
+        self.pending_operation = Some(cx.spawn_in(window, |this, mut cx| async move {
+            let result = operation(cx.clone()).await;
+            this.update_in(&mut cx, |this, window, cx| {
+                this.pending_operation = None;
+                match result {
+                    Ok(()) => this.refresh(window, cx),
+                    Err(error) => {
+                        this.set_error(error, cx);
+                        // Leave the plan in place if the rebase could not be started, so that it
+                        // can be adjusted and tried again.
+                        if !this.is_planning() {
+                            this.refresh(window, cx);
+                        }
+                    }
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn dispatch_context(&self, window: &Window) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("GitRebase");
+        if self.focus_handle.is_focused(window) {
+            dispatch_context.add("menu");
+        }
+        dispatch_context
+    }
+
+    fn planned_entries_mut(&mut self) -> Option<&mut Vec<RebaseTodoEntry>> {
+        match &mut self.state {
+            RebaseState::Planning { entries, .. } => Some(entries),
+            _ => None,
+        }
+    }
+
+    fn entry_count(&self) -> usize {
+        match &self.state {
+            RebaseState::Planning { entries, .. } => entries.len(),
+            RebaseState::Paused(status) => status.remaining.len(),
+            RebaseState::Idle { .. } => 0,
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, window: &mut Window, cx: &mut Context<Self>) {
+        if self.entry_count() > 0 {
+            self.select_entry(0, window, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(last) = self.entry_count().checked_sub(1) {
+            self.select_entry(last, window, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        let next = self.selected_entry.map_or(0, |ix| ix + 1);
+        if next < self.entry_count() {
+            self.select_entry(next, window, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(prev) = self.selected_entry.and_then(|ix| ix.checked_sub(1)) {
+            self.select_entry(prev, window, cx);
+        }
+    }
+
+    fn select_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_entry = Some(ix);
+        self.load_reword_message(window, cx);
+        cx.notify();
+    }
+
+    fn move_entry_up(&mut self, _: &MoveRebaseEntryUp, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.move_entry(ix, -1, cx);
+        }
+    }
+
+    fn move_entry_down(&mut self, _: &MoveRebaseEntryDown, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.move_entry(ix, 1, cx);
+        }
+    }
+
+    fn move_entry(&mut self, ix: usize, offset: isize, cx: &mut Context<Self>) {
+        let Some(entries) = self.planned_entries_mut() else {
+            return;
+        };
+        let Some(target) = ix
+            .checked_add_signed(offset)
+            .filter(|ix| *ix < entries.len())
+        else {
+            return;
+        };
+        entries.swap(ix, target);
+        self.selected_entry = Some(target);
+        cx.notify();
+    }
+
+    fn cycle_action(&mut self, _: &CycleRebaseAction, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.cycle_entry_action(ix, window, cx);
+        }
+    }
+
+    fn cycle_entry_action(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self
+            .planned_entries_mut()
+            .and_then(|entries| entries.get_mut(ix))
+        else {
+            return;
+        };
+        let next = PLANNING_ACTIONS
+            .iter()
+            .position(|action| *action == entry.action)
+            .map_or(0, |position| (position + 1) % PLANNING_ACTIONS.len());
+        entry.action = PLANNING_ACTIONS[next];
+        self.load_reword_message(window, cx);
+        cx.notify();
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &RebaseTodoEntry,
+        editable: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let selected = self.selected_entry == Some(ix);
+        let short_sha = entry.sha.chars().take(SHORT_SHA_LENGTH).collect::<String>();
+        let is_dropped = entry.action == RebaseAction::Drop;
+        let action_color = match entry.action {
+            RebaseAction::Pick => Color::Default,
+            RebaseAction::Reword | RebaseAction::Edit => Color::Accent,
+            RebaseAction::Squash | RebaseAction::Fixup => Color::Warning,
+            RebaseAction::Drop => Color::Error,
+        };
+
+        h_flex()
+            .id(("rebase-entry", ix))
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .when(selected, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .hover(|this| this.bg(cx.theme().colors().element_hover))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select_entry(ix, window, cx);
+            }))
+            .child(
+                Button::new(("rebase-action", ix), entry.action.as_str())
+                    .label_size(LabelSize::Small)
+                    .color(action_color)
+                    .disabled(!editable)
+                    .when(editable, |this| {
+                        this.tooltip(Tooltip::text("Change Action"))
+                    })
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected_entry = Some(ix);
+                        this.cycle_entry_action(ix, window, cx);
+                    })),
+            )
+            .child(
+                Label::new(short_sha)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div().flex_1().overflow_hidden().child(
+                    Label::new(entry.subject.clone())
+                        .size(LabelSize::Small)
+                        .single_line()
+                        .text_ellipsis()
+                        .when(is_dropped, |this| this.strikethrough().color(Color::Muted)),
+                ),
+            )
+            .when(editable, |this| {
+                this.child(
+                    IconButton::new(("move-up", ix), IconName::ArrowUp)
+                        .icon_size(IconSize::Small)
+                        .disabled(ix == 0)
+                        .tooltip(Tooltip::text("Move Up"))
+                        .on_click(cx.listener(move |this, _, _, cx| this.move_entry(ix, -1, cx))),
+                )
+                .child(
+                    IconButton::new(("move-down", ix), IconName::ArrowDown)
+                        .icon_size(IconSize::Small)
+                        .disabled(ix + 1 == self.entry_count())
+                        .tooltip(Tooltip::text("Move Down"))
+                        .on_click(cx.listener(move |this, _, _, cx| this.move_entry(ix, 1, cx))),
+                )
+            })
+            .into_any_element()
+    }
+
+    /// Renders the message editor for the selected entry, if it is to be reworded.
+    fn render_reword_message(
+        &self,
+        entries: &[RebaseTodoEntry],
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let entry = self
+            .selected_entry
+            .and_then(|ix| entries.get(ix))
+            .filter(|entry| entry.action == RebaseAction::Reword)?;
+        self.loaded_message
+            .as_ref()
+            .filter(|(sha, _)| *sha == entry.sha)?;
+        Some(
+            v_flex()
+                .gap_2()
+                .child(
+                    Label::new(format!("New message for \"{}\"", entry.subject))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    div()
+                        .p_1()
+                        .border_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .rounded_sm()
+                        .child(self.message_editor.clone()),
+                ),
+        )
+    }
+
+    fn render_paused(&self, status: &RebaseStatus, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_busy = self.pending_operation.is_some();
+        let headline: SharedString = match (&status.stopped_at, status.has_conflicts) {
+            (Some(entry), true) => format!("Conflicts while applying \"{}\"", entry.subject).into(),
+            (None, true) => "Resolve conflicts to continue the rebase".into(),
+            (Some(entry), false) => format!("Stopped to edit \"{}\"", entry.subject).into(),
+            (None, false) => "Rebase in progress".into(),
+        };
+        let shows_message = self.loaded_message.is_some() && !status.has_conflicts;
+
+        v_flex()
+            .gap_2()
+            .p_2()
+            .child(Label::new(headline))
+            .when(status.has_conflicts, |this| {
+                this.child(
+                    Label::new("Resolve and stage the conflicted files, then continue.")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when(shows_message, |this| {
+                this.child(
+                    Label::new("Edit the commit message, then continue.")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    div()
+                        .p_1()
+                        .border_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .rounded_sm()
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .when(status.has_conflicts, |this| {
+                        this.child(
+                            Button::new("show-conflicts", "Show Conflicts")
+                                .icon(IconName::Warning)
+                                .icon_position(IconPosition::Start)
+                                .icon_size(IconSize::Small)
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(ShowConflicts.boxed_clone(), cx)
+                                }),
+                        )
+                    })
+                    .child(
+                        Button::new("continue-rebase", "Continue")
+                            .style(ButtonStyle::Filled)
+                            .disabled(is_busy)
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.continue_rebase(window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("abort-rebase", "Abort")
+                            .disabled(is_busy)
+                            .on_click(cx.listener(|this, _, window, cx| this.abort(window, cx))),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<EditorEvent> for RebaseView {}
+
+impl Focusable for RebaseView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some("Interactive Rebase".into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new("Rebase")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git Rebase Opened")
+    }
+}
+
+impl Render for RebaseView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_busy = self.pending_operation.is_some();
+        let (header, body, entries, editable): (SharedString, Option<AnyElement>, _, _) =
+            match &self.state {
+                RebaseState::Planning { base, entries } => {
+                    let short_base = base.chars().take(SHORT_SHA_LENGTH).collect::<String>();
+                    let header = format!("Rebase {} commits onto {short_base}", entries.len());
+                    let start = v_flex()
+                        .gap_2()
+                        .p_2()
+                        .child(
+                            h_flex().child(
+                                Button::new("start-rebase", "Start Rebase")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(is_busy || entries.is_empty())
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.start(window, cx)),
+                                    ),
+                            ),
+                        )
+                        .children(self.render_reword_message(entries, cx));
+                    (
+                        header.into(),
+                        Some(start.into_any_element()),
+                        entries.clone(),
+                        true,
+                    )
+                }
+                RebaseState::Paused(status) => {
+                    let short_onto = status
+                        .onto
+                        .chars()
+                        .take(SHORT_SHA_LENGTH)
+                        .collect::<String>();
+                    (
+                        format!("Rebasing onto {short_onto}").into(),
+                        Some(self.render_paused(status, cx).into_any_element()),
+                        status.remaining.clone(),
+                        false,
+                    )
+                }
+                RebaseState::Idle { finished } => (
+                    if *finished {
+                        "Rebase complete".into()
+                    } else {
+                        "No rebase in progress. Choose a base commit from the Git history.".into()
+                    },
+                    None,
+                    Vec::new(),
+                    false,
+                ),
+            };
+
+        v_flex()
+            .key_context(self.dispatch_context(window))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::move_entry_up))
+            .on_action(cx.listener(Self::move_entry_down))
+            .on_action(cx.listener(Self::cycle_action))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Icon::new(IconName::GitBranch).color(Color::Muted))
+                    .child(Label::new(header)),
+            )
+            .children(self.error.clone().map(|error| {
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .child(Icon::new(IconName::XCircle).color(Color::Error))
+                    .child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            }))
+            .children(body)
+            .when(!entries.is_empty(), |this| {
+                this.child(
+                    div().px_2().child(
+                        Label::new(if editable { "Commits" } else { "Remaining" })
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("rebase-entries")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(
+                        entries
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, entry)| self.render_entry(ix, entry, editable, cx)),
+                    ),
+            )
+    }
+}
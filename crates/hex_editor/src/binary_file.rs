@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use fs::{Encoding, Fs, MTime};
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Subscription, Task};
+use language::{DiskState, LocalFile as _};
+use project::{File, Project, ProjectEntryId, ProjectPath, Worktree};
+use workspace::UnsupportedFileError;
+
+pub enum BinaryFileEvent {
+    Edited,
+    Saved,
+    Reloaded,
+    /// The file was changed, moved or deleted on disk.
+    FileHandleChanged,
+}
+
+/// The contents of a file opened as bytes rather than as text.
+pub struct BinaryFile {
+    file: Arc<File>,
+    content: Vec<u8>,
+    is_dirty: bool,
+    /// The modification time of the file when it was last loaded or saved.
+    saved_mtime: Option<MTime>,
+    _worktree_subscription: Subscription,
+}
+
+impl EventEmitter<BinaryFileEvent> for BinaryFile {}
+
+impl BinaryFile {
+    pub fn new(file: Arc<File>, content: Vec<u8>, cx: &mut Context<Self>) -> Self {
+        Self {
+            saved_mtime: file.disk_state.mtime(),
+            _worktree_subscription: cx.subscribe(&file.worktree, Self::on_worktree_event),
+            file,
+            content,
+            is_dirty: false,
+        }
+    }
+
+    /// Keeps track of the file on disk, reloading it when it changes and there are no
+    /// changes to lose.
+    fn on_worktree_event(
+        &mut self,
+        worktree: Entity<Worktree>,
+        event: &worktree::Event,
+        cx: &mut Context<Self>,
+    ) {
+        let worktree::Event::UpdatedEntries(changes) = event else {
+            return;
+        };
+        let is_affected = changes.iter().any(|(path, entry_id, _)| {
+            self.file.entry_id == Some(*entry_id) || *path == self.file.path
+        });
+        if !is_affected {
+            return;
+        }
+
+        let snapshot = worktree.read(cx).snapshot();
+        let entry = self
+            .file
+            .entry_id
+            .and_then(|entry_id| snapshot.entry_for_id(entry_id))
+            .or_else(|| snapshot.entry_for_path(&self.file.path));
+        let new_file = match entry {
+            Some(entry) => File {
+                disk_state: entry
+                    .mtime
+                    .map_or(self.file.disk_state, |mtime| DiskState::Present { mtime }),
+                entry_id: Some(entry.id),
+                path: entry.path.clone(),
+                is_private: entry.is_private,
+                ..File::clone(&self.file)
+            },
+            None => File {
+                disk_state: DiskState::Deleted,
+                ..File::clone(&self.file)
+            },
+        };
+        if new_file == *self.file {
+            return;
+        }
+
+        self.file = Arc::new(new_file);
+        let changed_on_disk = self
+            .file
+            .disk_state
+            .mtime()
+            .is_some_and(|mtime| Some(mtime) != self.saved_mtime);
+        if changed_on_disk && !self.is_dirty {
+            self.reload(cx).detach_and_log_err(cx);
+        }
+        cx.emit(BinaryFileEvent::FileHandleChanged);
+        cx.notify();
+    }
+
+    pub fn file(&self) -> &Arc<File> {
+        &self.file
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Whether the file changed on disk since it was loaded or saved while there are unsaved
+    /// changes, or was deleted.
+    pub fn has_conflict(&self) -> bool {
+        match self.file.disk_state {
+            DiskState::New => false,
+            DiskState::Present { mtime } => match self.saved_mtime {
+                Some(saved_mtime) => mtime.bad_is_greater_than(saved_mtime) && self.is_dirty,
+                None => true,
+            },
+            DiskState::Deleted => true,
+        }
+    }
+
+    pub fn has_deleted_file(&self) -> bool {
+        self.file.disk_state == DiskState::Deleted
+    }
+
+    /// Replaces the bytes starting at `offset` with `bytes`, without changing the length of
+    /// the file. Bytes past the end of the file are dropped.
+    pub fn overwrite(&mut self, offset: usize, bytes: &[u8], cx: &mut Context<Self>) {
+        let Some(target) = self.content.get_mut(offset..) else {
+            return;
+        };
+        let len = bytes.len().min(target.len());
+        if len == 0 || target[..len] == bytes[..len] {
+            return;
+        }
+        target[..len].copy_from_slice(&bytes[..len]);
+        self.is_dirty = true;
+        cx.emit(BinaryFileEvent::Edited);
+        cx.notify();
+    }
+
+    pub fn save(&mut self, fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let abs_path = self.file.abs_path(cx);
+        let content = self.content.clone();
+        cx.spawn(|this, mut cx| async move {
+            fs.write(&abs_path, &content).await?;
+            let metadata = fs.metadata(&abs_path).await?;
+            this.update(&mut cx, |this, cx| {
+                if let Some(metadata) = metadata {
+                    this.saved_mtime = Some(metadata.mtime);
+                }
+                this.is_dirty = false;
+                cx.emit(BinaryFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Writes the contents to another file of the project, which this then refers to.
+    pub fn save_as(
+        &mut self,
+        project: &Entity<Project>,
+        path: ProjectPath,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let project = project.read(cx);
+        let (Some(worktree), Some(abs_path)) = (
+            project.worktree_for_id(path.worktree_id, cx),
+            project.absolute_path(&path, cx),
+        ) else {
+            return Task::ready(Err(anyhow!("no such worktree")));
+        };
+        let fs = project.fs().clone();
+        let content = self.content.clone();
+        cx.spawn(|this, mut cx| async move {
+            fs.write(&abs_path, &content).await?;
+            let loaded = worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree.load_binary_file(&path.path, cx)
+                })?
+                .await?;
+            this.update(&mut cx, |this, cx| {
+                this._worktree_subscription =
+                    cx.subscribe(&loaded.file.worktree, Self::on_worktree_event);
+                this.saved_mtime = loaded.file.disk_state.mtime();
+                this.file = loaded.file;
+                this.is_dirty = false;
+                cx.emit(BinaryFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Reads the file again, discarding any changes.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let path = self.file.path.clone();
+        let load = self
+            .file
+            .worktree
+            .update(cx, |worktree, cx| worktree.load_binary_file(&path, cx));
+        cx.spawn(|this, mut cx| async move {
+            let loaded = load.await?;
+            this.update(&mut cx, |this, cx| {
+                this.saved_mtime = loaded.file.disk_state.mtime();
+                this.file = loaded.file;
+                this.content = loaded.content;
+                this.is_dirty = false;
+                cx.emit(BinaryFileEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl project::ProjectItem for BinaryFile {
+    /// Opens files that aren't text, declining the others with an [UnsupportedFileError] so
+    /// that they are opened as text.
+    ///
+    /// Binary files are read through the worktree, which only local worktrees support, so
+    /// they can't be opened in remote and collaborative projects. Opening them there fails
+    /// with the error of the host, as the error of binary files doesn't survive the RPC.
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let worktree = project.read(cx).worktree_for_id(path.worktree_id, cx)?;
+        if !worktree.read(cx).is_local() {
+            return None;
+        }
+        let load = worktree.update(cx, |worktree, cx| worktree.load_binary_file(&path.path, cx));
+        Some(cx.spawn(|mut cx| async move {
+            let loaded = load.await?;
+            let is_text = Encoding::detect(&loaded.content, None)
+                .and_then(|encoding| encoding.decode(loaded.content.clone()))
+                .is_ok();
+            if is_text {
+                return Err(UnsupportedFileError.into());
+            }
+            cx.new(|cx| BinaryFile::new(loaded.file, loaded.content, cx))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.file.entry_id
+    }
+
+    fn project_path(&self, cx: &App) -> Option<ProjectPath> {
+        Some(ProjectPath {
+            worktree_id: self.file.worktree_id(cx),
+            path: self.file.path.clone(),
+        })
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.is_dirty || self.has_deleted_file()
+    }
+}
@@ -0,0 +1,1057 @@
+mod binary_file;
+mod query_modal;
+
+use std::{
+    cmp,
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use editor::{
+    actions::{
+        Copy, MoveDown, MoveLeft, MoveRight, MoveToBeginning, MoveToEnd, MoveUp, SelectAll,
+        SelectDown, SelectLeft, SelectRight, SelectToBeginning, SelectToEnd, SelectUp,
+    },
+    items::entry_git_aware_label_color,
+};
+use file_icons::FileIcons;
+use gpui::{
+    actions, uniform_list, AnyElement, App, ClipboardItem, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    ScrollStrategy, Subscription, Task, UniformListScrollHandle, Window,
+};
+use language::LocalFile as _;
+use project::{Project, ProjectPath};
+use settings::Settings;
+use theme::Theme;
+use ui::prelude::*;
+use util::paths::PathExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, ProjectItem, TabContentParams},
+    ItemSettings, ToolbarItemLocation, Workspace, WorkspaceId,
+};
+
+pub use binary_file::{BinaryFile, BinaryFileEvent};
+use query_modal::{QueryModal, QueryMode};
+
+actions!(
+    hex_editor,
+    [GoToOffset, FindBytes, FindNext, FindPrevious, ToggleColumn]
+);
+
+const BYTES_PER_ROW: usize = 16;
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexEditor>(cx);
+    cx.observe_new(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _window: Option<&mut Window>, _: &mut Context<Workspace>) {
+    workspace.register_action(|workspace, _: &GoToOffset, window, cx| {
+        QueryModal::toggle(workspace, QueryMode::GoToOffset, window, cx);
+    });
+    workspace.register_action(|workspace, _: &FindBytes, window, cx| {
+        QueryModal::toggle(workspace, QueryMode::FindBytes, window, cx);
+    });
+}
+
+/// Which of the columns typing overwrites bytes in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// Bytes are typed as pairs of hexadecimal digits.
+    Hex,
+    /// Bytes are typed as ASCII characters.
+    Ascii,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SearchDirection {
+    Next,
+    Previous,
+}
+
+/// Shows a file as rows of offsets, bytes in hexadecimal and their ASCII characters, and
+/// overwrites its bytes in place.
+pub struct HexEditor {
+    binary_file: Entity<BinaryFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The byte the selection started at.
+    selection_anchor: usize,
+    /// The byte the selection extends to, where bytes are typed.
+    cursor: usize,
+    column: Column,
+    /// The high half of the byte being typed in the hex column.
+    pending_nibble: Option<u8>,
+    last_search: Option<Vec<Option<u8>>>,
+    _subscription: Subscription,
+}
+
+impl HexEditor {
+    pub fn new(
+        binary_file: Entity<BinaryFile>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&binary_file, Self::on_binary_file_event);
+        Self {
+            binary_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            selection_anchor: 0,
+            cursor: 0,
+            column: Column::Hex,
+            pending_nibble: None,
+            last_search: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn on_binary_file_event(
+        &mut self,
+        _: Entity<BinaryFile>,
+        event: &BinaryFileEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            BinaryFileEvent::Edited => cx.emit(HexEditorEvent::Edited),
+            BinaryFileEvent::Saved | BinaryFileEvent::FileHandleChanged => {
+                cx.emit(HexEditorEvent::TitleChanged)
+            }
+            BinaryFileEvent::Reloaded => {
+                let len = self.binary_file.read(cx).len();
+                self.selection_anchor = self.selection_anchor.min(len.saturating_sub(1));
+                self.cursor = self.cursor.min(len.saturating_sub(1));
+                self.pending_nibble = None;
+                cx.emit(HexEditorEvent::TitleChanged);
+            }
+        }
+        cx.notify();
+    }
+
+    pub fn binary_file(&self) -> &Entity<BinaryFile> {
+        &self.binary_file
+    }
+
+    /// The offsets of the first and last selected bytes.
+    pub fn selection(&self) -> RangeInclusive<usize> {
+        cmp::min(self.selection_anchor, self.cursor)..=cmp::max(self.selection_anchor, self.cursor)
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn column(&self) -> Column {
+        self.column
+    }
+
+    /// Moves the cursor to `offset`, extending the selection to it when `select` is true.
+    pub fn set_cursor(&mut self, offset: usize, select: bool, cx: &mut Context<Self>) {
+        let len = self.binary_file.read(cx).len();
+        self.cursor = offset.min(len.saturating_sub(1));
+        if !select {
+            self.selection_anchor = self.cursor;
+        }
+        self.pending_nibble = None;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    /// Selects `len` bytes starting at `offset`, scrolling them into the middle of the view.
+    pub fn select_bytes(&mut self, offset: usize, len: usize, cx: &mut Context<Self>) {
+        self.set_cursor(offset, false, cx);
+        self.set_cursor(offset + len.saturating_sub(1), true, cx);
+        self.scroll_handle
+            .scroll_to_item(offset / BYTES_PER_ROW, ScrollStrategy::Center);
+    }
+
+    fn move_by(&mut self, delta: isize, select: bool, cx: &mut Context<Self>) {
+        self.set_cursor(self.cursor.saturating_add_signed(delta), select, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as isize), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as isize, false, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(usize::MAX, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as isize), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as isize, true, cx);
+    }
+
+    fn select_to_beginning(
+        &mut self,
+        _: &SelectToBeginning,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_cursor(0, true, cx);
+    }
+
+    fn select_to_end(&mut self, _: &SelectToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(usize::MAX, true, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        let len = self.binary_file.read(cx).len();
+        self.select_bytes(0, len, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_nibble = None;
+        cx.notify();
+    }
+
+    /// Copies the selected bytes, as hexadecimal in the hex column and as text in the ASCII
+    /// column.
+    fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        let content = self.binary_file.read(cx).content();
+        let Some(bytes) = content.get(self.selection()) else {
+            return;
+        };
+        let text = match self.column {
+            Column::Hex => bytes
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Column::Ascii => bytes.iter().copied().map(ascii_char).collect(),
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+    }
+
+    /// Overwrites bytes at the cursor with a typed hexadecimal digit or ASCII character.
+    pub fn type_char(&mut self, ch: char, cx: &mut Context<Self>) {
+        if self.binary_file.read(cx).is_empty() {
+            return;
+        }
+        let offset = self.cursor;
+        match self.column {
+            Column::Hex => {
+                let Some(nibble) = ch.to_digit(16) else {
+                    return;
+                };
+                let nibble = nibble as u8;
+                match self.pending_nibble.take() {
+                    Some(high) => {
+                        self.binary_file.update(cx, |binary_file, cx| {
+                            binary_file.overwrite(offset, &[high << 4 | nibble], cx)
+                        });
+                        self.move_by(1, false, cx);
+                    }
+                    None => {
+                        let low = self.binary_file.read(cx).content()[offset] & 0x0F;
+                        self.binary_file.update(cx, |binary_file, cx| {
+                            binary_file.overwrite(offset, &[nibble << 4 | low], cx)
+                        });
+                        self.selection_anchor = offset;
+                        self.pending_nibble = Some(nibble);
+                        cx.notify();
+                    }
+                }
+            }
+            Column::Ascii => {
+                if !ch.is_ascii() || ch.is_ascii_control() {
+                    return;
+                }
+                self.binary_file.update(cx, |binary_file, cx| {
+                    binary_file.overwrite(offset, &[ch as u8], cx)
+                });
+                self.move_by(1, false, cx);
+            }
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = event.keystroke.modifiers;
+        if modifiers.control || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_ref() else {
+            return;
+        };
+        let mut chars = key_char.chars();
+        // Keys like tab and enter are left to bindings.
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            if ch.is_control() {
+                return;
+            }
+            self.type_char(ch, cx);
+            cx.stop_propagation();
+        }
+    }
+
+    /// Selects the next or previous occurrence of `pattern`, where `None` matches any byte,
+    /// wrapping around at the ends of the file. Returns whether there is one.
+    pub fn find(&mut self, pattern: Vec<Option<u8>>, cx: &mut Context<Self>) -> bool {
+        let found = self.find_in_direction(&pattern, SearchDirection::Next, cx);
+        self.last_search = Some(pattern);
+        found
+    }
+
+    fn find_in_direction(
+        &mut self,
+        pattern: &[Option<u8>],
+        direction: SearchDirection,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let content = self.binary_file.read(cx).content();
+        let start = match direction {
+            SearchDirection::Next => self.selection_start() + 1,
+            SearchDirection::Previous => self.selection_start(),
+        };
+        let Some(offset) = find_pattern(content, pattern, start, direction) else {
+            return false;
+        };
+        self.select_bytes(offset, pattern.len(), cx);
+        true
+    }
+
+    fn selection_start(&self) -> usize {
+        *self.selection().start()
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        self.repeat_search(SearchDirection::Next, window, cx);
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        self.repeat_search(SearchDirection::Previous, window, cx);
+    }
+
+    fn repeat_search(
+        &mut self,
+        direction: SearchDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match self.last_search.clone() {
+            Some(pattern) => {
+                self.find_in_direction(&pattern, direction, cx);
+            }
+            None => window.dispatch_action(Box::new(FindBytes), cx),
+        }
+    }
+
+    fn mouse_down_on_byte(
+        &mut self,
+        offset: usize,
+        column: Column,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle);
+        self.column = column;
+        self.set_cursor(offset, event.modifiers.shift, cx);
+    }
+
+    fn mouse_move_over_byte(
+        &mut self,
+        offset: usize,
+        event: &MouseMoveEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if event.pressed_button == Some(MouseButton::Left) && self.cursor != offset {
+            self.set_cursor(offset, true, cx);
+        }
+    }
+
+    fn render_rows(
+        &mut self,
+        rows: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let content = self.binary_file.read(cx).content();
+        let offset_digits = offset_digits(content.len());
+        let selection = self.selection();
+        let colors = cx.theme().colors();
+        let player = cx.theme().players().local();
+
+        rows.map(|row| {
+            let row_start = row * BYTES_PER_ROW;
+            let row_end = cmp::min(row_start + BYTES_PER_ROW, content.len());
+            let byte_cell = |offset: usize, column: Column, text: String| {
+                let is_cursor = offset == self.cursor;
+                let id = match column {
+                    Column::Hex => ("hex-byte", offset),
+                    Column::Ascii => ("ascii-byte", offset),
+                };
+                div()
+                    .id(id)
+                    .when(selection.contains(&offset), |cell| {
+                        cell.bg(player.selection)
+                    })
+                    .when(is_cursor && column == self.column, |cell| {
+                        cell.border_b_2().border_color(player.cursor)
+                    })
+                    .child(text)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event, window, cx| {
+                            this.mouse_down_on_byte(offset, column, event, window, cx)
+                        }),
+                    )
+                    .on_mouse_move(cx.listener(move |this, event, _, cx| {
+                        this.mouse_move_over_byte(offset, event, cx)
+                    }))
+            };
+
+            h_flex()
+                .gap_4()
+                .child(div().text_color(colors.editor_line_number).child(format!(
+                    "{:0width$X}",
+                    row_start,
+                    width = offset_digits
+                )))
+                .child(
+                    h_flex().children((row_start..row_start + BYTES_PER_ROW).map(|offset| {
+                        let cell = match content.get(offset) {
+                            Some(byte) => byte_cell(offset, Column::Hex, format!("{byte:02X}"))
+                                .into_any_element(),
+                            None => div().child("  ").into_any_element(),
+                        };
+                        // Separate the bytes, with a wider gap in the middle of the row.
+                        let gap = if offset % BYTES_PER_ROW == BYTES_PER_ROW / 2 {
+                            "  "
+                        } else {
+                            " "
+                        };
+                        h_flex()
+                            .when(offset % BYTES_PER_ROW != 0, |this| this.child(gap))
+                            .child(cell)
+                    })),
+                )
+                .child(h_flex().children((row_start..row_end).map(|offset| {
+                    byte_cell(
+                        offset,
+                        Column::Ascii,
+                        ascii_char(content[offset]).to_string(),
+                    )
+                })))
+                .into_any_element()
+        })
+        .collect()
+    }
+}
+
+/// The number of hexadecimal digits offsets are shown with.
+fn offset_digits(len: usize) -> usize {
+    let digits = (usize::BITS - len.leading_zeros()).div_ceil(4) as usize;
+    digits.max(8)
+}
+
+/// The character a byte is shown as in the ASCII column.
+fn ascii_char(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+/// Parses an offset, written in hexadecimal like in the offset column.
+pub fn parse_offset(query: &str) -> Option<usize> {
+    let query = query.trim();
+    let query = query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("0X"))
+        .unwrap_or(query);
+    usize::from_str_radix(query, 16).ok()
+}
+
+/// Parses a byte pattern: either pairs of hexadecimal digits, optionally separated by
+/// whitespace, with `??` matching any byte, or text within double quotes.
+pub fn parse_byte_pattern(query: &str) -> Option<Vec<Option<u8>>> {
+    let query = query.trim();
+    if let Some(text) = query
+        .strip_prefix('"')
+        .and_then(|query| query.strip_suffix('"'))
+    {
+        return (!text.is_empty()).then(|| text.bytes().map(Some).collect());
+    }
+
+    let digits = query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            ['?', '?'] => Some(None),
+            [high, low] => Some(Some((high.to_digit(16)? << 4 | low.to_digit(16)?) as u8)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The offset of the first occurrence of `pattern` at or after `start` when searching
+/// forward, or before `start` when searching backward, wrapping around at the ends.
+fn find_pattern(
+    content: &[u8],
+    pattern: &[Option<u8>],
+    start: usize,
+    direction: SearchDirection,
+) -> Option<usize> {
+    if pattern.is_empty() || pattern.len() > content.len() {
+        return None;
+    }
+    let matches_at = |offset: usize| {
+        content[offset..offset + pattern.len()]
+            .iter()
+            .zip(pattern)
+            .all(|(byte, pattern_byte)| {
+                pattern_byte.map_or(true, |pattern_byte| pattern_byte == *byte)
+            })
+    };
+    let last_offset = content.len() - pattern.len();
+    let start = start.min(last_offset + 1);
+    match direction {
+        SearchDirection::Next => (start..=last_offset)
+            .chain(0..start)
+            .find(|offset| matches_at(*offset)),
+        SearchDirection::Previous => (0..start)
+            .rev()
+            .chain((start..=last_offset).rev())
+            .find(|offset| matches_at(*offset)),
+    }
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    TitleChanged,
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::TitleChanged => {
+                f(ItemEvent::UpdateTab);
+                f(ItemEvent::UpdateBreadcrumbs);
+            }
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.binary_file.entity_id(), self.binary_file.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.binary_file.read(cx).file().abs_path(cx);
+        Some(abs_path.compact().to_string_lossy().to_string().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &Window, cx: &App) -> AnyElement {
+        let file = self.binary_file.read(cx).file();
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        };
+
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .project_path_git_status(&project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(&project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        let title = language::File::file_name(file.as_ref(), cx)
+            .to_string_lossy()
+            .to_string();
+        Label::new(title)
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.binary_file.read(cx).file().path.clone();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let file = self.binary_file.read(cx).file();
+        let mut text = PathBuf::from(language::File::file_name(file.as_ref(), cx));
+        if self.project.read(cx).visible_worktrees(cx).count() > 1 {
+            text = PathBuf::from(file.worktree.read(cx).root_name()).join(&file.path);
+        }
+        Some(vec![BreadcrumbText {
+            text: text.to_string_lossy().to_string(),
+            highlights: None,
+            font: None,
+        }])
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new(|cx| Self::new(self.binary_file.clone(), self.project.clone(), cx)))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        project::ProjectItem::is_dirty(self.binary_file.read(cx))
+    }
+
+    fn has_deleted_file(&self, cx: &App) -> bool {
+        self.binary_file.read(cx).has_deleted_file()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.binary_file.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let fs = project.read(cx).fs().clone();
+        self.binary_file
+            .update(cx, |binary_file, cx| binary_file.save(fs, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        project: Entity<Project>,
+        path: ProjectPath,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.binary_file.update(cx, |binary_file, cx| {
+            binary_file.save_as(&project, path, cx)
+        })
+    }
+
+    fn reload(
+        &mut self,
+        _project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.binary_file
+            .update(cx, |binary_file, cx| binary_file.reload(cx))
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = self.binary_file.read(cx).len().div_ceil(BYTES_PER_ROW);
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::select_to_beginning))
+            .on_action(cx.listener(Self::select_to_end))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .on_key_down(cx.listener(Self::key_down))
+            .size_full()
+            .p_2()
+            .font_buffer(cx)
+            .bg(cx.theme().colors().editor_background)
+            .text_color(cx.theme().colors().editor_foreground)
+            .child(
+                uniform_list(cx.entity(), "hex-editor-rows", row_count, Self::render_rows)
+                    .track_scroll(self.scroll_handle.clone())
+                    .size_full(),
+            )
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = BinaryFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        item: Entity<Self::Item>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use std::{path::Path, sync::Arc};
+    use util::path;
+    use workspace::AppState;
+
+    #[test]
+    fn test_parse_byte_pattern() {
+        assert_eq!(
+            parse_byte_pattern("DE ad ?? 0f"),
+            Some(vec![Some(0xDE), Some(0xAD), None, Some(0x0F)])
+        );
+        assert_eq!(
+            parse_byte_pattern("cafe"),
+            Some(vec![Some(0xCA), Some(0xFE)])
+        );
+        assert_eq!(
+            parse_byte_pattern("\"PK\""),
+            Some(vec![Some(b'P'), Some(b'K')])
+        );
+        assert_eq!(parse_byte_pattern("abc"), None);
+        assert_eq!(parse_byte_pattern("zz"), None);
+        assert_eq!(parse_byte_pattern(""), None);
+        assert_eq!(parse_byte_pattern("\"\""), None);
+
+        assert_eq!(parse_offset("1f"), Some(0x1F));
+        assert_eq!(parse_offset(" 0x100 "), Some(0x100));
+        assert_eq!(parse_offset("g"), None);
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let content = b"\x00\x01\x02\x00\x01\x03";
+        let pattern = [Some(0x00), Some(0x01)];
+        assert_eq!(
+            find_pattern(content, &pattern, 0, SearchDirection::Next),
+            Some(0)
+        );
+        assert_eq!(
+            find_pattern(content, &pattern, 1, SearchDirection::Next),
+            Some(3)
+        );
+        // Searching wraps around at the ends of the file.
+        assert_eq!(
+            find_pattern(content, &pattern, 4, SearchDirection::Next),
+            Some(0)
+        );
+        assert_eq!(
+            find_pattern(content, &pattern, 3, SearchDirection::Previous),
+            Some(0)
+        );
+        assert_eq!(
+            find_pattern(content, &pattern, 0, SearchDirection::Previous),
+            Some(3)
+        );
+        assert_eq!(
+            find_pattern(
+                content,
+                &[Some(0x01), None, Some(0x00)],
+                0,
+                SearchDirection::Next
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            find_pattern(content, &[Some(0x04)], 0, SearchDirection::Next),
+            None
+        );
+    }
+
+    #[gpui::test]
+    async fn test_open_and_overwrite_binary_file(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "text.txt": "hello\n" }))
+            .await;
+        fs.insert_file(path!("/dir/data.bin"), b"\x7fELF\x00\x01\x02\xff".to_vec())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+
+        // Text files still open in an editor, binary files in a hex editor.
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, "text.txt"), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        assert!(editor.downcast::<editor::Editor>().is_some());
+        let hex_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, "data.bin"), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<HexEditor>()
+            .unwrap();
+
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.set_cursor(5, false, cx);
+            hex_editor.type_char('a', cx);
+            hex_editor.type_char('B', cx);
+            assert_eq!(hex_editor.cursor(), 6);
+
+            hex_editor.column = Column::Ascii;
+            hex_editor.type_char('Z', cx);
+            assert_eq!(
+                hex_editor.binary_file().read(cx).content(),
+                b"\x7fELF\x00\xab\x5a\xff"
+            );
+            assert!(hex_editor.is_dirty(cx));
+
+            assert!(hex_editor.find(vec![Some(0x00), None, Some(b'Z')], cx));
+            assert_eq!(hex_editor.selection(), 4..=6);
+        });
+
+        let save = hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor
+                .binary_file()
+                .update(cx, |binary_file, cx| binary_file.save(fs.clone(), cx))
+        });
+        save.await.unwrap();
+        assert_eq!(
+            fs.load_bytes(Path::new(path!("/dir/data.bin")))
+                .await
+                .unwrap(),
+            b"\x7fELF\x00\xab\x5a\xff"
+        );
+        hex_editor.update(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_binary_file_changes_on_disk(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/dir/data.bin"), b"\x7fELF\x00\x01\x02\xff".to_vec())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let hex_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, "data.bin"), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<HexEditor>()
+            .unwrap();
+        let path = Path::new(path!("/dir/data.bin"));
+
+        // Changes on disk are loaded when there are no unsaved changes.
+        fs.write(path, b"\x7fELF\x00\x03\x04\xff").await.unwrap();
+        cx.run_until_parked();
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(
+                hex_editor.binary_file().read(cx).content(),
+                b"\x7fELF\x00\x03\x04\xff"
+            );
+            assert!(!hex_editor.is_dirty(cx));
+            assert!(!hex_editor.has_conflict(cx));
+        });
+
+        // With unsaved changes, they are kept and the file is in conflict instead.
+        hex_editor.update(cx, |hex_editor, cx| {
+            hex_editor.set_cursor(5, false, cx);
+            hex_editor.type_char('a', cx);
+            hex_editor.type_char('b', cx);
+        });
+        fs.write(path, b"\x7fELF\x00\x05\x06\xff").await.unwrap();
+        cx.run_until_parked();
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(
+                hex_editor.binary_file().read(cx).content(),
+                b"\x7fELF\x00\xab\x04\xff"
+            );
+            assert!(hex_editor.is_dirty(cx));
+            assert!(hex_editor.has_conflict(cx));
+            assert!(!hex_editor.has_deleted_file(cx));
+        });
+
+        // Reloading discards the unsaved changes, which resolves the conflict.
+        hex_editor
+            .update_in(cx, |hex_editor, window, cx| {
+                hex_editor.reload(project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(
+                hex_editor.binary_file().read(cx).content(),
+                b"\x7fELF\x00\x05\x06\xff"
+            );
+            assert!(!hex_editor.is_dirty(cx));
+            assert!(!hex_editor.has_conflict(cx));
+        });
+
+        fs.remove_file(path, Default::default()).await.unwrap();
+        cx.run_until_parked();
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert!(hex_editor.has_deleted_file(cx));
+            assert!(hex_editor.has_conflict(cx));
+            assert!(hex_editor.is_dirty(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_open_files_when_registered_after_editor(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            crate::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "text.txt": "hello\n" }))
+            .await;
+        fs.insert_file(path!("/dir/data.bin"), b"\x7fELF\x00\x01\x02\xff".to_vec())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+
+        // The hex editor declines text files, which then open in an editor.
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, "text.txt"), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        assert!(editor.downcast::<editor::Editor>().is_some());
+        let hex_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, "data.bin"), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        assert!(hex_editor.downcast::<HexEditor>().is_some());
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            language::init(cx);
+            crate::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            state
+        })
+    }
+}
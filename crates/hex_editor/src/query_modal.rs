@@ -0,0 +1,168 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString,
+    Subscription, Window,
+};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::{parse_byte_pattern, parse_offset, HexEditor};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum QueryMode {
+    GoToOffset,
+    FindBytes,
+}
+
+impl QueryMode {
+    fn placeholder_text(self) -> &'static str {
+        match self {
+            QueryMode::GoToOffset => "Offset, in hexadecimal",
+            QueryMode::FindBytes => "Bytes, like 4D 5A ?? 00, or \"text\"",
+        }
+    }
+
+    fn help_text(self) -> &'static str {
+        match self {
+            QueryMode::GoToOffset => "Go to the byte at an offset",
+            QueryMode::FindBytes => "Find bytes, with ?? matching any byte",
+        }
+    }
+}
+
+/// Asks for an offset to go to, or for bytes to find, in the active hex editor.
+pub(crate) struct QueryModal {
+    query_editor: Entity<Editor>,
+    hex_editor: Entity<HexEditor>,
+    mode: QueryMode,
+    error: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for QueryModal {}
+
+impl Focusable for QueryModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for QueryModal {}
+
+impl QueryModal {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        mode: QueryMode,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(hex_editor) = workspace.active_item_as::<HexEditor>(cx) else {
+            return;
+        };
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            QueryModal::new(hex_editor, mode, window, cx)
+        });
+    }
+
+    fn new(
+        hex_editor: Entity<HexEditor>,
+        mode: QueryMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(mode.placeholder_text(), cx);
+            editor
+        });
+        let query_editor_change =
+            cx.subscribe_in(&query_editor, window, Self::on_query_editor_event);
+
+        Self {
+            query_editor,
+            hex_editor,
+            mode,
+            error: None,
+            _subscriptions: vec![query_editor_change],
+        }
+    }
+
+    fn on_query_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            EditorEvent::Blurred => cx.emit(DismissEvent),
+            EditorEvent::BufferEdited => {
+                self.error = None;
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let error = match self.mode {
+            QueryMode::GoToOffset => match parse_offset(&query) {
+                Some(offset) if offset < self.hex_editor.read(cx).binary_file().read(cx).len() => {
+                    self.hex_editor
+                        .update(cx, |hex_editor, cx| hex_editor.select_bytes(offset, 1, cx));
+                    None
+                }
+                Some(_) => Some("Offset is past the end of the file"),
+                None => Some("Not a hexadecimal offset"),
+            },
+            QueryMode::FindBytes => match parse_byte_pattern(&query) {
+                Some(pattern) => {
+                    let found = self
+                        .hex_editor
+                        .update(cx, |hex_editor, cx| hex_editor.find(pattern, cx));
+                    (!found).then_some("No matches")
+                }
+                None => Some("Not a byte pattern"),
+            },
+        };
+
+        if let Some(error) = error {
+            self.error = Some(error.into());
+            cx.notify();
+            return;
+        }
+        self.hex_editor.focus_handle(cx).focus(window);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for QueryModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match &self.error {
+            Some(error) => Label::new(error.clone()).color(Color::Error),
+            None => Label::new(self.mode.help_text()).color(Color::Muted),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("HexEditorQuery")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.query_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().gap_1().child(help_text))
+    }
+}
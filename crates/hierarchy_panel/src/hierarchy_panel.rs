@@ -0,0 +1,881 @@
+mod hierarchy_panel_settings;
+
+use anyhow::Result;
+use editor::{scroll::Autoscroll, Editor, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT};
+use gpui::{
+    actions, Action, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyContext, Pixels, Render, Task, WeakEntity, Window,
+};
+use hierarchy_panel_settings::HierarchyPanelSettings;
+use language::{Buffer, Capability, OffsetRangeExt as _, ToPoint as _};
+use menu::{Confirm, SelectNext, SelectPrev};
+use panel::PanelHeader;
+use project::{
+    lsp_command::{HierarchyCall, HierarchyItem},
+    Location, Project,
+};
+use settings::Settings;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(
+    hierarchy_panel,
+    [
+        ToggleFocus,
+        ShowCallHierarchy,
+        ShowTypeHierarchy,
+        ToggleDirection,
+        ExpandSelectedEntry,
+        CollapseSelectedEntry,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    HierarchyPanelSettings::register(cx);
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
+            workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+            });
+            workspace.register_action(|workspace, _: &ShowCallHierarchy, window, cx| {
+                show_hierarchy(workspace, HierarchyKind::IncomingCalls, window, cx);
+            });
+            workspace.register_action(|workspace, _: &ShowTypeHierarchy, window, cx| {
+                show_hierarchy(workspace, HierarchyKind::Supertypes, window, cx);
+            });
+        },
+    )
+    .detach();
+}
+
+/// Shows the hierarchy of the symbol under the cursor of the active editor.
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    kind: HierarchyKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| {
+            panel.show_hierarchy(kind, buffer, position, window, cx)
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn is_call_hierarchy(self) -> bool {
+        matches!(self, Self::IncomingCalls | Self::OutgoingCalls)
+    }
+
+    fn reversed(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+}
+
+/// Shows the callers or callees of a function, or the supertypes or subtypes of a type, as a
+/// tree that is resolved as it is expanded, together with a preview of the selected entry.
+pub struct HierarchyPanel {
+    focus_handle: FocusHandle,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    kind: HierarchyKind,
+    /// The items at the position the hierarchy was requested for.
+    roots: Vec<HierarchyItem>,
+    /// All entries resolved so far, referring to their children by index.
+    entries: Vec<HierarchyEntry>,
+    selected_entry: Option<usize>,
+    preview: Option<Entity<Editor>>,
+    pending_roots: Option<Task<()>>,
+    error: Option<SharedString>,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+struct HierarchyEntry {
+    item: HierarchyItem,
+    /// Where the item calls its parent, or is called by it. Empty for roots and types.
+    call_sites: Vec<Location>,
+    depth: usize,
+    expanded: bool,
+    /// The entries of the next level of the hierarchy, once resolved.
+    children: Option<Vec<usize>>,
+    resolve_children: Option<Task<()>>,
+}
+
+impl HierarchyPanel {
+    pub fn new(
+        workspace: &mut Workspace,
+        _window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace = cx.entity().downgrade();
+
+        cx.new(|cx| Self {
+            focus_handle: cx.focus_handle(),
+            workspace,
+            project,
+            kind: HierarchyKind::IncomingCalls,
+            roots: Vec::new(),
+            entries: Vec::new(),
+            selected_entry: None,
+            preview: None,
+            pending_roots: None,
+            error: None,
+            width: None,
+            height: None,
+        })
+    }
+
+    fn show_hierarchy(
+        &mut self,
+        kind: HierarchyKind,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let roots = self.project.update(cx, |project, cx| {
+            if kind.is_call_hierarchy() {
+                project.prepare_call_hierarchy(&buffer, position, cx)
+            } else {
+                project.prepare_type_hierarchy(&buffer, position, cx)
+            }
+        });
+        self.kind = kind;
+        self.roots.clear();
+        self.error = None;
+        self.reset_entries(window, cx);
+        self.pending_roots = Some(cx.spawn_in(window, |this, mut cx| async move {
+            let roots = roots.await;
+            this.update_in(&mut cx, |this, window, cx| {
+                this.pending_roots = None;
+                match roots {
+                    Ok(roots) => this.roots = roots,
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                this.reset_entries(window, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn toggle_direction(
+        &mut self,
+        _: &ToggleDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kind = self.kind.reversed();
+        self.reset_entries(window, cx);
+    }
+
+    /// Replaces the entries with the roots of the hierarchy, resolving their children.
+    fn reset_entries(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.entries = self
+            .roots
+            .iter()
+            .map(|item| HierarchyEntry {
+                item: item.clone(),
+                call_sites: Vec::new(),
+                depth: 0,
+                expanded: false,
+                children: None,
+                resolve_children: None,
+            })
+            .collect();
+        for ix in 0..self.entries.len() {
+            self.expand_entry(ix, cx);
+        }
+        self.selected_entry = None;
+        if self.entries.is_empty() {
+            self.preview = None;
+            cx.notify();
+        } else {
+            self.select_entry(0, window, cx);
+        }
+    }
+
+    fn children(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let kind = self.kind;
+        self.project.update(cx, |project, cx| match kind {
+            HierarchyKind::IncomingCalls => project.incoming_calls(item, cx),
+            HierarchyKind::OutgoingCalls => project.outgoing_calls(item, cx),
+            HierarchyKind::Supertypes | HierarchyKind::Subtypes => {
+                let types = if kind == HierarchyKind::Supertypes {
+                    project.supertypes(item, cx)
+                } else {
+                    project.subtypes(item, cx)
+                };
+                cx.spawn(|_, _| async move {
+                    Ok(types
+                        .await?
+                        .into_iter()
+                        .map(|item| HierarchyCall {
+                            item,
+                            call_sites: Vec::new(),
+                        })
+                        .collect())
+                })
+            }
+        })
+    }
+
+    fn expand_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.expanded = true;
+        if entry.children.is_some() || entry.resolve_children.is_some() {
+            cx.notify();
+            return;
+        }
+
+        let item = entry.item.clone();
+        let children = self.children(&item, cx);
+        self.entries[ix].resolve_children = Some(cx.spawn(|this, mut cx| async move {
+            let children = children.await;
+            this.update(&mut cx, |this, cx| {
+                let depth = this.entries[ix].depth + 1;
+                let children = children
+                    .log_err()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| {
+                        this.entries.push(HierarchyEntry {
+                            item: call.item,
+                            call_sites: call.call_sites,
+                            depth,
+                            expanded: false,
+                            children: None,
+                            resolve_children: None,
+                        });
+                        this.entries.len() - 1
+                    })
+                    .collect();
+                this.entries[ix].children = Some(children);
+                this.entries[ix].resolve_children = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn collapse_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let Some(entry) = self.entries.get_mut(ix) {
+            entry.expanded = false;
+            cx.notify();
+        }
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_entry {
+            self.expand_entry(ix, cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_entry {
+            self.collapse_entry(ix, cx);
+        }
+    }
+
+    /// The indices of the entries that are shown, in the order they are shown in.
+    fn visible_entries(&self) -> Vec<usize> {
+        let mut visible_entries = Vec::new();
+        let mut stack = (0..self.roots.len().min(self.entries.len()))
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(ix) = stack.pop() {
+            visible_entries.push(ix);
+            let entry = &self.entries[ix];
+            if let (true, Some(children)) = (entry.expanded, &entry.children) {
+                stack.extend(children.iter().rev());
+            }
+        }
+        visible_entries
+    }
+
+    fn select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        let visible_entries = self.visible_entries();
+        let next = match self
+            .selected_entry
+            .and_then(|selected| visible_entries.iter().position(|ix| *ix == selected))
+        {
+            Some(position) => visible_entries.get(position + 1),
+            None => visible_entries.first(),
+        };
+        if let Some(ix) = next.copied() {
+            self.select_entry(ix, window, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, window: &mut Window, cx: &mut Context<Self>) {
+        let visible_entries = self.visible_entries();
+        let prev = match self
+            .selected_entry
+            .and_then(|selected| visible_entries.iter().position(|ix| *ix == selected))
+        {
+            Some(position) => position
+                .checked_sub(1)
+                .and_then(|position| visible_entries.get(position)),
+            None => visible_entries.last(),
+        };
+        if let Some(ix) = prev.copied() {
+            self.select_entry(ix, window, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.open_entry(ix, window, cx);
+        }
+    }
+
+    fn select_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_entry == Some(ix) {
+            return;
+        }
+        self.selected_entry = Some(ix);
+        self.preview = self
+            .entries
+            .get(ix)
+            .map(|entry| self.build_preview(entry, window, cx));
+        cx.notify();
+    }
+
+    /// Builds an editor showing the call sites of the entry, or its definition when it has none.
+    fn build_preview(
+        &self,
+        entry: &HierarchyEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        let mut locations = entry.call_sites.clone();
+        if locations.is_empty() {
+            locations.push(Location {
+                buffer: entry.item.location.buffer.clone(),
+                range: entry.item.selection_range.clone(),
+            });
+        }
+
+        let mut highlighted_ranges = Vec::new();
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::new(Capability::ReadOnly);
+            while !locations.is_empty() {
+                let buffer = locations[0].buffer.clone();
+                let mut ranges = Vec::new();
+                locations.retain(|location| {
+                    if location.buffer == buffer {
+                        ranges.push(location.range.to_offset(buffer.read(cx)));
+                        false
+                    } else {
+                        true
+                    }
+                });
+                ranges.sort_by_key(|range| range.start);
+                highlighted_ranges.extend(multibuffer.push_excerpts_with_context_lines(
+                    buffer,
+                    ranges,
+                    DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                ));
+            }
+            multibuffer
+        });
+
+        cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer, Some(self.project.clone()), true, window, cx);
+            editor.highlight_background::<Self>(
+                &highlighted_ranges,
+                |theme| theme.editor_highlighted_line_background,
+                cx,
+            );
+            editor
+        })
+    }
+
+    fn open_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let buffer = entry.item.location.buffer.clone();
+        let position = entry.item.selection_range.start.to_point(buffer.read(cx));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+                        s.select_ranges([position..position])
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_header(&self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let (direction_icon, direction_tooltip) = match self.kind {
+            HierarchyKind::IncomingCalls => (IconName::ArrowUp, "Show Outgoing Calls"),
+            HierarchyKind::OutgoingCalls => (IconName::ArrowDown, "Show Incoming Calls"),
+            HierarchyKind::Supertypes => (IconName::ArrowUp, "Show Subtypes"),
+            HierarchyKind::Subtypes => (IconName::ArrowDown, "Show Supertypes"),
+        };
+        self.panel_header_container(window, cx)
+            .gap_1()
+            .child(Label::new(self.kind.title()).size(LabelSize::Small))
+            .child(div().flex_1())
+            .child(
+                IconButton::new("toggle-hierarchy-direction", direction_icon)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.roots.is_empty())
+                    .tooltip(Tooltip::for_action_title(
+                        direction_tooltip,
+                        &ToggleDirection,
+                    ))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(ToggleDirection.boxed_clone(), cx)
+                    }),
+            )
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry = &self.entries[ix];
+        let buffer = entry.item.location.buffer.read(cx);
+        let row = entry.item.selection_range.start.to_point(buffer).row + 1;
+        let location = match buffer.file() {
+            Some(file) => format!("{}:{row}", file.path().display()),
+            None => format!("{row}"),
+        };
+        let is_leaf = entry
+            .children
+            .as_ref()
+            .map_or(false, |children| children.is_empty());
+
+        ListItem::new(("hierarchy-entry", ix))
+            .indent_level(entry.depth)
+            .toggle((!is_leaf).then_some(entry.expanded))
+            .toggle_state(self.selected_entry == Some(ix))
+            .on_toggle(cx.listener(move |this, _, _, cx| {
+                if this.entries[ix].expanded {
+                    this.collapse_entry(ix, cx);
+                } else {
+                    this.expand_entry(ix, cx);
+                }
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(entry.item.name.clone())
+                            .size(LabelSize::Small)
+                            .single_line(),
+                    )
+                    .children(entry.item.detail.clone().map(|detail| {
+                        Label::new(detail)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line()
+                    }))
+                    .when(entry.call_sites.len() > 1, |this| {
+                        this.child(
+                            Label::new(format!("({})", entry.call_sites.len()))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .tooltip(Tooltip::text(location))
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.select_entry(ix, window, cx);
+                if event.down.click_count > 1 {
+                    this.open_entry(ix, window, cx);
+                }
+            }))
+    }
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .child(self.render_header(window, cx));
+
+        if self.entries.is_empty() {
+            let (title, description) = if self.pending_roots.is_some() {
+                ("Loading…".into(), None)
+            } else if let Some(error) = self.error.clone() {
+                ("Failed to resolve the hierarchy".into(), Some(error))
+            } else {
+                (
+                    SharedString::from("No hierarchy to show"),
+                    Some(SharedString::from(
+                        "Show the call or type hierarchy of the symbol under the cursor.",
+                    )),
+                )
+            };
+            return panel.child(
+                v_flex()
+                    .size_full()
+                    .items_center()
+                    .justify_center()
+                    .gap_1()
+                    .child(Label::new(title))
+                    .children(description.map(|description| {
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    })),
+            );
+        }
+
+        let entries = self
+            .visible_entries()
+            .into_iter()
+            .map(|ix| self.render_entry(ix, cx).into_any_element())
+            .collect::<Vec<_>>();
+        panel
+            .child(
+                v_flex()
+                    .id("hierarchy-entries")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(entries),
+            )
+            .children(self.preview.clone().map(|preview| {
+                div()
+                    .h_1_2()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(preview)
+            }))
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "HierarchyPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        HierarchyPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        let fs = self.project.read(cx).fs().clone();
+        settings::update_settings_file::<HierarchyPanelSettings>(fs, cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, window: &Window, cx: &App) -> Pixels {
+        let settings = HierarchyPanelSettings::get_global(cx);
+        match self.position(window, cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        match self.position(window, cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        Some(IconName::ListTree).filter(|_| HierarchyPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        11
+    }
+}
+
+impl PanelHeader for HierarchyPanel {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, VisualTestContext};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher, Point};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_expanding_call_hierarchy(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "main.rs": "fn main() { helper(); }\nfn helper() { leaf(); }\nfn leaf() {}\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let (buffer, _handle) = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp("/root/main.rs", cx)
+            })
+            .await
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+
+        let lsp_item = |name: &str, row: u32| lsp::CallHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path("/root/main.rs").unwrap(),
+            range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 12)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(row, 3),
+                lsp::Position::new(row, 3 + name.len() as u32),
+            ),
+            data: None,
+        };
+        fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>(
+            move |_, _| async move { Ok(Some(vec![lsp_item("leaf", 2)])) },
+        );
+        let incoming_call_requests = Arc::new(AtomicUsize::new(0));
+        fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+            let incoming_call_requests = incoming_call_requests.clone();
+            move |params, _| {
+                incoming_call_requests.fetch_add(1, Ordering::SeqCst);
+                let caller = match params.item.name.as_str() {
+                    "leaf" => Some(("helper", 1, 14)),
+                    "helper" => Some(("main", 0, 12)),
+                    _ => None,
+                };
+                let calls = caller
+                    .into_iter()
+                    .map(
+                        |(caller, row, call_column)| lsp::CallHierarchyIncomingCall {
+                            from: lsp_item(caller, row),
+                            from_ranges: vec![lsp::Range::new(
+                                lsp::Position::new(row, call_column),
+                                lsp::Position::new(row, call_column + 4),
+                            )],
+                        },
+                    )
+                    .collect::<Vec<_>>();
+                async move { Ok(Some(calls)) }
+            }
+        });
+
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let panel = window
+            .update(cx, |workspace, window, cx| {
+                HierarchyPanel::new(workspace, window, cx)
+            })
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        let position = buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(2, 4)));
+        panel.update_in(cx, |panel, window, cx| {
+            panel.show_hierarchy(HierarchyKind::IncomingCalls, buffer, position, window, cx)
+        });
+        cx.run_until_parked();
+        // The roots are expanded right away.
+        assert_eq!(
+            display_entries(&panel, cx),
+            ["v leaf  <== selected", "  > helper"]
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_next(&SelectNext, window, cx);
+            panel.expand_selected_entry(&ExpandSelectedEntry, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            display_entries(&panel, cx),
+            ["v leaf", "  v helper  <== selected", "    > main"]
+        );
+        assert_eq!(incoming_call_requests.load(Ordering::SeqCst), 2);
+        let preview_text = panel.update(cx, |panel, cx| {
+            panel.preview.as_ref().unwrap().read(cx).text(cx)
+        });
+        assert!(
+            preview_text.contains("fn helper() { leaf(); }"),
+            "the preview shows where helper calls leaf, got {preview_text:?}"
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.collapse_selected_entry(&CollapseSelectedEntry, window, cx);
+        });
+        assert_eq!(
+            display_entries(&panel, cx),
+            ["v leaf", "  > helper  <== selected"]
+        );
+
+        // Expanding an entry again shows the children resolved before.
+        panel.update_in(cx, |panel, window, cx| {
+            panel.expand_selected_entry(&ExpandSelectedEntry, window, cx);
+            panel.select_next(&SelectNext, window, cx);
+            panel.expand_selected_entry(&ExpandSelectedEntry, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            display_entries(&panel, cx),
+            ["v leaf", "  v helper", "    v main  <== selected"]
+        );
+        assert_eq!(incoming_call_requests.load(Ordering::SeqCst), 3);
+    }
+
+    fn display_entries(panel: &Entity<HierarchyPanel>, cx: &mut VisualTestContext) -> Vec<String> {
+        panel.read_with(cx, |panel, _| {
+            panel
+                .visible_entries()
+                .into_iter()
+                .map(|ix| {
+                    let entry = &panel.entries[ix];
+                    format!(
+                        "{}{} {}{}",
+                        "  ".repeat(entry.depth),
+                        if entry.expanded { "v" } else { ">" },
+                        entry.item.name,
+                        if panel.selected_entry == Some(ix) {
+                            "  <== selected"
+                        } else {
+                            ""
+                        }
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            super::init(cx);
+        });
+    }
+}
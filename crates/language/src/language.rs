@@ -71,6 +71,7 @@ use util::serde::default_true;
 pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::{DiagnosticEntry, DiagnosticGroup};
+pub use fs::Encoding;
 pub use language_registry::{
     AvailableLanguage, LanguageNotFound, LanguageQueries, LanguageRegistry,
     LanguageServerBinaryStatus, QUERY_FILENAME_PREFIXES,
@@ -1886,6 +1887,7 @@ impl Default for FakeLspAdapter {
                 path: "/the/fake/lsp/path".into(),
                 arguments: vec![],
                 env: Default::default(),
+                transport: Default::default(),
             },
             label_for_completion: None,
         }
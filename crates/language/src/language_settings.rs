@@ -144,6 +144,10 @@ pub struct LanguageSettings {
     /// Whether to display inline and alongside documentation for items in the
     /// completions menu.
     pub show_completion_documentation: bool,
+    /// Where the ranges of a buffer that can be folded come from.
+    pub folding_ranges: FoldingRangesProvider,
+    /// Where the ranges a selection can be expanded to come from.
+    pub selection_ranges: SelectionRangesProvider,
 }
 
 impl LanguageSettings {
@@ -425,6 +429,14 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub show_completion_documentation: Option<bool>,
+    /// Where the ranges of a buffer that can be folded come from.
+    ///
+    /// Default: indent
+    pub folding_ranges: Option<FoldingRangesProvider>,
+    /// Where the ranges a selection can be expanded to come from.
+    ///
+    /// Default: tree_sitter
+    pub selection_ranges: Option<SelectionRangesProvider>,
 }
 
 /// The contents of the edit prediction settings.
@@ -582,6 +594,31 @@ impl<'de> Deserialize<'de> for FormatOnSave {
     }
 }
 
+/// Where the ranges of a buffer that can be folded come from.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingRangesProvider {
+    /// Use the folding ranges reported by the language server.
+    LanguageServer,
+    /// Fold the bracket pairs spanning several lines, as found by the language's grammar.
+    TreeSitter,
+    /// Fold the lines that are indented more than the line before them.
+    #[default]
+    Indent,
+}
+
+/// Where the ranges a selection can be expanded to come from.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionRangesProvider {
+    /// Use the selection ranges reported by the language server, falling back to the syntax
+    /// tree around them.
+    LanguageServer,
+    /// Use the nodes of the language's syntax tree.
+    #[default]
+    TreeSitter,
+}
+
 /// Controls how whitespace should be displayedin the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -1246,6 +1283,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.show_completion_documentation,
         src.show_completion_documentation,
     );
+    merge(&mut settings.folding_ranges, src.folding_ranges);
+    merge(&mut settings.selection_ranges, src.selection_ranges);
 }
 
 /// Allows to enable/disable formatting with Prettier
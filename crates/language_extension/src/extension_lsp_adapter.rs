@@ -15,7 +15,10 @@ use language::{
     CodeLabel, HighlightId, Language, LanguageName, LanguageServerBinaryStatus,
     LanguageToolchainStore, LspAdapter, LspAdapterDelegate,
 };
-use lsp::{CodeActionKind, LanguageServerBinary, LanguageServerBinaryOptions, LanguageServerName};
+use lsp::{
+    CodeActionKind, LanguageServerBinary, LanguageServerBinaryOptions, LanguageServerName,
+    LanguageServerTransport,
+};
 use serde::Serialize;
 use serde_json::Value;
 use util::{maybe, ResultExt};
@@ -132,7 +135,25 @@ impl LspAdapter for ExtensionLspAdapter {
                 )
                 .await?;
 
-            let path = self.extension.path_from_extension(command.command.as_ref());
+            let transport = match command.transport {
+                extension::Transport::Stdio => LanguageServerTransport::Stdio,
+                extension::Transport::Tcp { host, port } => LanguageServerTransport::Tcp {
+                    host,
+                    port,
+                    connect_timeout_ms: None,
+                },
+                extension::Transport::UnixSocket { path } => LanguageServerTransport::UnixSocket {
+                    path: path.into(),
+                    connect_timeout_ms: None,
+                },
+            };
+            // Servers listening on a socket may already be running, and have no command.
+            let path = if command.command.is_empty() && transport != LanguageServerTransport::Stdio
+            {
+                PathBuf::new()
+            } else {
+                self.extension.path_from_extension(command.command.as_ref())
+            };
 
             // TODO: This should now be done via the `zed::make_file_executable` function in
             // Zed extension API, but we're leaving these existing usages in place temporarily
@@ -158,6 +179,7 @@ impl LspAdapter for ExtensionLspAdapter {
                 path,
                 arguments: command.args.into_iter().map(|arg| arg.into()).collect(),
                 env: Some(command.env.into_iter().collect()),
+                transport,
             })
         }
         .boxed_local()
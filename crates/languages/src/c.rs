@@ -33,6 +33,7 @@ impl super::LspAdapter for CLspAdapter {
             path,
             arguments: vec![],
             env: None,
+            transport: Default::default(),
         })
     }
 
@@ -104,6 +105,7 @@ impl super::LspAdapter for CLspAdapter {
             path: binary_path,
             env: None,
             arguments: vec![],
+            transport: Default::default(),
         })
     }
 
@@ -297,6 +299,7 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
                 path: clangd_bin,
                 env: None,
                 arguments: vec![],
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
@@ -55,6 +55,7 @@ impl LspAdapter for CssLspAdapter {
             path,
             env: Some(env),
             arguments: vec!["--stdio".into()],
+            transport: Default::default(),
         })
     }
 
@@ -89,6 +90,7 @@ impl LspAdapter for CssLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -113,6 +115,7 @@ impl LspAdapter for CssLspAdapter {
                 path: self.node.binary_path().await.ok()?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         }
     }
@@ -156,6 +159,7 @@ async fn get_cached_server_binary(
                 path: node.binary_path().await?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
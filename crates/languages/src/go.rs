@@ -83,6 +83,7 @@ impl super::LspAdapter for GoLspAdapter {
             path,
             arguments: server_binary_arguments(),
             env: None,
+            transport: Default::default(),
         })
     }
 
@@ -142,6 +143,7 @@ impl super::LspAdapter for GoLspAdapter {
                         path: binary_path.to_path_buf(),
                         arguments: server_binary_arguments(),
                         env: None,
+                        transport: Default::default(),
                     });
                 }
             }
@@ -185,6 +187,7 @@ impl super::LspAdapter for GoLspAdapter {
             path: binary_path.to_path_buf(),
             arguments: server_binary_arguments(),
             env: None,
+            transport: Default::default(),
         })
     }
 
@@ -408,6 +411,7 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
                 path,
                 arguments: server_binary_arguments(),
                 env: None,
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!("no cached binary"))
@@ -164,6 +164,7 @@ impl LspAdapter for JsonLspAdapter {
             path,
             env: Some(env),
             arguments: vec!["--stdio".into()],
+            transport: Default::default(),
         })
     }
 
@@ -199,6 +200,7 @@ impl LspAdapter for JsonLspAdapter {
                 path: self.node.binary_path().await.ok()?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         }
     }
@@ -223,6 +225,7 @@ impl LspAdapter for JsonLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -300,6 +303,7 @@ async fn get_cached_server_binary(
                 path: node.binary_path().await?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
@@ -418,6 +422,7 @@ impl LspAdapter for NodeVersionAdapter {
             path: destination_path,
             env: None,
             arguments: Default::default(),
+            transport: Default::default(),
         })
     }
 
@@ -442,6 +447,7 @@ async fn get_cached_version_server_binary(container_dir: PathBuf) -> Option<Lang
             path: last.ok_or_else(|| anyhow!("no cached binary"))?,
             env: None,
             arguments: Default::default(),
+            transport: Default::default(),
         })
     })
     .await
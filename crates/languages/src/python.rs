@@ -89,6 +89,7 @@ impl LspAdapter for PythonLspAdapter {
                 path: pyright_bin,
                 env: Some(env),
                 arguments: vec!["--stdio".into()],
+                transport: Default::default(),
             })
         } else {
             let node = delegate.which("node".as_ref()).await?;
@@ -103,6 +104,7 @@ impl LspAdapter for PythonLspAdapter {
                 path: node,
                 env: None,
                 arguments: server_binary_arguments(&path),
+                transport: Default::default(),
             })
         }
     }
@@ -138,6 +140,7 @@ impl LspAdapter for PythonLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -167,6 +170,7 @@ impl LspAdapter for PythonLspAdapter {
                 path: self.node.binary_path().await.ok()?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         }
     }
@@ -305,6 +309,7 @@ async fn get_cached_server_binary(
             path: node.binary_path().await.log_err()?,
             env: None,
             arguments: server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     } else {
         log::error!("missing executable in directory {:?}", server_path);
@@ -806,6 +811,7 @@ impl LspAdapter for PyLspAdapter {
                 path: pylsp_bin,
                 env: Some(env),
                 arguments: vec![],
+                transport: Default::default(),
             })
         } else {
             let venv = toolchains
@@ -820,6 +826,7 @@ impl LspAdapter for PyLspAdapter {
                 path: venv.path.to_string().into(),
                 arguments: vec![pylsp_path.into()],
                 env: None,
+                transport: Default::default(),
             })
         }
     }
@@ -874,6 +881,7 @@ impl LspAdapter for PyLspAdapter {
             path: pylsp,
             env: None,
             arguments: vec![],
+            transport: Default::default(),
         })
     }
 
@@ -888,6 +896,7 @@ impl LspAdapter for PyLspAdapter {
             path: pylsp,
             env: None,
             arguments: vec![],
+            transport: Default::default(),
         })
     }
 
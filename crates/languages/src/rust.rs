@@ -17,7 +17,10 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, LazyLock},
 };
-use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use task::{
+    BuiltinOutputValueMatcher, OutputValueMatcher, TaskTemplate, TaskTemplates, TaskVariables,
+    VariableName,
+};
 use util::{fs::remove_matching, maybe, ResultExt};
 
 use crate::language_settings::language_settings;
@@ -108,6 +111,7 @@ impl LspAdapter for RustLspAdapter {
                 path: path.clone(),
                 arguments: vec!["--help".into()],
                 env: Some(env.clone()),
+                transport: Default::default(),
             })
             .await;
         if let Err(err) = result {
@@ -123,6 +127,7 @@ impl LspAdapter for RustLspAdapter {
             path,
             env: Some(env),
             arguments: vec![],
+            transport: Default::default(),
         })
     }
 
@@ -222,6 +227,7 @@ impl LspAdapter for RustLspAdapter {
             path: server_path,
             env: None,
             arguments: Default::default(),
+            transport: Default::default(),
         })
     }
 
@@ -576,6 +582,7 @@ impl ContextProvider for RustContextProvider {
                 ],
                 tags: vec!["rust-test".to_owned()],
                 cwd: Some("$ZED_DIRNAME".to_owned()),
+                output_values: vec![OutputValueMatcher::Builtin(BuiltinOutputValueMatcher::Dbg)],
                 ..TaskTemplate::default()
             },
             TaskTemplate {
@@ -818,6 +825,7 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
             path: last.ok_or_else(|| anyhow!("no cached binary"))?,
             env: None,
             arguments: Default::default(),
+            transport: Default::default(),
         })
     })
     .await
@@ -60,6 +60,7 @@ impl LspAdapter for TailwindLspAdapter {
             path,
             env: Some(env),
             arguments: vec!["--stdio".into()],
+            transport: Default::default(),
         })
     }
 
@@ -94,6 +95,7 @@ impl LspAdapter for TailwindLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -118,6 +120,7 @@ impl LspAdapter for TailwindLspAdapter {
                 path: self.node.binary_path().await.ok()?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         }
     }
@@ -205,6 +208,7 @@ async fn get_cached_server_binary(
                 path: node.binary_path().await?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
@@ -150,6 +150,7 @@ impl LspAdapter for TypeScriptLspAdapter {
                 path: self.node.binary_path().await.ok()?,
                 env: None,
                 arguments: typescript_server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         }
     }
@@ -183,6 +184,7 @@ impl LspAdapter for TypeScriptLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: typescript_server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -307,12 +309,14 @@ async fn get_cached_ts_server_binary(
                 path: node.binary_path().await?,
                 env: None,
                 arguments: typescript_server_binary_arguments(&new_server_path),
+                transport: Default::default(),
             })
         } else if old_server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 env: None,
                 arguments: typescript_server_binary_arguments(&old_server_path),
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
@@ -554,6 +558,7 @@ impl LspAdapter for EsLintLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: eslint_server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -568,6 +573,7 @@ impl LspAdapter for EsLintLspAdapter {
             path: self.node.binary_path().await.ok()?,
             env: None,
             arguments: eslint_server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 }
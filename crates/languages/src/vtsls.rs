@@ -95,6 +95,7 @@ impl LspAdapter for VtslsLspAdapter {
             path: path.clone(),
             arguments: typescript_server_binary_arguments(&path),
             env: Some(env),
+            transport: Default::default(),
         })
     }
 
@@ -146,6 +147,7 @@ impl LspAdapter for VtslsLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: typescript_server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -289,6 +291,7 @@ async fn get_cached_ts_server_binary(
                 path: node.binary_path().await?,
                 env: None,
                 arguments: typescript_server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
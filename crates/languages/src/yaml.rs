@@ -67,6 +67,7 @@ impl LspAdapter for YamlLspAdapter {
             path,
             env: Some(env),
             arguments: vec!["--stdio".into()],
+            transport: Default::default(),
         })
     }
 
@@ -90,6 +91,7 @@ impl LspAdapter for YamlLspAdapter {
             path: self.node.binary_path().await?,
             env: None,
             arguments: server_binary_arguments(&server_path),
+            transport: Default::default(),
         })
     }
 
@@ -114,6 +116,7 @@ impl LspAdapter for YamlLspAdapter {
                 path: self.node.binary_path().await.ok()?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         }
     }
@@ -180,6 +183,7 @@ async fn get_cached_server_binary(
                 path: node.binary_path().await?,
                 env: None,
                 arguments: server_binary_arguments(&server_path),
+                transport: Default::default(),
             })
         } else {
             Err(anyhow!(
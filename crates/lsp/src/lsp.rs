@@ -46,6 +46,8 @@ const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 
 const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
 const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_SOCKET_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const SOCKET_CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
 
 type NotificationHandler = Box<dyn Send + FnMut(Option<RequestId>, Value, AsyncApp)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
@@ -66,6 +68,46 @@ pub struct LanguageServerBinary {
     pub path: PathBuf,
     pub arguments: Vec<OsString>,
     pub env: Option<HashMap<String, String>>,
+    /// How to talk to the language server once it is launched. Servers listening on a socket
+    /// may have an empty path, in which case they are expected to be running already.
+    #[serde(default)]
+    pub transport: LanguageServerTransport,
+}
+
+/// The channel used to exchange messages with a language server.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageServerTransport {
+    /// Talk to the server over its standard input and output.
+    #[default]
+    Stdio,
+    /// Connect to the server over TCP.
+    Tcp {
+        host: String,
+        port: u16,
+        /// How long to keep trying to connect while the server starts listening.
+        /// Defaults to 5 seconds.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        connect_timeout_ms: Option<u64>,
+    },
+    /// Connect to the server over a Unix domain socket.
+    UnixSocket {
+        path: PathBuf,
+        /// How long to keep trying to connect while the server starts listening.
+        /// Defaults to 5 seconds.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        connect_timeout_ms: Option<u64>,
+    },
+}
+
+impl fmt::Display for LanguageServerTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdio => write!(f, "stdio"),
+            Self::Tcp { host, port, .. } => write!(f, "tcp://{host}:{port}"),
+            Self::UnixSocket { path, .. } => write!(f, "unix://{}", path.display()),
+        }
+    }
 }
 
 /// Configures the search (and installation) of language servers.
@@ -327,8 +369,8 @@ impl lsp_types::notification::Notification for ServerStatus {
 }
 
 impl LanguageServer {
-    /// Starts a language server process.
-    pub fn new(
+    /// Starts a language server process, or connects to one listening on a socket.
+    pub async fn new(
         stderr_capture: Arc<Mutex<Option<String>>>,
         server_id: LanguageServerId,
         server_name: LanguageServerName,
@@ -342,20 +384,145 @@ impl LanguageServer {
         } else {
             root_path.parent().unwrap_or_else(|| Path::new("/"))
         };
+        let on_unhandled_notification = move |notification: AnyNotification| {
+            log::info!(
+                "Language server with id {} sent unhandled notification {}:\n{}",
+                server_id,
+                notification.method,
+                serde_json::to_string_pretty(&notification.params).unwrap(),
+            );
+        };
+
+        match binary.transport.clone() {
+            LanguageServerTransport::Stdio => {
+                log::info!(
+                    "starting language server process. binary path: {:?}, working directory: {:?}, args: {:?}",
+                    binary.path,
+                    working_dir,
+                    &binary.arguments
+                );
+
+                let mut server = util::command::new_smol_command(&binary.path)
+                    .current_dir(working_dir)
+                    .args(&binary.arguments)
+                    .envs(binary.env.clone().unwrap_or_default())
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .with_context(|| {
+                        format!(
+                            "failed to spawn command. path: {:?}, working directory: {:?}, args: {:?}",
+                            binary.path, working_dir, &binary.arguments
+                        )
+                    })?;
+
+                let stdin = server.stdin.take().unwrap();
+                let stdout = server.stdout.take().unwrap();
+                let stderr = server.stderr.take().unwrap();
+                Ok(Self::new_internal(
+                    server_id,
+                    server_name,
+                    stdin,
+                    stdout,
+                    Some(stderr),
+                    stderr_capture,
+                    Some(server),
+                    code_action_kinds,
+                    binary,
+                    cx,
+                    on_unhandled_notification,
+                ))
+            }
+            LanguageServerTransport::Tcp {
+                host,
+                port,
+                connect_timeout_ms,
+            } => {
+                let mut server = Self::spawn_socket_server(&binary, working_dir)?;
+                let stderr = server.as_mut().and_then(|server| server.stderr.take());
+                let stream = connect_with_retries(&cx, connect_timeout_ms, || {
+                    smol::net::TcpStream::connect((host.as_str(), port))
+                })
+                .await
+                .with_context(|| {
+                    format!("failed to connect to language server at {host}:{port}")
+                })?;
+                Ok(Self::new_internal(
+                    server_id,
+                    server_name,
+                    stream.clone(),
+                    stream,
+                    stderr,
+                    stderr_capture,
+                    server,
+                    code_action_kinds,
+                    binary,
+                    cx,
+                    on_unhandled_notification,
+                ))
+            }
+            #[cfg(unix)]
+            LanguageServerTransport::UnixSocket {
+                path,
+                connect_timeout_ms,
+            } => {
+                let mut server = Self::spawn_socket_server(&binary, working_dir)?;
+                let stderr = server.as_mut().and_then(|server| server.stderr.take());
+                let stream = connect_with_retries(&cx, connect_timeout_ms, || {
+                    smol::net::unix::UnixStream::connect(&path)
+                })
+                .await
+                .with_context(|| format!("failed to connect to language server at {path:?}"))?;
+                Ok(Self::new_internal(
+                    server_id,
+                    server_name,
+                    stream.clone(),
+                    stream,
+                    stderr,
+                    stderr_capture,
+                    server,
+                    code_action_kinds,
+                    binary,
+                    cx,
+                    on_unhandled_notification,
+                ))
+            }
+            #[cfg(not(unix))]
+            LanguageServerTransport::UnixSocket { .. } => Err(anyhow!(
+                "language servers can only be reached over Unix sockets on Unix platforms"
+            )),
+        }
+    }
+
+    /// Starts the process of a language server that listens on a socket, unless the binary has
+    /// no path because the server is expected to be running already.
+    fn spawn_socket_server(
+        binary: &LanguageServerBinary,
+        working_dir: &Path,
+    ) -> Result<Option<Child>> {
+        if binary.path.as_os_str().is_empty() {
+            log::info!(
+                "connecting to running language server. transport: {}",
+                binary.transport
+            );
+            return Ok(None);
+        }
 
         log::info!(
-            "starting language server process. binary path: {:?}, working directory: {:?}, args: {:?}",
+            "starting language server process. binary path: {:?}, working directory: {:?}, args: {:?}, transport: {}",
             binary.path,
             working_dir,
-            &binary.arguments
+            &binary.arguments,
+            binary.transport
         );
-
-        let mut server = util::command::new_smol_command(&binary.path)
+        let server = util::command::new_smol_command(&binary.path)
             .current_dir(working_dir)
             .args(&binary.arguments)
             .envs(binary.env.clone().unwrap_or_default())
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
             .stderr(Stdio::piped())
             .kill_on_drop(true)
             .spawn()
@@ -365,32 +532,7 @@ impl LanguageServer {
                     binary.path, working_dir, &binary.arguments
                 )
             })?;
-
-        let stdin = server.stdin.take().unwrap();
-        let stdout = server.stdout.take().unwrap();
-        let stderr = server.stderr.take().unwrap();
-        let server = Self::new_internal(
-            server_id,
-            server_name,
-            stdin,
-            stdout,
-            Some(stderr),
-            stderr_capture,
-            Some(server),
-            code_action_kinds,
-            binary,
-            cx,
-            move |notification| {
-                log::info!(
-                    "Language server with id {} sent unhandled notification {}:\n{}",
-                    server_id,
-                    notification.method,
-                    serde_json::to_string_pretty(&notification.params).unwrap(),
-                );
-            },
-        );
-
-        Ok(server)
+        Ok(Some(server))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -639,8 +781,14 @@ impl LanguageServer {
                     inlay_hint: Some(InlayHintWorkspaceClientCapabilities {
                         refresh_support: Some(true),
                     }),
+                    semantic_tokens: Some(SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
+                    code_lens: Some(CodeLensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     diagnostic: Some(DiagnosticWorkspaceClientCapabilities {
-                        refresh_support: None,
+                        refresh_support: Some(true),
                     }),
                     workspace_edit: Some(WorkspaceEditClientCapabilities {
                         resource_operations: Some(vec![
@@ -740,10 +888,63 @@ impl LanguageServer {
                         }),
                         dynamic_registration: Some(false),
                     }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: Some(false),
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            SemanticTokenType::NAMESPACE,
+                            SemanticTokenType::TYPE,
+                            SemanticTokenType::CLASS,
+                            SemanticTokenType::ENUM,
+                            SemanticTokenType::INTERFACE,
+                            SemanticTokenType::STRUCT,
+                            SemanticTokenType::TYPE_PARAMETER,
+                            SemanticTokenType::PARAMETER,
+                            SemanticTokenType::VARIABLE,
+                            SemanticTokenType::PROPERTY,
+                            SemanticTokenType::ENUM_MEMBER,
+                            SemanticTokenType::EVENT,
+                            SemanticTokenType::FUNCTION,
+                            SemanticTokenType::METHOD,
+                            SemanticTokenType::MACRO,
+                            SemanticTokenType::KEYWORD,
+                            SemanticTokenType::MODIFIER,
+                            SemanticTokenType::COMMENT,
+                            SemanticTokenType::STRING,
+                            SemanticTokenType::NUMBER,
+                            SemanticTokenType::REGEXP,
+                            SemanticTokenType::OPERATOR,
+                            SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            SemanticTokenModifier::DECLARATION,
+                            SemanticTokenModifier::DEFINITION,
+                            SemanticTokenModifier::READONLY,
+                            SemanticTokenModifier::STATIC,
+                            SemanticTokenModifier::DEPRECATED,
+                            SemanticTokenModifier::ABSTRACT,
+                            SemanticTokenModifier::ASYNC,
+                            SemanticTokenModifier::MODIFICATION,
+                            SemanticTokenModifier::DOCUMENTATION,
+                            SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(false),
+                        augments_syntax_tokens: Some(true),
+                    }),
                     publish_diagnostics: Some(PublishDiagnosticsClientCapabilities {
                         related_information: Some(true),
                         ..Default::default()
                     }),
+                    diagnostic: Some(DiagnosticClientCapabilities {
+                        dynamic_registration: Some(false),
+                        related_document_support: Some(true),
+                    }),
                     formatting: Some(DynamicRegistrationClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
@@ -766,6 +967,33 @@ impl LanguageServer {
                         }),
                         ..SignatureHelpClientCapabilities::default()
                     }),
+                    call_hierarchy: Some(CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    type_hierarchy: Some(TypeHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    code_lens: Some(CodeLensClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: Some(false),
+                        tooltip_support: Some(true),
+                    }),
+                    folding_range: Some(FoldingRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                        line_folding_only: Some(false),
+                        ..Default::default()
+                    }),
+                    selection_range: Some(SelectionRangeClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    inline_value: Some(InlineValueClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    color_provider: Some(DocumentColorClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
                     synchronization: Some(TextDocumentSyncClientCapabilities {
                         did_save: Some(true),
                         ..TextDocumentSyncClientCapabilities::default()
@@ -836,7 +1064,13 @@ impl LanguageServer {
                 &executor,
                 (),
             );
-            let exit = Self::notify_internal::<notification::Exit>(&outbound_tx, &());
+            // A server that Zed only connected to keeps running for its other clients, so it
+            // is not asked to exit.
+            let exit = self
+                .server
+                .lock()
+                .is_some()
+                .then(|| Self::notify_internal::<notification::Exit>(&outbound_tx, &()));
             outbound_tx.close();
 
             let server = self.server.clone();
@@ -857,7 +1091,9 @@ impl LanguageServer {
                     }
 
                     response_handlers.lock().take();
-                    exit?;
+                    if let Some(exit) = exit {
+                        exit?;
+                    }
                     output_done.recv().await;
                     server.lock().take().map(|mut child| child.kill());
                     log::debug!("language server shutdown finished");
@@ -1304,6 +1540,35 @@ impl LanguageServer {
     }
 }
 
+/// Connects to a socket, retrying until the timeout (or [`DEFAULT_SOCKET_CONNECT_TIMEOUT`])
+/// elapses as a server that was just started may not be listening yet.
+async fn connect_with_retries<S, F, Fut>(
+    cx: &AsyncApp,
+    timeout_ms: Option<u64>,
+    mut connect: F,
+) -> std::io::Result<S>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::io::Result<S>>,
+{
+    let timeout = timeout_ms.map_or(DEFAULT_SOCKET_CONNECT_TIMEOUT, Duration::from_millis);
+    let attempts = (timeout.as_millis() / SOCKET_CONNECT_RETRY_INTERVAL.as_millis()).max(1);
+    let mut attempt = 1;
+    loop {
+        match connect().await {
+            Ok(stream) => return Ok(stream),
+            Err(error) if attempt < attempts => {
+                log::debug!("failed to connect to language server, retrying: {error}");
+                attempt += 1;
+                cx.background_executor()
+                    .timer(SOCKET_CONNECT_RETRY_INTERVAL)
+                    .await;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
 impl Drop for LanguageServer {
     fn drop(&mut self) {
         if let Some(shutdown) = self.shutdown() {
@@ -1614,6 +1879,7 @@ mod tests {
                 path: "path/to/language-server".into(),
                 arguments: vec![],
                 env: None,
+                transport: Default::default(),
             },
             "the-lsp".to_string(),
             Default::default(),
@@ -1732,4 +1998,51 @@ mod tests {
             "{\"jsonrpc\":\"\",\"id\":0,\"error\":null}"
         );
     }
+
+    #[test]
+    fn test_deserialize_transport() {
+        let binary = serde_json::from_str::<LanguageServerBinary>(
+            r#"{"path":"","arguments":[],"env":null}"#,
+        )
+        .unwrap();
+        assert_eq!(binary.transport, LanguageServerTransport::Stdio);
+
+        let transport = serde_json::from_str::<LanguageServerTransport>(
+            r#"{"tcp":{"host":"127.0.0.1","port":6005}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            transport,
+            LanguageServerTransport::Tcp {
+                host: "127.0.0.1".into(),
+                port: 6005,
+                connect_timeout_ms: None,
+            }
+        );
+
+        let transport = serde_json::from_str::<LanguageServerTransport>(
+            r#"{"unix_socket":{"path":"/tmp/server.sock"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            transport,
+            LanguageServerTransport::UnixSocket {
+                path: "/tmp/server.sock".into(),
+                connect_timeout_ms: None,
+            }
+        );
+
+        let transport = serde_json::from_str::<LanguageServerTransport>(
+            r#"{"tcp":{"host":"127.0.0.1","port":6005,"connect_timeout_ms":30000}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            transport,
+            LanguageServerTransport::Tcp {
+                host: "127.0.0.1".into(),
+                port: 6005,
+                connect_timeout_ms: Some(30000),
+            }
+        );
+    }
 }
@@ -271,6 +271,7 @@ impl Prettier {
             path: node_path,
             arguments: vec![prettier_server.into(), prettier_dir.as_path().into()],
             env: None,
+            transport: Default::default(),
         };
         let server = LanguageServer::new(
             Arc::new(parking_lot::Mutex::new(None)),
@@ -281,6 +282,7 @@ impl Prettier {
             None,
             cx.clone(),
         )
+        .await
         .context("prettier server creation")?;
 
         let server = cx
@@ -0,0 +1,269 @@
+use collections::HashMap;
+use gpui::{App, Context, Entity, EventEmitter, SharedString, Subscription, WeakEntity};
+use language::{Bias, Buffer, BufferEvent, BufferSnapshot, Point, ToPoint as _};
+use text::{Anchor, BufferId};
+
+use crate::{
+    buffer_store::{BufferStore, BufferStoreEvent},
+    ProjectItem as _, ProjectPath,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BookmarkStoreEvent {
+    /// Bookmarks were added or removed.
+    BookmarksChanged,
+    /// A buffer with bookmarks was edited, which may have moved them to other rows.
+    BookmarkedBufferEdited(BufferId),
+    /// A buffer with bookmarks was saved.
+    BookmarkedBufferSaved(BufferId),
+}
+
+/// A bookmarked line of a file.
+#[derive(Clone, Debug)]
+pub struct Bookmark {
+    pub path: ProjectPath,
+    pub row: u32,
+    pub label: Option<SharedString>,
+}
+
+/// Keeps track of the lines bookmarked in the project's files.
+///
+/// Bookmarks of open buffers are anchored so that they follow edits. Bookmarks of files that
+/// aren't open are kept as rows, and are only anchored once their buffer is opened.
+pub struct BookmarkStore {
+    buffer_store: Entity<BufferStore>,
+    bookmarks: HashMap<BufferId, BufferBookmarks>,
+    unopened_bookmarks: HashMap<ProjectPath, Vec<(u32, Option<SharedString>)>>,
+    _subscription: Subscription,
+}
+
+struct BufferBookmarks {
+    buffer: WeakEntity<Buffer>,
+    /// Anchors at the start of each bookmarked row, so that bookmarks follow edits, with the
+    /// label of each bookmark.
+    positions: Vec<(Anchor, Option<SharedString>)>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+impl BookmarkStore {
+    pub fn new(buffer_store: &Entity<BufferStore>, cx: &mut Context<Self>) -> Self {
+        Self {
+            buffer_store: buffer_store.clone(),
+            bookmarks: HashMap::default(),
+            unopened_bookmarks: HashMap::default(),
+            _subscription: cx.subscribe(buffer_store, Self::on_buffer_store_event),
+        }
+    }
+
+    fn on_buffer_store_event(
+        &mut self,
+        _: Entity<BufferStore>,
+        event: &BufferStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if let BufferStoreEvent::BufferAdded(buffer) = event {
+            let Some(path) = buffer.read(cx).project_path(cx) else {
+                return;
+            };
+            if let Some(rows) = self.unopened_bookmarks.remove(&path) {
+                self.anchor_bookmarks(buffer, rows, cx);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Bookmarked rows of the given buffer with their labels, in ascending order.
+    pub fn bookmark_rows(
+        &self,
+        buffer_id: BufferId,
+        snapshot: &BufferSnapshot,
+    ) -> Vec<(u32, Option<SharedString>)> {
+        let Some(bookmarks) = self.bookmarks.get(&buffer_id) else {
+            return Vec::new();
+        };
+        let mut rows = bookmarks
+            .positions
+            .iter()
+            .map(|(position, label)| (position.to_point(snapshot).row, label.clone()))
+            .collect::<Vec<_>>();
+        // Deleting the lines between bookmarks moves them onto the same row.
+        rows.sort_by_key(|(row, _)| *row);
+        rows.dedup_by_key(|(row, _)| *row);
+        rows
+    }
+
+    /// All of the project's bookmarks, grouped by file and in ascending order of rows within
+    /// each file. Bookmarks of buffers without a file are left out.
+    pub fn bookmarks(&self, cx: &App) -> Vec<Bookmark> {
+        let open_bookmarks = self
+            .bookmarks
+            .iter()
+            .filter_map(|(buffer_id, bookmarks)| {
+                let buffer = bookmarks.buffer.upgrade()?;
+                let buffer = buffer.read(cx);
+                let path = buffer.project_path(cx)?;
+                let rows = self.bookmark_rows(*buffer_id, &buffer.snapshot());
+                Some(rows.into_iter().map(move |(row, label)| Bookmark {
+                    path: path.clone(),
+                    row,
+                    label,
+                }))
+            })
+            .flatten();
+        let unopened_bookmarks = self.unopened_bookmarks.iter().flat_map(|(path, rows)| {
+            rows.iter().map(|(row, label)| Bookmark {
+                path: path.clone(),
+                row: *row,
+                label: label.clone(),
+            })
+        });
+        open_bookmarks.chain(unopened_bookmarks).collect()
+    }
+
+    /// Bookmarks the given rows of a file, with their labels. The bookmarks are anchored right
+    /// away if the file is open, and once it's opened otherwise.
+    pub fn add_bookmarks(
+        &mut self,
+        path: ProjectPath,
+        rows: Vec<(u32, Option<SharedString>)>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(buffer) = self.buffer_store.read(cx).get_by_path(&path, cx) {
+            self.anchor_bookmarks(&buffer, rows, cx);
+        } else {
+            self.add_unopened_bookmarks(path, rows);
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    /// Bookmarks the row containing `position`, or removes the bookmark already there.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: Anchor,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+        let is_bookmarked = self
+            .bookmark_rows(snapshot.remote_id(), &snapshot)
+            .iter()
+            .any(|(bookmarked_row, _)| *bookmarked_row == row);
+        if is_bookmarked {
+            self.remove_bookmark(&buffer, position, cx);
+        } else {
+            self.set_bookmark(buffer, position, label, cx);
+        }
+    }
+
+    /// Bookmarks the row containing `position`, replacing the label of the bookmark already
+    /// there.
+    pub fn set_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: Anchor,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let row = position.to_point(&buffer.read(cx).snapshot()).row;
+        self.anchor_bookmarks(&buffer, vec![(row, label)], cx);
+
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    /// Removes the bookmark on the row containing `position`, if any.
+    pub fn remove_bookmark(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = snapshot.remote_id();
+        let row = position.to_point(&snapshot).row;
+        let Some(bookmarks) = self.bookmarks.get_mut(&buffer_id) else {
+            return;
+        };
+        bookmarks
+            .positions
+            .retain(|(position, _)| position.to_point(&snapshot).row != row);
+        if bookmarks.positions.is_empty() {
+            self.bookmarks.remove(&buffer_id);
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    fn anchor_bookmarks(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        rows: Vec<(u32, Option<SharedString>)>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let bookmarks = self
+            .bookmarks
+            .entry(snapshot.remote_id())
+            .or_insert_with(|| BufferBookmarks {
+                buffer: buffer.downgrade(),
+                positions: Vec::new(),
+                _subscriptions: [
+                    cx.subscribe(buffer, |_, buffer, event: &BufferEvent, cx| {
+                        let buffer_id = buffer.read(cx).remote_id();
+                        match event {
+                            BufferEvent::Edited => {
+                                cx.emit(BookmarkStoreEvent::BookmarkedBufferEdited(buffer_id))
+                            }
+                            BufferEvent::Saved => {
+                                cx.emit(BookmarkStoreEvent::BookmarkedBufferSaved(buffer_id))
+                            }
+                            _ => {}
+                        }
+                    }),
+                    cx.observe_release(buffer, |this, buffer, cx| {
+                        this.unanchor_bookmarks(buffer, cx);
+                    }),
+                ],
+            });
+        for (row, label) in rows {
+            // The file may have become shorter since the bookmark was set.
+            let position = snapshot.clip_point(Point::new(row, 0), Bias::Left);
+            bookmarks
+                .positions
+                .retain(|(bookmark, _)| bookmark.to_point(&snapshot).row != position.row);
+            bookmarks
+                .positions
+                .push((snapshot.anchor_before(position), label));
+        }
+    }
+
+    /// Keeps the bookmarks of a buffer that is being released as rows of its file, to be
+    /// anchored again when the file is reopened.
+    fn unanchor_bookmarks(&mut self, buffer: &Buffer, cx: &App) {
+        let buffer_id = buffer.remote_id();
+        let rows = self.bookmark_rows(buffer_id, &buffer.snapshot());
+        self.bookmarks.remove(&buffer_id);
+        if let Some(path) = buffer.project_path(cx) {
+            self.add_unopened_bookmarks(path, rows);
+        }
+    }
+
+    fn add_unopened_bookmarks(
+        &mut self,
+        path: ProjectPath,
+        rows: Vec<(u32, Option<SharedString>)>,
+    ) {
+        let bookmarks = self.unopened_bookmarks.entry(path).or_default();
+        // Bookmarks added later replace the ones already on the same row.
+        bookmarks.retain(|(row, _)| !rows.iter().any(|(new_row, _)| new_row == row));
+        bookmarks.extend(rows);
+        bookmarks.sort_by_key(|(row, _)| *row);
+    }
+}
@@ -9,7 +9,7 @@ use anyhow::{anyhow, bail, Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffEvent};
 use client::Client;
 use collections::{hash_map, HashMap, HashSet};
-use fs::Fs;
+use fs::{Encoding, Fs, InvalidTextError};
 use futures::{channel::oneshot, future::Shared, Future, FutureExt as _, StreamExt};
 use git::{blame::Blame, repository::RepoPath};
 use gpui::{
@@ -18,8 +18,8 @@ use gpui::{
 use http_client::Url;
 use language::{
     proto::{
-        deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
-        split_operations,
+        deserialize_encoding, deserialize_line_ending, deserialize_version, serialize_encoding,
+        serialize_line_ending, serialize_version, split_operations,
     },
     Buffer, BufferEvent, Capability, DiskState, File as _, Language, LanguageRegistry, Operation,
 };
@@ -412,6 +412,7 @@ impl RemoteBufferStore {
         &self,
         buffer_handle: Entity<Buffer>,
         new_path: Option<proto::ProjectPath>,
+        encoding: Option<Encoding>,
         cx: &Context<BufferStore>,
     ) -> Task<Result<()>> {
         let buffer = buffer_handle.read(cx);
@@ -426,12 +427,17 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    encoding: encoding.map(serialize_encoding),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
             let mtime = response.mtime.map(|mtime| mtime.into());
+            let encoding = response.encoding.map(deserialize_encoding).transpose()?;
 
             buffer_handle.update(&mut cx, |buffer, cx| {
+                if let Some(encoding) = encoding {
+                    buffer.set_encoding(encoding, cx);
+                }
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
@@ -627,6 +633,7 @@ impl RemoteBufferStore {
         &self,
         buffers: HashSet<Entity<Buffer>>,
         push_to_history: bool,
+        encoding: Option<Encoding>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
         let request = self.upstream_client.request(proto::ReloadBuffers {
@@ -635,6 +642,7 @@ impl RemoteBufferStore {
                 .iter()
                 .map(|buffer| buffer.read(cx).remote_id().to_proto())
                 .collect(),
+            encoding: encoding.map(serialize_encoding),
         });
 
         cx.spawn(|this, mut cx| async move {
@@ -692,6 +700,7 @@ impl LocalBufferStore {
         worktree: Entity<Worktree>,
         path: Arc<Path>,
         mut has_changed_file: bool,
+        encoding: Encoding,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<()>> {
         let buffer = buffer_handle.read(cx);
@@ -708,7 +717,7 @@ impl LocalBufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         cx.spawn(move |this, mut cx| async move {
@@ -731,6 +740,7 @@ impl LocalBufferStore {
                             buffer_id: buffer_id.to_proto(),
                             version: serialize_version(&version),
                             mtime: mtime.map(|time| time.into()),
+                            encoding: Some(serialize_encoding(encoding)),
                         })
                         .log_err();
                 }
@@ -739,6 +749,7 @@ impl LocalBufferStore {
                 if has_changed_file {
                     buffer.file_updated(new_file, cx);
                 }
+                buffer.set_encoding(encoding, cx);
                 buffer.did_save(version.clone(), mtime, cx);
             })
         })
@@ -1091,13 +1102,16 @@ impl LocalBufferStore {
     fn save_buffer(
         &self,
         buffer: Entity<Buffer>,
+        encoding: Option<Encoding>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<()>> {
         let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
             return Task::ready(Err(anyhow!("buffer doesn't have a file")));
         };
         let worktree = file.worktree.clone();
-        self.save_local_buffer(buffer, worktree, file.path.clone(), false, cx)
+        let path = file.path.clone();
+        let encoding = encoding.unwrap_or_else(|| buffer.read(cx).encoding());
+        self.save_local_buffer(buffer, worktree, path, false, encoding, cx)
     }
 
     fn save_buffer_as(
@@ -1113,7 +1127,8 @@ impl LocalBufferStore {
         else {
             return Task::ready(Err(anyhow!("no such worktree")));
         };
-        self.save_local_buffer(buffer, worktree, path.path.clone(), true, cx)
+        let encoding = buffer.read(cx).encoding();
+        self.save_local_buffer(buffer, worktree, path.path.clone(), true, encoding, cx)
     }
 
     fn open_buffer(
@@ -1132,8 +1147,11 @@ impl LocalBufferStore {
                     .background_executor()
                     .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
                     .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                cx.insert_entity(reservation, |cx| {
+                    let mut buffer =
+                        Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite);
+                    buffer.set_encoding(loaded.encoding, cx);
+                    buffer
                 })
             })
         });
@@ -1200,13 +1218,17 @@ impl LocalBufferStore {
         &self,
         buffers: HashSet<Entity<Buffer>>,
         push_to_history: bool,
+        encoding: Option<Encoding>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
         cx.spawn(move |_, mut cx| async move {
             let mut project_transaction = ProjectTransaction::default();
             for buffer in buffers {
                 let transaction = buffer
-                    .update(&mut cx, |buffer, cx| buffer.reload(cx))?
+                    .update(&mut cx, |buffer, cx| match encoding {
+                        Some(encoding) => buffer.reload_with_encoding(encoding, cx),
+                        None => buffer.reload(cx),
+                    })?
                     .await?;
                 buffer.update(&mut cx, |buffer, cx| {
                     if let Some(transaction) = transaction {
@@ -1347,8 +1369,17 @@ impl BufferStore {
             }
         };
 
-        cx.background_executor()
-            .spawn(async move { task.await.map_err(|e| anyhow!("{e}")) })
+        cx.background_executor().spawn(async move {
+            task.await.map_err(|e| {
+                // Keep files that aren't text recognizable, so that they can be opened
+                // another way.
+                if e.is::<InvalidTextError>() {
+                    anyhow::Error::new(InvalidTextError).context(e.to_string())
+                } else {
+                    anyhow!("{e}")
+                }
+            })
+        })
     }
 
     pub fn open_unstaged_diff(
@@ -1527,8 +1558,25 @@ impl BufferStore {
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
         match &mut self.state {
-            BufferStoreState::Local(this) => this.save_buffer(buffer, cx),
-            BufferStoreState::Remote(this) => this.save_remote_buffer(buffer.clone(), None, cx),
+            BufferStoreState::Local(this) => this.save_buffer(buffer, None, cx),
+            BufferStoreState::Remote(this) => {
+                this.save_remote_buffer(buffer.clone(), None, None, cx)
+            }
+        }
+    }
+
+    /// Saves a buffer in the given encoding, which the buffer then keeps for later saves.
+    pub fn save_buffer_with_encoding(
+        &mut self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        match &mut self.state {
+            BufferStoreState::Local(this) => this.save_buffer(buffer, Some(encoding), cx),
+            BufferStoreState::Remote(this) => {
+                this.save_remote_buffer(buffer.clone(), None, Some(encoding), cx)
+            }
         }
     }
 
@@ -1542,7 +1590,7 @@ impl BufferStore {
         let task = match &self.state {
             BufferStoreState::Local(this) => this.save_buffer_as(buffer.clone(), path, cx),
             BufferStoreState::Remote(this) => {
-                this.save_remote_buffer(buffer.clone(), Some(path.to_proto()), cx)
+                this.save_remote_buffer(buffer.clone(), Some(path.to_proto()), None, cx)
             }
         };
         cx.spawn(|this, mut cx| async move {
@@ -1977,6 +2025,7 @@ impl BufferStore {
                         version: serialize_version(&buffer.version()),
                         mtime: buffer.saved_mtime().map(|t| t.into()),
                         line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
             }
@@ -2103,6 +2152,7 @@ impl BufferStore {
                         mtime: buffer.saved_mtime().map(|time| time.into()),
                         line_ending: language::proto::serialize_line_ending(buffer.line_ending())
                             as i32,
+                        encoding: Some(serialize_encoding(buffer.encoding())),
                     })
                     .log_err();
 
@@ -2225,6 +2275,12 @@ impl BufferStore {
                 this.save_buffer_as(buffer.clone(), new_path, cx)
             })?
             .await?;
+        } else if let Some(encoding) = envelope.payload.encoding {
+            let encoding = deserialize_encoding(encoding)?;
+            this.update(&mut cx, |this, cx| {
+                this.save_buffer_with_encoding(buffer.clone(), encoding, cx)
+            })?
+            .await?;
         } else {
             this.update(&mut cx, |this, cx| this.save_buffer(buffer.clone(), cx))?
                 .await?;
@@ -2235,6 +2291,7 @@ impl BufferStore {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: Some(serialize_encoding(buffer.encoding())),
         })
     }
 
@@ -2270,9 +2327,18 @@ impl BufferStore {
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let version = deserialize_version(&envelope.payload.version);
         let mtime = envelope.payload.mtime.clone().map(|time| time.into());
+        let encoding = envelope
+            .payload
+            .encoding
+            .clone()
+            .map(deserialize_encoding)
+            .transpose()?;
         this.update(&mut cx, move |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -2284,6 +2350,7 @@ impl BufferStore {
                         buffer_id: buffer_id.into(),
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -2302,9 +2369,18 @@ impl BufferStore {
             proto::LineEnding::from_i32(envelope.payload.line_ending)
                 .ok_or_else(|| anyhow!("missing line ending"))?,
         );
+        let encoding = envelope
+            .payload
+            .encoding
+            .clone()
+            .map(deserialize_encoding)
+            .transpose()?;
         this.update(&mut cx, |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
                     buffer.did_reload(version, line_ending, mtime, cx);
                 });
             }
@@ -2317,6 +2393,7 @@ impl BufferStore {
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
                         line_ending: envelope.payload.line_ending,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -2488,8 +2565,28 @@ impl BufferStore {
             return Task::ready(Ok(ProjectTransaction::default()));
         }
         match &self.state {
-            BufferStoreState::Local(this) => this.reload_buffers(buffers, push_to_history, cx),
-            BufferStoreState::Remote(this) => this.reload_buffers(buffers, push_to_history, cx),
+            BufferStoreState::Local(this) => {
+                this.reload_buffers(buffers, push_to_history, None, cx)
+            }
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, push_to_history, None, cx)
+            }
+        }
+    }
+
+    /// Reloads a buffer from disk in the given encoding, which the buffer then keeps for saving.
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let buffers = HashSet::from_iter([buffer]);
+        match &self.state {
+            BufferStoreState::Local(this) => this.reload_buffers(buffers, true, Some(encoding), cx),
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, true, Some(encoding), cx)
+            }
         }
     }
 
@@ -2499,13 +2596,28 @@ impl BufferStore {
         mut cx: AsyncApp,
     ) -> Result<proto::ReloadBuffersResponse> {
         let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let encoding = envelope
+            .payload
+            .encoding
+            .map(deserialize_encoding)
+            .transpose()?;
         let reload = this.update(&mut cx, |this, cx| {
             let mut buffers = HashSet::default();
             for buffer_id in &envelope.payload.buffer_ids {
                 let buffer_id = BufferId::new(*buffer_id)?;
                 buffers.insert(this.get_existing(buffer_id)?);
             }
-            Ok::<_, anyhow::Error>(this.reload_buffers(buffers, false, cx))
+            if buffers.is_empty() {
+                return Ok(Task::ready(Ok(ProjectTransaction::default())));
+            }
+            Ok::<_, anyhow::Error>(match &this.state {
+                BufferStoreState::Local(state) => {
+                    state.reload_buffers(buffers, false, encoding, cx)
+                }
+                BufferStoreState::Remote(state) => {
+                    state.reload_buffers(buffers, false, encoding, cx)
+                }
+            })
         })??;
 
         let project_transaction = reload.await?;
@@ -0,0 +1,318 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+use collections::HashMap;
+use dap::{
+    events, requests,
+    types::{InitializeRequestArguments, SetBreakpointsArguments, Source, SourceBreakpoint},
+    DebugAdapterBinary, DebugAdapterClient, DebugAdapterClientId,
+};
+use futures::{channel::oneshot, future::join_all, select, FutureExt as _};
+use gpui::{AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity};
+use language::{Buffer, BufferSnapshot, Point, ToPoint as _};
+use serde_json::json;
+use task::{DebugAdapterConfig, DebugRequestType};
+use text::{Anchor, BufferId};
+use util::ResultExt as _;
+
+use crate::debug_session::{DebugSession, SessionStatus};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DapStoreEvent {
+    SessionStarted(DebugAdapterClientId),
+    SessionRemoved(DebugAdapterClientId),
+    BreakpointsChanged(BufferId),
+}
+
+/// Keeps track of the project's breakpoints and of the debug sessions started in it.
+#[derive(Default)]
+pub struct DapStore {
+    next_client_id: usize,
+    sessions: BTreeMap<DebugAdapterClientId, Entity<DebugSession>>,
+    breakpoints: HashMap<BufferId, BufferBreakpoints>,
+}
+
+struct BufferBreakpoints {
+    buffer: Entity<Buffer>,
+    /// Anchors at the start of each row that has a breakpoint.
+    positions: Vec<Anchor>,
+}
+
+impl EventEmitter<DapStoreEvent> for DapStore {}
+
+impl DapStore {
+    pub fn sessions(&self) -> impl Iterator<Item = &Entity<DebugSession>> {
+        self.sessions.values()
+    }
+
+    pub fn session(&self, id: DebugAdapterClientId) -> Option<&Entity<DebugSession>> {
+        self.sessions.get(&id)
+    }
+
+    pub fn next_client_id(&mut self) -> DebugAdapterClientId {
+        let id = DebugAdapterClientId(self.next_client_id);
+        self.next_client_id += 1;
+        id
+    }
+
+    /// Rows of the given buffer that have a breakpoint, in ascending order.
+    pub fn breakpoint_rows(&self, buffer_id: BufferId, snapshot: &BufferSnapshot) -> Vec<u32> {
+        let Some(breakpoints) = self.breakpoints.get(&buffer_id) else {
+            return Vec::new();
+        };
+        let mut rows = breakpoints
+            .positions
+            .iter()
+            .map(|position| position.to_point(snapshot).row)
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    /// Adds a breakpoint on the row containing `position`, or removes the one already there.
+    pub fn toggle_breakpoint(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = snapshot.remote_id();
+        let row = position.to_point(&snapshot).row;
+        let breakpoints = self
+            .breakpoints
+            .entry(buffer_id)
+            .or_insert_with(|| BufferBreakpoints {
+                buffer: buffer.clone(),
+                positions: Vec::new(),
+            });
+        let previous_len = breakpoints.positions.len();
+        breakpoints
+            .positions
+            .retain(|position| position.to_point(&snapshot).row != row);
+        if breakpoints.positions.len() == previous_len {
+            breakpoints
+                .positions
+                .push(snapshot.anchor_before(Point::new(row, 0)));
+        }
+
+        let sessions = self
+            .sessions
+            .values()
+            .filter(|session| session.read(cx).status() != SessionStatus::Exited)
+            .cloned()
+            .collect::<Vec<_>>();
+        for session in sessions {
+            self.send_breakpoints(&session, buffer_id, cx)
+                .detach_and_log_err(cx);
+        }
+        if self
+            .breakpoints
+            .get(&buffer_id)
+            .map_or(false, |breakpoints| breakpoints.positions.is_empty())
+        {
+            self.breakpoints.remove(&buffer_id);
+        }
+
+        cx.emit(DapStoreEvent::BreakpointsChanged(buffer_id));
+        cx.notify();
+    }
+
+    fn send_breakpoints(
+        &self,
+        session: &Entity<DebugSession>,
+        buffer_id: BufferId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(breakpoints) = self.breakpoints.get(&buffer_id) else {
+            return Task::ready(Ok(()));
+        };
+        let buffer = breakpoints.buffer.read(cx);
+        let Some(abs_path) = buffer
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(()));
+        };
+        let snapshot = buffer.snapshot();
+        let lines = self
+            .breakpoint_rows(buffer_id, &snapshot)
+            .into_iter()
+            .map(|row| SourceBreakpoint {
+                line: row as u64 + 1,
+                ..Default::default()
+            })
+            .collect();
+
+        let request = session
+            .read(cx)
+            .client()
+            .request::<requests::SetBreakpoints>(SetBreakpointsArguments {
+                source: source_for_path(abs_path),
+                breakpoints: Some(lines),
+                source_modified: None,
+            });
+        cx.spawn(|_, _| async move { request.await.map(|_| ()) })
+    }
+
+    /// Starts a debug adapter process and begins debugging with it.
+    pub fn start_debug_session(
+        &mut self,
+        label: SharedString,
+        binary: DebugAdapterBinary,
+        config: DebugAdapterConfig,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<DebugSession>>> {
+        let id = self.next_client_id();
+        match DebugAdapterClient::new(id, label, binary, cx.to_async()) {
+            Ok(client) => self.start_session_with_client(client, config, cx),
+            Err(error) => Task::ready(Err(error)),
+        }
+    }
+
+    /// Begins debugging with an already connected debug adapter: initializes it, launches or
+    /// attaches to the debuggee and sends it the project's breakpoints.
+    pub fn start_session_with_client(
+        &mut self,
+        client: DebugAdapterClient,
+        config: DebugAdapterConfig,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<DebugSession>>> {
+        let id = client.id();
+        let client = Arc::new(client);
+
+        // Adapters accept breakpoints once they sent the `initialized` event, which may arrive
+        // before or after the response to the launch request.
+        let (initialized_tx, initialized_rx) = oneshot::channel();
+        let mut initialized_tx = Some(initialized_tx);
+        client
+            .on_event::<events::Initialized, _>(move |_, _| {
+                if let Some(initialized_tx) = initialized_tx.take() {
+                    initialized_tx.send(()).ok();
+                }
+            })
+            .detach();
+
+        let session = cx.new(|cx| DebugSession::new(client.clone(), config.clone(), cx));
+        self.sessions.insert(id, session.clone());
+        cx.emit(DapStoreEvent::SessionStarted(id));
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let result = Self::launch(
+                this,
+                session.clone(),
+                client,
+                config,
+                initialized_rx,
+                &mut cx,
+            )
+            .await;
+            match result {
+                Ok(()) => Ok(session),
+                Err(error) => {
+                    session.update(&mut cx, |session, cx| session.fail_to_start(&error, cx))?;
+                    Err(error)
+                }
+            }
+        })
+    }
+
+    async fn launch(
+        this: WeakEntity<Self>,
+        session: Entity<DebugSession>,
+        client: Arc<DebugAdapterClient>,
+        config: DebugAdapterConfig,
+        initialized: oneshot::Receiver<()>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let capabilities = client
+            .request::<requests::Initialize>(InitializeRequestArguments {
+                client_id: Some("zed".into()),
+                client_name: Some("Zed".into()),
+                adapter_id: client.name().to_string(),
+                path_format: Some("path".into()),
+                supports_variable_type: Some(true),
+                ..Default::default()
+            })
+            .await?;
+        session.update(cx, |session, _| {
+            session.set_capabilities(capabilities.clone())
+        })?;
+
+        let launch = match &config.request {
+            DebugRequestType::Launch(launch) => client
+                .request::<requests::Launch>(json!({
+                    "program": launch.program,
+                    "args": launch.args,
+                    "stopOnEntry": config.stop_on_entry,
+                }))
+                .boxed_local(),
+            DebugRequestType::Attach(attach) => client
+                .request::<requests::Attach>(json!({
+                    // Adapters disagree on the name of this argument.
+                    "pid": attach.process_id,
+                    "processId": attach.process_id,
+                    "stopOnEntry": config.stop_on_entry,
+                }))
+                .boxed_local(),
+        };
+        let mut launch = launch.fuse();
+        let mut initialized = initialized.fuse();
+        let mut launched = false;
+        select! {
+            result = launch => {
+                result?;
+                launched = true;
+            }
+            _ = initialized => {}
+        }
+        if launched {
+            initialized.await.ok();
+        }
+
+        let send_breakpoints = this.update(cx, |this, cx| {
+            this.breakpoints
+                .keys()
+                .map(|buffer_id| this.send_breakpoints(&session, *buffer_id, cx))
+                .collect::<Vec<_>>()
+        })?;
+        for result in join_all(send_breakpoints).await {
+            result.log_err();
+        }
+        if capabilities
+            .supports_configuration_done_request
+            .unwrap_or(false)
+        {
+            client.request::<requests::ConfigurationDone>(()).await?;
+        }
+        if !launched {
+            launch.await?;
+        }
+
+        session.update(cx, |session, cx| session.finish_starting(cx))
+    }
+
+    /// Ends a debug session and forgets about it.
+    pub fn remove_session(&mut self, id: DebugAdapterClientId, cx: &mut Context<Self>) {
+        if let Some(session) = self.sessions.remove(&id) {
+            session
+                .update(cx, |session, cx| session.stop(cx))
+                .detach_and_log_err(cx);
+            cx.emit(DapStoreEvent::SessionRemoved(id));
+            cx.notify();
+        }
+    }
+}
+
+fn source_for_path(abs_path: PathBuf) -> Source {
+    Source {
+        name: abs_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()),
+        path: Some(abs_path.to_string_lossy().into_owned()),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,471 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use dap::{
+    events, requests,
+    types::{
+        Capabilities, ContinueArguments, DisconnectArguments, EvaluateArguments, OutputEventBody,
+        PauseArguments, Scope, ScopesArguments, StackFrame, StackTraceArguments, StepArguments,
+        StoppedEventBody, Thread, Variable, VariablesArguments,
+    },
+    DebugAdapterClient, DebugAdapterClientId,
+};
+use futures::Future;
+use gpui::{Context, EventEmitter, SharedString, Task};
+use task::DebugAdapterConfig;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// The debug adapter is being initialized and configured.
+    Starting,
+    Running,
+    Stopped,
+    Exited,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsoleEntryKind {
+    /// Output of the debuggee or the debug adapter.
+    Output,
+    Error,
+    /// An expression entered in the console.
+    Input,
+    /// The result of evaluating an expression entered in the console.
+    Result,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsoleEntry {
+    pub kind: ConsoleEntryKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugSessionEvent {
+    StatusChanged,
+    ThreadsChanged,
+    StackFramesChanged,
+    ConsoleChanged,
+}
+
+/// A program being debugged through a debug adapter, as seen from the last events the adapter sent.
+pub struct DebugSession {
+    client: Arc<DebugAdapterClient>,
+    config: DebugAdapterConfig,
+    capabilities: Capabilities,
+    status: SessionStatus,
+    exit_code: Option<i64>,
+    threads: Vec<Thread>,
+    active_thread: Option<u64>,
+    stack_frames: Vec<StackFrame>,
+    selected_frame: Option<u64>,
+    console: Vec<ConsoleEntry>,
+    _subscriptions: Vec<dap::Subscription>,
+}
+
+impl EventEmitter<DebugSessionEvent> for DebugSession {}
+
+impl DebugSession {
+    pub(crate) fn new(
+        client: Arc<DebugAdapterClient>,
+        config: DebugAdapterConfig,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let this = cx.weak_entity();
+        let subscriptions = vec![
+            client.on_event::<events::Stopped, _>({
+                let this = this.clone();
+                move |body, mut cx| {
+                    this.update(&mut cx, |this, cx| this.handle_stopped(body, cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Continued, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |this, cx| this.handle_continued(cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Thread, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |this, cx| this.refresh_threads(cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Output, _>({
+                let this = this.clone();
+                move |body, mut cx| {
+                    this.update(&mut cx, |this, cx| this.handle_output(body, cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Exited, _>({
+                let this = this.clone();
+                move |body, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        this.exit_code = Some(body.exit_code);
+                        this.push_console_entry(
+                            ConsoleEntryKind::Output,
+                            format!("Process exited with code {}", body.exit_code),
+                            cx,
+                        )
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Terminated, _>(move |_, mut cx| {
+                this.update(&mut cx, |this, cx| this.shutdown(cx)).ok();
+            }),
+        ];
+
+        Self {
+            client,
+            config,
+            capabilities: Capabilities::default(),
+            status: SessionStatus::Starting,
+            exit_code: None,
+            threads: Vec::new(),
+            active_thread: None,
+            stack_frames: Vec::new(),
+            selected_frame: None,
+            console: Vec::new(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn id(&self) -> DebugAdapterClientId {
+        self.client.id()
+    }
+
+    pub fn label(&self) -> SharedString {
+        self.client.name()
+    }
+
+    pub fn client(&self) -> &Arc<DebugAdapterClient> {
+        &self.client
+    }
+
+    pub fn config(&self) -> &DebugAdapterConfig {
+        &self.config
+    }
+
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    pub fn status(&self) -> SessionStatus {
+        self.status
+    }
+
+    /// The exit code of the debuggee, if the adapter reported that it exited.
+    pub fn exit_code(&self) -> Option<i64> {
+        self.exit_code
+    }
+
+    pub fn threads(&self) -> &[Thread] {
+        &self.threads
+    }
+
+    pub fn active_thread(&self) -> Option<u64> {
+        self.active_thread
+    }
+
+    /// The call stack of the active thread, innermost frame first. Empty unless the thread is stopped.
+    pub fn stack_frames(&self) -> &[StackFrame] {
+        &self.stack_frames
+    }
+
+    pub fn selected_frame(&self) -> Option<&StackFrame> {
+        let selected_frame = self.selected_frame?;
+        self.stack_frames
+            .iter()
+            .find(|frame| frame.id == selected_frame)
+    }
+
+    pub fn console(&self) -> &[ConsoleEntry] {
+        &self.console
+    }
+
+    pub(crate) fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    pub(crate) fn finish_starting(&mut self, cx: &mut Context<Self>) {
+        if self.status == SessionStatus::Starting {
+            self.status = SessionStatus::Running;
+            cx.emit(DebugSessionEvent::StatusChanged);
+            cx.notify();
+        }
+    }
+
+    pub(crate) fn fail_to_start(&mut self, error: &anyhow::Error, cx: &mut Context<Self>) {
+        self.push_console_entry(
+            ConsoleEntryKind::Error,
+            format!("Failed to start debugging: {error:#}"),
+            cx,
+        );
+        self.shutdown(cx);
+    }
+
+    pub fn select_thread(&mut self, thread_id: u64, cx: &mut Context<Self>) {
+        if self.active_thread != Some(thread_id) {
+            self.active_thread = Some(thread_id);
+            self.stack_frames.clear();
+            self.selected_frame = None;
+            cx.emit(DebugSessionEvent::StackFramesChanged);
+            if self.status == SessionStatus::Stopped {
+                self.refresh_stack_frames(cx);
+            }
+            cx.notify();
+        }
+    }
+
+    pub fn select_frame(&mut self, frame_id: u64, cx: &mut Context<Self>) {
+        if self.selected_frame != Some(frame_id) {
+            self.selected_frame = Some(frame_id);
+            cx.emit(DebugSessionEvent::StackFramesChanged);
+            cx.notify();
+        }
+    }
+
+    pub fn continue_thread(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let thread_id = match self.stopped_thread() {
+            Ok(thread_id) => thread_id,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let request = self
+            .client
+            .request::<requests::Continue>(ContinueArguments {
+                thread_id,
+                single_thread: None,
+            });
+        self.resume(async move { request.await.map(|_| ()) }, cx)
+    }
+
+    pub fn step_over(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.step::<requests::Next>(cx)
+    }
+
+    pub fn step_in(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.step::<requests::StepIn>(cx)
+    }
+
+    pub fn step_out(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        self.step::<requests::StepOut>(cx)
+    }
+
+    fn step<R>(&mut self, cx: &mut Context<Self>) -> Task<Result<()>>
+    where
+        R: dap::Request<Arguments = StepArguments, Response = ()>,
+    {
+        let thread_id = match self.stopped_thread() {
+            Ok(thread_id) => thread_id,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let request = self.client.request::<R>(StepArguments { thread_id });
+        self.resume(request, cx)
+    }
+
+    fn resume(
+        &mut self,
+        request: impl 'static + Future<Output = Result<()>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|this, mut cx| async move {
+            request.await?;
+            // Not every adapter sends a `continued` event for requests made by the client.
+            this.update(&mut cx, |this, cx| this.handle_continued(cx))
+        })
+    }
+
+    pub fn pause(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if self.status != SessionStatus::Running {
+            return Task::ready(Err(anyhow!("the program is not running")));
+        }
+        let Some(thread_id) = self
+            .active_thread
+            .or_else(|| self.threads.first().map(|thread| thread.id))
+        else {
+            return Task::ready(Err(anyhow!("the program has no threads to pause")));
+        };
+        let request = self
+            .client
+            .request::<requests::Pause>(PauseArguments { thread_id });
+        cx.spawn(|_, _| request)
+    }
+
+    /// Ends the debug session, terminating the debuggee if it was launched by the debugger.
+    pub fn stop(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if self.status == SessionStatus::Exited {
+            return Task::ready(Ok(()));
+        }
+        let request = self
+            .client
+            .request::<requests::Disconnect>(DisconnectArguments {
+                restart: None,
+                terminate_debuggee: Some(true),
+            });
+        cx.spawn(|this, mut cx| async move {
+            let result = request.await;
+            this.update(&mut cx, |this, cx| this.shutdown(cx))?;
+            result
+        })
+    }
+
+    pub fn scopes(&self, frame_id: u64, cx: &mut Context<Self>) -> Task<Result<Vec<Scope>>> {
+        let request = self
+            .client
+            .request::<requests::Scopes>(ScopesArguments { frame_id });
+        cx.spawn(|_, _| async move { Ok(request.await?.scopes) })
+    }
+
+    pub fn variables(
+        &self,
+        variables_reference: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Variable>>> {
+        let request = self
+            .client
+            .request::<requests::Variables>(VariablesArguments {
+                variables_reference,
+            });
+        cx.spawn(|_, _| async move { Ok(request.await?.variables) })
+    }
+
+    /// Evaluates an expression in the context of the selected stack frame, writing the expression
+    /// and its result to the console.
+    pub fn evaluate(&mut self, expression: String, cx: &mut Context<Self>) -> Task<()> {
+        self.push_console_entry(ConsoleEntryKind::Input, expression.clone(), cx);
+        let request = self
+            .client
+            .request::<requests::Evaluate>(EvaluateArguments {
+                expression,
+                frame_id: self.selected_frame,
+                context: Some("repl".into()),
+            });
+        cx.spawn(|this, mut cx| async move {
+            let (kind, text) = match request.await {
+                Ok(response) => (ConsoleEntryKind::Result, response.result),
+                Err(error) => (ConsoleEntryKind::Error, format!("{error:#}")),
+            };
+            this.update(&mut cx, |this, cx| this.push_console_entry(kind, text, cx))
+                .ok();
+        })
+    }
+
+    fn stopped_thread(&self) -> Result<u64> {
+        if self.status != SessionStatus::Stopped {
+            return Err(anyhow!("the program is not stopped"));
+        }
+        self.active_thread
+            .ok_or_else(|| anyhow!("no thread is stopped"))
+    }
+
+    fn handle_stopped(&mut self, body: StoppedEventBody, cx: &mut Context<Self>) {
+        self.status = SessionStatus::Stopped;
+        if let Some(thread_id) = body.thread_id {
+            self.active_thread = Some(thread_id);
+        }
+        if body.reason == "exception" {
+            if let Some(text) = body.text.or(body.description) {
+                self.push_console_entry(ConsoleEntryKind::Error, text, cx);
+            }
+        }
+        cx.emit(DebugSessionEvent::StatusChanged);
+        cx.notify();
+        self.refresh_threads(cx);
+    }
+
+    fn handle_continued(&mut self, cx: &mut Context<Self>) {
+        if self.status == SessionStatus::Exited {
+            return;
+        }
+        self.status = SessionStatus::Running;
+        self.stack_frames.clear();
+        self.selected_frame = None;
+        cx.emit(DebugSessionEvent::StatusChanged);
+        cx.emit(DebugSessionEvent::StackFramesChanged);
+        cx.notify();
+    }
+
+    fn handle_output(&mut self, body: OutputEventBody, cx: &mut Context<Self>) {
+        let kind = match body.category.as_deref() {
+            Some("telemetry") => return,
+            Some("stderr") => ConsoleEntryKind::Error,
+            _ => ConsoleEntryKind::Output,
+        };
+        let text = body.output.strip_suffix('\n').unwrap_or(&body.output);
+        self.push_console_entry(kind, text.to_string(), cx);
+    }
+
+    fn push_console_entry(&mut self, kind: ConsoleEntryKind, text: String, cx: &mut Context<Self>) {
+        self.console.push(ConsoleEntry { kind, text });
+        cx.emit(DebugSessionEvent::ConsoleChanged);
+        cx.notify();
+    }
+
+    fn refresh_threads(&mut self, cx: &mut Context<Self>) {
+        let request = self.client.request::<requests::Threads>(());
+        cx.spawn(|this, mut cx| async move {
+            let threads = request.await?.threads;
+            this.update(&mut cx, |this, cx| {
+                if this
+                    .active_thread
+                    .map_or(true, |id| threads.iter().all(|thread| thread.id != id))
+                {
+                    this.active_thread = threads.first().map(|thread| thread.id);
+                }
+                this.threads = threads;
+                cx.emit(DebugSessionEvent::ThreadsChanged);
+                cx.notify();
+                if this.status == SessionStatus::Stopped {
+                    this.refresh_stack_frames(cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn refresh_stack_frames(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.active_thread else {
+            return;
+        };
+        let request = self
+            .client
+            .request::<requests::StackTrace>(StackTraceArguments {
+                thread_id,
+                start_frame: None,
+                levels: None,
+            });
+        cx.spawn(|this, mut cx| async move {
+            let stack_frames = request.await?.stack_frames;
+            this.update(&mut cx, |this, cx| {
+                if this.active_thread != Some(thread_id) || this.status != SessionStatus::Stopped {
+                    return;
+                }
+                this.selected_frame = stack_frames.first().map(|frame| frame.id);
+                this.stack_frames = stack_frames;
+                cx.emit(DebugSessionEvent::StackFramesChanged);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn shutdown(&mut self, cx: &mut Context<Self>) {
+        if self.status == SessionStatus::Exited {
+            return;
+        }
+        self.client.shutdown();
+        self.status = SessionStatus::Exited;
+        self.threads.clear();
+        self.stack_frames.clear();
+        self.selected_frame = None;
+        cx.emit(DebugSessionEvent::StatusChanged);
+        cx.emit(DebugSessionEvent::ThreadsChanged);
+        cx.emit(DebugSessionEvent::StackFramesChanged);
+        cx.notify();
+    }
+}
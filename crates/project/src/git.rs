@@ -2,10 +2,15 @@ use crate::buffer_store::BufferStore;
 use crate::worktree_store::{WorktreeStore, WorktreeStoreEvent};
 use crate::{Project, ProjectPath};
 use anyhow::{Context as _, Result};
+use askpass::{AskPassDelegate, AskPassSession};
 use client::ProjectId;
+use collections::HashMap;
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt as _;
-use git::repository::{Branch, CommitDetails, ResetMode};
+use git::repository::{
+    Branch, CommitDetails, CommitFileChange, LogEntry, LogOptions, PushOptions, RebaseAction,
+    RebaseStatus, RebaseTodoEntry, Remote, RemoteCommandOutput, ResetMode, StashEntry,
+};
 use git::{
     repository::{GitRepository, RepoPath},
     status::{GitSummary, TrackedSummary},
@@ -15,7 +20,8 @@ use gpui::{
     WeakEntity,
 };
 use language::{Buffer, LanguageRegistry};
-use rpc::proto::{git_reset, ToProto};
+use parking_lot::Mutex;
+use rpc::proto::{git_reset, rebase_todo_entry, ToProto};
 use rpc::{proto, AnyProtoClient, TypedEnvelope};
 use settings::WorktreeId;
 use std::path::{Path, PathBuf};
@@ -28,6 +34,7 @@ pub struct GitStore {
     buffer_store: Entity<BufferStore>,
     pub(super) project_id: Option<ProjectId>,
     pub(super) client: Option<AnyProtoClient>,
+    downstream_client: Option<(AnyProtoClient, ProjectId)>,
     repositories: Vec<Entity<Repository>>,
     active_index: Option<usize>,
     update_sender: mpsc::UnboundedSender<(Message, oneshot::Sender<Result<()>>)>,
@@ -42,6 +49,16 @@ pub struct Repository {
     pub git_repo: GitRepo,
     pub merge_message: Option<String>,
     update_sender: mpsc::UnboundedSender<(Message, oneshot::Sender<Result<()>>)>,
+    remote_operations: Arc<Mutex<HashMap<u64, RemoteOperationDelegate>>>,
+    latest_askpass_id: u64,
+}
+
+/// Answers the askpass prompts and receives the progress of a remote operation that the host
+/// runs on our behalf.
+#[derive(Clone)]
+struct RemoteOperationDelegate {
+    askpass: AskPassDelegate,
+    progress: mpsc::UnboundedSender<String>,
 }
 
 #[derive(Clone)]
@@ -74,6 +91,38 @@ pub enum Message {
     Stage(GitRepo, Vec<RepoPath>),
     Unstage(GitRepo, Vec<RepoPath>),
     SetIndexText(GitRepo, RepoPath, Option<String>),
+    StashPush {
+        repo: GitRepo,
+        message: Option<SharedString>,
+        paths: Vec<RepoPath>,
+    },
+    StashApply {
+        repo: GitRepo,
+        sha: SharedString,
+    },
+    StashPop {
+        repo: GitRepo,
+        sha: SharedString,
+    },
+    StashDrop {
+        repo: GitRepo,
+        sha: SharedString,
+    },
+    RebaseInteractive {
+        repo: GitRepo,
+        base: SharedString,
+        todo: Vec<RebaseTodoEntry>,
+    },
+    RebaseContinue {
+        repo: GitRepo,
+    },
+    RebaseAbort {
+        repo: GitRepo,
+    },
+    AmendCommitMessage {
+        repo: GitRepo,
+        message: SharedString,
+    },
 }
 
 pub enum GitEvent {
@@ -98,6 +147,7 @@ impl GitStore {
         GitStore {
             project_id,
             client,
+            downstream_client: None,
             buffer_store,
             repositories: Vec::new(),
             active_index: None,
@@ -115,6 +165,34 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
+        client.add_entity_request_handler(Self::handle_get_remotes);
+        client.add_entity_request_handler(Self::handle_fetch);
+        client.add_entity_request_handler(Self::handle_pull);
+        client.add_entity_request_handler(Self::handle_push);
+        client.add_entity_request_handler(Self::handle_askpass);
+        client.add_entity_request_handler(Self::handle_remote_progress);
+        client.add_entity_request_handler(Self::handle_stash_push);
+        client.add_entity_request_handler(Self::handle_stash_apply);
+        client.add_entity_request_handler(Self::handle_stash_pop);
+        client.add_entity_request_handler(Self::handle_stash_drop);
+        client.add_entity_request_handler(Self::handle_get_stash_entries);
+        client.add_entity_request_handler(Self::handle_get_stash_changes);
+        client.add_entity_request_handler(Self::handle_get_log);
+        client.add_entity_request_handler(Self::handle_get_commit_changes);
+        client.add_entity_request_handler(Self::handle_get_rebase_todo);
+        client.add_entity_request_handler(Self::handle_rebase_interactive);
+        client.add_entity_request_handler(Self::handle_rebase_continue);
+        client.add_entity_request_handler(Self::handle_rebase_abort);
+        client.add_entity_request_handler(Self::handle_get_rebase_status);
+        client.add_entity_request_handler(Self::handle_amend_commit_message);
+    }
+
+    pub fn shared(&mut self, remote_id: u64, downstream_client: AnyProtoClient, _: &mut App) {
+        self.downstream_client = Some((downstream_client, ProjectId(remote_id)));
+    }
+
+    pub fn unshared(&mut self, _: &mut Context<Self>) {
+        self.downstream_client.take();
     }
 
     pub fn active_repository(&self) -> Option<Entity<Repository>> {
@@ -206,6 +284,8 @@ impl GitStore {
                                 update_sender: self.update_sender.clone(),
                                 merge_message,
                                 commit_message_buffer: None,
+                                remote_operations: Default::default(),
+                                latest_askpass_id: 0,
                             })
                         };
                         new_repositories.push(handle);
@@ -413,121 +493,591 @@ impl GitStore {
                     text,
                 }),
             },
+            Message::StashPush {
+                repo,
+                message,
+                paths,
+            } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.stash_paths(message.as_deref(), &paths)?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::StashPush {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                                message: message.map(String::from),
+                                paths: paths
+                                    .into_iter()
+                                    .map(|repo_path| repo_path.as_ref().to_proto())
+                                    .collect(),
+                            })
+                            .await
+                            .context("sending stash request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::StashApply { repo, sha } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.stash_apply(&sha)?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::StashApply {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                                sha: sha.to_string(),
+                            })
+                            .await
+                            .context("sending stash apply request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::StashPop { repo, sha } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.stash_pop(&sha)?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::StashPop {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                                sha: sha.to_string(),
+                            })
+                            .await
+                            .context("sending stash pop request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::StashDrop { repo, sha } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.stash_drop(&sha)?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::StashDrop {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                                sha: sha.to_string(),
+                            })
+                            .await
+                            .context("sending stash drop request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::RebaseInteractive { repo, base, todo } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.rebase_interactive(&base, &todo)?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::RebaseInteractive {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                                base: base.into(),
+                                todo: todo.into_iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await
+                            .context("sending rebase request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::RebaseContinue { repo } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.rebase_continue()?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::RebaseContinue {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                            })
+                            .await
+                            .context("sending rebase continue request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::RebaseAbort { repo } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.rebase_abort()?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::RebaseAbort {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                            })
+                            .await
+                            .context("sending rebase abort request")?;
+                    }
+                }
+                Ok(())
+            }
+            Message::AmendCommitMessage { repo, message } => {
+                match repo {
+                    GitRepo::Local(repo) => repo.amend_commit_message(&message)?,
+                    GitRepo::Remote {
+                        project_id,
+                        client,
+                        worktree_id,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::AmendCommitMessage {
+                                project_id: project_id.0,
+                                worktree_id: worktree_id.to_proto(),
+                                work_directory_id: work_directory_id.to_proto(),
+                                message: message.into(),
+                            })
+                            .await
+                            .context("sending amend request")?;
+                    }
+                }
+                Ok(())
+            }
         }
     }
 
-    async fn handle_stage(
+    async fn handle_get_remotes(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::Stage>,
+        envelope: TypedEnvelope<proto::GetRemotes>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::GetRemotesResponse> {
         let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
         let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
         let repository_handle =
             Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
 
-        let entries = envelope
-            .payload
-            .paths
-            .into_iter()
-            .map(PathBuf::from)
-            .map(RepoPath::new)
-            .collect();
-
-        repository_handle
+        let remotes = repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.stage_entries(entries, cx)
+                repository_handle.get_remotes(envelope.payload.branch_name, cx)
             })?
             .await?;
-        Ok(proto::Ack {})
+
+        Ok(proto::GetRemotesResponse {
+            remotes: remotes
+                .into_iter()
+                .map(|remote| proto::get_remotes_response::Remote {
+                    name: remote.name.to_string(),
+                })
+                .collect(),
+        })
     }
 
-    async fn handle_unstage(
+    async fn handle_fetch(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::Unstage>,
+        envelope: TypedEnvelope<proto::Fetch>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::RemoteMessageResponse> {
         let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
         let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
         let repository_handle =
             Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+        let askpass = Self::askpass_for_request(
+            &this,
+            worktree_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            envelope.original_sender_id,
+            &mut cx,
+        )?;
+        let (progress, progress_task) = Self::progress_for_request(
+            &this,
+            worktree_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            envelope.original_sender_id,
+            &mut cx,
+        )?;
 
-        let entries = envelope
-            .payload
-            .paths
-            .into_iter()
-            .map(PathBuf::from)
-            .map(RepoPath::new)
-            .collect();
-
-        repository_handle
+        let output = repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.unstage_entries(entries, cx)
+                repository_handle.fetch(askpass, progress, cx)
             })?
-            .await?;
+            .await;
+        progress_task.await;
+        let output = output?;
 
-        Ok(proto::Ack {})
+        Ok(proto::RemoteMessageResponse {
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
     }
 
-    async fn handle_set_index_text(
+    async fn handle_pull(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::SetIndexText>,
+        envelope: TypedEnvelope<proto::Pull>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::RemoteMessageResponse> {
         let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
         let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
         let repository_handle =
             Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+        let askpass = Self::askpass_for_request(
+            &this,
+            worktree_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            envelope.original_sender_id,
+            &mut cx,
+        )?;
+        let (progress, progress_task) = Self::progress_for_request(
+            &this,
+            worktree_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            envelope.original_sender_id,
+            &mut cx,
+        )?;
 
-        repository_handle
-            .update(&mut cx, |repository_handle, _| {
-                repository_handle.set_index_text(
-                    &RepoPath::from_str(&envelope.payload.path),
-                    envelope.payload.text,
-                )
+        let branch_name = envelope.payload.branch_name.into();
+        let remote_name = envelope.payload.remote_name.into();
+
+        let output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.pull(branch_name, remote_name, askpass, progress, cx)
             })?
-            .await??;
-        Ok(proto::Ack {})
+            .await;
+        progress_task.await;
+        let output = output?;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
     }
 
-    async fn handle_commit(
+    async fn handle_push(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::Commit>,
+        envelope: TypedEnvelope<proto::Push>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::RemoteMessageResponse> {
         let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
         let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
         let repository_handle =
             Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+        let askpass = Self::askpass_for_request(
+            &this,
+            worktree_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            envelope.original_sender_id,
+            &mut cx,
+        )?;
+        let (progress, progress_task) = Self::progress_for_request(
+            &this,
+            worktree_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            envelope.original_sender_id,
+            &mut cx,
+        )?;
 
-        let message = SharedString::from(envelope.payload.message);
-        let name = envelope.payload.name.map(SharedString::from);
-        let email = envelope.payload.email.map(SharedString::from);
-
-        repository_handle
-            .update(&mut cx, |repository_handle, _| {
-                repository_handle.commit(message, name.zip(email))
+        let options = envelope
+            .payload
+            .options
+            .as_ref()
+            .map(|_| match envelope.payload.options() {
+                proto::push::PushOptions::SetUpstream => PushOptions::SetUpstream,
+                proto::push::PushOptions::Force => PushOptions::Force,
+            });
+        let branch_name = envelope.payload.branch_name.into();
+        let remote_name = envelope.payload.remote_name.into();
+
+        let output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.push(branch_name, remote_name, options, askpass, progress, cx)
             })?
-            .await??;
-        Ok(proto::Ack {})
+            .await;
+        progress_task.await;
+        let output = output?;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
     }
 
-    async fn handle_show(
+    async fn handle_askpass(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitShow>,
+        envelope: TypedEnvelope<proto::AskPassRequest>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitCommitDetails> {
+    ) -> Result<proto::AskPassResponse> {
         let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
         let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
         let repository_handle =
             Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
 
-        let commit = repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.show(&envelope.payload.commit, cx)
-            })?
-            .await?;
-        Ok(proto::GitCommitDetails {
+        let delegate = repository_handle.read_with(&cx, |repository_handle, _| {
+            repository_handle
+                .remote_operations
+                .lock()
+                .get(&envelope.payload.askpass_id)
+                .map(|operation| operation.askpass.clone())
+        })?;
+        let Some(delegate) = delegate else {
+            anyhow::bail!("no askpass found");
+        };
+
+        let response = delegate.ask_password(envelope.payload.prompt).await?;
+        Ok(proto::AskPassResponse { response })
+    }
+
+    async fn handle_remote_progress(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RemoteProgress>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle.read_with(&cx, |repository_handle, _| {
+            if let Some(operation) = repository_handle
+                .remote_operations
+                .lock()
+                .get(&envelope.payload.askpass_id)
+            {
+                operation
+                    .progress
+                    .unbounded_send(envelope.payload.message)
+                    .ok();
+            }
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    /// Creates a delegate that forwards askpass prompts for a remote request back to the client
+    /// that made it. Without a downstream client, prompts are dismissed immediately.
+    ///
+    /// When the request was forwarded from a collaborator, `peer_id` routes the prompt to them.
+    fn askpass_for_request(
+        this: &Entity<Self>,
+        worktree_id: WorktreeId,
+        work_directory_id: ProjectEntryId,
+        askpass_id: u64,
+        peer_id: Option<proto::PeerId>,
+        cx: &mut AsyncApp,
+    ) -> Result<AskPassDelegate> {
+        let downstream_client = this.read_with(cx, |this, _| this.downstream_client.clone())?;
+        Ok(AskPassDelegate::new(cx, move |prompt, tx, cx| {
+            let Some((client, project_id)) = downstream_client.clone() else {
+                return;
+            };
+            cx.spawn(|_| async move {
+                let response = client
+                    .request(proto::AskPassRequest {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        askpass_id,
+                        prompt,
+                        peer_id,
+                    })
+                    .await
+                    .log_err();
+                if let Some(response) = response {
+                    tx.send(response.response).ok();
+                }
+            })
+            .detach();
+        }))
+    }
+
+    /// Creates a channel whose progress lines are sent back to the client that made a remote
+    /// request, the same way as [`Self::askpass_for_request`] sends its prompts. The returned
+    /// task finishes once the channel is closed and every line has been acknowledged, so that
+    /// awaiting it before responding keeps the progress from arriving after the response.
+    fn progress_for_request(
+        this: &Entity<Self>,
+        worktree_id: WorktreeId,
+        work_directory_id: ProjectEntryId,
+        askpass_id: u64,
+        peer_id: Option<proto::PeerId>,
+        cx: &mut AsyncApp,
+    ) -> Result<(mpsc::UnboundedSender<String>, Task<()>)> {
+        let downstream_client = this.read_with(cx, |this, _| this.downstream_client.clone())?;
+        let (progress_tx, mut progress_rx) = mpsc::unbounded::<String>();
+        let task = cx.background_executor().spawn(async move {
+            while let Some(mut message) = progress_rx.next().await {
+                // Only the latest line is shown, so skip the ones that queued up while the
+                // previous one was being sent.
+                while let Ok(Some(next_message)) = progress_rx.try_next() {
+                    message = next_message;
+                }
+                let Some((client, project_id)) = &downstream_client else {
+                    continue;
+                };
+                client
+                    .request(proto::RemoteProgress {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        askpass_id,
+                        message,
+                        peer_id,
+                    })
+                    .await
+                    .log_err();
+            }
+        });
+        Ok((progress_tx, task))
+    }
+
+    async fn handle_stage(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::Stage>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let entries = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(PathBuf::from)
+            .map(RepoPath::new)
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stage_entries(entries, cx)
+            })?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_unstage(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::Unstage>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let entries = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(PathBuf::from)
+            .map(RepoPath::new)
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.unstage_entries(entries, cx)
+            })?
+            .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_set_index_text(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SetIndexText>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.set_index_text(
+                    &RepoPath::from_str(&envelope.payload.path),
+                    envelope.payload.text,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_commit(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::Commit>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let message = SharedString::from(envelope.payload.message);
+        let name = envelope.payload.name.map(SharedString::from);
+        let email = envelope.payload.email.map(SharedString::from);
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit(message, name.zip(email))
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_show(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitShow>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitDetails> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let commit = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.show(&envelope.payload.commit, cx)
+            })?
+            .await?;
+        Ok(proto::GitCommitDetails {
             sha: commit.sha.into(),
             message: commit.message.into(),
             commit_timestamp: commit.commit_timestamp,
@@ -536,6 +1086,330 @@ impl GitStore {
         })
     }
 
+    async fn handle_stash_push(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::StashPush>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(PathBuf::from)
+            .map(RepoPath::new)
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .stash_paths(envelope.payload.message.map(SharedString::from), paths)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_apply(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::StashApply>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_apply(envelope.payload.sha.into())
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_pop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::StashPop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_pop(envelope.payload.sha.into())
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_drop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::StashDrop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_drop(envelope.payload.sha.into())
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_stash_entries(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetStashEntries>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetStashEntriesResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_entries(cx)
+            })?
+            .await?;
+        Ok(proto::GetStashEntriesResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::get_stash_entries_response::StashEntry {
+                    index: entry.index as u64,
+                    sha: entry.sha.into(),
+                    message: entry.message.into(),
+                    timestamp: entry.timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_get_stash_changes(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetStashChanges>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetStashChangesResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let changes = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_changes(envelope.payload.sha.into(), cx)
+            })?
+            .await?;
+        Ok(proto::GetStashChangesResponse {
+            changes: changes
+                .into_iter()
+                .map(commit_file_change_to_proto)
+                .collect(),
+        })
+    }
+
+    async fn handle_get_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetLogResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let options = LogOptions {
+            skip: envelope.payload.skip as usize,
+            limit: envelope.payload.limit as usize,
+            path: envelope
+                .payload
+                .path
+                .map(|path| RepoPath::new(PathBuf::from(path))),
+            author: envelope.payload.author,
+            message: envelope.payload.message,
+        };
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.log(options, cx)
+            })?
+            .await?;
+        Ok(proto::GetLogResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::get_log_response::LogEntry {
+                    sha: entry.sha.into(),
+                    parent_shas: entry.parent_shas.into_iter().map(Into::into).collect(),
+                    subject: entry.subject.into(),
+                    author_name: entry.author_name.into(),
+                    author_email: entry.author_email.into(),
+                    commit_timestamp: entry.commit_timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_get_commit_changes(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetCommitChanges>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetCommitChangesResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let changes = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.commit_changes(&envelope.payload.commit, cx)
+            })?
+            .await?;
+        Ok(proto::GetCommitChangesResponse {
+            changes: changes
+                .into_iter()
+                .map(commit_file_change_to_proto)
+                .collect(),
+        })
+    }
+
+    async fn handle_get_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetRebaseTodoResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase_todo(&envelope.payload.base, cx)
+            })?
+            .await?;
+        Ok(proto::GetRebaseTodoResponse {
+            entries: entries
+                .into_iter()
+                .map(rebase_todo_entry_to_proto)
+                .collect(),
+        })
+    }
+
+    async fn handle_rebase_interactive(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RebaseInteractive>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let todo = envelope
+            .payload
+            .todo
+            .into_iter()
+            .map(rebase_todo_entry_from_proto)
+            .collect();
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_interactive(envelope.payload.base.into(), todo)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_continue(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RebaseContinue>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_continue()
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_abort(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RebaseAbort>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_abort()
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_rebase_status(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetRebaseStatus>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetRebaseStatusResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        let status = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase_status(cx)
+            })?
+            .await?;
+        Ok(proto::GetRebaseStatusResponse {
+            status: status.map(|status| proto::get_rebase_status_response::RebaseStatus {
+                onto: status.onto.into(),
+                stopped_at: status.stopped_at.map(rebase_todo_entry_to_proto),
+                remaining: status
+                    .remaining
+                    .into_iter()
+                    .map(rebase_todo_entry_to_proto)
+                    .collect(),
+                has_conflicts: status.has_conflicts,
+            }),
+        })
+    }
+
+    async fn handle_amend_commit_message(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::AmendCommitMessage>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle =
+            Self::repository_for_request(&this, worktree_id, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.amend_commit_message(envelope.payload.message.into())
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -730,89 +1604,361 @@ impl Repository {
             return Task::ready(Ok(buffer));
         }
 
-        if let GitRepo::Remote {
-            project_id,
-            client,
-            worktree_id,
-            work_directory_id,
-        } = self.git_repo.clone()
-        {
-            let client = client.clone();
-            cx.spawn(|repository, mut cx| async move {
-                let request = client.request(proto::OpenCommitMessageBuffer {
-                    project_id: project_id.0,
-                    worktree_id: worktree_id.to_proto(),
-                    work_directory_id: work_directory_id.to_proto(),
-                });
-                let response = request.await.context("requesting to open commit buffer")?;
-                let buffer_id = BufferId::new(response.buffer_id)?;
-                let buffer = buffer_store
-                    .update(&mut cx, |buffer_store, cx| {
-                        buffer_store.wait_for_remote_buffer(buffer_id, cx)
-                    })?
+        if let GitRepo::Remote {
+            project_id,
+            client,
+            worktree_id,
+            work_directory_id,
+        } = self.git_repo.clone()
+        {
+            let client = client.clone();
+            cx.spawn(|repository, mut cx| async move {
+                let request = client.request(proto::OpenCommitMessageBuffer {
+                    project_id: project_id.0,
+                    worktree_id: worktree_id.to_proto(),
+                    work_directory_id: work_directory_id.to_proto(),
+                });
+                let response = request.await.context("requesting to open commit buffer")?;
+                let buffer_id = BufferId::new(response.buffer_id)?;
+                let buffer = buffer_store
+                    .update(&mut cx, |buffer_store, cx| {
+                        buffer_store.wait_for_remote_buffer(buffer_id, cx)
+                    })?
+                    .await?;
+                if let Some(language_registry) = languages {
+                    let git_commit_language =
+                        language_registry.language_for_name("Git Commit").await?;
+                    buffer.update(&mut cx, |buffer, cx| {
+                        buffer.set_language(Some(git_commit_language), cx);
+                    })?;
+                }
+                repository.update(&mut cx, |repository, _| {
+                    repository.commit_message_buffer = Some(buffer.clone());
+                })?;
+                Ok(buffer)
+            })
+        } else {
+            self.open_local_commit_buffer(languages, buffer_store, cx)
+        }
+    }
+
+    fn open_local_commit_buffer(
+        &mut self,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        let merge_message = self.merge_message.clone();
+        cx.spawn(|repository, mut cx| async move {
+            let buffer = buffer_store
+                .update(&mut cx, |buffer_store, cx| buffer_store.create_buffer(cx))?
+                .await?;
+
+            if let Some(language_registry) = language_registry {
+                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_language(Some(git_commit_language), cx);
+                })?;
+            }
+
+            if let Some(merge_message) = merge_message {
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.set_text(merge_message.as_str(), cx)
+                })?;
+            }
+
+            repository.update(&mut cx, |repository, _| {
+                repository.commit_message_buffer = Some(buffer.clone());
+            })?;
+            Ok(buffer)
+        })
+    }
+
+    pub fn checkout_files(
+        &self,
+        commit: &str,
+        paths: Vec<RepoPath>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        let commit = commit.to_string().into();
+        self.update_sender
+            .unbounded_send((
+                Message::CheckoutFiles {
+                    repo: self.git_repo.clone(),
+                    commit,
+                    paths,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn reset(&self, commit: &str, reset_mode: ResetMode) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        let commit = commit.to_string().into();
+        self.update_sender
+            .unbounded_send((
+                Message::Reset {
+                    repo: self.git_repo.clone(),
+                    commit,
+                    reset_mode,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn stash_paths(
+        &self,
+        message: Option<SharedString>,
+        paths: Vec<RepoPath>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.update_sender
+            .unbounded_send((
+                Message::StashPush {
+                    repo: self.git_repo.clone(),
+                    message,
+                    paths,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn stash_apply(&self, sha: SharedString) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.update_sender
+            .unbounded_send((
+                Message::StashApply {
+                    repo: self.git_repo.clone(),
+                    sha,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn stash_pop(&self, sha: SharedString) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.update_sender
+            .unbounded_send((
+                Message::StashPop {
+                    repo: self.git_repo.clone(),
+                    sha,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn stash_drop(&self, sha: SharedString) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.update_sender
+            .unbounded_send((
+                Message::StashDrop {
+                    repo: self.git_repo.clone(),
+                    sha,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn stash_entries(&self, cx: &Context<Self>) -> Task<Result<Vec<StashEntry>>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.stash_entries() }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetStashEntries {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                    })
+                    .await?;
+
+                Ok(response
+                    .entries
+                    .into_iter()
+                    .map(|entry| StashEntry {
+                        index: entry.index as usize,
+                        sha: entry.sha.into(),
+                        message: entry.message.into(),
+                        timestamp: entry.timestamp,
+                    })
+                    .collect())
+            }),
+        }
+    }
+
+    pub fn stash_changes(
+        &self,
+        sha: SharedString,
+        cx: &Context<Self>,
+    ) -> Task<Result<Vec<CommitFileChange>>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.stash_changes(&sha) }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetStashChanges {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        sha: sha.to_string(),
+                    })
+                    .await?;
+
+                Ok(response
+                    .changes
+                    .into_iter()
+                    .map(commit_file_change_from_proto)
+                    .collect())
+            }),
+        }
+    }
+
+    /// Loads a page of the repository's history. See [`GitRepository::log`].
+    pub fn log(&self, options: LogOptions, cx: &Context<Self>) -> Task<Result<Vec<LogEntry>>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.log(&options) }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetLog {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        skip: options.skip as u64,
+                        limit: options.limit as u64,
+                        path: options.path.map(|path| path.as_ref().to_proto()),
+                        author: options.author,
+                        message: options.message,
+                    })
                     .await?;
-                if let Some(language_registry) = languages {
-                    let git_commit_language =
-                        language_registry.language_for_name("Git Commit").await?;
-                    buffer.update(&mut cx, |buffer, cx| {
-                        buffer.set_language(Some(git_commit_language), cx);
-                    })?;
-                }
-                repository.update(&mut cx, |repository, _| {
-                    repository.commit_message_buffer = Some(buffer.clone());
-                })?;
-                Ok(buffer)
-            })
-        } else {
-            self.open_local_commit_buffer(languages, buffer_store, cx)
+
+                Ok(response
+                    .entries
+                    .into_iter()
+                    .map(|entry| LogEntry {
+                        sha: entry.sha.into(),
+                        parent_shas: entry.parent_shas.into_iter().map(Into::into).collect(),
+                        subject: entry.subject.into(),
+                        author_name: entry.author_name.into(),
+                        author_email: entry.author_email.into(),
+                        commit_timestamp: entry.commit_timestamp,
+                    })
+                    .collect())
+            }),
         }
     }
 
-    fn open_local_commit_buffer(
-        &mut self,
-        language_registry: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
-        cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
-        let merge_message = self.merge_message.clone();
-        cx.spawn(|repository, mut cx| async move {
-            let buffer = buffer_store
-                .update(&mut cx, |buffer_store, cx| buffer_store.create_buffer(cx))?
-                .await?;
+    pub fn commit_changes(
+        &self,
+        commit: &str,
+        cx: &Context<Self>,
+    ) -> Task<Result<Vec<CommitFileChange>>> {
+        let commit = commit.to_string();
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.commit_changes(&commit) }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetCommitChanges {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        commit,
+                    })
+                    .await?;
 
-            if let Some(language_registry) = language_registry {
-                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
-                buffer.update(&mut cx, |buffer, cx| {
-                    buffer.set_language(Some(git_commit_language), cx);
-                })?;
-            }
+                Ok(response
+                    .changes
+                    .into_iter()
+                    .map(commit_file_change_from_proto)
+                    .collect())
+            }),
+        }
+    }
 
-            if let Some(merge_message) = merge_message {
-                buffer.update(&mut cx, |buffer, cx| {
-                    buffer.set_text(merge_message.as_str(), cx)
-                })?;
-            }
+    /// The todo list for rebasing the current branch onto `base`, with every commit picked.
+    pub fn rebase_todo(
+        &self,
+        base: &str,
+        cx: &Context<Self>,
+    ) -> Task<Result<Vec<RebaseTodoEntry>>> {
+        let base = base.to_string();
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.rebase_todo(&base) }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetRebaseTodo {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        base,
+                    })
+                    .await?;
 
-            repository.update(&mut cx, |repository, _| {
-                repository.commit_message_buffer = Some(buffer.clone());
-            })?;
-            Ok(buffer)
-        })
+                Ok(response
+                    .entries
+                    .into_iter()
+                    .map(rebase_todo_entry_from_proto)
+                    .collect())
+            }),
+        }
     }
 
-    pub fn checkout_files(
+    pub fn rebase_interactive(
         &self,
-        commit: &str,
-        paths: Vec<RepoPath>,
+        base: SharedString,
+        todo: Vec<RebaseTodoEntry>,
     ) -> oneshot::Receiver<Result<()>> {
         let (result_tx, result_rx) = futures::channel::oneshot::channel();
-        let commit = commit.to_string().into();
         self.update_sender
             .unbounded_send((
-                Message::CheckoutFiles {
+                Message::RebaseInteractive {
                     repo: self.git_repo.clone(),
-                    commit,
-                    paths,
+                    base,
+                    todo,
                 },
                 result_tx,
             ))
@@ -820,15 +1966,73 @@ impl Repository {
         result_rx
     }
 
-    pub fn reset(&self, commit: &str, reset_mode: ResetMode) -> oneshot::Receiver<Result<()>> {
+    pub fn rebase_continue(&self) -> oneshot::Receiver<Result<()>> {
         let (result_tx, result_rx) = futures::channel::oneshot::channel();
-        let commit = commit.to_string().into();
         self.update_sender
             .unbounded_send((
-                Message::Reset {
+                Message::RebaseContinue {
                     repo: self.git_repo.clone(),
-                    commit,
-                    reset_mode,
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    pub fn rebase_abort(&self) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.update_sender
+            .unbounded_send((
+                Message::RebaseAbort {
+                    repo: self.git_repo.clone(),
+                },
+                result_tx,
+            ))
+            .ok();
+        result_rx
+    }
+
+    /// The state of an interactive rebase that is in progress, if any.
+    pub fn rebase_status(&self, cx: &Context<Self>) -> Task<Result<Option<RebaseStatus>>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.rebase_status() }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetRebaseStatus {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                    })
+                    .await?;
+
+                Ok(response.status.map(|status| RebaseStatus {
+                    onto: status.onto.into(),
+                    stopped_at: status.stopped_at.map(rebase_todo_entry_from_proto),
+                    remaining: status
+                        .remaining
+                        .into_iter()
+                        .map(rebase_todo_entry_from_proto)
+                        .collect(),
+                    has_conflicts: status.has_conflicts,
+                }))
+            }),
+        }
+    }
+
+    pub fn amend_commit_message(&self, message: SharedString) -> oneshot::Receiver<Result<()>> {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.update_sender
+            .unbounded_send((
+                Message::AmendCommitMessage {
+                    repo: self.git_repo.clone(),
+                    message,
                 },
                 result_tx,
             ))
@@ -870,6 +2074,207 @@ impl Repository {
         }
     }
 
+    pub fn get_remotes(
+        &self,
+        branch_name: Option<String>,
+        cx: &Context<Self>,
+    ) -> Task<Result<Vec<Remote>>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => cx
+                .background_executor()
+                .spawn(async move { git_repository.get_remotes(branch_name.as_deref()) }),
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => cx.background_executor().spawn(async move {
+                let response = client
+                    .request(proto::GetRemotes {
+                        project_id: project_id.0,
+                        worktree_id: worktree_id.to_proto(),
+                        work_directory_id: work_directory_id.to_proto(),
+                        branch_name,
+                    })
+                    .await?;
+
+                Ok(response
+                    .remotes
+                    .into_iter()
+                    .map(|remote| Remote {
+                        name: remote.name.into(),
+                    })
+                    .collect())
+            }),
+        }
+    }
+
+    /// Fetches from all remotes, sending git's progress output to `progress` line by line.
+    pub fn fetch(
+        &mut self,
+        askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<RemoteCommandOutput>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => {
+                let executor = cx.background_executor().clone();
+                cx.background_executor().spawn(async move {
+                    let askpass = AskPassSession::new(&executor, askpass).await?;
+                    git_repository.fetch(&askpass, &|line| {
+                        progress.unbounded_send(line.to_string()).ok();
+                    })
+                })
+            }
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => {
+                let askpass_id = self.register_remote_operation(askpass, progress);
+                let remote_operations = self.remote_operations.clone();
+                cx.background_executor().spawn(async move {
+                    let response = client
+                        .request(proto::Fetch {
+                            project_id: project_id.0,
+                            worktree_id: worktree_id.to_proto(),
+                            work_directory_id: work_directory_id.to_proto(),
+                            askpass_id,
+                        })
+                        .await;
+                    remote_operations.lock().remove(&askpass_id);
+                    let response = response.context("sending fetch request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                })
+            }
+        }
+    }
+
+    pub fn pull(
+        &mut self,
+        branch_name: SharedString,
+        remote_name: SharedString,
+        askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<RemoteCommandOutput>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => {
+                let executor = cx.background_executor().clone();
+                cx.background_executor().spawn(async move {
+                    let askpass = AskPassSession::new(&executor, askpass).await?;
+                    git_repository.pull(&branch_name, &remote_name, &askpass, &|line| {
+                        progress.unbounded_send(line.to_string()).ok();
+                    })
+                })
+            }
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => {
+                let askpass_id = self.register_remote_operation(askpass, progress);
+                let remote_operations = self.remote_operations.clone();
+                cx.background_executor().spawn(async move {
+                    let response = client
+                        .request(proto::Pull {
+                            project_id: project_id.0,
+                            worktree_id: worktree_id.to_proto(),
+                            work_directory_id: work_directory_id.to_proto(),
+                            branch_name: branch_name.to_string(),
+                            remote_name: remote_name.to_string(),
+                            askpass_id,
+                        })
+                        .await;
+                    remote_operations.lock().remove(&askpass_id);
+                    let response = response.context("sending pull request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                })
+            }
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        branch_name: SharedString,
+        remote_name: SharedString,
+        options: Option<PushOptions>,
+        askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<RemoteCommandOutput>> {
+        match self.git_repo.clone() {
+            GitRepo::Local(git_repository) => {
+                let executor = cx.background_executor().clone();
+                cx.background_executor().spawn(async move {
+                    let askpass = AskPassSession::new(&executor, askpass).await?;
+                    git_repository.push(&branch_name, &remote_name, options, &askpass, &|line| {
+                        progress.unbounded_send(line.to_string()).ok();
+                    })
+                })
+            }
+            GitRepo::Remote {
+                project_id,
+                client,
+                worktree_id,
+                work_directory_id,
+            } => {
+                let askpass_id = self.register_remote_operation(askpass, progress);
+                let remote_operations = self.remote_operations.clone();
+                cx.background_executor().spawn(async move {
+                    let response = client
+                        .request(proto::Push {
+                            project_id: project_id.0,
+                            worktree_id: worktree_id.to_proto(),
+                            work_directory_id: work_directory_id.to_proto(),
+                            branch_name: branch_name.to_string(),
+                            remote_name: remote_name.to_string(),
+                            options: options.map(|options| match options {
+                                PushOptions::SetUpstream => {
+                                    proto::push::PushOptions::SetUpstream.into()
+                                }
+                                PushOptions::Force => proto::push::PushOptions::Force.into(),
+                            }),
+                            askpass_id,
+                        })
+                        .await;
+                    remote_operations.lock().remove(&askpass_id);
+                    let response = response.context("sending push request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                })
+            }
+        }
+    }
+
+    /// Registers the delegates that answer askpass requests and receive the progress the host
+    /// sends back while running a remote operation on our behalf.
+    fn register_remote_operation(
+        &mut self,
+        askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<String>,
+    ) -> u64 {
+        self.latest_askpass_id += 1;
+        let askpass_id = self.latest_askpass_id;
+        self.remote_operations
+            .lock()
+            .insert(askpass_id, RemoteOperationDelegate { askpass, progress });
+        askpass_id
+    }
+
     fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
         Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
     }
@@ -1020,3 +2425,53 @@ impl Repository {
         result_rx
     }
 }
+
+fn commit_file_change_to_proto(change: CommitFileChange) -> proto::CommitFileChange {
+    proto::CommitFileChange {
+        path: change.path.as_ref().to_proto(),
+        old_text: change.old_text,
+        new_text: change.new_text,
+    }
+}
+
+fn commit_file_change_from_proto(change: proto::CommitFileChange) -> CommitFileChange {
+    CommitFileChange {
+        path: RepoPath::new(PathBuf::from(change.path)),
+        old_text: change.old_text,
+        new_text: change.new_text,
+    }
+}
+
+fn rebase_todo_entry_to_proto(entry: RebaseTodoEntry) -> proto::RebaseTodoEntry {
+    let action = match entry.action {
+        RebaseAction::Pick => rebase_todo_entry::RebaseAction::Pick,
+        RebaseAction::Reword => rebase_todo_entry::RebaseAction::Reword,
+        RebaseAction::Edit => rebase_todo_entry::RebaseAction::Edit,
+        RebaseAction::Squash => rebase_todo_entry::RebaseAction::Squash,
+        RebaseAction::Fixup => rebase_todo_entry::RebaseAction::Fixup,
+        RebaseAction::Drop => rebase_todo_entry::RebaseAction::Drop,
+    };
+    proto::RebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.into(),
+        subject: entry.subject.into(),
+        message: entry.message.map(Into::into),
+    }
+}
+
+fn rebase_todo_entry_from_proto(entry: proto::RebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match entry.action() {
+        rebase_todo_entry::RebaseAction::Pick => RebaseAction::Pick,
+        rebase_todo_entry::RebaseAction::Reword => RebaseAction::Reword,
+        rebase_todo_entry::RebaseAction::Edit => RebaseAction::Edit,
+        rebase_todo_entry::RebaseAction::Squash => RebaseAction::Squash,
+        rebase_todo_entry::RebaseAction::Fixup => RebaseAction::Fixup,
+        rebase_todo_entry::RebaseAction::Drop => RebaseAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: entry.sha.into(),
+        subject: entry.subject.into(),
+        message: entry.message.map(Into::into),
+    }
+}
@@ -0,0 +1,429 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use client::proto::{self, PeerId};
+use gpui::{App, AsyncApp, Entity};
+use language::{
+    point_from_lsp,
+    proto::{deserialize_anchor, serialize_anchor},
+    Anchor, Bias, Buffer, CachedLspAdapter,
+};
+use lsp::{LanguageServer, LanguageServerId};
+use text::BufferId;
+
+use crate::{lsp_store::LspStore, Location};
+
+/// A symbol of a call or type hierarchy, such as a function or a class.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The whole symbol, including its body.
+    pub location: Location,
+    /// The part of the symbol to reveal when navigating to it, such as its name.
+    pub selection_range: Range<Anchor>,
+    pub language_server_id: LanguageServerId,
+    lsp_item: LspHierarchyItem,
+}
+
+/// A call from or to a [`HierarchyItem`].
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The caller for incoming calls, and the callee for outgoing ones.
+    pub item: HierarchyItem,
+    /// Where the calls are made, which is always in the caller.
+    pub call_sites: Vec<Location>,
+}
+
+/// The item as reported by the language server, which has to be sent back verbatim to
+/// resolve the calls or types related to it.
+#[derive(Clone, Debug)]
+pub(crate) enum LspHierarchyItem {
+    Call(lsp::CallHierarchyItem),
+    Type(lsp::TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    pub(crate) fn call_hierarchy_item(&self) -> Option<&lsp::CallHierarchyItem> {
+        match &self.lsp_item {
+            LspHierarchyItem::Call(item) => Some(item),
+            LspHierarchyItem::Type(_) => None,
+        }
+    }
+
+    pub(crate) fn type_hierarchy_item(&self) -> Option<&lsp::TypeHierarchyItem> {
+        match &self.lsp_item {
+            LspHierarchyItem::Type(item) => Some(item),
+            LspHierarchyItem::Call(_) => None,
+        }
+    }
+}
+
+impl LspHierarchyItem {
+    fn uri(&self) -> &lsp::Url {
+        match self {
+            Self::Call(item) => &item.uri,
+            Self::Type(item) => &item.uri,
+        }
+    }
+
+    fn range(&self) -> lsp::Range {
+        match self {
+            Self::Call(item) => item.range,
+            Self::Type(item) => item.range,
+        }
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        match self {
+            Self::Call(item) => item.selection_range,
+            Self::Type(item) => item.selection_range,
+        }
+    }
+
+    fn into_hierarchy_item(
+        self,
+        location: Location,
+        selection_range: Range<Anchor>,
+        language_server_id: LanguageServerId,
+    ) -> HierarchyItem {
+        let (name, kind, detail) = match &self {
+            Self::Call(item) => (item.name.clone(), item.kind, item.detail.clone()),
+            Self::Type(item) => (item.name.clone(), item.kind, item.detail.clone()),
+        };
+        HierarchyItem {
+            name,
+            kind,
+            detail,
+            location,
+            selection_range,
+            language_server_id,
+            lsp_item: self,
+        }
+    }
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+/// Opens the buffer containing the item, positioning the item in it.
+async fn hierarchy_item_from_lsp(
+    lsp_item: LspHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    (lsp_adapter, language_server): &(Arc<CachedLspAdapter>, Arc<LanguageServer>),
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(
+                lsp_item.uri().clone(),
+                language_server.server_id(),
+                lsp_adapter.name.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    buffer.update(cx, |target_buffer, _| {
+        let range = anchor_range_from_lsp(target_buffer, lsp_item.range());
+        let selection_range = anchor_range_from_lsp(target_buffer, lsp_item.selection_range());
+        lsp_item.into_hierarchy_item(
+            Location {
+                buffer: buffer.clone(),
+                range,
+            },
+            selection_range,
+            language_server.server_id(),
+        )
+    })
+}
+
+pub(crate) async fn hierarchy_items_from_lsp(
+    lsp_items: impl IntoIterator<Item = LspHierarchyItem>,
+    lsp_store: &Entity<LspStore>,
+    buffer: &Entity<Buffer>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let language_server = super::language_server_for_buffer(lsp_store, buffer, server_id, cx)?;
+    let mut items = Vec::new();
+    for lsp_item in lsp_items {
+        items.push(hierarchy_item_from_lsp(lsp_item, lsp_store, &language_server, cx).await?);
+    }
+    Ok(items)
+}
+
+/// Resolves the calls reported by the language server, together with the items they are made
+/// from or to.
+///
+/// The call sites of incoming calls are in the calling items, the ones of outgoing calls in
+/// `caller_buffer`, the buffer of the item whose calls were requested.
+pub(crate) async fn hierarchy_calls_from_lsp(
+    lsp_calls: impl IntoIterator<Item = (lsp::CallHierarchyItem, Vec<lsp::Range>)>,
+    caller_buffer: Option<&Entity<Buffer>>,
+    lsp_store: &Entity<LspStore>,
+    buffer: &Entity<Buffer>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    let language_server = super::language_server_for_buffer(lsp_store, buffer, server_id, cx)?;
+    let mut calls = Vec::new();
+    for (lsp_item, ranges) in lsp_calls {
+        let item = hierarchy_item_from_lsp(
+            LspHierarchyItem::Call(lsp_item),
+            lsp_store,
+            &language_server,
+            cx,
+        )
+        .await?;
+        let call_sites_buffer = caller_buffer.unwrap_or(&item.location.buffer).clone();
+        let call_sites = call_sites_buffer.update(cx, |call_sites_buffer_ref, _| {
+            ranges
+                .into_iter()
+                .map(|range| Location {
+                    buffer: call_sites_buffer.clone(),
+                    range: anchor_range_from_lsp(call_sites_buffer_ref, range),
+                })
+                .collect()
+        })?;
+        calls.push(HierarchyCall { item, call_sites });
+    }
+    Ok(calls)
+}
+
+pub(crate) fn hierarchy_items_to_proto(
+    items: Vec<HierarchyItem>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItemsResponse {
+    proto::HierarchyItemsResponse {
+        items: items
+            .into_iter()
+            .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+            .collect(),
+    }
+}
+
+pub(crate) async fn hierarchy_items_from_proto(
+    message: proto::HierarchyItemsResponse,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::new();
+    for item in message.items {
+        items.push(hierarchy_item_from_proto(item, lsp_store, cx).await?);
+    }
+    Ok(items)
+}
+
+fn hierarchy_item_to_proto(
+    item: HierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItem {
+    proto::HierarchyItem {
+        language_server_id: item.language_server_id.to_proto(),
+        location: Some(location_to_proto(item.location, lsp_store, peer_id, cx)),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        lsp_item: Some(lsp_item_to_proto(&item.lsp_item)),
+    }
+}
+
+fn lsp_item_to_proto(lsp_item: &LspHierarchyItem) -> proto::hierarchy_item::LspItem {
+    match lsp_item {
+        LspHierarchyItem::Call(item) => {
+            proto::hierarchy_item::LspItem::CallHierarchyItem(serde_json::to_vec(item).unwrap())
+        }
+        LspHierarchyItem::Type(item) => {
+            proto::hierarchy_item::LspItem::TypeHierarchyItem(serde_json::to_vec(item).unwrap())
+        }
+    }
+}
+
+fn lsp_item_from_proto(lsp_item: proto::hierarchy_item::LspItem) -> Result<LspHierarchyItem> {
+    Ok(match lsp_item {
+        proto::hierarchy_item::LspItem::CallHierarchyItem(item) => {
+            LspHierarchyItem::Call(serde_json::from_slice(&item)?)
+        }
+        proto::hierarchy_item::LspItem::TypeHierarchyItem(item) => {
+            LspHierarchyItem::Type(serde_json::from_slice(&item)?)
+        }
+    })
+}
+
+async fn hierarchy_item_from_proto(
+    item: proto::HierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let lsp_item = lsp_item_from_proto(item.lsp_item.ok_or_else(|| anyhow!("missing lsp item"))?)?;
+    let location = location_from_proto(
+        item.location.ok_or_else(|| anyhow!("missing location"))?,
+        lsp_store,
+        cx,
+    )
+    .await?;
+    let selection_start = item
+        .selection_start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing selection start"))?;
+    let selection_end = item
+        .selection_end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing selection end"))?;
+    location
+        .buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_anchors([selection_start, selection_end])
+        })?
+        .await?;
+    Ok(lsp_item.into_hierarchy_item(
+        location,
+        selection_start..selection_end,
+        LanguageServerId::from_proto(item.language_server_id),
+    ))
+}
+
+pub(crate) fn hierarchy_call_to_proto(
+    call: HierarchyCall,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyCall {
+    proto::HierarchyCall {
+        item: Some(hierarchy_item_to_proto(call.item, lsp_store, peer_id, cx)),
+        call_sites: call
+            .call_sites
+            .into_iter()
+            .map(|location| location_to_proto(location, lsp_store, peer_id, cx))
+            .collect(),
+    }
+}
+
+pub(crate) async fn hierarchy_call_from_proto(
+    call: proto::HierarchyCall,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyCall> {
+    let item = hierarchy_item_from_proto(
+        call.item.ok_or_else(|| anyhow!("missing hierarchy item"))?,
+        lsp_store,
+        cx,
+    )
+    .await?;
+    let mut call_sites = Vec::new();
+    for location in call.call_sites {
+        call_sites.push(location_from_proto(location, lsp_store, cx).await?);
+    }
+    Ok(HierarchyCall { item, call_sites })
+}
+
+fn location_to_proto(
+    location: Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::Location {
+        buffer_id: location.buffer.read(cx).remote_id().into(),
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+    }
+}
+
+async fn location_from_proto(
+    location: proto::Location,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location start"))?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location end"))?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_lsp_item_proto_round_trip() {
+        let range = lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(3, 1));
+        let selection_range = lsp::Range::new(lsp::Position::new(1, 3), lsp::Position::new(1, 9));
+        let uri = lsp::Url::parse("file:///root/main.rs").unwrap();
+        let call_item = lsp::CallHierarchyItem {
+            name: "helper".to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: Some(vec![lsp::SymbolTag::DEPRECATED]),
+            detail: Some("fn helper()".to_string()),
+            uri: uri.clone(),
+            range,
+            selection_range,
+            data: Some(json!({ "id": 1, "nested": ["opaque"] })),
+        };
+        let type_item = lsp::TypeHierarchyItem {
+            name: "Shape".to_string(),
+            kind: lsp::SymbolKind::INTERFACE,
+            tags: None,
+            detail: None,
+            uri,
+            range,
+            selection_range,
+            data: None,
+        };
+
+        let proto_item = lsp_item_to_proto(&LspHierarchyItem::Call(call_item.clone()));
+        assert!(matches!(
+            proto_item,
+            proto::hierarchy_item::LspItem::CallHierarchyItem(_)
+        ));
+        match lsp_item_from_proto(proto_item).unwrap() {
+            LspHierarchyItem::Call(item) => assert_eq!(item, call_item),
+            LspHierarchyItem::Type(_) => panic!("expected a call hierarchy item"),
+        }
+
+        let proto_item = lsp_item_to_proto(&LspHierarchyItem::Type(type_item.clone()));
+        match lsp_item_from_proto(proto_item).unwrap() {
+            LspHierarchyItem::Type(item) => assert_eq!(item, type_item),
+            LspHierarchyItem::Call(_) => panic!("expected a type hierarchy item"),
+        }
+
+        assert!(
+            lsp_item_from_proto(proto::hierarchy_item::LspItem::CallHierarchyItem(
+                b"not json".to_vec()
+            ))
+            .is_err()
+        );
+    }
+}
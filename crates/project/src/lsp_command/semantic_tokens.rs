@@ -0,0 +1,259 @@
+use std::{ops::Range, sync::Arc};
+
+use clock::Global;
+use language::{Anchor, Bias, BufferSnapshot, PointUtf16, Unclipped};
+use lsp::{SemanticTokensFullOptions, SemanticTokensServerCapabilities, ServerCapabilities};
+use rpc::proto;
+
+/// Semantic tokens reported by a language server for a particular version of a buffer.
+#[derive(Clone, Debug, Default)]
+pub struct SemanticTokens {
+    pub version: Global,
+    /// Names of the token types, indexed by [`SemanticToken::token_type`].
+    pub token_types: Arc<[String]>,
+    /// Names of the token modifiers, indexed by the bits of [`SemanticToken::modifiers`].
+    pub token_modifiers: Arc<[String]>,
+    /// Tokens ordered by their position in the buffer.
+    pub tokens: Arc<[SemanticToken]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    pub token_type: u32,
+    pub modifiers: u32,
+}
+
+impl SemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.token_types
+            .get(token.token_type as usize)
+            .map(String::as_str)
+    }
+
+    pub fn modifiers<'a>(&'a self, token: &SemanticToken) -> impl Iterator<Item = &'a str> + 'a {
+        let modifiers = token.modifiers;
+        self.token_modifiers
+            .iter()
+            .take(u32::BITS as usize)
+            .enumerate()
+            .filter(move |(ix, _)| modifiers & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+}
+
+/// Maps the indices used in the encoded tokens to the names of token types and modifiers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SemanticTokensLegend {
+    pub token_types: Arc<[String]>,
+    pub token_modifiers: Arc<[String]>,
+}
+
+/// Replaces `delete_count` numbers at `start` of the previously reported encoded tokens with `data`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SemanticTokensEdit {
+    pub start: u32,
+    pub delete_count: u32,
+    pub data: Vec<u32>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) enum SemanticTokensResponse {
+    /// The language server does not provide semantic tokens for the buffer.
+    #[default]
+    Unsupported,
+    Full {
+        legend: SemanticTokensLegend,
+        result_id: Option<String>,
+        data: Vec<u32>,
+    },
+    Delta {
+        legend: SemanticTokensLegend,
+        result_id: Option<String>,
+        edits: Vec<SemanticTokensEdit>,
+    },
+}
+
+pub(crate) fn semantic_tokens_options(
+    capabilities: &ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
+pub(crate) fn supports_full_semantic_tokens(capabilities: &ServerCapabilities) -> bool {
+    semantic_tokens_options(capabilities).map_or(false, |options| match options.full {
+        Some(SemanticTokensFullOptions::Bool(full)) => full,
+        Some(SemanticTokensFullOptions::Delta { .. }) => true,
+        None => false,
+    })
+}
+
+pub(crate) fn supports_semantic_tokens_delta(capabilities: &ServerCapabilities) -> bool {
+    semantic_tokens_options(capabilities).map_or(false, |options| {
+        matches!(
+            options.full,
+            Some(SemanticTokensFullOptions::Delta { delta: Some(true) })
+        )
+    })
+}
+
+impl SemanticTokensLegend {
+    pub fn from_lsp(legend: &lsp::SemanticTokensLegend) -> Self {
+        Self {
+            token_types: legend
+                .token_types
+                .iter()
+                .map(|token_type| token_type.as_str().to_string())
+                .collect(),
+            token_modifiers: legend
+                .token_modifiers
+                .iter()
+                .map(|modifier| modifier.as_str().to_string())
+                .collect(),
+        }
+    }
+
+    pub fn from_proto(legend: proto::SemanticTokensLegend) -> Self {
+        Self {
+            token_types: legend.token_types.into(),
+            token_modifiers: legend.token_modifiers.into(),
+        }
+    }
+
+    pub fn to_proto(&self) -> proto::SemanticTokensLegend {
+        proto::SemanticTokensLegend {
+            token_types: self.token_types.to_vec(),
+            token_modifiers: self.token_modifiers.to_vec(),
+        }
+    }
+}
+
+impl SemanticTokensEdit {
+    pub fn from_lsp(edit: lsp::SemanticTokensEdit) -> Self {
+        Self {
+            start: edit.start,
+            delete_count: edit.delete_count,
+            data: encode_lsp_tokens(edit.data.unwrap_or_default()),
+        }
+    }
+
+    pub fn from_proto(edit: proto::SemanticTokensEdit) -> Self {
+        Self {
+            start: edit.start,
+            delete_count: edit.delete_count,
+            data: edit.data,
+        }
+    }
+
+    pub fn to_proto(&self) -> proto::SemanticTokensEdit {
+        proto::SemanticTokensEdit {
+            start: self.start,
+            delete_count: self.delete_count,
+            data: self.data.clone(),
+        }
+    }
+}
+
+/// Flattens tokens into the integer encoding used by the protocol, which delta edits refer to.
+pub(crate) fn encode_lsp_tokens(tokens: Vec<lsp::SemanticToken>) -> Vec<u32> {
+    tokens
+        .into_iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+/// Applies delta edits to the encoded tokens they were computed against.
+pub(crate) fn apply_semantic_tokens_edits(data: &mut Vec<u32>, mut edits: Vec<SemanticTokensEdit>) {
+    // Every edit refers to the original data, so splice from the end to keep the offsets valid.
+    edits.sort_unstable_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = (edit.start as usize).min(data.len());
+        let end = (start + edit.delete_count as usize).min(data.len());
+        data.splice(start..end, edit.data);
+    }
+}
+
+/// Decodes tokens in the relative format, positioning them in the buffer snapshot they were computed for.
+pub(crate) fn decode_semantic_tokens(
+    data: &[u32],
+    legend: &SemanticTokensLegend,
+    snapshot: &BufferSnapshot,
+) -> SemanticTokens {
+    let mut row = 0;
+    let mut column = 0;
+    let tokens = data
+        .chunks_exact(5)
+        .filter_map(|token| {
+            let &[delta_line, delta_start, length, token_type, modifiers] = token else {
+                return None;
+            };
+            if delta_line > 0 {
+                row += delta_line;
+                column = delta_start;
+            } else {
+                column += delta_start;
+            }
+            if length == 0 {
+                return None;
+            }
+
+            let start =
+                snapshot.clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left);
+            let end = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(row, column.saturating_add(length))),
+                Bias::Left,
+            );
+            Some(SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                token_type,
+                modifiers,
+            })
+        })
+        .collect();
+
+    SemanticTokens {
+        version: snapshot.version().clone(),
+        token_types: legend.token_types.clone(),
+        token_modifiers: legend.token_modifiers.clone(),
+        tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_semantic_tokens_edits() {
+        let mut data = vec![0, 0, 3, 1, 0, 1, 4, 2, 0, 0, 0, 5, 6, 2, 1];
+        apply_semantic_tokens_edits(
+            &mut data,
+            vec![
+                SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 5,
+                    data: Vec::new(),
+                },
+                SemanticTokensEdit {
+                    start: 0,
+                    delete_count: 5,
+                    data: vec![0, 0, 4, 1, 1, 0, 5, 1, 3, 0],
+                },
+            ],
+        );
+        assert_eq!(data, vec![0, 0, 4, 1, 1, 0, 5, 1, 3, 0, 1, 4, 2, 0, 0]);
+    }
+}
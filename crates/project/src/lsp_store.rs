@@ -1,3 +1,5 @@
+mod pull_diagnostics;
+
 use crate::{
     buffer_store::{BufferStore, BufferStoreEvent},
     deserialize_code_actions,
@@ -11,8 +13,9 @@ use crate::{
     toolchain_store::{EmptyToolchainStore, ToolchainStoreEvent},
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     yarn::YarnPathStore,
-    CodeAction, Completion, CoreCompletion, Hover, InlayHint, ProjectItem as _, ProjectPath,
-    ProjectTransaction, ResolveState, Symbol, ToolchainStore,
+    CodeAction, CodeLens, Completion, CoreCompletion, DocumentColor, Hover, InlayHint,
+    ProjectItem as _, ProjectPath, ProjectTransaction, ResolveState, Symbol, ToolchainStore,
+    WorkspaceEditPreview, WorkspaceFileOperation,
 };
 use anyhow::{anyhow, Context as _, Result};
 use async_trait::async_trait;
@@ -47,13 +50,15 @@ use lsp::{
     DiagnosticTag, DidChangeWatchedFilesRegistrationOptions, Edit, FileOperationFilter,
     FileOperationPatternKind, FileOperationRegistrationOptions, FileRename, FileSystemWatcher,
     InsertTextFormat, LanguageServer, LanguageServerBinary, LanguageServerBinaryOptions,
-    LanguageServerId, LanguageServerName, LspRequestFuture, MessageActionItem, MessageType, OneOf,
-    RenameFilesParams, ServerHealthStatus, ServerStatus, SymbolKind, TextEdit, WillRenameFiles,
-    WorkDoneProgressCancelParams, WorkspaceFolder,
+    LanguageServerId, LanguageServerName, LanguageServerTransport, LspRequestFuture,
+    MessageActionItem, MessageType, NumberOrString, OneOf, RenameFilesParams, ServerHealthStatus,
+    ServerStatus, SymbolKind, TextEdit, WillRenameFiles, WorkDoneProgressCancelParams,
+    WorkspaceFolder,
 };
 use node_runtime::read_package_installed_version;
 use parking_lot::Mutex;
 use postage::watch;
+use pull_diagnostics::PullDiagnosticsState;
 use rand::prelude::*;
 
 use rpc::{
@@ -155,6 +160,10 @@ pub struct LocalLspStore {
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
     prettier_store: Entity<PrettierStore>,
     next_diagnostic_group_id: usize,
+    /// For each task with problem matchers, the id its diagnostics are published under and
+    /// the paths it last reported problems in.
+    task_diagnostics: HashMap<String, (LanguageServerId, HashSet<PathBuf>)>,
+    pull_diagnostics: HashMap<LanguageServerId, PullDiagnosticsState>,
     diagnostics: HashMap<
         WorktreeId,
         HashMap<
@@ -244,6 +253,7 @@ impl LocalLspStore {
                     adapter.code_action_kinds(),
                     cx,
                 )
+                .await
             }
         });
 
@@ -403,11 +413,17 @@ impl LocalLspStore {
         .get(&adapter.name)
         .and_then(|s| s.binary.clone());
 
-        if settings.as_ref().is_some_and(|b| b.path.is_some()) {
+        let connect_only = settings.as_ref().is_some_and(|b| {
+            b.path.is_none()
+                && b.transport
+                    .as_ref()
+                    .is_some_and(|transport| *transport != LanguageServerTransport::Stdio)
+        });
+        if settings.as_ref().is_some_and(|b| b.path.is_some()) || connect_only {
             let settings = settings.unwrap();
             return cx.spawn(|_| async move {
                 Ok(LanguageServerBinary {
-                    path: PathBuf::from(&settings.path.unwrap()),
+                    path: settings.path.map(PathBuf::from).unwrap_or_default(),
                     env: Some(delegate.shell_env().await),
                     arguments: settings
                         .arguments
@@ -415,6 +431,7 @@ impl LocalLspStore {
                         .iter()
                         .map(Into::into)
                         .collect(),
+                    transport: settings.transport.unwrap_or_default(),
                 })
             });
         }
@@ -440,8 +457,13 @@ impl LocalLspStore {
             delegate.update_status(adapter.name.clone(), LanguageServerBinaryStatus::None);
 
             let mut binary = binary_result?;
-            if let Some(arguments) = settings.and_then(|b| b.arguments) {
-                binary.arguments = arguments.into_iter().map(Into::into).collect();
+            if let Some(settings) = settings {
+                if let Some(arguments) = settings.arguments {
+                    binary.arguments = arguments.into_iter().map(Into::into).collect();
+                }
+                if let Some(transport) = settings.transport {
+                    binary.transport = transport;
+                }
             }
 
             let mut shell_env = delegate.shell_env().await;
@@ -469,6 +491,7 @@ impl LocalLspStore {
                     if let Some(this) = this.upgrade() {
                         adapter.process_diagnostics(&mut params);
                         this.update(&mut cx, |this, cx| {
+                            this.merge_pulled_diagnostics(server_id, &mut params);
                             this.update_diagnostics(
                                 server_id,
                                 params,
@@ -780,6 +803,64 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.semantic_tokens.clear();
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshCodeLens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshCodeLens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.pull_diagnostics_for_server(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -1973,6 +2054,9 @@ impl LocalLspStore {
                 language_server.unregister_buffer(file_url.clone());
             }
         });
+        for state in self.pull_diagnostics.values_mut() {
+            state.forget_document(file_url);
+        }
     }
 
     fn buffer_snapshot_for_lsp_version(
@@ -2243,28 +2327,8 @@ impl LocalLspStore {
     ) -> Result<ProjectTransaction> {
         let fs = this.read_with(cx, |this, _| this.as_local().unwrap().fs.clone())?;
 
-        let mut operations = Vec::new();
-        if let Some(document_changes) = edit.document_changes {
-            match document_changes {
-                lsp::DocumentChanges::Edits(edits) => {
-                    operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
-                }
-                lsp::DocumentChanges::Operations(ops) => operations = ops,
-            }
-        } else if let Some(changes) = edit.changes {
-            operations.extend(changes.into_iter().map(|(uri, edits)| {
-                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
-                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
-                        uri,
-                        version: None,
-                    },
-                    edits: edits.into_iter().map(Edit::Plain).collect(),
-                })
-            }));
-        }
-
         let mut project_transaction = ProjectTransaction::default();
-        for operation in operations {
+        for operation in Self::workspace_edit_operations(edit) {
             match operation {
                 lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
                     let abs_path = op
@@ -2459,6 +2523,174 @@ impl LocalLspStore {
         Ok(project_transaction)
     }
 
+    fn workspace_edit_operations(edit: lsp::WorkspaceEdit) -> Vec<lsp::DocumentChangeOperation> {
+        let mut operations = Vec::new();
+        if let Some(document_changes) = edit.document_changes {
+            match document_changes {
+                lsp::DocumentChanges::Edits(edits) => {
+                    operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
+                }
+                lsp::DocumentChanges::Operations(ops) => operations = ops,
+            }
+        } else if let Some(changes) = edit.changes {
+            operations.extend(changes.into_iter().map(|(uri, edits)| {
+                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                        uri,
+                        version: None,
+                    },
+                    edits: edits.into_iter().map(Edit::Plain).collect(),
+                })
+            }));
+        }
+        operations
+    }
+
+    /// Resolves the changes of a workspace edit without making any of them, so that they can be
+    /// reviewed first.
+    ///
+    /// Edits of files the workspace edit creates become the text of these files, and edits of
+    /// files it renames are made to the buffers of their current paths.
+    pub(crate) async fn preview_workspace_edit(
+        this: Entity<LspStore>,
+        edit: lsp::WorkspaceEdit,
+        lsp_adapter: Arc<CachedLspAdapter>,
+        language_server: Arc<LanguageServer>,
+        cx: &mut AsyncApp,
+    ) -> Result<WorkspaceEditPreview> {
+        let mut preview = WorkspaceEditPreview::default();
+        let mut created_files = HashMap::<PathBuf, usize>::default();
+        let mut original_paths = HashMap::<PathBuf, PathBuf>::default();
+        for operation in Self::workspace_edit_operations(edit) {
+            match operation {
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
+                    let path = op
+                        .uri
+                        .to_file_path()
+                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    created_files.insert(path.clone(), preview.file_operations.len());
+                    preview
+                        .file_operations
+                        .push(WorkspaceFileOperation::Create {
+                            path,
+                            is_dir: op.uri.path().ends_with('/'),
+                            text: String::new(),
+                        });
+                }
+
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(op)) => {
+                    let old_path = op
+                        .old_uri
+                        .to_file_path()
+                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    let new_path = op
+                        .new_uri
+                        .to_file_path()
+                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    let original_path = original_paths
+                        .remove(&old_path)
+                        .unwrap_or_else(|| old_path.clone());
+                    original_paths.insert(new_path.clone(), original_path);
+                    preview
+                        .file_operations
+                        .push(WorkspaceFileOperation::Rename { old_path, new_path });
+                }
+
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(op)) => {
+                    let path = op
+                        .uri
+                        .to_file_path()
+                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    preview
+                        .file_operations
+                        .push(WorkspaceFileOperation::Delete { path });
+                }
+
+                lsp::DocumentChangeOperation::Edit(op) => {
+                    let mut edits = op
+                        .edits
+                        .into_iter()
+                        .map(|edit| match edit {
+                            Edit::Plain(edit) => edit,
+                            Edit::Annotated(edit) => edit.text_edit,
+                            Edit::Snippet(edit) => TextEdit {
+                                range: edit.range,
+                                new_text: Snippet::parse(&edit.snippet.value)
+                                    .map(|snippet| snippet.text)
+                                    .unwrap_or(edit.snippet.value),
+                            },
+                        })
+                        .collect::<Vec<_>>();
+                    edits.dedup();
+
+                    let path = op
+                        .text_document
+                        .uri
+                        .to_file_path()
+                        .map_err(|_| anyhow!("can't convert URI to path"))?;
+                    if let Some(WorkspaceFileOperation::Create { text, .. }) = created_files
+                        .get(&path)
+                        .and_then(|ix| preview.file_operations.get_mut(*ix))
+                    {
+                        // Created files are empty, so their edits can only insert text.
+                        edits.sort_by_key(|edit| edit.range.start);
+                        for edit in edits {
+                            text.push_str(&edit.new_text);
+                        }
+                        continue;
+                    }
+
+                    let (uri, version) = match original_paths.get(&path) {
+                        Some(original_path) => (
+                            lsp::Url::from_file_path(original_path)
+                                .map_err(|_| anyhow!("can't convert path to URI"))?,
+                            None,
+                        ),
+                        None => (op.text_document.uri, op.text_document.version),
+                    };
+                    let buffer = this
+                        .update(cx, |this, cx| {
+                            this.open_local_buffer_via_lsp(
+                                uri,
+                                language_server.server_id(),
+                                lsp_adapter.name.clone(),
+                                cx,
+                            )
+                        })?
+                        .await?;
+
+                    // Edits of a document that was already edited are relative to the text
+                    // left by the earlier edits, so they are rebased onto them.
+                    if let Some((_, buffer_edits)) = preview
+                        .buffer_edits
+                        .iter_mut()
+                        .find(|(edited_buffer, _)| *edited_buffer == buffer)
+                    {
+                        *buffer_edits = buffer.read_with(cx, |buffer, _| {
+                            rebase_document_edits(&buffer.snapshot(), buffer_edits, edits)
+                        })?;
+                        continue;
+                    }
+
+                    let edits = this
+                        .update(cx, |this, cx| {
+                            this.as_local_mut().unwrap().edits_from_lsp(
+                                &buffer,
+                                edits,
+                                language_server.server_id(),
+                                version,
+                                cx,
+                            )
+                        })?
+                        .await?;
+                    preview.buffer_edits.push((buffer, edits));
+                }
+            }
+        }
+
+        Ok(preview)
+    }
+
     async fn on_lsp_workspace_edit(
         this: WeakEntity<LspStore>,
         params: lsp::ApplyWorkspaceEditParams,
@@ -2826,6 +3058,14 @@ pub struct LspStore {
     _maintain_buffer_languages: Task<()>,
     diagnostic_summaries:
         HashMap<WorktreeId, HashMap<Arc<Path>, HashMap<LanguageServerId, DiagnosticSummary>>>,
+    semantic_tokens: HashMap<BufferId, CachedSemanticTokens>,
+}
+
+/// The last semantic tokens received for a buffer, along with what is needed to request a delta.
+struct CachedSemanticTokens {
+    result_id: Option<String>,
+    data: Arc<Vec<u32>>,
+    tokens: SemanticTokens,
 }
 
 pub enum LspStoreEvent {
@@ -2843,6 +3083,8 @@ pub enum LspStoreEvent {
     },
     Notification(String),
     RefreshInlayHints,
+    RefreshSemanticTokens,
+    RefreshCodeLens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -2897,6 +3139,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
+        client.add_entity_request_handler(Self::handle_refresh_code_lens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -2913,6 +3157,23 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSemanticTokens>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSemanticTokensDelta>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<ResolveCodeLens>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetRenameEdits>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetInlineValues>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentColors>);
+        client.add_entity_request_handler(Self::handle_get_color_presentations);
     }
 
     pub fn as_remote(&self) -> Option<&RemoteLspStore> {
@@ -3005,6 +3266,8 @@ impl LspStore {
                 fs,
                 yarn,
                 next_diagnostic_group_id: Default::default(),
+                task_diagnostics: Default::default(),
+                pull_diagnostics: Default::default(),
                 diagnostics: Default::default(),
                 _subscription: cx.on_app_quit(|this, cx| {
                     this.as_local_mut().unwrap().shutdown_language_servers(cx)
@@ -3021,6 +3284,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().gen(),
             diagnostic_summaries: Default::default(),
+            semantic_tokens: Default::default(),
             active_entry: None,
 
             _maintain_workspace_config,
@@ -3078,6 +3342,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().gen(),
             diagnostic_summaries: Default::default(),
+            semantic_tokens: Default::default(),
             active_entry: None,
             toolchain_store,
             _maintain_workspace_config,
@@ -3097,6 +3362,7 @@ impl LspStore {
             }
             BufferStoreEvent::BufferChangedFilePath { buffer, old_file } => {
                 let buffer_id = buffer.read(cx).remote_id();
+                self.semantic_tokens.remove(&buffer_id);
                 if let Some(local) = self.as_local_mut() {
                     if let Some(old_file) = File::from_dyn(old_file.as_ref()) {
                         local.reset_buffer(buffer, old_file, cx);
@@ -3115,7 +3381,9 @@ impl LspStore {
                     }
                 }
             }
-            BufferStoreEvent::BufferDropped(_) => {}
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                self.semantic_tokens.remove(buffer_id);
+            }
         }
     }
 
@@ -3255,7 +3523,8 @@ impl LspStore {
                 *refcount += 1;
             }
 
-            if !ignore_refcounts || *refcount == 1 {
+            let register = !ignore_refcounts || *refcount == 1;
+            if register {
                 local.register_buffer_with_language_servers(buffer, cx);
             }
             if !ignore_refcounts {
@@ -3276,6 +3545,9 @@ impl LspStore {
                 })
                 .detach();
             }
+            if register {
+                self.pull_diagnostics_for_buffer(buffer, cx);
+            }
         } else if let Some((upstream_client, upstream_project_id)) = self.upstream_client() {
             let buffer_id = buffer.read(cx).remote_id().to_proto();
             cx.background_executor()
@@ -4716,6 +4988,111 @@ impl LspStore {
         }
     }
 
+    /// Returns the semantic tokens for the current version of the buffer, requesting only the
+    /// changes since the last response when the language server supports it.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<SemanticTokens>>> {
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = snapshot.remote_id();
+        let cached = self.semantic_tokens.get(&buffer_id);
+        if let Some(cached) = cached.filter(|cached| cached.tokens.version == *snapshot.version()) {
+            return Task::ready(Ok(Some(cached.tokens.clone())));
+        }
+
+        let server = if self.upstream_client().is_some() {
+            LanguageServerToQuery::Primary
+        } else {
+            let server_id = self.as_local().and_then(|local| {
+                buffer.update(cx, |buffer, cx| {
+                    local
+                        .language_servers_for_buffer(buffer, cx)
+                        .find(|(_, server)| {
+                            lsp_command::semantic_tokens_options(&server.capabilities()).is_some()
+                        })
+                        .map(|(_, server)| server.server_id())
+                })
+            });
+            let Some(server_id) = server_id else {
+                return Task::ready(Ok(None));
+            };
+            LanguageServerToQuery::Other(server_id)
+        };
+
+        let previous =
+            cached.and_then(|cached| Some((cached.result_id.clone()?, cached.data.clone())));
+        let delta_request = previous.as_ref().map(|(previous_result_id, _)| {
+            self.request_lsp(
+                buffer.clone(),
+                server,
+                GetSemanticTokensDelta {
+                    previous_result_id: previous_result_id.clone(),
+                },
+                cx,
+            )
+        });
+        let buffer = buffer.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut response = match delta_request {
+                Some(delta_request) => delta_request.await.log_err().unwrap_or_default(),
+                None => SemanticTokensResponse::Unsupported,
+            };
+            if let SemanticTokensResponse::Unsupported = response {
+                response = this
+                    .update(&mut cx, |this, cx| {
+                        this.request_lsp(buffer.clone(), server, GetSemanticTokens, cx)
+                    })?
+                    .await?;
+            }
+
+            let (legend, result_id, data) = match response {
+                SemanticTokensResponse::Unsupported => return Ok(None),
+                SemanticTokensResponse::Full {
+                    legend,
+                    result_id,
+                    data,
+                } => (legend, result_id, data),
+                SemanticTokensResponse::Delta {
+                    legend,
+                    result_id,
+                    edits,
+                } => {
+                    let mut data = previous
+                        .map(|(_, data)| data.as_ref().clone())
+                        .unwrap_or_default();
+                    apply_semantic_tokens_edits(&mut data, edits);
+                    (legend, result_id, data)
+                }
+            };
+
+            let (data, tokens) = cx
+                .background_executor()
+                .spawn(async move {
+                    let tokens = decode_semantic_tokens(&data, &legend, &snapshot);
+                    (data, tokens)
+                })
+                .await;
+            this.update(&mut cx, |this, _| {
+                let is_newer = this.semantic_tokens.get(&buffer_id).map_or(true, |cached| {
+                    !cached.tokens.version.changed_since(&tokens.version)
+                });
+                if is_newer {
+                    this.semantic_tokens.insert(
+                        buffer_id,
+                        CachedSemanticTokens {
+                            result_id,
+                            data: Arc::new(data),
+                            tokens: tokens.clone(),
+                        },
+                    );
+                }
+                Some(tokens)
+            })
+        })
+    }
+
     pub fn signature_help<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -4790,6 +5167,72 @@ impl LspStore {
         }
     }
 
+    pub fn document_colors(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<DocumentColor>> {
+        if let Some((client, upstream_project_id)) = self.upstream_client() {
+            let request_task = client.request(proto::MultiLspQuery {
+                buffer_id: buffer.read(cx).remote_id().into(),
+                version: serialize_version(&buffer.read(cx).version()),
+                project_id: upstream_project_id,
+                strategy: Some(proto::multi_lsp_query::Strategy::All(
+                    proto::AllLanguageServers {},
+                )),
+                request: Some(proto::multi_lsp_query::Request::GetDocumentColors(
+                    GetDocumentColors.to_proto(upstream_project_id, buffer.read(cx)),
+                )),
+            });
+            let buffer = buffer.clone();
+            cx.spawn(|weak_project, cx| async move {
+                let Some(project) = weak_project.upgrade() else {
+                    return Vec::new();
+                };
+                join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .map(|response| response.responses)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|lsp_response| match lsp_response.response? {
+                            proto::lsp_response::Response::GetDocumentColorsResponse(response) => {
+                                Some(response)
+                            }
+                            unexpected => {
+                                debug_panic!("Unexpected response: {unexpected:?}");
+                                None
+                            }
+                        })
+                        .map(|colors_response| {
+                            let response = GetDocumentColors.response_from_proto(
+                                colors_response,
+                                project.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move { response.await.log_err().unwrap_or_default() }
+                        }),
+                )
+                .await
+                .into_iter()
+                .flatten()
+                .collect()
+            })
+        } else {
+            let all_colors_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetDocumentColors, cx);
+            cx.spawn(|_, _| async move {
+                all_colors_task
+                    .await
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+            })
+        }
+    }
+
     pub fn hover(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -5083,10 +5526,10 @@ impl LspStore {
 
     pub fn on_buffer_edited(
         &mut self,
-        buffer: Entity<Buffer>,
+        buffer_handle: Entity<Buffer>,
         cx: &mut Context<Self>,
     ) -> Option<()> {
-        let language_servers: Vec<_> = buffer.update(cx, |buffer, cx| {
+        let language_servers: Vec<_> = buffer_handle.update(cx, |buffer, cx| {
             Some(
                 self.as_local()?
                     .language_servers_for_buffer(buffer, cx)
@@ -5095,7 +5538,7 @@ impl LspStore {
             )
         })?;
 
-        let buffer = buffer.read(cx);
+        let buffer = buffer_handle.read(cx);
         let file = File::from_dyn(buffer.file())?;
         let abs_path = file.as_local()?.abs_path(cx);
         let uri = lsp::Url::from_file_path(abs_path).unwrap();
@@ -5182,6 +5625,7 @@ impl LspStore {
                 .log_err();
         }
 
+        self.pull_diagnostics_for_buffer(&buffer_handle, cx);
         None
     }
 
@@ -5222,6 +5666,7 @@ impl LspStore {
         for language_server_id in language_servers {
             self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
         }
+        self.pull_workspace_diagnostics_for_buffer(&buffer, cx);
 
         None
     }
@@ -5515,6 +5960,109 @@ impl LspStore {
         Ok(())
     }
 
+    /// Replaces the diagnostics of a task with the problems found in its output so far.
+    /// Problems in relative paths are resolved against the task's working directory.
+    ///
+    /// Each task's diagnostics are published under an id of their own, registered like a
+    /// language server named after the task, so that the diagnostics' source can be looked up.
+    /// Tasks only run problem matchers in local projects.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: String,
+        cwd: Option<&Path>,
+        problems: Vec<task::Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let local = self
+            .as_local_mut()
+            .context("task diagnostics are only supported in local projects")?;
+        let languages = local.languages.clone();
+        let mut is_new_source = false;
+        let (server_id, previously_reported) = local
+            .task_diagnostics
+            .entry(task_label.clone())
+            .or_insert_with(|| {
+                is_new_source = true;
+                (languages.next_language_server_id(), HashSet::default())
+            });
+        let server_id = *server_id;
+        let previously_reported = mem::take(previously_reported);
+
+        let mut diagnostics_by_path =
+            HashMap::<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>::default();
+        for problem in problems {
+            let path = Path::new(&problem.path);
+            let abs_path = if path.is_absolute() {
+                path.to_path_buf()
+            } else if let Some(cwd) = cwd {
+                cwd.join(path)
+            } else {
+                continue;
+            };
+            let start = PointUtf16::new(problem.row, problem.column);
+            let end = problem
+                .end
+                .map_or(start, |(row, column)| PointUtf16::new(row, column));
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end),
+                    diagnostic: Diagnostic {
+                        source: Some(task_label.clone()),
+                        code: problem.code.map(NumberOrString::String),
+                        severity: match problem.severity {
+                            task::ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            task::ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            task::ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            task::ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id: post_inc(&mut local.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+        let reported = diagnostics_by_path.keys().cloned().collect::<HashSet<_>>();
+        // Clear the problems the task no longer reports.
+        for abs_path in previously_reported {
+            diagnostics_by_path.entry(abs_path).or_default();
+        }
+        if let Some((_, previously_reported)) = local.task_diagnostics.get_mut(&task_label) {
+            *previously_reported = reported;
+        }
+        if is_new_source {
+            if let Some((downstream_client, project_id)) = self.downstream_client.as_ref() {
+                downstream_client
+                    .send(proto::StartLanguageServer {
+                        project_id: *project_id,
+                        server: Some(proto::LanguageServer {
+                            id: server_id.0 as u64,
+                            name: task_label.clone(),
+                            worktree_id: None,
+                        }),
+                    })
+                    .log_err();
+            }
+            self.language_server_statuses.insert(
+                server_id,
+                LanguageServerStatus {
+                    name: task_label,
+                    pending_work: Default::default(),
+                    has_pending_diagnostic_updates: false,
+                    progress_tokens: Default::default(),
+                },
+            );
+        }
+
+        for (abs_path, diagnostics) in diagnostics_by_path {
+            self.update_diagnostic_entries(server_id, abs_path, None, diagnostics, cx)?;
+        }
+        Ok(())
+    }
+
     fn update_worktree_diagnostics(
         &mut self,
         worktree_id: WorktreeId,
@@ -5978,10 +6526,89 @@ impl LspStore {
                         .collect(),
                 })
             }
+            Some(proto::multi_lsp_query::Request::GetDocumentColors(get_document_colors)) => {
+                let get_document_colors = GetDocumentColors::from_proto(
+                    get_document_colors,
+                    this.clone(),
+                    buffer.clone(),
+                    cx.clone(),
+                )
+                .await?;
+
+                let all_colors = this
+                    .update(&mut cx, |project, cx| {
+                        project.request_multiple_lsp_locally(
+                            &buffer,
+                            None::<usize>,
+                            get_document_colors,
+                            cx,
+                        )
+                    })?
+                    .await
+                    .into_iter();
+
+                this.update(&mut cx, |project, cx| proto::MultiLspQueryResponse {
+                    responses: all_colors
+                        .map(|colors| proto::LspResponse {
+                            response: Some(
+                                proto::lsp_response::Response::GetDocumentColorsResponse(
+                                    GetDocumentColors::response_to_proto(
+                                        colors,
+                                        project,
+                                        sender_id,
+                                        &buffer_version,
+                                        cx,
+                                    ),
+                                ),
+                            ),
+                        })
+                        .collect(),
+                })
+            }
             None => anyhow::bail!("empty multi lsp query request"),
         }
     }
 
+    async fn handle_get_color_presentations(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetColorPresentations>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetColorPresentationsResponse> {
+        let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let buffer_id = GetColorPresentations::buffer_id_from_proto(&envelope.payload)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let request = GetColorPresentations::from_proto(
+            envelope.payload,
+            this.clone(),
+            buffer.clone(),
+            cx.clone(),
+        )
+        .await?;
+        // Only the language server that found the color knows how to write it.
+        let server_id = request.color.server_id;
+        let presentations = this
+            .update(&mut cx, |this, cx| {
+                this.request_lsp(
+                    buffer.clone(),
+                    LanguageServerToQuery::Other(server_id),
+                    request,
+                    cx,
+                )
+            })?
+            .await?;
+        this.update(&mut cx, |this, cx| {
+            GetColorPresentations::response_to_proto(
+                presentations,
+                this,
+                sender_id,
+                &buffer.read(cx).version(),
+                cx,
+            )
+        })
+    }
+
     async fn handle_apply_code_action(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::ApplyCodeAction>,
@@ -6808,6 +7435,28 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_refresh_code_lens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshCodeLens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshCodeLens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_inlay_hints(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::InlayHints>,
@@ -7350,6 +7999,7 @@ impl LspStore {
             });
         }
         local.language_server_watched_paths.remove(&server_id);
+        local.pull_diagnostics.remove(&server_id);
         let server_state = local.language_servers.remove(&server_id);
         cx.notify();
         cx.emit(LspStoreEvent::LanguageServerRemoved(server_id));
@@ -7607,6 +8257,8 @@ impl LspStore {
             Some(key.0),
         ));
         cx.emit(LspStoreEvent::RefreshInlayHints);
+        cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        cx.emit(LspStoreEvent::RefreshCodeLens);
 
         if let Some((downstream_client, project_id)) = self.downstream_client.as_ref() {
             downstream_client
@@ -7700,6 +8352,7 @@ impl LspStore {
                 });
             }
         });
+        self.pull_diagnostics_for_server(server_id, cx);
 
         cx.notify();
     }
@@ -8039,6 +8692,32 @@ impl LspStore {
         })
     }
 
+    pub(crate) fn serialize_code_lens(lens: &CodeLens) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: lens.server_id.0 as u64,
+            start: Some(serialize_anchor(&lens.range.start)),
+            end: Some(serialize_anchor(&lens.range.end)),
+            lsp_lens: serde_json::to_vec(&lens.lsp_lens).unwrap(),
+        }
+    }
+
+    pub(crate) fn deserialize_code_lens(lens: proto::CodeLens) -> Result<CodeLens> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        let lsp_lens = serde_json::from_slice(&lens.lsp_lens)?;
+        Ok(CodeLens {
+            server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            lsp_lens,
+        })
+    }
+
     fn update_last_formatting_failure<T>(&mut self, formatting_result: &anyhow::Result<T>) {
         match &formatting_result {
             Ok(_) => self.last_formatting_failure = None,
@@ -8065,6 +8744,44 @@ fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     }
 }
 
+/// Applies LSP edits that are relative to the text left by earlier edits of a buffer after these
+/// edits, returning the combined edits of the buffer.
+fn rebase_document_edits(
+    snapshot: &BufferSnapshot,
+    edits: &[(Range<Anchor>, String)],
+    lsp_edits: Vec<lsp::TextEdit>,
+) -> Vec<(Range<Anchor>, String)> {
+    let mut text = text::Buffer::new(0, snapshot.remote_id(), snapshot.text());
+    let base_version = text.version();
+
+    let mut edits = edits
+        .iter()
+        .map(|(range, new_text)| (range.to_offset(snapshot), new_text.clone()))
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|(range, _)| range.start);
+    text.edit(edits);
+
+    let mut lsp_edits = lsp_edits
+        .into_iter()
+        .map(|edit| {
+            let range = range_from_lsp(edit.range);
+            let start = text.clip_point_utf16(range.start, Bias::Left);
+            let end = text.clip_point_utf16(range.end, Bias::Left);
+            (start.to_offset(&text)..end.to_offset(&text), edit.new_text)
+        })
+        .collect::<Vec<_>>();
+    lsp_edits.sort_by_key(|(range, _)| range.start);
+    text.edit(lsp_edits);
+
+    text.edits_since::<usize>(&base_version)
+        .map(|edit| {
+            let new_text = text.text_for_range(edit.new).collect::<String>();
+            let range = snapshot.anchor_after(edit.old.start)..snapshot.anchor_before(edit.old.end);
+            (range, new_text)
+        })
+        .collect()
+}
+
 async fn populate_labels_for_completions(
     mut new_completions: Vec<CoreCompletion>,
     language_registry: &Arc<LanguageRegistry>,
@@ -8116,7 +8833,7 @@ async fn populate_labels_for_completions(
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum LanguageServerToQuery {
     Primary,
     Other(LanguageServerId),
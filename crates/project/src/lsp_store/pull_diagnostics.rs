@@ -0,0 +1,470 @@
+use std::{sync::Arc, time::Duration};
+
+use collections::HashMap;
+use gpui::{Context, Entity, Task};
+use language::{Buffer, CachedLspAdapter};
+use lsp::{
+    DiagnosticServerCapabilities, DocumentDiagnosticReport, DocumentDiagnosticReportKind,
+    DocumentDiagnosticReportResult, LanguageServer, LanguageServerId, ServerCapabilities,
+    WorkspaceDiagnosticReportResult, WorkspaceDocumentDiagnosticReport,
+};
+use util::ResultExt as _;
+use worktree::File;
+
+use super::{LanguageServerState, LspStore};
+
+const PULL_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(125);
+
+/// The diagnostics of a language server that supports pulling them, which are merged with the
+/// ones it publishes before being shown.
+#[derive(Default)]
+pub(super) struct PullDiagnosticsState {
+    pushed: HashMap<lsp::Url, Vec<lsp::Diagnostic>>,
+    pulled: HashMap<lsp::Url, PulledDiagnostics>,
+    document_pulls: HashMap<lsp::Url, Task<()>>,
+    workspace_pull: Option<Task<()>>,
+}
+
+#[derive(Default)]
+struct PulledDiagnostics {
+    /// The id the server reported the diagnostics with, sent back so it can tell us when they are
+    /// unchanged.
+    result_id: Option<String>,
+    diagnostics: Vec<lsp::Diagnostic>,
+}
+
+pub(super) fn diagnostic_options(
+    capabilities: &ServerCapabilities,
+) -> Option<&lsp::DiagnosticOptions> {
+    match capabilities.diagnostic_provider.as_ref()? {
+        DiagnosticServerCapabilities::Options(options) => Some(options),
+        DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(&options.diagnostic_options)
+        }
+    }
+}
+
+/// Combines the diagnostics a server published for a document with the ones pulled for it,
+/// leaving out pulled diagnostics that were also published.
+fn merge_diagnostics(
+    pushed: &[lsp::Diagnostic],
+    pulled: &[lsp::Diagnostic],
+) -> Vec<lsp::Diagnostic> {
+    let mut merged = pushed.to_vec();
+    for diagnostic in pulled {
+        let is_duplicate = pushed.iter().any(|pushed| {
+            pushed.range == diagnostic.range
+                && pushed.severity == diagnostic.severity
+                && pushed.code == diagnostic.code
+                && pushed.source == diagnostic.source
+                && pushed.message == diagnostic.message
+        });
+        if !is_duplicate {
+            merged.push(diagnostic.clone());
+        }
+    }
+    merged
+}
+
+impl PullDiagnosticsState {
+    /// Forgets a document that was closed, whose diagnostics the server may no longer report.
+    pub(super) fn forget_document(&mut self, uri: &lsp::Url) {
+        self.pushed.remove(uri);
+        self.pulled.remove(uri);
+        self.document_pulls.remove(uri);
+    }
+
+    fn merged_diagnostics(&self, uri: &lsp::Url) -> Vec<lsp::Diagnostic> {
+        merge_diagnostics(
+            self.pushed.get(uri).map_or(&[], Vec::as_slice),
+            self.pulled
+                .get(uri)
+                .map_or(&[], |pulled| pulled.diagnostics.as_slice()),
+        )
+    }
+}
+
+impl LspStore {
+    /// Records the diagnostics a server published, and adds the ones pulled for the same document.
+    pub(super) fn merge_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        params: &mut lsp::PublishDiagnosticsParams,
+    ) {
+        let Some(state) = self
+            .as_local_mut()
+            .and_then(|local| local.pull_diagnostics.get_mut(&server_id))
+        else {
+            return;
+        };
+        state
+            .pushed
+            .insert(params.uri.clone(), params.diagnostics.clone());
+        params.diagnostics = state.merged_diagnostics(&params.uri);
+    }
+
+    /// Pulls the diagnostics of every document the server knows about, as well as the
+    /// workspace diagnostics if it reports them.
+    pub(super) fn pull_diagnostics_for_server(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(LanguageServerState::Running {
+            adapter, server, ..
+        }) = local.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        if diagnostic_options(&server.capabilities()).is_none() {
+            return;
+        }
+        let (adapter, server) = (adapter.clone(), server.clone());
+        local.pull_diagnostics.entry(server_id).or_default();
+
+        for buffer in self.buffers_open_in_server(server_id, None, cx) {
+            self.pull_document_diagnostics(&buffer, &adapter, &server, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    /// Pulls the diagnostics of a buffer that was opened or changed, and of the documents that
+    /// may depend on it.
+    pub(super) fn pull_diagnostics_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let servers = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .map(|(adapter, server)| (adapter.clone(), server.clone()))
+                .collect::<Vec<_>>()
+        });
+        for (adapter, server) in servers {
+            let capabilities = server.capabilities();
+            let Some(options) = diagnostic_options(&capabilities) else {
+                continue;
+            };
+            let inter_file_dependencies = options.inter_file_dependencies;
+            self.pull_document_diagnostics(buffer, &adapter, &server, cx);
+            if inter_file_dependencies {
+                let buffer_id = buffer.read(cx).remote_id();
+                for other_buffer in
+                    self.buffers_open_in_server(server.server_id(), Some(buffer_id), cx)
+                {
+                    self.pull_document_diagnostics(&other_buffer, &adapter, &server, cx);
+                }
+            }
+        }
+    }
+
+    /// Pulls the workspace diagnostics of the servers of a buffer that was saved.
+    pub(super) fn pull_workspace_diagnostics_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let server_ids = buffer.update(cx, |buffer, cx| {
+            local.language_server_ids_for_buffer(buffer, cx)
+        });
+        for server_id in server_ids {
+            self.pull_workspace_diagnostics(server_id, cx);
+        }
+    }
+
+    fn buffers_open_in_server(
+        &self,
+        server_id: LanguageServerId,
+        except: Option<text::BufferId>,
+        cx: &Context<Self>,
+    ) -> Vec<Entity<Buffer>> {
+        let Some(local) = self.as_local() else {
+            return Vec::new();
+        };
+        let buffer_store = self.buffer_store.read(cx);
+        local
+            .buffer_snapshots
+            .iter()
+            .filter(|(buffer_id, snapshots)| {
+                Some(**buffer_id) != except && snapshots.contains_key(&server_id)
+            })
+            .filter_map(|(buffer_id, _)| buffer_store.get(*buffer_id))
+            .collect()
+    }
+
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        adapter: &Arc<CachedLspAdapter>,
+        server: &Arc<LanguageServer>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(file) = File::from_dyn(buffer.read(cx).file()).and_then(|file| file.as_local())
+        else {
+            return;
+        };
+        let Some(uri) = lsp::Url::from_file_path(file.abs_path(cx)).log_err() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let server_id = server.server_id();
+        let version = local
+            .buffer_snapshots
+            .get(&buffer_id)
+            .and_then(|snapshots| snapshots.get(&server_id)?.last())
+            .map(|snapshot| snapshot.version);
+        let Some(state) = local.pull_diagnostics.get_mut(&server_id) else {
+            return;
+        };
+        let params = lsp::DocumentDiagnosticParams {
+            text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+            identifier: diagnostic_options(&server.capabilities())
+                .and_then(|options| options.identifier.clone()),
+            previous_result_id: state
+                .pulled
+                .get(&uri)
+                .and_then(|pulled| pulled.result_id.clone()),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let adapter = adapter.clone();
+        let server = server.clone();
+        let task = cx.spawn({
+            let uri = uri.clone();
+            move |this, mut cx| async move {
+                cx.background_executor()
+                    .timer(PULL_DIAGNOSTICS_DEBOUNCE)
+                    .await;
+                let report = server
+                    .request::<lsp::request::DocumentDiagnosticRequest>(params)
+                    .await;
+                this.update(&mut cx, |this, cx| match report {
+                    Ok(report) => {
+                        this.apply_document_diagnostic_report(
+                            server_id, &adapter, uri, version, report, cx,
+                        );
+                    }
+                    Err(error) => {
+                        log::warn!("failed to pull diagnostics for {uri}: {error:#}");
+                    }
+                })
+                .ok();
+            }
+        });
+        state.document_pulls.insert(uri, task);
+    }
+
+    fn pull_workspace_diagnostics(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(LanguageServerState::Running {
+            adapter, server, ..
+        }) = local.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let capabilities = server.capabilities();
+        let Some(options) =
+            diagnostic_options(&capabilities).filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+        let Some(state) = local.pull_diagnostics.get_mut(&server_id) else {
+            return;
+        };
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: options.identifier.clone(),
+            previous_result_ids: state
+                .pulled
+                .iter()
+                .filter_map(|(uri, pulled)| {
+                    Some(lsp::PreviousResultId {
+                        uri: uri.clone(),
+                        value: pulled.result_id.clone()?,
+                    })
+                })
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+
+        let adapter = adapter.clone();
+        let server = server.clone();
+        state.workspace_pull = Some(cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(PULL_DIAGNOSTICS_DEBOUNCE)
+                .await;
+            let report = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(params)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                let items = match report {
+                    Ok(WorkspaceDiagnosticReportResult::Report(report)) => report.items,
+                    Ok(WorkspaceDiagnosticReportResult::Partial(report)) => report.items,
+                    Err(error) => {
+                        log::warn!("failed to pull workspace diagnostics: {error:#}");
+                        return;
+                    }
+                };
+                for item in items {
+                    let (uri, version, report) = match item {
+                        WorkspaceDocumentDiagnosticReport::Full(report) => (
+                            report.uri,
+                            report.version,
+                            DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                        ),
+                        WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                            report.uri,
+                            report.version,
+                            DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                        ),
+                    };
+                    let version = version.and_then(|version| i32::try_from(version).ok());
+                    this.apply_diagnostic_report(server_id, &adapter, uri, version, report, cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn apply_document_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &Arc<CachedLspAdapter>,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: DocumentDiagnosticReportResult,
+        cx: &mut Context<Self>,
+    ) {
+        let related_documents = match report {
+            DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) => {
+                self.apply_diagnostic_report(
+                    server_id,
+                    adapter,
+                    uri,
+                    version,
+                    DocumentDiagnosticReportKind::Full(report.full_document_diagnostic_report),
+                    cx,
+                );
+                report.related_documents
+            }
+            DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Unchanged(report)) => {
+                self.apply_diagnostic_report(
+                    server_id,
+                    adapter,
+                    uri,
+                    version,
+                    DocumentDiagnosticReportKind::Unchanged(
+                        report.unchanged_document_diagnostic_report,
+                    ),
+                    cx,
+                );
+                report.related_documents
+            }
+            DocumentDiagnosticReportResult::Partial(report) => report.related_documents,
+        };
+        for (uri, report) in related_documents.into_iter().flatten() {
+            self.apply_diagnostic_report(server_id, adapter, uri, None, report, cx);
+        }
+    }
+
+    fn apply_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &Arc<CachedLspAdapter>,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: DocumentDiagnosticReportKind,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(state) = self
+            .as_local_mut()
+            .and_then(|local| local.pull_diagnostics.get_mut(&server_id))
+        else {
+            return;
+        };
+        let report = match report {
+            DocumentDiagnosticReportKind::Full(report) => report,
+            DocumentDiagnosticReportKind::Unchanged(report) => {
+                if let Some(pulled) = state.pulled.get_mut(&uri) {
+                    pulled.result_id = Some(report.result_id);
+                }
+                return;
+            }
+        };
+
+        let mut params = lsp::PublishDiagnosticsParams {
+            uri,
+            diagnostics: report.items,
+            version,
+        };
+        adapter.process_diagnostics(&mut params);
+        state.pulled.insert(
+            params.uri.clone(),
+            PulledDiagnostics {
+                result_id: report.result_id,
+                diagnostics: params.diagnostics,
+            },
+        );
+        params.diagnostics = state.merged_diagnostics(&params.uri);
+        self.update_diagnostics(
+            server_id,
+            params,
+            &adapter.disk_based_diagnostic_sources,
+            cx,
+        )
+        .log_err();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_diagnostics() {
+        let diagnostic = |line, message: &str| lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 1)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: message.to_string(),
+            ..Default::default()
+        };
+        let pushed = vec![diagnostic(0, "a"), diagnostic(1, "b")];
+        let pulled = vec![
+            lsp::Diagnostic {
+                data: Some(serde_json::json!({ "pulled": true })),
+                ..diagnostic(1, "b")
+            },
+            diagnostic(1, "c"),
+            diagnostic(2, "a"),
+        ];
+        assert_eq!(
+            merge_diagnostics(&pushed, &pulled),
+            vec![
+                diagnostic(0, "a"),
+                diagnostic(1, "b"),
+                diagnostic(1, "c"),
+                diagnostic(2, "a")
+            ]
+        );
+    }
+}
@@ -1,7 +1,10 @@
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
+pub mod dap_store;
 pub mod debounced_delay;
+pub mod debug_session;
 pub mod git;
 pub mod image_store;
 pub mod lsp_command;
@@ -14,6 +17,7 @@ pub mod search;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
+pub mod test_store;
 pub mod toolchain_store;
 pub mod worktree_store;
 
@@ -30,13 +34,17 @@ mod yarn;
 
 use crate::git::GitStore;
 use anyhow::{anyhow, Context as _, Result};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{
     proto, Client, Collaborator, PendingEntitySubscription, ProjectId, TypedEnvelope, UserStore,
 };
 use clock::ReplicaId;
 use collections::{BTreeSet, HashMap, HashSet};
+use dap::DebugAdapterBinary;
+use dap_store::DapStore;
 use debounced_delay::DebouncedDelay;
+use debug_session::DebugSession;
 pub use environment::ProjectEnvironment;
 use futures::{
     channel::mpsc::{self, UnboundedReceiver},
@@ -58,8 +66,9 @@ use gpui::{
 use itertools::Itertools;
 use language::{
     language_settings::InlayHintKind, proto::split_operations, Buffer, BufferEvent, Capability,
-    CodeLabel, CompletionDocumentation, File as _, Language, LanguageName, LanguageRegistry,
-    PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction, Unclipped,
+    CodeLabel, CompletionDocumentation, Encoding, File as _, Language, LanguageName,
+    LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
+    Unclipped,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, LanguageServerId,
@@ -91,8 +100,10 @@ use std::{
     sync::Arc,
     time::Duration,
 };
+use task::{DebugAdapterConfig, SpawnInTerminal};
 use task_store::TaskStore;
 use terminals::Terminals;
+use test_store::TestStore;
 use text::{Anchor, BufferId};
 use toolchain_store::EmptyToolchainStore;
 use util::{
@@ -162,6 +173,9 @@ pub struct Project {
     ssh_client: Option<Entity<SshRemoteClient>>,
     client_state: ProjectClientState,
     git_store: Entity<GitStore>,
+    bookmark_store: Entity<BookmarkStore>,
+    dap_store: Entity<DapStore>,
+    test_store: Entity<TestStore>,
     collaborators: HashMap<proto::PeerId, Collaborator>,
     client_subscriptions: Vec<client::Subscription>,
     worktree_store: Entity<WorktreeStore>,
@@ -285,6 +299,9 @@ pub enum Event {
     Reshared,
     Rejoined,
     RefreshInlayHints,
+    RefreshSemanticTokens,
+    RefreshCodeLens,
+    TaskOutputValuesChanged(BufferId),
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -418,6 +435,114 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A code lens provided by a language server.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer the code lens is shown for.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server.
+    pub lsp_lens: lsp::CodeLens,
+}
+
+impl CodeLens {
+    /// The title of the command the code lens runs, once it has been resolved.
+    pub fn title(&self) -> Option<&str> {
+        self.lsp_lens
+            .command
+            .as_ref()
+            .map(|command| command.title.as_str())
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.lsp_lens.command.is_some()
+    }
+}
+
+/// A link to another document or a URL, provided by a language server for a range of a buffer.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<Anchor>,
+    pub target: lsp::Url,
+    pub tooltip: Option<String>,
+}
+
+/// A value to show next to a range of a buffer, as provided by a language server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineValue {
+    pub range: Range<Anchor>,
+    pub kind: InlineValueKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InlineValueKind {
+    /// Text to show as is.
+    Text(String),
+    /// A variable to look the value of up, named by the text of the range if there is no name.
+    VariableLookup {
+        variable_name: Option<String>,
+        case_sensitive: bool,
+    },
+    /// An expression to evaluate, which is the text of the range if there is no expression.
+    EvaluatableExpression { expression: Option<String> },
+}
+
+/// A value that a task printed for a line of a buffer, such as with Rust's `dbg!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskOutputValue {
+    /// The start of the line that printed the value.
+    pub position: Anchor,
+    /// The expression whose value was printed, if the output names it.
+    pub expression: Option<String>,
+    pub value: String,
+}
+
+/// A color a language server found in a buffer, such as a color literal in a stylesheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentColor {
+    pub range: Range<Anchor>,
+    pub color: Hsla,
+    /// The language server that found the color, which is the one to ask how to write it.
+    pub server_id: LanguageServerId,
+}
+
+/// A way of writing a color in a buffer, as proposed by a language server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorPresentation {
+    pub label: String,
+    /// The edits writing the color, the first of which replaces the color's range.
+    pub edits: Vec<(Range<Anchor>, String)>,
+}
+
+/// The changes a language server proposes to make to the workspace, such as those of a rename,
+/// before any of them are made.
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceEditPreview {
+    /// The edits of each buffer, in the coordinates of the buffer when they were proposed.
+    pub buffer_edits: Vec<(Entity<Buffer>, Vec<(Range<Anchor>, String)>)>,
+    /// The files to create, rename and delete, in the order they should happen in.
+    pub file_operations: Vec<WorkspaceFileOperation>,
+}
+
+/// A change to the files of the workspace proposed by a language server, with absolute paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WorkspaceFileOperation {
+    /// Creates a file with the given text, or an empty directory.
+    Create {
+        path: PathBuf,
+        is_dir: bool,
+        text: String,
+    },
+    Rename {
+        old_path: PathBuf,
+        new_path: PathBuf,
+    },
+    Delete {
+        path: PathBuf,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResolveState {
     Resolved,
@@ -522,6 +647,7 @@ enum EntitySubscription {
     WorktreeStore(PendingEntitySubscription<WorktreeStore>),
     LspStore(PendingEntitySubscription<LspStore>),
     SettingsObserver(PendingEntitySubscription<SettingsObserver>),
+    GitStore(PendingEntitySubscription<GitStore>),
 }
 
 #[derive(Clone)]
@@ -703,6 +829,7 @@ impl Project {
 
             let git_store =
                 cx.new(|cx| GitStore::new(&worktree_store, buffer_store.clone(), None, None, cx));
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(&buffer_store, cx));
 
             cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
 
@@ -716,6 +843,9 @@ impl Project {
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 git_store,
+                bookmark_store,
+                dap_store: cx.new(|_| DapStore::default()),
+                test_store: cx.new(|_| TestStore::default()),
                 client_subscriptions: Vec::new(),
                 _subscriptions: vec![cx.on_release(Self::release)],
                 active_entry: None,
@@ -731,6 +861,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_output_values: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -831,6 +962,7 @@ impl Project {
                     cx,
                 )
             });
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(&buffer_store, cx));
 
             cx.subscribe(&ssh, Self::on_ssh_event).detach();
             cx.observe(&ssh, |_, _, cx| cx.notify()).detach();
@@ -845,6 +977,9 @@ impl Project {
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 git_store,
+                bookmark_store,
+                dap_store: cx.new(|_| DapStore::default()),
+                test_store: cx.new(|_| TestStore::default()),
                 client_subscriptions: Vec::new(),
                 _subscriptions: vec![
                     cx.on_release(Self::release),
@@ -875,6 +1010,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_output_values: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -952,6 +1088,7 @@ impl Project {
             EntitySubscription::SettingsObserver(
                 client.subscribe_to_entity::<SettingsObserver>(remote_id)?,
             ),
+            EntitySubscription::GitStore(client.subscribe_to_entity::<GitStore>(remote_id)?),
         ];
         let response = client
             .request_envelope(proto::JoinProject {
@@ -974,7 +1111,7 @@ impl Project {
     #[allow(clippy::too_many_arguments)]
     async fn from_join_project_response(
         response: TypedEnvelope<proto::JoinProjectResponse>,
-        subscriptions: [EntitySubscription; 5],
+        subscriptions: [EntitySubscription; 6],
         client: Arc<Client>,
         run_tasks: bool,
         user_store: Entity<UserStore>,
@@ -1090,11 +1227,15 @@ impl Project {
                     remote_id,
                     replica_id,
                 },
-                git_store,
+                git_store: git_store.clone(),
+                bookmark_store: cx.new(|cx| BookmarkStore::new(&buffer_store, cx)),
+                dap_store: cx.new(|_| DapStore::default()),
+                test_store: cx.new(|_| TestStore::default()),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_output_values: HashMap::default(),
                 },
                 node: None,
                 search_history: Self::new_search_history(),
@@ -1129,6 +1270,9 @@ impl Project {
                 EntitySubscription::LspStore(subscription) => {
                     subscription.set_entity(&lsp_store, &mut cx)
                 }
+                EntitySubscription::GitStore(subscription) => {
+                    subscription.set_entity(&git_store, &mut cx)
+                }
             })
             .collect::<Vec<_>>();
 
@@ -1636,6 +1780,74 @@ impl Project {
         })
     }
 
+    /// Performs a file operation of a [`WorkspaceEditPreview`], once it has been accepted.
+    pub fn apply_file_operation(
+        &mut self,
+        operation: WorkspaceFileOperation,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        match operation {
+            WorkspaceFileOperation::Create { path, is_dir, text } => {
+                let project_path = match self.project_path_for_abs_path(&path, cx) {
+                    Ok(project_path) => project_path,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+                let create = self.create_entry(project_path.clone(), is_dir, cx);
+                cx.spawn(|this, mut cx| async move {
+                    create.await?;
+                    if is_dir || text.is_empty() {
+                        return Ok(());
+                    }
+                    let buffer = this
+                        .update(&mut cx, |this, cx| this.open_buffer(project_path, cx))?
+                        .await?;
+                    buffer.update(&mut cx, |buffer, cx| buffer.set_text(text, cx))?;
+                    this.update(&mut cx, |this, cx| this.save_buffer(buffer, cx))?
+                        .await
+                })
+            }
+            WorkspaceFileOperation::Rename { old_path, new_path } => {
+                let (old_path, new_path) = match (
+                    self.project_path_for_abs_path(&old_path, cx),
+                    self.project_path_for_abs_path(&new_path, cx),
+                ) {
+                    (Ok(old_path), Ok(new_path)) => (old_path, new_path),
+                    (Err(error), _) | (_, Err(error)) => return Task::ready(Err(error)),
+                };
+                if old_path.worktree_id != new_path.worktree_id {
+                    return Task::ready(Err(anyhow!(
+                        "cannot rename {:?} into another worktree",
+                        old_path.path
+                    )));
+                }
+                let Some(entry) = self.entry_for_path(&old_path, cx) else {
+                    return Task::ready(Err(anyhow!("no entry for {:?}", old_path.path)));
+                };
+                let rename = self.rename_entry(entry.id, new_path.path, cx);
+                cx.background_executor()
+                    .spawn(async move { rename.await.map(|_| ()) })
+            }
+            WorkspaceFileOperation::Delete { path } => {
+                let project_path = match self.project_path_for_abs_path(&path, cx) {
+                    Ok(project_path) => project_path,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+                self.delete_file(project_path, false, cx)
+                    .unwrap_or_else(|| Task::ready(Ok(())))
+            }
+        }
+    }
+
+    fn project_path_for_abs_path(&self, abs_path: &Path, cx: &App) -> Result<ProjectPath> {
+        let (worktree, path) = self
+            .find_worktree(abs_path, cx)
+            .ok_or_else(|| anyhow!("{abs_path:?} is not in the project"))?;
+        Ok(ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.into(),
+        })
+    }
+
     pub fn expand_entry(
         &mut self,
         worktree_id: WorktreeId,
@@ -1706,6 +1918,9 @@ impl Project {
         self.settings_observer.update(cx, |settings_observer, cx| {
             settings_observer.shared(project_id, self.client.clone().into(), cx)
         });
+        self.git_store.update(cx, |git_store, cx| {
+            git_store.shared(project_id, self.client.clone().into(), cx)
+        });
 
         self.client_state = ProjectClientState::Shared {
             remote_id: project_id,
@@ -1790,6 +2005,9 @@ impl Project {
             self.settings_observer.update(cx, |settings_observer, cx| {
                 settings_observer.unshared(cx);
             });
+            self.git_store.update(cx, |git_store, cx| {
+                git_store.unshared(cx);
+            });
 
             self.client
                 .send(proto::UnshareProject {
@@ -2096,6 +2314,18 @@ impl Project {
         })
     }
 
+    /// Saves a buffer in the given encoding, which the buffer then keeps for later saves.
+    pub fn save_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.save_buffer_with_encoding(buffer, encoding, cx)
+        })
+    }
+
     pub fn get_open_buffer(&self, path: &ProjectPath, cx: &App) -> Option<Entity<Buffer>> {
         self.buffer_store.read(cx).get_by_path(path, cx)
     }
@@ -2332,6 +2562,8 @@ impl Project {
                 };
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
+            LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -2747,6 +2979,18 @@ impl Project {
         })
     }
 
+    /// Reloads a buffer from disk in the given encoding, which the buffer then keeps for saving.
+    pub fn reload_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.reload_buffer_with_encoding(buffer, encoding, cx)
+        })
+    }
+
     pub fn reload_images(
         &self,
         images: HashSet<Entity<ImageItem>>,
@@ -2856,6 +3100,112 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Finds the calls made to the given item of a call hierarchy.
+    pub fn incoming_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let Some(lsp_item) = item.call_hierarchy_item() else {
+            return Task::ready(Err(anyhow!("not a call hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetIncomingCalls {
+                item: lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    /// Finds the calls made from the given item of a call hierarchy.
+    pub fn outgoing_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let Some(lsp_item) = item.call_hierarchy_item() else {
+            return Task::ready(Err(anyhow!("not a call hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetOutgoingCalls {
+                item: lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Finds the types the given item of a type hierarchy derives from.
+    pub fn supertypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let Some(lsp_item) = item.type_hierarchy_item() else {
+            return Task::ready(Err(anyhow!("not a type hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSupertypes {
+                item: lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    /// Finds the types deriving from the given item of a type hierarchy.
+    pub fn subtypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let Some(lsp_item) = item.type_hierarchy_item() else {
+            return Task::ready(Err(anyhow!("not a type hierarchy item")));
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSubtypes {
+                item: lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
     pub fn references<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -3030,6 +3380,159 @@ impl Project {
         })
     }
 
+    /// Requests the code lenses of a buffer from its primary language server.
+    pub fn code_lens(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    /// Requests the links to other documents and URLs in a buffer from its primary language server.
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Requests the colors in a buffer from all of its language servers.
+    pub fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<DocumentColor>> {
+        self.lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.document_colors(buffer, cx))
+    }
+
+    /// Requests the ways of writing a color from the language server that found it in a buffer.
+    pub fn color_presentations(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        color: DocumentColor,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<ColorPresentation>>> {
+        let server_id = color.server_id;
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(server_id),
+            GetColorPresentations { color },
+            cx,
+        )
+    }
+
+    /// Requests the values to show inline in a range of a buffer from its primary language server.
+    pub fn inline_values(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        range: Range<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<InlineValue>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetInlineValues { range },
+            cx,
+        )
+    }
+
+    /// Requests the ranges of a buffer that can be folded from its primary language server.
+    pub fn folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    /// Requests the ranges a selection at each of the given positions could expand to from the
+    /// primary language server of a buffer, sorted by their start and with outer ranges first.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
+        let snapshot = buffer.read(cx).snapshot();
+        let positions = positions
+            .iter()
+            .map(|position| position.to_point_utf16(&snapshot))
+            .collect();
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
+    /// Asks the language server that produced a code lens for its command, if it is missing.
+    pub fn resolve_code_lens(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        lens: CodeLens,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeLens>> {
+        if lens.is_resolved() {
+            return Task::ready(Ok(lens));
+        }
+        let request = self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(lens.server_id),
+            ResolveCodeLens {
+                lens: lens.lsp_lens.clone(),
+            },
+            cx,
+        );
+        cx.spawn(|_, _| async move {
+            let lsp_lens = request.await?;
+            Ok(CodeLens {
+                lsp_lens: lsp_lens.unwrap_or(lens.lsp_lens),
+                ..lens
+            })
+        })
+    }
+
+    /// Runs the command of a resolved code lens through `workspace/executeCommand`.
+    pub fn apply_code_lens(
+        &self,
+        buffer_handle: Entity<Buffer>,
+        lens: CodeLens,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command else {
+            return Task::ready(Err(anyhow!("code lens has no command")));
+        };
+        let action = CodeAction {
+            server_id: lens.server_id,
+            range: lens.range,
+            lsp_action: lsp::CodeAction {
+                title: command.title.clone(),
+                command: Some(command),
+                ..lsp::CodeAction::default()
+            },
+        };
+        self.apply_code_action(buffer_handle, action, true, cx)
+    }
+
     fn prepare_rename_impl(
         &mut self,
         buffer: Entity<Buffer>,
@@ -3074,6 +3577,24 @@ impl Project {
         )
     }
 
+    /// Asks the primary language server of a buffer for the edits renaming the symbol at a
+    /// position would make, without making them.
+    pub fn rename_edits<T: ToPointUtf16>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<WorkspaceEditPreview>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer,
+            LanguageServerToQuery::Primary,
+            GetRenameEdits { position, new_name },
+            cx,
+        )
+    }
+
     pub fn on_type_format<T: ToPointUtf16>(
         &mut self,
         buffer: Entity<Buffer>,
@@ -3100,6 +3621,15 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<SemanticTokens>>> {
+        self.lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.semantic_tokens(buffer, cx))
+    }
+
     pub fn resolve_inlay_hint(
         &self,
         hint: InlayHint,
@@ -4285,6 +4815,41 @@ impl Project {
         &self.git_store
     }
 
+    pub fn bookmark_store(&self) -> &Entity<BookmarkStore> {
+        &self.bookmark_store
+    }
+
+    pub fn dap_store(&self) -> &Entity<DapStore> {
+        &self.dap_store
+    }
+
+    pub fn test_store(&self) -> &Entity<TestStore> {
+        &self.test_store
+    }
+
+    /// Starts debugging with the debug adapter that the given task spawns.
+    pub fn start_debug_session(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        config: DebugAdapterConfig,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<DebugSession>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "debugging is only supported in local projects"
+            )));
+        }
+        let binary = DebugAdapterBinary {
+            command: spawn_in_terminal.command,
+            arguments: spawn_in_terminal.args,
+            env: spawn_in_terminal.env,
+            cwd: spawn_in_terminal.cwd,
+        };
+        self.dap_store.update(cx, |dap_store, cx| {
+            dap_store.start_debug_session(spawn_in_terminal.label.into(), binary, config, cx)
+        })
+    }
+
     pub fn active_repository(&self, cx: &App) -> Option<Entity<Repository>> {
         self.git_store.read(cx).active_repository()
     }
@@ -2,7 +2,7 @@ use anyhow::Context as _;
 use collections::HashMap;
 use fs::Fs;
 use gpui::{App, AsyncApp, BorrowAppContext, Context, Entity, EventEmitter};
-use lsp::LanguageServerName;
+use lsp::{LanguageServerName, LanguageServerTransport};
 use paths::{
     local_settings_file_relative_path, local_tasks_file_relative_path,
     local_vscode_tasks_file_relative_path, EDITORCONFIG_NAME,
@@ -173,6 +173,9 @@ pub struct BinarySettings {
     pub path: Option<String>,
     pub arguments: Option<Vec<String>>,
     pub ignore_system_version: Option<bool>,
+    /// How to talk to the language server. When it listens on a socket and no path is set,
+    /// the server is expected to be running already and is only connected to.
+    pub transport: Option<LanguageServerTransport>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
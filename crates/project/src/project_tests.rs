@@ -1,5 +1,14 @@
 use crate::{Event, *};
 use buffer_diff::{assert_hunks, DiffHunkSecondaryStatus, DiffHunkStatus};
+use dap::{
+    events, requests,
+    types::{
+        ContinueResponse, OutputEventBody, SetBreakpointsResponse, StackFrame, StackTraceResponse,
+        StoppedEventBody, Thread, ThreadsResponse,
+    },
+    FakeAdapter,
+};
+use debug_session::{ConsoleEntry, ConsoleEntryKind, SessionStatus};
 use fs::FakeFs;
 use futures::{future, StreamExt};
 use gpui::{App, SemanticVersion, UpdateGlobal};
@@ -22,7 +31,7 @@ use std::os;
 use std::{str::FromStr, sync::OnceLock};
 
 use std::{mem, num::NonZeroU32, ops::Range, task::Poll};
-use task::{ResolvedTask, TaskContext};
+use task::{DebugAdapterConfig, DebugRequestType, LaunchConfig, ResolvedTask, TaskContext};
 use unindent::Unindent as _;
 use util::{
     assert_set_eq, path, paths::PathMatcher, separator, test::TempTree, uri, TryFutureExt as _,
@@ -1294,6 +1303,8 @@ async fn test_disk_based_diagnostics_progress(cx: &mut gpui::TestAppContext) {
         .start_progress(format!("{}/0", progress_token))
         .await;
     assert_eq!(events.next().await.unwrap(), Event::RefreshInlayHints);
+    assert_eq!(events.next().await.unwrap(), Event::RefreshSemanticTokens);
+    assert_eq!(events.next().await.unwrap(), Event::RefreshCodeLens);
     assert_eq!(
         events.next().await.unwrap(),
         Event::DiskBasedDiagnosticsStarted {
@@ -1427,6 +1438,8 @@ async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppC
         )
     );
     assert_eq!(events.next().await.unwrap(), Event::RefreshInlayHints);
+    assert_eq!(events.next().await.unwrap(), Event::RefreshSemanticTokens);
+    assert_eq!(events.next().await.unwrap(), Event::RefreshCodeLens);
     fake_server.start_progress(progress_token).await;
     assert_eq!(
         events.next().await.unwrap(),
@@ -2182,6 +2195,288 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_pulled_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "let one = 1;" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: Some("fake".to_string()),
+                        inter_file_dependencies: false,
+                        workspace_diagnostics: false,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // The server reports its diagnostics once, then tells they are unchanged.
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            assert_eq!(params.identifier.as_deref(), Some("fake"));
+            let mut previous_result_ids = previous_result_ids.lock();
+            previous_result_ids.push(params.previous_result_id.clone());
+            let result_id = previous_result_ids.len().to_string();
+            let report = match params.previous_result_id {
+                None => {
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some(result_id),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 4),
+                                    lsp::Position::new(0, 7),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                message: "pulled warning".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                }
+                Some(_) => lsp::DocumentDiagnosticReport::Unchanged(
+                    lsp::RelatedUnchangedDocumentDiagnosticReport {
+                        related_documents: None,
+                        unchanged_document_diagnostic_report:
+                            lsp::UnchangedDocumentDiagnosticReport { result_id },
+                    },
+                ),
+            };
+            async move { Ok(lsp::DocumentDiagnosticReportResult::Report(report)) }
+        }
+    });
+    let diagnostic_messages = |buffer: &Entity<Buffer>, cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| entry.diagnostic.message)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // The diagnostics of open documents are pulled once the server starts.
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(*previous_result_ids.lock(), [None]);
+    assert_eq!(diagnostic_messages(&buffer, cx), ["pulled warning"]);
+
+    // Edits pull the diagnostics again, sending the id of the last report.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(12..12, "\nlet two = 2;")], None, cx)
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(*previous_result_ids.lock(), [None, Some("1".to_string())]);
+    assert_eq!(diagnostic_messages(&buffer, cx), ["pulled warning"]);
+
+    // So does a refresh request, with the id of the unchanged report.
+    fake_server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *previous_result_ids.lock(),
+        [None, Some("1".to_string()), Some("2".to_string())]
+    );
+
+    // Published diagnostics are shown alongside the pulled ones.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: lsp::Url::from_file_path(path!("/dir/a.rs")).unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 3)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "pushed error".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        diagnostic_messages(&buffer, cx),
+        ["pulled warning", "pushed error"]
+    );
+
+    // Once the buffer is closed, its diagnostics are pulled from scratch.
+    drop(handle);
+    drop(buffer);
+    cx.executor().run_until_parked();
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(previous_result_ids.lock().last(), Some(&None));
+    assert!(diagnostic_messages(&buffer, cx).contains(&"pulled warning".to_string()));
+}
+
+#[gpui::test]
+async fn test_pulled_diagnostics_of_dependent_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "pub const A: u32 = 1;",
+            "b.rs": "const B: u32 = a::A;",
+            "c.rs": "const C: u32 = a::A;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: None,
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer_a, _handle_a) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let (_buffer_b, _handle_b) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let pulled_documents = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let pulled_documents = pulled_documents.clone();
+        move |params, _| {
+            pulled_documents
+                .lock()
+                .push(params.text_document.uri.clone());
+            async move {
+                Ok(lsp::DocumentDiagnosticReportResult::Report(
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: Default::default(),
+                    }),
+                ))
+            }
+        }
+    });
+    let mut workspace_pulls = fake_server
+        .handle_request::<lsp::request::WorkspaceDiagnosticRequest, _, _>(|_, _| async move {
+            Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                lsp::WorkspaceDiagnosticReport {
+                    items: vec![lsp::WorkspaceDocumentDiagnosticReport::Full(
+                        lsp::WorkspaceFullDocumentDiagnosticReport {
+                            uri: lsp::Url::from_file_path(path!("/dir/c.rs")).unwrap(),
+                            version: None,
+                            full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                                result_id: Some("1".to_string()),
+                                items: vec![lsp::Diagnostic {
+                                    range: lsp::Range::new(
+                                        lsp::Position::new(0, 15),
+                                        lsp::Position::new(0, 19),
+                                    ),
+                                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                    message: "A changed its type".to_string(),
+                                    ..Default::default()
+                                }],
+                            },
+                        },
+                    )],
+                },
+            ))
+        });
+
+    // Changing one document pulls the diagnostics of the other open ones, which may depend on it.
+    buffer_a.update(cx, |buffer, cx| buffer.edit([(19..20, "2")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert!(
+        matches!(workspace_pulls.try_next(), Ok(Some(()))),
+        "the workspace diagnostics are pulled once the server starts"
+    );
+    let mut pulled_documents = mem::take(&mut *pulled_documents.lock());
+    pulled_documents.sort();
+    assert_eq!(
+        pulled_documents,
+        [
+            lsp::Url::from_file_path(path!("/dir/a.rs")).unwrap(),
+            lsp::Url::from_file_path(path!("/dir/b.rs")).unwrap(),
+        ]
+    );
+
+    // Saving pulls the diagnostics of the whole workspace, including files that are not open.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer_a.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert!(matches!(workspace_pulls.try_next(), Ok(Some(()))));
+    let summaries = project.read_with(cx, |project, cx| {
+        project
+            .diagnostic_summaries(false, cx)
+            .map(|(path, _, summary)| (path.path.to_string_lossy().into_owned(), summary))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        summaries,
+        [(
+            "c.rs".to_string(),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 0,
+            }
+        )]
+    );
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4276,6 +4571,204 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_rename_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = project.update(cx, |project, cx| {
+        project.rename_edits(buffer.clone(), 7, "THREE".to_string(), cx)
+    });
+    fake_server
+        .handle_request::<lsp::request::Rename, _, _>(|_, _| async move {
+            let edit = |uri: &str, start, end| {
+                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                        uri: lsp::Url::from_file_path(uri).unwrap(),
+                        version: None,
+                    },
+                    edits: vec![lsp::OneOf::Left(lsp::TextEdit::new(
+                        lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end)),
+                        "THREE".to_string(),
+                    ))],
+                })
+            };
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                    edit(path!("/dir/one.rs"), 6, 9),
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+                        old_uri: lsp::Url::from_file_path(path!("/dir/two.rs")).unwrap(),
+                        new_uri: lsp::Url::from_file_path(path!("/dir/three.rs")).unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                    // Edits of renamed files refer to their new path.
+                    edit(path!("/dir/three.rs"), 24, 27),
+                ])),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    let preview = response.await.unwrap();
+
+    assert_eq!(
+        preview.file_operations,
+        vec![WorkspaceFileOperation::Rename {
+            old_path: PathBuf::from(path!("/dir/two.rs")),
+            new_path: PathBuf::from(path!("/dir/three.rs")),
+        }]
+    );
+    let edited = preview
+        .buffer_edits
+        .iter()
+        .map(|(buffer, edits)| {
+            buffer.read_with(cx, |buffer, _| {
+                let edits = edits
+                    .iter()
+                    .map(|(range, text)| (range.to_offset(buffer), text.clone()))
+                    .collect::<Vec<_>>();
+                (buffer.text(), edits)
+            })
+        })
+        .collect::<Vec<_>>();
+    // Nothing is edited until the preview is applied.
+    assert_eq!(
+        edited,
+        vec![
+            (
+                "const ONE: usize = 1;".to_string(),
+                vec![(6..9, "THREE".to_string())]
+            ),
+            (
+                "const TWO: usize = one::ONE;".to_string(),
+                vec![(24..27, "THREE".to_string())]
+            ),
+        ]
+    );
+    assert!(fs.is_file(path!("/dir/two.rs").as_ref()).await);
+}
+
+#[gpui::test]
+async fn test_rename_edits_of_same_document(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = project.update(cx, |project, cx| {
+        project.rename_edits(buffer.clone(), 7, "THREE".to_string(), cx)
+    });
+    fake_server
+        .handle_request::<lsp::request::Rename, _, _>(|_, _| async move {
+            let edit = |start, end, new_text: &str| lsp::TextDocumentEdit {
+                text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path!("/dir/one.rs")).unwrap(),
+                    version: None,
+                },
+                edits: vec![lsp::OneOf::Left(lsp::TextEdit::new(
+                    lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end)),
+                    new_text.to_string(),
+                ))],
+            };
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Edits(vec![
+                    edit(6, 9, "THREE"),
+                    // The second edit refers to the text left by the first one.
+                    edit(21, 22, "3"),
+                ])),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    let preview = response.await.unwrap();
+
+    let edited = preview
+        .buffer_edits
+        .iter()
+        .map(|(buffer, edits)| {
+            buffer.read_with(cx, |buffer, _| {
+                edits
+                    .iter()
+                    .map(|(range, text)| (range.to_offset(buffer), text.clone()))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edited,
+        vec![vec![(6..9, "THREE".to_string()), (19..20, "3".to_string())]]
+    );
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(preview.buffer_edits[0].1.clone(), None, cx);
+        assert_eq!(buffer.text(), "const THREE: usize = 3;");
+    });
+}
+
 #[gpui::test]
 async fn test_search(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5922,6 +6415,327 @@ async fn test_single_file_diffs(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_repository_remote_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    // Each operation writes a real askpass script to a temporary directory.
+    cx.executor().allow_parking();
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "src": {
+                "main.rs": "fn main() {}",
+            }
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/dir/.git");
+    fs.insert_branches(dot_git, &["main"]);
+    fs.with_git_state(dot_git, true, |state| {
+        state.remotes.insert("origin".to_string());
+        state.remote_progress = vec![
+            "Enumerating objects: 5, done.".to_string(),
+            "Writing objects: 100% (3/3), 290 bytes | 290.00 KiB/s, done.".to_string(),
+        ];
+    });
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+    let askpass = askpass::AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+
+    let (progress_tx, progress_rx) = futures::channel::mpsc::unbounded();
+    let output = repository
+        .update(cx, |repository, cx| {
+            repository.push(
+                "main".into(),
+                "origin".into(),
+                None,
+                askpass.clone(),
+                progress_tx,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        output.stderr,
+        "Enumerating objects: 5, done.\nWriting objects: 100% (3/3), 290 bytes | 290.00 KiB/s, done."
+    );
+    assert_eq!(
+        progress_rx.collect::<Vec<_>>().await,
+        [
+            "Enumerating objects: 5, done.",
+            "Writing objects: 100% (3/3), 290 bytes | 290.00 KiB/s, done.",
+        ]
+    );
+
+    let (progress_tx, progress_rx) = futures::channel::mpsc::unbounded();
+    repository
+        .update(cx, |repository, cx| {
+            repository.fetch(askpass.clone(), progress_tx, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(progress_rx.collect::<Vec<_>>().await.len(), 2);
+
+    let (progress_tx, progress_rx) = futures::channel::mpsc::unbounded();
+    let error = repository
+        .update(cx, |repository, cx| {
+            repository.pull(
+                "main".into(),
+                "upstream".into(),
+                askpass.clone(),
+                progress_tx,
+                cx,
+            )
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "no such remote: upstream");
+    assert!(progress_rx.collect::<Vec<_>>().await.is_empty());
+
+    let (progress_tx, _progress_rx) = futures::channel::mpsc::unbounded();
+    let error = repository
+        .update(cx, |repository, cx| {
+            repository.push(
+                "feature".into(),
+                "origin".into(),
+                None,
+                askpass.clone(),
+                progress_tx,
+                cx,
+            )
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "no such branch: feature");
+}
+
+#[gpui::test]
+async fn test_repository_stash_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "a",
+            "b.txt": "b",
+        }),
+    )
+    .await;
+    fs.insert_branches(Path::new("/dir/.git"), &["main"]);
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+    let stash_entries = |cx: &mut gpui::TestAppContext| {
+        repository.update(cx, |repository, cx| repository.stash_entries(cx))
+    };
+
+    for message in ["first", "second"] {
+        repository
+            .update(cx, |repository, _| {
+                repository.stash_paths(Some(message.into()), Vec::new())
+            })
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    let entries = stash_entries(cx).await.unwrap();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.index, entry.message.as_ref()))
+            .collect::<Vec<_>>(),
+        [(0, "On main: second"), (1, "On main: first")]
+    );
+
+    // Dropping the older entry by sha leaves the newer one in place, and the
+    // remaining entry's index is updated.
+    let first_sha = entries[1].sha.clone();
+    repository
+        .update(cx, |repository, _| repository.stash_drop(first_sha.clone()))
+        .await
+        .unwrap()
+        .unwrap();
+    let remaining = stash_entries(cx).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].index, 0);
+    assert_eq!(remaining[0].sha, entries[0].sha);
+
+    // An entry that is no longer in the stash can't be applied, even though
+    // its old index is still valid.
+    let error = repository
+        .update(cx, |repository, _| {
+            repository.stash_apply(first_sha.clone())
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!("no such stash entry: {first_sha}")
+    );
+}
+
+#[gpui::test]
+async fn test_debug_session(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "main.rs": "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let dap_store = project.read_with(cx, |project, _| project.dap_store().clone());
+    let breakpoint_position =
+        buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(1, 4)));
+
+    // Breakpoints set before the session starts are sent once the adapter is initialized.
+    dap_store.update(cx, |dap_store, cx| {
+        dap_store.toggle_breakpoint(buffer.clone(), breakpoint_position, cx)
+    });
+    let client_id = dap_store.update(cx, |dap_store, _| dap_store.next_client_id());
+    let (client, fake) = FakeAdapter::new(client_id, cx.to_async());
+    let mut set_breakpoints_requests =
+        fake.handle_request::<requests::SetBreakpoints, _, _>(|_, _| async move {
+            Ok(SetBreakpointsResponse::default())
+        });
+    let mut configuration_done_requests =
+        fake.handle_request::<requests::ConfigurationDone, _, _>(|_, _| async move { Ok(()) });
+
+    let start_session = dap_store.update(cx, |dap_store, cx| {
+        dap_store.start_session_with_client(
+            client,
+            DebugAdapterConfig {
+                request: DebugRequestType::Launch(LaunchConfig {
+                    program: "main".into(),
+                    args: Vec::new(),
+                }),
+                stop_on_entry: false,
+            },
+            cx,
+        )
+    });
+    cx.run_until_parked();
+    assert!(set_breakpoints_requests.try_next().is_err());
+
+    fake.send_event::<events::Initialized>(None);
+    let session = start_session.await.unwrap();
+    let arguments = set_breakpoints_requests.next().await.unwrap();
+    assert_eq!(
+        arguments.source.path.as_deref(),
+        Some(path!("/dir/main.rs"))
+    );
+    assert_eq!(
+        arguments
+            .breakpoints
+            .unwrap()
+            .iter()
+            .map(|breakpoint| breakpoint.line)
+            .collect::<Vec<_>>(),
+        vec![2]
+    );
+    assert!(configuration_done_requests.next().await.is_some());
+    session.read_with(cx, |session, _| {
+        assert_eq!(session.status(), SessionStatus::Running)
+    });
+
+    // Stopping fetches the threads, and the call stack of the thread that stopped.
+    fake.handle_request::<requests::Threads, _, _>(|_, _| async move {
+        Ok(ThreadsResponse {
+            threads: vec![Thread {
+                id: 1,
+                name: "main".into(),
+            }],
+        })
+    });
+    fake.handle_request::<requests::StackTrace, _, _>(|arguments, _| async move {
+        assert_eq!(arguments.thread_id, 1);
+        Ok(StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 7,
+                name: "main".into(),
+                line: 2,
+                column: 5,
+                ..Default::default()
+            }],
+            total_frames: None,
+        })
+    });
+    fake.send_event::<events::Stopped>(StoppedEventBody {
+        reason: "breakpoint".into(),
+        thread_id: Some(1),
+        ..Default::default()
+    });
+    cx.run_until_parked();
+    session.read_with(cx, |session, _| {
+        assert_eq!(session.status(), SessionStatus::Stopped);
+        assert_eq!(session.active_thread(), Some(1));
+        assert_eq!(session.selected_frame().map(|frame| frame.id), Some(7));
+    });
+
+    // Removing the breakpoint during the session clears it in the adapter.
+    dap_store.update(cx, |dap_store, cx| {
+        dap_store.toggle_breakpoint(buffer.clone(), breakpoint_position, cx)
+    });
+    let arguments = set_breakpoints_requests.next().await.unwrap();
+    assert_eq!(arguments.breakpoints, Some(Vec::new()));
+    dap_store.read_with(cx, |dap_store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        assert!(dap_store
+            .breakpoint_rows(snapshot.remote_id(), &snapshot)
+            .is_empty());
+    });
+
+    let mut continue_requests =
+        fake.handle_request::<requests::Continue, _, _>(|_, _| async move {
+            Ok(ContinueResponse::default())
+        });
+    session
+        .update(cx, |session, cx| session.continue_thread(cx))
+        .await
+        .unwrap();
+    assert!(continue_requests.next().await.is_some());
+    session.read_with(cx, |session, _| {
+        assert_eq!(session.status(), SessionStatus::Running);
+        assert!(session.stack_frames().is_empty());
+    });
+
+    fake.send_event::<events::Output>(OutputEventBody {
+        category: Some("stdout".into()),
+        output: "1\n".into(),
+    });
+    fake.send_event::<events::Terminated>(None);
+    cx.run_until_parked();
+    session.read_with(cx, |session, _| {
+        assert_eq!(session.status(), SessionStatus::Exited);
+        assert_eq!(
+            session.console(),
+            &[ConsoleEntry {
+                kind: ConsoleEntryKind::Output,
+                text: "1".into(),
+            }]
+        );
+    });
+}
+
 async fn search(
     project: &Entity<Project>,
     query: SearchQuery,
@@ -15,7 +15,8 @@ use itertools::Itertools;
 use language::{ContextProvider, File, Language, LanguageToolchainStore, Location};
 use settings::{parse_json_with_comments, SettingsLocation};
 use task::{
-    ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables, VariableName,
+    DependsOrder, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables,
+    VariableName,
 };
 use text::{Point, ToPoint};
 use util::{paths::PathExt as _, post_inc, NumericPrefixWithSuffix, ResultExt as _};
@@ -242,6 +243,42 @@ impl Inventory {
         self.last_scheduled_tasks.retain(|(_, task)| &task.id != id);
     }
 
+    /// Resolves the tasks that have to run before and after the given one, in the context the task got resolved with.
+    /// Returns stages to run one after another, each consisting of tasks that may run in parallel; the given task is in one of them.
+    /// Fails if a task in the dependency graph is unknown, cannot be resolved or depends on itself.
+    pub fn resolve_dependencies(
+        &self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+    ) -> Result<Vec<Vec<(TaskSourceKind, ResolvedTask)>>> {
+        let worktree = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let task_context = resolved_task.task_context().clone();
+        let mut resolver = DependencyResolver {
+            inventory: self,
+            worktree,
+            task_context: &task_context,
+            stack: Vec::new(),
+            scheduled: HashMap::default(),
+            stages: Vec::new(),
+        };
+        resolver.schedule(task_source_kind, resolved_task, 0)?;
+        Ok(resolver.stages)
+    }
+
+    /// Finds a task a dependency label refers to, preferring the tasks of the worktree given.
+    fn dependency_template(
+        &self,
+        label: &str,
+        worktree: Option<WorktreeId>,
+    ) -> Option<(TaskSourceKind, TaskTemplate)> {
+        self.worktree_templates_from_settings(worktree)
+            .chain(self.global_templates_from_settings())
+            .find(|(_, template)| template.label == label)
+    }
+
     fn global_templates_from_settings(
         &self,
     ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
@@ -328,6 +365,90 @@ impl Inventory {
     }
 }
 
+struct DependencyResolver<'a> {
+    inventory: &'a Inventory,
+    worktree: Option<WorktreeId>,
+    task_context: &'a TaskContext,
+    /// Labels of the tasks being scheduled, outermost first, to detect cycles.
+    stack: Vec<String>,
+    /// Dependencies scheduled so far, with the stage they are finished after.
+    scheduled: HashMap<TaskId, usize>,
+    stages: Vec<Vec<(TaskSourceKind, ResolvedTask)>>,
+}
+
+impl DependencyResolver<'_> {
+    /// Schedules the task no earlier than the given stage, after its dependencies and pre-tasks, and schedules its post-tasks after it.
+    /// Returns the stage after which the task and all of its post-tasks are finished.
+    fn schedule(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        earliest_stage: usize,
+    ) -> Result<usize> {
+        let template = resolved_task.original_task().clone();
+        self.stack.push(template.label.clone());
+
+        let mut stage = earliest_stage;
+        let mut next_dependency_stage = earliest_stage;
+        for label in &template.depends_on {
+            let finished_stage = self.schedule_dependency(label, next_dependency_stage)?;
+            if template.depends_order == DependsOrder::Sequence {
+                next_dependency_stage = next_dependency_stage.max(finished_stage + 1);
+            }
+            stage = stage.max(finished_stage + 1);
+        }
+        for label in &template.pre_tasks {
+            let (kind, task) = self.resolve_label(label)?;
+            stage = self.schedule(kind, task, stage)? + 1;
+        }
+
+        if self.stages.len() <= stage {
+            self.stages.resize_with(stage + 1, Vec::new);
+        }
+        self.stages[stage].push((task_source_kind, resolved_task));
+
+        let mut finished_stage = stage;
+        for label in &template.post_tasks {
+            let (kind, task) = self.resolve_label(label)?;
+            finished_stage = self.schedule(kind, task, finished_stage + 1)?;
+        }
+
+        self.stack.pop();
+        Ok(finished_stage)
+    }
+
+    /// Dependencies run once, however many tasks depend on them.
+    fn schedule_dependency(&mut self, label: &str, earliest_stage: usize) -> Result<usize> {
+        let (kind, task) = self.resolve_label(label)?;
+        if let Some(finished_stage) = self.scheduled.get(&task.id) {
+            return Ok(*finished_stage);
+        }
+        let id = task.id.clone();
+        let finished_stage = self.schedule(kind, task, earliest_stage)?;
+        self.scheduled.insert(id, finished_stage);
+        Ok(finished_stage)
+    }
+
+    fn resolve_label(&self, label: &str) -> Result<(TaskSourceKind, ResolvedTask)> {
+        if let Some(ix) = self.stack.iter().position(|scheduled| scheduled == label) {
+            let cycle = self.stack[ix..]
+                .iter()
+                .map(String::as_str)
+                .chain([label])
+                .join(" -> ");
+            anyhow::bail!("task dependency cycle: {cycle}");
+        }
+        let (kind, template) = self
+            .inventory
+            .dependency_template(label, self.worktree)
+            .with_context(|| format!("unknown task `{label}`"))?;
+        let task = template
+            .resolve_task(&kind.to_id_base(), self.task_context)
+            .with_context(|| format!("resolving task `{label}`"))?;
+        Ok((kind, task))
+    }
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -851,6 +972,101 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_resolving_task_dependencies(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(Inventory::new);
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    None,
+                    Some(
+                        &json!([
+                            { "label": "build", "command": "cargo build" },
+                            { "label": "lint", "command": "cargo clippy" },
+                            { "label": "notify", "command": "notify-send done" },
+                            { "label": "test", "command": "cargo test", "depends_on": ["build"] },
+                            {
+                                "label": "check",
+                                "depends_on": ["build", "test", "lint"],
+                                "depends_order": "parallel",
+                                "post_tasks": ["notify"]
+                            },
+                            {
+                                "label": "release",
+                                "command": "cargo build --release",
+                                "depends_on": ["lint", "test"],
+                                "pre_tasks": ["notify"]
+                            },
+                            { "label": "a", "command": "echo a", "depends_on": ["b"] },
+                            { "label": "b", "command": "echo b", "depends_on": ["a"] },
+                            { "label": "broken", "command": "echo", "depends_on": ["missing"] }
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let resolve = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, cx| {
+                let (kind, template) = inventory
+                    .list_tasks(None, None, None, cx)
+                    .into_iter()
+                    .find(|(_, template)| template.label == label)
+                    .unwrap();
+                let task = template
+                    .resolve_task(&kind.to_id_base(), &TaskContext::default())
+                    .unwrap();
+                inventory
+                    .resolve_dependencies(kind, task)
+                    .map(|stages| {
+                        stages
+                            .into_iter()
+                            .map(|stage| {
+                                stage
+                                    .into_iter()
+                                    .map(|(_, task)| task.resolved_label)
+                                    .collect::<Vec<_>>()
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .map_err(|error| error.to_string())
+            })
+        };
+
+        assert_eq!(resolve("build", cx), Ok(vec![vec!["build".to_string()]]));
+        assert_eq!(
+            resolve("check", cx),
+            Ok(vec![
+                vec!["build".to_string(), "lint".to_string()],
+                vec!["test".to_string()],
+                vec!["check".to_string()],
+                vec!["notify".to_string()],
+            ]),
+            "parallel dependencies should run together, but after their own dependencies"
+        );
+        assert_eq!(
+            resolve("release", cx),
+            Ok(vec![
+                vec!["lint".to_string()],
+                vec!["build".to_string()],
+                vec!["test".to_string()],
+                vec!["notify".to_string()],
+                vec!["release".to_string()],
+            ]),
+            "sequential dependencies should run one after another, pre-tasks right before the task"
+        );
+        assert_eq!(
+            resolve("a", cx),
+            Err("task dependency cycle: a -> b -> a".to_string())
+        );
+        assert_eq!(
+            resolve("broken", cx),
+            Err("unknown task `missing`".to_string())
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         if std::env::var("RUST_LOG").is_ok() {
             env_logger::try_init().ok();
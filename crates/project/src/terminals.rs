@@ -1,9 +1,10 @@
-use crate::Project;
+use crate::{Project, ProjectPath, TaskOutputValue};
 use anyhow::{Context as _, Result};
 use collections::HashMap;
+use futures::StreamExt as _;
 use gpui::{AnyWindowHandle, App, AppContext as _, Context, Entity, Task, WeakEntity};
 use itertools::Itertools;
-use language::LanguageName;
+use language::{Buffer, LanguageName, Point};
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
@@ -13,11 +14,16 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use task::{Shell, ShellBuilder, SpawnInTerminal};
+use task::{
+    OutputValueMatcher, OutputValueParser, ProblemMatcher, ProblemParser, Shell, ShellBuilder,
+    SpawnInTerminal,
+};
 use terminal::{
     terminal_settings::{self, TerminalSettings, VenvSettings},
-    TaskState, TaskStatus, Terminal, TerminalBuilder,
+    Event as TerminalEvent, TaskOutput, TaskReadiness, TaskState, TaskStatus, Terminal,
+    TerminalBuilder,
 };
+use text::BufferId;
 use util::ResultExt;
 
 // #[cfg(target_os = "macos")]
@@ -25,6 +31,8 @@ use util::ResultExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    /// The values tasks printed for lines of each buffer, with the buffers kept open to show them.
+    pub(crate) task_output_values: HashMap<BufferId, (Entity<Buffer>, Vec<TaskOutputValue>)>,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -229,6 +237,8 @@ impl Project {
         };
 
         let mut python_venv_activate_command = None;
+        let mut task_problem_matchers = None;
+        let mut task_output_value_matchers = None;
 
         let (spawn_task, shell) = match kind {
             TerminalKind::Shell(_) => {
@@ -264,6 +274,13 @@ impl Project {
                 }
             }
             TerminalKind::Task(spawn_task) => {
+                if !spawn_task.problem_matchers.is_empty() && this.is_local() {
+                    task_problem_matchers =
+                        Some((spawn_task.label.clone(), spawn_task.problem_matchers));
+                }
+                if !spawn_task.output_values.is_empty() && this.is_local() {
+                    task_output_value_matchers = Some(spawn_task.output_values);
+                }
                 let task_state = Some(TaskState {
                     id: spawn_task.id,
                     full_label: spawn_task.full_label,
@@ -274,6 +291,8 @@ impl Project {
                     show_summary: spawn_task.show_summary,
                     show_command: spawn_task.show_command,
                     completion_rx,
+                    readiness: spawn_task.is_background.then_some(TaskReadiness::Busy),
+                    background_pattern: spawn_task.background_pattern,
                 });
 
                 env.extend(spawn_task.env);
@@ -359,13 +378,227 @@ impl Project {
             })
             .detach();
 
+            // Let the project observers, like the activity indicator, know about background tasks getting ready.
+            cx.subscribe(&terminal_handle, |_, _, event, cx| {
+                if let TerminalEvent::TaskReadinessChanged = event {
+                    cx.notify();
+                }
+            })
+            .detach();
+
             if let Some(activate_command) = python_venv_activate_command {
                 this.activate_python_virtual_environment(activate_command, &terminal_handle, cx);
             }
+            if let Some(output_value_matchers) = task_output_value_matchers {
+                this.capture_task_output_values(
+                    &output_value_matchers,
+                    path.clone(),
+                    &terminal_handle,
+                    cx,
+                );
+            }
+            if let Some((task_label, problem_matchers)) = task_problem_matchers {
+                this.publish_task_problems(
+                    task_label,
+                    &problem_matchers,
+                    path,
+                    &terminal_handle,
+                    cx,
+                );
+            }
             terminal_handle
         })
     }
 
+    /// Publishes the problems the output of the task in the terminal reports as diagnostics,
+    /// updating them as the output arrives. Only local projects run problem matchers, since the
+    /// output of remote tasks is not available on this side.
+    fn publish_task_problems(
+        &self,
+        task_label: String,
+        problem_matchers: &[ProblemMatcher],
+        cwd: Option<Arc<Path>>,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        let mut parser = match ProblemParser::new(problem_matchers) {
+            Ok(parser) => parser,
+            Err(e) => {
+                log::error!("Failed to set up problem matchers of task {task_label}: {e:#}");
+                return;
+            }
+        };
+        let Some(output) = terminal.update(cx, |terminal, _| terminal.subscribe_to_task_output())
+        else {
+            return;
+        };
+        let problem_matchers = problem_matchers.to_vec();
+        cx.spawn(|this, mut cx| async move {
+            let mut output = output.ready_chunks(1024);
+            let mut problems = Vec::new();
+            while let Some(chunk) = output.next().await {
+                let mut changed = false;
+                for output in chunk {
+                    match output {
+                        TaskOutput::Line(line) => {
+                            let new_problems = parser.push_line(&line);
+                            changed |= !new_problems.is_empty();
+                            problems.extend(new_problems);
+                        }
+                        TaskOutput::CycleStarted => {
+                            parser = ProblemParser::new(&problem_matchers)?;
+                            changed |= !problems.is_empty();
+                            problems.clear();
+                        }
+                    }
+                }
+                if changed {
+                    this.update(&mut cx, |this, cx| {
+                        this.lsp_store.update(cx, |lsp_store, cx| {
+                            lsp_store.update_task_diagnostics(
+                                task_label.clone(),
+                                cwd.as_deref(),
+                                problems.clone(),
+                                cx,
+                            )
+                        })
+                    })??;
+                }
+            }
+            // Publish even when no problems were found, to clear those of the previous run.
+            this.update(&mut cx, |this, cx| {
+                this.lsp_store.update(cx, |lsp_store, cx| {
+                    lsp_store.update_task_diagnostics(task_label, cwd.as_deref(), problems, cx)
+                })
+            })?
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Once the task in the terminal finishes, shows the values its output prints for source lines
+    /// next to those lines, replacing the values previously shown for the same buffers.
+    fn capture_task_output_values(
+        &self,
+        matchers: &[OutputValueMatcher],
+        cwd: Option<Arc<Path>>,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        let parser = match OutputValueParser::new(matchers) {
+            Ok(parser) => parser,
+            Err(e) => {
+                log::error!("Failed to set up output value matchers: {e:#}");
+                return;
+            }
+        };
+        let output = terminal.update(cx, |terminal, cx| terminal.collect_task_output(cx));
+        cx.spawn(|this, mut cx| async move {
+            let output = output.await;
+            let values = parser.parse(output.iter().map(String::as_str));
+
+            let mut values_by_path = HashMap::<ProjectPath, Vec<task::OutputValue>>::default();
+            this.update(&mut cx, |this, cx| {
+                for value in values {
+                    if let Some(project_path) =
+                        this.resolve_task_output_path(&value.path, cwd.as_deref(), cx)
+                    {
+                        values_by_path.entry(project_path).or_default().push(value);
+                    }
+                }
+            })?;
+
+            for (project_path, values) in values_by_path {
+                let buffer = this
+                    .update(&mut cx, |this, cx| this.open_buffer(project_path, cx))?
+                    .await?;
+                this.update(&mut cx, |this, cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let values = values
+                        .into_iter()
+                        .filter(|value| value.row <= snapshot.max_point().row)
+                        .map(|value| TaskOutputValue {
+                            position: snapshot.anchor_after(Point::new(value.row, 0)),
+                            expression: value.expression,
+                            value: value.value,
+                        })
+                        .collect();
+                    this.set_task_output_values(buffer, values, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Finds the file a path printed by a task refers to. Relative paths are tried against the
+    /// task's directory and its ancestors, as tools like `cargo` print them relative to the
+    /// workspace root, and then against the roots of the worktrees.
+    fn resolve_task_output_path(
+        &self,
+        path: &str,
+        cwd: Option<&Path>,
+        cx: &App,
+    ) -> Option<ProjectPath> {
+        let path = Path::new(path);
+        let candidates = if path.is_absolute() {
+            vec![path.to_path_buf()]
+        } else {
+            cwd.into_iter()
+                .flat_map(|cwd| cwd.ancestors())
+                .map(|dir| dir.join(path))
+                .chain(
+                    self.visible_worktrees(cx)
+                        .map(|worktree| worktree.read(cx).abs_path().join(path)),
+                )
+                .collect()
+        };
+        candidates.into_iter().find_map(|abs_path| {
+            let (worktree, relative_path) = self.find_worktree(&abs_path, cx)?;
+            let worktree = worktree.read(cx);
+            worktree
+                .entry_for_path(&relative_path)?
+                .is_file()
+                .then(|| ProjectPath {
+                    worktree_id: worktree.id(),
+                    path: relative_path.into(),
+                })
+        })
+    }
+
+    /// The values that tasks printed for lines of a buffer.
+    pub fn task_output_values(&self, buffer_id: BufferId) -> &[TaskOutputValue] {
+        self.terminals
+            .task_output_values
+            .get(&buffer_id)
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn set_task_output_values(
+        &mut self,
+        buffer: Entity<Buffer>,
+        values: Vec<TaskOutputValue>,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        self.terminals
+            .task_output_values
+            .insert(buffer_id, (buffer, values));
+        cx.emit(crate::Event::TaskOutputValuesChanged(buffer_id));
+    }
+
+    /// Stops showing the values that tasks printed for lines of a buffer.
+    pub fn clear_task_output_values(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        if self
+            .terminals
+            .task_output_values
+            .remove(&buffer_id)
+            .is_some()
+        {
+            cx.emit(crate::Event::TaskOutputValuesChanged(buffer_id));
+        }
+    }
+
     fn python_venv_directory(
         &self,
         abs_path: Arc<Path>,
@@ -512,6 +745,20 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakEntity<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Labels of the running background tasks that are not ready yet.
+    pub fn busy_background_tasks(&self, cx: &App) -> Vec<String> {
+        self.terminals
+            .local_handles
+            .iter()
+            .filter_map(|terminal| {
+                let terminal = terminal.upgrade()?;
+                let task = terminal.read(cx).task()?;
+                (task.status == TaskStatus::Running && task.readiness == Some(TaskReadiness::Busy))
+                    .then(|| task.label.clone())
+            })
+            .collect()
+    }
 }
 
 fn wrap_for_ssh(
@@ -0,0 +1,77 @@
+use collections::HashMap;
+use gpui::{Context, Entity, EventEmitter};
+use language::{Buffer, BufferSnapshot, Point, ToPoint as _};
+use text::{Anchor, BufferId};
+
+/// The outcome of the last run of a test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TestStatus {
+    Running,
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestStoreEvent {
+    StatusesChanged(BufferId),
+}
+
+/// Keeps track of the results of the tests run in the project, by the location of each test.
+#[derive(Default)]
+pub struct TestStore {
+    /// Anchors at the start of the row of each test, with the status of the test.
+    statuses: HashMap<BufferId, Vec<(Anchor, TestStatus)>>,
+}
+
+impl EventEmitter<TestStoreEvent> for TestStore {}
+
+impl TestStore {
+    /// Rows of the given buffer that have a test with a known status, in ascending order.
+    pub fn test_status_rows(
+        &self,
+        buffer_id: BufferId,
+        snapshot: &BufferSnapshot,
+    ) -> Vec<(u32, TestStatus)> {
+        let Some(statuses) = self.statuses.get(&buffer_id) else {
+            return Vec::new();
+        };
+        let mut rows = statuses
+            .iter()
+            .map(|(position, status)| (position.to_point(snapshot).row, *status))
+            .collect::<Vec<_>>();
+        rows.sort_unstable_by_key(|(row, _)| *row);
+        rows.dedup_by_key(|(row, _)| *row);
+        rows
+    }
+
+    /// Records the status of the test on the row containing `position`, or forgets it if
+    /// `status` is `None`.
+    pub fn set_test_status(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: Anchor,
+        status: Option<TestStatus>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let buffer_id = snapshot.remote_id();
+        let row = position.to_point(&snapshot).row;
+        let statuses = self.statuses.entry(buffer_id).or_default();
+        statuses.retain(|(position, _)| position.to_point(&snapshot).row != row);
+        if let Some(status) = status {
+            statuses.push((snapshot.anchor_before(Point::new(row, 0)), status));
+        }
+
+        cx.emit(TestStoreEvent::StatusesChanged(buffer_id));
+        cx.notify();
+    }
+
+    /// Forgets the statuses of all tests.
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        for buffer_id in self.statuses.drain().map(|(buffer_id, _)| buffer_id) {
+            cx.emit(TestStoreEvent::StatusesChanged(buffer_id));
+        }
+        cx.notify();
+    }
+}
@@ -445,6 +445,66 @@ messages!(
     (GitShow, Background),
     (GitCommitDetails, Background),
     (SetIndexText, Background),
+    (Push, Background),
+    (Fetch, Background),
+    (GetRemotes, Background),
+    (GetRemotesResponse, Background),
+    (Pull, Background),
+    (RemoteMessageResponse, Background),
+    (AskPassRequest, Background),
+    (AskPassResponse, Background),
+    (RemoteProgress, Background),
+    (StashPush, Background),
+    (StashApply, Background),
+    (StashPop, Background),
+    (StashDrop, Background),
+    (GetStashEntries, Background),
+    (GetStashEntriesResponse, Background),
+    (GetStashChanges, Background),
+    (GetStashChangesResponse, Background),
+    (GetLog, Background),
+    (GetLogResponse, Background),
+    (GetCommitChanges, Background),
+    (GetCommitChangesResponse, Background),
+    (GetRebaseTodo, Background),
+    (GetRebaseTodoResponse, Background),
+    (RebaseInteractive, Background),
+    (RebaseContinue, Background),
+    (RebaseAbort, Background),
+    (GetRebaseStatus, Background),
+    (GetRebaseStatusResponse, Background),
+    (AmendCommitMessage, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensDelta, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Foreground),
+    (PrepareCallHierarchy, Background),
+    (PrepareTypeHierarchy, Background),
+    (GetSupertypes, Background),
+    (GetSubtypes, Background),
+    (HierarchyItemsResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetOutgoingCalls, Background),
+    (HierarchyCallsResponse, Background),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
+    (RefreshCodeLens, Foreground),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
+    (GetRenameEdits, Background),
+    (GetRenameEditsResponse, Background),
+    (GetInlineValues, Background),
+    (GetInlineValuesResponse, Background),
+    (GetDocumentColors, Background),
+    (GetDocumentColorsResponse, Background),
+    (GetColorPresentations, Background),
+    (GetColorPresentationsResponse, Background),
 );
 
 request_messages!(
@@ -582,6 +642,45 @@ request_messages!(
     (GitReset, Ack),
     (GitCheckoutFiles, Ack),
     (SetIndexText, Ack),
+    (Push, RemoteMessageResponse),
+    (Fetch, RemoteMessageResponse),
+    (GetRemotes, GetRemotesResponse),
+    (Pull, RemoteMessageResponse),
+    (AskPassRequest, AskPassResponse),
+    (RemoteProgress, Ack),
+    (StashPush, Ack),
+    (StashApply, Ack),
+    (StashPop, Ack),
+    (StashDrop, Ack),
+    (GetStashEntries, GetStashEntriesResponse),
+    (GetStashChanges, GetStashChangesResponse),
+    (GetLog, GetLogResponse),
+    (GetCommitChanges, GetCommitChangesResponse),
+    (GetRebaseTodo, GetRebaseTodoResponse),
+    (RebaseInteractive, Ack),
+    (RebaseContinue, Ack),
+    (RebaseAbort, Ack),
+    (GetRebaseStatus, GetRebaseStatusResponse),
+    (AmendCommitMessage, Ack),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (GetSemanticTokensDelta, GetSemanticTokensResponse),
+    (RefreshSemanticTokens, Ack),
+    (PrepareCallHierarchy, HierarchyItemsResponse),
+    (PrepareTypeHierarchy, HierarchyItemsResponse),
+    (GetSupertypes, HierarchyItemsResponse),
+    (GetSubtypes, HierarchyItemsResponse),
+    (GetIncomingCalls, HierarchyCallsResponse),
+    (GetOutgoingCalls, HierarchyCallsResponse),
+    (GetCodeLens, GetCodeLensResponse),
+    (ResolveCodeLens, ResolveCodeLensResponse),
+    (RefreshCodeLens, Ack),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
+    (GetRenameEdits, GetRenameEditsResponse),
+    (GetInlineValues, GetInlineValuesResponse),
+    (GetDocumentColors, GetDocumentColorsResponse),
+    (GetColorPresentations, GetColorPresentationsResponse),
 );
 
 entity_messages!(
@@ -678,6 +777,45 @@ entity_messages!(
     GitReset,
     GitCheckoutFiles,
     SetIndexText,
+    Push,
+    Fetch,
+    GetRemotes,
+    Pull,
+    AskPassRequest,
+    RemoteProgress,
+    StashPush,
+    StashApply,
+    StashPop,
+    StashDrop,
+    GetStashEntries,
+    GetStashChanges,
+    GetLog,
+    GetCommitChanges,
+    GetRebaseTodo,
+    RebaseInteractive,
+    RebaseContinue,
+    RebaseAbort,
+    GetRebaseStatus,
+    AmendCommitMessage,
+    GetSemanticTokens,
+    GetSemanticTokensDelta,
+    RefreshSemanticTokens,
+    PrepareCallHierarchy,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    GetCodeLens,
+    ResolveCodeLens,
+    RefreshCodeLens,
+    GetDocumentLinks,
+    GetFoldingRanges,
+    GetSelectionRanges,
+    GetRenameEdits,
+    GetInlineValues,
+    GetDocumentColors,
+    GetColorPresentations,
 );
 
 entity_messages!(
@@ -87,8 +87,12 @@ impl HeadlessProject {
             buffer_store
         });
 
-        let git_store =
-            cx.new(|cx| GitStore::new(&worktree_store, buffer_store.clone(), None, None, cx));
+        let git_store = cx.new(|cx| {
+            let mut git_store =
+                GitStore::new(&worktree_store, buffer_store.clone(), None, None, cx);
+            git_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
+            git_store
+        });
         let prettier_store = cx.new(|cx| {
             PrettierStore::new(
                 node_runtime.clone(),
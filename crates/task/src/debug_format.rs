@@ -0,0 +1,69 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Turns a task into a debug session: the task's command is started as a debug adapter, which is
+/// then asked to launch or attach to the program being debugged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DebugAdapterConfig {
+    /// Whether to launch a new program, or attach to a running one.
+    #[serde(flatten)]
+    pub request: DebugRequestType,
+    /// Whether to stop at the entry point of the program, before any breakpoint is hit.
+    #[serde(default)]
+    pub stop_on_entry: bool,
+}
+
+/// How the debug adapter should start debugging.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum DebugRequestType {
+    /// Launch the program under the debugger.
+    Launch(LaunchConfig),
+    /// Attach the debugger to a process that is already running.
+    Attach(AttachConfig),
+}
+
+/// The program to launch under the debugger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LaunchConfig {
+    /// The program to debug. May use task variables, such as `$ZED_WORKTREE_ROOT`.
+    pub program: String,
+    /// Arguments to pass to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// The process to attach the debugger to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AttachConfig {
+    /// The id of the process to attach to.
+    pub process_id: u32,
+}
+
+impl DebugAdapterConfig {
+    /// Applies `substitute` to every string field that may contain task variables, returning
+    /// `None` if any substitution fails.
+    pub(crate) fn substitute(
+        &self,
+        mut substitute: impl FnMut(&str) -> Option<String>,
+    ) -> Option<Self> {
+        let request = match &self.request {
+            DebugRequestType::Launch(launch) => DebugRequestType::Launch(LaunchConfig {
+                program: substitute(&launch.program)?,
+                args: launch
+                    .args
+                    .iter()
+                    .map(|arg| substitute(arg))
+                    .collect::<Option<Vec<_>>>()?,
+            }),
+            DebugRequestType::Attach(attach) => DebugRequestType::Attach(attach.clone()),
+        };
+        Some(Self {
+            request,
+            stop_on_entry: self.stop_on_entry,
+        })
+    }
+}
@@ -1,6 +1,9 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 #![deny(missing_docs)]
 
+mod debug_format;
+mod output_value;
+mod problem_matcher;
 pub mod static_source;
 mod task_template;
 mod vscode_format;
@@ -13,7 +16,18 @@ use std::borrow::Cow;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-pub use task_template::{HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates};
+pub use debug_format::{AttachConfig, DebugAdapterConfig, DebugRequestType, LaunchConfig};
+pub use output_value::{
+    BuiltinOutputValueMatcher, OutputValue, OutputValueMatcher, OutputValueParser,
+    OutputValuePattern,
+};
+pub use problem_matcher::{
+    BuiltinProblemMatcher, CustomProblemMatcher, Problem, ProblemMatcher, ProblemParser,
+    ProblemPattern, ProblemPatterns, ProblemSeverity,
+};
+pub use task_template::{
+    BackgroundPattern, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+};
 pub use vscode_format::VsCodeTaskFile;
 pub use zed_actions::RevealTarget;
 
@@ -58,6 +72,14 @@ pub struct SpawnInTerminal {
     pub show_summary: bool,
     /// Whether to show the command line in the task output.
     pub show_command: bool,
+    /// Matchers to find problems in the task output with, once the task finishes.
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Matchers to find values printed for source lines in the task output with, once the task finishes.
+    pub output_values: Vec<OutputValueMatcher>,
+    /// Whether the task keeps running in the background, and is ready before it exits.
+    pub is_background: bool,
+    /// Patterns telling when a background task starts and finishes a cycle of work.
+    pub background_pattern: Option<BackgroundPattern>,
 }
 
 /// A final form of the [`TaskTemplate`], that got resolved with a particular [`TaskContext`] and now is ready to spawn the actual task.
@@ -78,6 +100,11 @@ pub struct ResolvedTask {
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: Option<SpawnInTerminal>,
+    /// For debug tasks, the debug session to start once the command in `resolved` is spawned as
+    /// its debug adapter.
+    pub debug: Option<DebugAdapterConfig>,
+    /// The context the task got resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
 }
 
 impl ResolvedTask {
@@ -91,6 +118,11 @@ impl ResolvedTask {
         &self.substituted_variables
     }
 
+    /// The context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// A human-readable label to display in the UI.
     pub fn display_label(&self) -> &str {
         self.resolved
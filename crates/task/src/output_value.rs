@@ -0,0 +1,244 @@
+use anyhow::Context as _;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Recognizes values printed by a task, along with the source line that printed them,
+/// so that they can be shown next to that line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum OutputValueMatcher {
+    /// One of the matchers Zed knows about, referred to by name.
+    Builtin(BuiltinOutputValueMatcher),
+    /// A matcher defined by a regular expression.
+    Custom(OutputValuePattern),
+}
+
+/// Output value matchers for common debugging output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinOutputValueMatcher {
+    /// The output of Rust's `dbg!` macro, such as `[src/main.rs:4:5] x = 5`, including
+    /// values pretty-printed over several lines.
+    Dbg,
+}
+
+/// A regular expression matching a line of task output that prints a value, with the indices
+/// of the groups that capture each part of it. Group indices start at 1.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct OutputValuePattern {
+    /// The regular expression to match a line with.
+    pub regexp: String,
+    /// The group capturing the path of the file that printed the value.
+    pub file: usize,
+    /// The group capturing the 1-based line that printed the value.
+    pub line: usize,
+    /// The group capturing the expression whose value was printed, if the output names it.
+    #[serde(default)]
+    pub expression: Option<usize>,
+    /// The group capturing the printed value.
+    pub value: usize,
+}
+
+/// A value found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputValue {
+    /// The path of the file that printed the value, as printed by the task.
+    pub path: String,
+    /// The 0-based row that printed the value.
+    pub row: u32,
+    /// The expression whose value was printed, if the output names it.
+    pub expression: Option<String>,
+    /// The printed value, with values printed over several lines joined into one.
+    pub value: String,
+}
+
+/// Finds the values printed in task output with a set of [`OutputValueMatcher`]s.
+pub struct OutputValueParser {
+    matchers: Vec<CompiledMatcher>,
+}
+
+struct CompiledMatcher {
+    regex: Regex,
+    pattern: OutputValuePattern,
+    /// Whether a value opening a bracket continues on the following, indented, lines.
+    multiline_values: bool,
+}
+
+impl OutputValueParser {
+    /// Prepares the given matchers, failing if any of their patterns is not a valid regex.
+    pub fn new(matchers: &[OutputValueMatcher]) -> anyhow::Result<Self> {
+        let matchers = matchers
+            .iter()
+            .map(|matcher| {
+                let (pattern, multiline_values) = match matcher {
+                    OutputValueMatcher::Builtin(builtin) => (builtin.pattern(), true),
+                    OutputValueMatcher::Custom(pattern) => (pattern.clone(), false),
+                };
+                let regex = Regex::new(&pattern.regexp).with_context(|| {
+                    format!("invalid output value pattern `{}`", pattern.regexp)
+                })?;
+                Ok(CompiledMatcher {
+                    regex,
+                    pattern,
+                    multiline_values,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { matchers })
+    }
+
+    /// Finds all values printed in the given lines of output.
+    pub fn parse<'a>(&self, lines: impl IntoIterator<Item = &'a str>) -> Vec<OutputValue> {
+        let mut lines = lines
+            .into_iter()
+            .map(|line| line.trim_end_matches(['\r', '\n']));
+        let mut values = Vec::new();
+        while let Some(line) = lines.next() {
+            let Some((matcher, mut value)) = self
+                .matchers
+                .iter()
+                .find_map(|matcher| Some((matcher, matcher.match_line(line)?)))
+            else {
+                continue;
+            };
+            if matcher.multiline_values && value.value.ends_with(['{', '[', '(']) {
+                let mut parts = vec![value.value];
+                for line in lines.by_ref() {
+                    parts.push(line.trim().to_string());
+                    // The closing bracket is the first line that is not indented.
+                    if !line.starts_with(char::is_whitespace) {
+                        break;
+                    }
+                }
+                value.value = parts
+                    .join(" ")
+                    .replace(", }", " }")
+                    .replace(", ]", " ]")
+                    .replace(", )", " )");
+            }
+            values.push(value);
+        }
+        values
+    }
+}
+
+impl CompiledMatcher {
+    fn match_line(&self, line: &str) -> Option<OutputValue> {
+        let captures = self.regex.captures(line)?;
+        let group = |ix: usize| {
+            captures
+                .get(ix)
+                .map(|group| group.as_str().trim())
+                .filter(|text| !text.is_empty())
+        };
+        Some(OutputValue {
+            path: group(self.pattern.file)?.to_string(),
+            row: group(self.pattern.line)?
+                .parse::<u32>()
+                .ok()?
+                .saturating_sub(1),
+            expression: self.pattern.expression.and_then(group).map(str::to_string),
+            value: group(self.pattern.value)?.to_string(),
+        })
+    }
+}
+
+impl BuiltinOutputValueMatcher {
+    fn pattern(&self) -> OutputValuePattern {
+        match self {
+            Self::Dbg => OutputValuePattern {
+                regexp: r"^\[(.+?):(\d+)(?::\d+)?\] (.+?) = (.*)$".to_string(),
+                file: 1,
+                line: 2,
+                expression: Some(3),
+                value: 4,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(matcher: OutputValueMatcher, output: &str) -> Vec<OutputValue> {
+        OutputValueParser::new(&[matcher])
+            .unwrap()
+            .parse(output.lines())
+    }
+
+    #[test]
+    fn test_dbg_values() {
+        let output = "running 1 test\n\
+            [src/lib.rs:4:9] x * 2 = 10\n\
+            [src/lib.rs:5:9] &point = Point {\n    \
+                x: 1,\n    \
+                y: 2,\n\
+            }\n\
+            [src/lib.rs:6] items = []\n\
+            test tests::it_works ... ok";
+        assert_eq!(
+            parse(
+                OutputValueMatcher::Builtin(BuiltinOutputValueMatcher::Dbg),
+                output
+            ),
+            vec![
+                OutputValue {
+                    path: "src/lib.rs".to_string(),
+                    row: 3,
+                    expression: Some("x * 2".to_string()),
+                    value: "10".to_string(),
+                },
+                OutputValue {
+                    path: "src/lib.rs".to_string(),
+                    row: 4,
+                    expression: Some("&point".to_string()),
+                    value: "Point { x: 1, y: 2 }".to_string(),
+                },
+                OutputValue {
+                    path: "src/lib.rs".to_string(),
+                    row: 5,
+                    expression: Some("items".to_string()),
+                    value: "[]".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: OutputValueMatcher = serde_json_lenient::from_str(
+            r#"{
+                "regexp": "^(.+?):(\\d+): (.*)$",
+                "file": 1,
+                "line": 2,
+                "value": 3
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse(matcher, "app.py:12: {'a': 1}\ndone"),
+            vec![OutputValue {
+                path: "app.py".to_string(),
+                row: 11,
+                expression: None,
+                value: "{'a': 1}".to_string(),
+            }]
+        );
+
+        let matcher: OutputValueMatcher = serde_json_lenient::from_str(r#""dbg""#).unwrap();
+        assert_eq!(
+            matcher,
+            OutputValueMatcher::Builtin(BuiltinOutputValueMatcher::Dbg)
+        );
+        assert!(
+            OutputValueParser::new(&[OutputValueMatcher::Custom(OutputValuePattern {
+                regexp: "(unclosed".to_string(),
+                ..OutputValuePattern::default()
+            })])
+            .is_err()
+        );
+    }
+}
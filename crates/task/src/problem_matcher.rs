@@ -0,0 +1,561 @@
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Recognizes problems, such as compiler errors, in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// One of the matchers Zed knows about, referred to by name.
+    Builtin(BuiltinProblemMatcher),
+    /// A matcher defined by regular expressions.
+    Custom(CustomProblemMatcher),
+}
+
+/// Problem matchers for the output of common tools.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinProblemMatcher {
+    /// Errors and warnings of `rustc`, as printed by `cargo build`, `cargo check` and friends.
+    #[serde(alias = "cargo")]
+    Rustc,
+    /// Errors, warnings and notes of `gcc` and `clang`.
+    #[serde(alias = "clang")]
+    Gcc,
+    /// Errors of the TypeScript compiler.
+    Tsc,
+    /// Problems reported by ESLint's default, "stylish", formatter.
+    Eslint,
+}
+
+/// A problem matcher defined by regular expressions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// A pattern matching a single line, or several patterns matching consecutive lines of one problem.
+    pub pattern: ProblemPatterns,
+    /// The severity of problems whose pattern has no `severity` group, or whose severity is not recognized.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+}
+
+/// One or several [`ProblemPattern`]s.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemPatterns {
+    /// A pattern matching each problem on a single line.
+    Single(ProblemPattern),
+    /// Patterns matching consecutive lines, which together describe a problem.
+    Multiline(Vec<ProblemPattern>),
+}
+
+impl ProblemPatterns {
+    /// The patterns, in the order of the lines they match.
+    pub fn patterns(&self) -> &[ProblemPattern] {
+        match self {
+            Self::Single(pattern) => std::slice::from_ref(pattern),
+            Self::Multiline(patterns) => patterns,
+        }
+    }
+}
+
+/// A regular expression matching a line of task output, with the indices of the groups that
+/// capture each part of a problem. Group indices start at 1.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match a line with.
+    pub regexp: String,
+    /// The group capturing the path of the file with the problem.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The group capturing the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The group capturing the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The group capturing the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The group capturing the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The group capturing the severity of the problem, such as `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The group capturing the code of the problem, such as `E0308`.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The group capturing the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the pattern may match several lines in a row, each of which is a separate problem.
+    /// Only the last pattern of a multiline matcher may loop.
+    #[serde(default, rename = "loop")]
+    pub r#loop: bool,
+}
+
+/// How severe a problem is.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// An error, the default.
+    #[default]
+    Error,
+    /// A warning.
+    Warning,
+    /// An informational message, such as a compiler note.
+    Info,
+    /// A hint, such as a suggestion on how to fix a problem.
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_lowercase();
+        if text.starts_with("err") || text.starts_with("fatal") {
+            Some(Self::Error)
+        } else if text.starts_with("warn") {
+            Some(Self::Warning)
+        } else if text.starts_with("info") || text.starts_with("note") {
+            Some(Self::Info)
+        } else if text.starts_with("hint") || text.starts_with("help") {
+            Some(Self::Hint)
+        } else {
+            None
+        }
+    }
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The path of the file with the problem, as printed by the task.
+    pub path: String,
+    /// The 0-based row the problem starts at.
+    pub row: u32,
+    /// The 0-based column the problem starts at.
+    pub column: u32,
+    /// The 0-based row and column the problem ends at, if the task printed them.
+    pub end: Option<(u32, u32)>,
+    /// How severe the problem is.
+    pub severity: ProblemSeverity,
+    /// The code of the problem, such as `E0308`, if the task printed one.
+    pub code: Option<String>,
+    /// The description of the problem.
+    pub message: String,
+}
+
+/// Feeds task output through a set of [`ProblemMatcher`]s, line by line.
+pub struct ProblemParser {
+    matchers: Vec<CompiledMatcher>,
+}
+
+struct CompiledMatcher {
+    patterns: Vec<(Regex, ProblemPattern)>,
+    default_severity: ProblemSeverity,
+    /// The pattern the next line has to match to continue the current multiline problem,
+    /// or 0 if there is none.
+    next_pattern: usize,
+    partial: PartialProblem,
+}
+
+#[derive(Clone, Default)]
+struct PartialProblem {
+    path: Option<String>,
+    row: Option<u32>,
+    column: Option<u32>,
+    end_row: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemParser {
+    /// Prepares the given matchers, failing if any of their patterns is not a valid regex.
+    pub fn new(matchers: &[ProblemMatcher]) -> anyhow::Result<Self> {
+        let matchers = matchers
+            .iter()
+            .map(|matcher| {
+                let (patterns, default_severity) = match matcher {
+                    ProblemMatcher::Builtin(builtin) => {
+                        (builtin.patterns(), ProblemSeverity::default())
+                    }
+                    ProblemMatcher::Custom(custom) => {
+                        (custom.pattern.patterns().to_vec(), custom.severity)
+                    }
+                };
+                anyhow::ensure!(!patterns.is_empty(), "problem matcher has no patterns");
+                let patterns = patterns
+                    .into_iter()
+                    .map(|pattern| {
+                        let regex = Regex::new(&pattern.regexp).with_context(|| {
+                            format!("invalid problem matcher pattern `{}`", pattern.regexp)
+                        })?;
+                        Ok((regex, pattern))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                Ok(CompiledMatcher {
+                    patterns,
+                    default_severity,
+                    next_pattern: 0,
+                    partial: PartialProblem::default(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { matchers })
+    }
+
+    /// Processes the next line of output, returning the problems it completes.
+    pub fn push_line(&mut self, line: &str) -> Vec<Problem> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        self.matchers
+            .iter_mut()
+            .filter_map(|matcher| matcher.push_line(line))
+            .collect()
+    }
+
+    /// Finds all problems in the given output.
+    pub fn parse(mut self, output: &str) -> Vec<Problem> {
+        output
+            .lines()
+            .flat_map(|line| self.push_line(line))
+            .collect()
+    }
+}
+
+impl CompiledMatcher {
+    fn push_line(&mut self, line: &str) -> Option<Problem> {
+        if self.next_pattern > 0 {
+            let ix = self.next_pattern;
+            let (regex, pattern) = &self.patterns[ix];
+            if let Some(captures) = regex.captures(line) {
+                if ix + 1 < self.patterns.len() {
+                    self.partial.fill(pattern, &captures);
+                    self.next_pattern += 1;
+                    return None;
+                }
+                let mut problem = self.partial.clone();
+                problem.fill(pattern, &captures);
+                if !pattern.r#loop {
+                    self.reset();
+                }
+                return problem.finish(self.default_severity);
+            }
+            self.reset();
+        }
+
+        let (regex, pattern) = &self.patterns[0];
+        let captures = regex.captures(line)?;
+        let mut problem = PartialProblem::default();
+        problem.fill(pattern, &captures);
+        if self.patterns.len() == 1 {
+            problem.finish(self.default_severity)
+        } else {
+            self.partial = problem;
+            self.next_pattern = 1;
+            None
+        }
+    }
+
+    fn reset(&mut self) {
+        self.next_pattern = 0;
+        self.partial = PartialProblem::default();
+    }
+}
+
+impl PartialProblem {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |ix: Option<usize>| {
+            ix.and_then(|ix| captures.get(ix))
+                .map(|group| group.as_str().trim())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |ix: Option<usize>| {
+            group(ix)
+                .and_then(|text| text.parse::<u32>().ok())
+                .map(|number| number.saturating_sub(1))
+        };
+
+        if let Some(path) = group(pattern.file) {
+            self.path = Some(path.to_string());
+        }
+        if let Some(row) = number(pattern.line) {
+            self.row = Some(row);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_row) = number(pattern.end_line) {
+            self.end_row = Some(end_row);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity) {
+            self.severity = ProblemSeverity::parse(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.to_string());
+        }
+    }
+
+    fn finish(self, default_severity: ProblemSeverity) -> Option<Problem> {
+        let row = self.row?;
+        let end = match (self.end_row, self.end_column) {
+            (Some(end_row), Some(end_column)) => Some((end_row, end_column)),
+            (Some(end_row), None) => Some((end_row, 0)),
+            (None, Some(end_column)) => Some((row, end_column)),
+            (None, None) => None,
+        };
+        Some(Problem {
+            path: self.path?,
+            row,
+            column: self.column.unwrap_or(0),
+            end,
+            severity: self.severity.unwrap_or(default_severity),
+            code: self.code,
+            message: self.message?,
+        })
+    }
+}
+
+impl BuiltinProblemMatcher {
+    fn patterns(&self) -> Vec<ProblemPattern> {
+        match self {
+            Self::Rustc => vec![
+                ProblemPattern {
+                    regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*-->\s*(.*):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+            Self::Gcc => vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d+):\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+            Self::Tsc => vec![ProblemPattern {
+                regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+            Self::Eslint => vec![
+                ProblemPattern {
+                    regexp: r"^([^\s].*)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(\S+))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    r#loop: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(matcher: ProblemMatcher, output: &str) -> Vec<Problem> {
+        ProblemParser::new(&[matcher]).unwrap().parse(output)
+    }
+
+    #[test]
+    fn test_rustc_problems() {
+        let output = r#"
+   Compiling foo v0.1.0 (/home/user/foo)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+  |
+error[E0308]: mismatched types
+ --> src/lib.rs:10:18
+   |
+error: could not compile `foo` (bin "foo") due to 1 previous error; 1 warning emitted
+"#;
+        assert_eq!(
+            parse(
+                ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc),
+                output
+            ),
+            vec![
+                Problem {
+                    path: "src/main.rs".to_string(),
+                    row: 1,
+                    column: 8,
+                    end: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                },
+                Problem {
+                    path: "src/lib.rs".to_string(),
+                    row: 9,
+                    column: 17,
+                    end: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_problems() {
+        let gcc_output = "main.c:3:5: error: use of undeclared identifier 'y'\n\
+            main.c:1:1: warning: type specifier missing [-Wimplicit-int]\n\
+            1 warning and 1 error generated.";
+        assert_eq!(
+            parse(
+                ProblemMatcher::Builtin(BuiltinProblemMatcher::Gcc),
+                gcc_output
+            )
+            .into_iter()
+            .map(|problem| (problem.path, problem.row, problem.severity))
+            .collect::<Vec<_>>(),
+            vec![
+                ("main.c".to_string(), 2, ProblemSeverity::Error),
+                ("main.c".to_string(), 0, ProblemSeverity::Warning),
+            ]
+        );
+
+        let tsc_output =
+            "src/index.ts(4,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+            src/util.ts:12:3 - error TS2304: Cannot find name 'foo'.";
+        assert_eq!(
+            parse(
+                ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc),
+                tsc_output
+            )
+            .into_iter()
+            .map(|problem| (problem.path, problem.row, problem.column, problem.code))
+            .collect::<Vec<_>>(),
+            vec![
+                ("src/index.ts".to_string(), 3, 6, Some("TS2322".to_string())),
+                ("src/util.ts".to_string(), 11, 2, Some("TS2304".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let output = "\n/home/user/app/src/a.js\n  \
+            1:10  error    'foo' is defined but never used  no-unused-vars\n  \
+            3:1   warning  Unexpected console statement     no-console\n\
+            \n/home/user/app/src/b.js\n  \
+            7:5  error  Missing semicolon  semi\n\
+            \n✖ 3 problems (2 errors, 1 warning)\n";
+        assert_eq!(
+            parse(
+                ProblemMatcher::Builtin(BuiltinProblemMatcher::Eslint),
+                output
+            )
+            .into_iter()
+            .map(|problem| (problem.path, problem.row, problem.message, problem.code))
+            .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/home/user/app/src/a.js".to_string(),
+                    0,
+                    "'foo' is defined but never used".to_string(),
+                    Some("no-unused-vars".to_string())
+                ),
+                (
+                    "/home/user/app/src/a.js".to_string(),
+                    2,
+                    "Unexpected console statement".to_string(),
+                    Some("no-console".to_string())
+                ),
+                (
+                    "/home/user/app/src/b.js".to_string(),
+                    6,
+                    "Missing semicolon".to_string(),
+                    Some("semi".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(
+            r#"{
+                "pattern": {
+                    "regexp": "^(.+?):(\\d+): (.*)$",
+                    "file": 1,
+                    "line": 2,
+                    "message": 3
+                },
+                "severity": "warning"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse(matcher, "lint.py:12: line too long\nall done"),
+            vec![Problem {
+                path: "lint.py".to_string(),
+                row: 11,
+                column: 0,
+                end: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "line too long".to_string(),
+            }]
+        );
+
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(r#""cargo""#).unwrap();
+        assert_eq!(
+            matcher,
+            ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)
+        );
+        assert!(
+            ProblemParser::new(&[ProblemMatcher::Custom(CustomProblemMatcher {
+                pattern: ProblemPatterns::Single(ProblemPattern {
+                    regexp: "(unclosed".to_string(),
+                    ..ProblemPattern::default()
+                }),
+                severity: ProblemSeverity::Error,
+            })])
+            .is_err()
+        );
+    }
+}
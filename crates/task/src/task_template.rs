@@ -9,8 +9,8 @@ use sha2::{Digest, Sha256};
 use util::{truncate_and_remove_front, ResultExt};
 
 use crate::{
-    ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId, VariableName,
-    ZED_VARIABLE_NAME_PREFIX,
+    DebugAdapterConfig, OutputValueMatcher, ProblemMatcher, ResolvedTask, RevealTarget, Shell,
+    SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
 };
 
 /// A template definition of a Zed task to run.
@@ -70,6 +70,51 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Starts a debug session instead of running the command in a terminal.
+    /// The command is then the debug adapter, which launches or attaches to the program to debug.
+    #[serde(default)]
+    pub debug: Option<DebugAdapterConfig>,
+    /// Labels of the tasks to run before this one. If any of them fails, this task is not run.
+    /// Tasks that have dependencies may omit the `command`, to only run their dependencies.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Whether to run the tasks from `depends_on` one after another, or all at once.
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Labels of the tasks to run right before this one, after its dependencies.
+    /// Unlike dependencies, which run once even if several tasks depend on them, these run every time this task does.
+    #[serde(default)]
+    pub pre_tasks: Vec<String>,
+    /// Labels of the tasks to run after this one finished successfully.
+    #[serde(default)]
+    pub post_tasks: Vec<String>,
+    /// Matchers that turn the task output into diagnostics, once the task finishes.
+    /// Either names of built-in matchers (`rustc`, `gcc`, `tsc`, `eslint`), or custom matchers with regex patterns.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Matchers that find values the task printed for source lines, such as with Rust's `dbg!`,
+    /// to show next to those lines once the task finishes.
+    /// Either `dbg`, or custom matchers with a regex pattern.
+    #[serde(default)]
+    pub output_values: Vec<OutputValueMatcher>,
+    /// Whether the task keeps running in the background, like a dev server or a file watcher.
+    /// Tasks that depend on a background task start once it is ready, instead of waiting for it to exit.
+    #[serde(default)]
+    pub is_background: bool,
+    /// Patterns in the output of a background task that tell when it starts and finishes a cycle of work, e.g. a rebuild.
+    /// Without them, a background task is ready as soon as it starts.
+    #[serde(default)]
+    pub background_pattern: Option<BackgroundPattern>,
+}
+
+/// Regexes matched against each line of the output of a background task, to tell whether it is ready.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundPattern {
+    /// Marks the start of a cycle of work, after which the task is busy.
+    pub begins: String,
+    /// Marks the end of a cycle of work, after which the task is ready.
+    pub ends: String,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -98,6 +143,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// In which order to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, in the order they are listed.
+    #[default]
+    Sequence,
+    /// Run all dependencies at once.
+    Parallel,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -115,6 +171,11 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether other tasks have to run before or after this one.
+    pub fn has_dependencies(&self) -> bool {
+        !self.depends_on.is_empty() || !self.pre_tasks.is_empty() || !self.post_tasks.is_empty()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -122,7 +183,8 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        let has_command = !self.command.trim().is_empty();
+        if self.label.trim().is_empty() || (!has_command && self.depends_on.is_empty()) {
             return None;
         }
 
@@ -198,6 +260,18 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let debug = match &self.debug {
+            Some(debug) => Some(debug.substitute(|value| {
+                substitute_all_template_variables_in_str(
+                    value,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )
+            })?),
+            None => None,
+        };
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
@@ -231,7 +305,9 @@ impl TaskTemplate {
             substituted_variables,
             original_task: self.clone(),
             resolved_label: full_label.clone(),
-            resolved: Some(SpawnInTerminal {
+            debug,
+            task_context: cx.clone(),
+            resolved: has_command.then(|| SpawnInTerminal {
                 id,
                 cwd,
                 full_label,
@@ -255,6 +331,10 @@ impl TaskTemplate {
                 shell: self.shell.clone(),
                 show_summary: self.show_summary,
                 show_command: self.show_command,
+                problem_matchers: self.problem_matchers.clone(),
+                output_values: self.output_values.clone(),
+                is_background: self.is_background,
+                background_pattern: self.background_pattern.clone(),
             }),
         })
     }
@@ -831,4 +911,76 @@ mod tests {
             "overwritten"
         );
     }
+
+    #[test]
+    fn test_debug_task_resolution() {
+        let template: TaskTemplate = serde_json_lenient::from_str(
+            r#"{
+                "label": "debug $ZED_STEM",
+                "command": "lldb-dap",
+                "debug": {
+                    "request": "launch",
+                    "program": "$ZED_WORKTREE_ROOT/target/debug/$ZED_STEM",
+                    "args": ["--verbose"]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let context = TaskContext {
+            cwd: None,
+            task_variables: TaskVariables::from_iter([
+                (VariableName::WorktreeRoot, "/project".to_string()),
+                (VariableName::Stem, "app".to_string()),
+            ]),
+            project_env: HashMap::default(),
+        };
+        let resolved = template.resolve_task(TEST_ID_BASE, &context).unwrap();
+        assert_substituted_variables(
+            &resolved,
+            vec![VariableName::WorktreeRoot, VariableName::Stem],
+        );
+        assert_eq!(resolved.resolved.unwrap().command, "lldb-dap");
+        assert_eq!(
+            resolved.debug,
+            Some(DebugAdapterConfig {
+                request: crate::DebugRequestType::Launch(crate::LaunchConfig {
+                    program: "/project/target/debug/app".to_string(),
+                    args: vec!["--verbose".to_string()],
+                }),
+                stop_on_entry: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_dependency_only_task_resolution() {
+        let template: TaskTemplate = serde_json_lenient::from_str(
+            r#"{
+                "label": "build and test",
+                "depends_on": ["build", "test"],
+                "depends_order": "parallel",
+                "post_tasks": ["notify"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(template.depends_order, DependsOrder::Parallel);
+        assert!(template.has_dependencies());
+
+        let resolved = template
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        assert_eq!(resolved.resolved_label, "build and test");
+        assert_eq!(resolved.resolved, None);
+
+        let template = TaskTemplate {
+            depends_on: Vec::new(),
+            ..template
+        };
+        assert_eq!(
+            template.resolve_task(TEST_ID_BASE, &TaskContext::default()),
+            None,
+            "tasks without a command need dependencies to run"
+        );
+    }
 }
@@ -3,7 +3,10 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BackgroundPattern, BuiltinProblemMatcher, CustomProblemMatcher, DependsOrder, ProblemMatcher,
+    ProblemPattern, ProblemPatterns, ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -19,11 +22,190 @@ struct VsCodeTaskDefinition {
     label: String,
     #[serde(flatten)]
     command: Option<Command>,
+    depends_on: Option<DependsOn>,
+    depends_order: Option<VsCodeDependsOrder>,
+    problem_matcher: Option<VsCodeProblemMatchers>,
+    #[serde(default)]
+    is_background: bool,
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    Sequence,
+    Parallel,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatchers {
+    Single(VsCodeProblemMatcher),
+    Multiple(Vec<VsCodeProblemMatcher>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    /// A reference to a predefined matcher, such as `$rustc`.
+    Named(String),
+    Definition(VsCodeProblemMatcherDefinition),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    base: Option<String>,
+    pattern: Option<VsCodeProblemPatterns>,
+    severity: Option<String>,
+    background: Option<VsCodeBackgroundMatcher>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeBackgroundMatcher {
+    begins_pattern: VsCodeBackgroundPattern,
+    ends_pattern: VsCodeBackgroundPattern,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeBackgroundPattern {
+    Regexp(String),
+    Definition { regexp: String },
+}
+
+impl VsCodeBackgroundPattern {
+    fn regexp(&self) -> &str {
+        match self {
+            Self::Regexp(regexp) | Self::Definition { regexp } => regexp,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Named(String),
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    r#loop: bool,
+}
+
+impl VsCodeProblemMatcher {
+    /// The patterns telling whether a background task using this matcher is busy or ready.
+    fn background_pattern(&self) -> Option<BackgroundPattern> {
+        let (name, background) = match self {
+            Self::Named(name) => (Some(name.as_str()), None),
+            Self::Definition(definition) => {
+                (definition.base.as_deref(), definition.background.as_ref())
+            }
+        };
+        match background {
+            Some(background) => Some(BackgroundPattern {
+                begins: background.begins_pattern.regexp().to_string(),
+                ends: background.ends_pattern.regexp().to_string(),
+            }),
+            None => builtin_background_pattern(name?),
+        }
+    }
+
+    /// Converts the matcher, if it is one Zed can handle. Code's `fileLocation` is not
+    /// supported: relative paths are always resolved against the task's working directory.
+    fn into_zed_format(self) -> Option<ProblemMatcher> {
+        let definition = match self {
+            Self::Named(name) => return builtin_problem_matcher(&name),
+            Self::Definition(definition) => definition,
+        };
+        let patterns = match definition.pattern {
+            Some(VsCodeProblemPatterns::Single(pattern)) => vec![pattern],
+            Some(VsCodeProblemPatterns::Multiple(patterns)) if !patterns.is_empty() => patterns,
+            Some(VsCodeProblemPatterns::Named(_) | VsCodeProblemPatterns::Multiple(_)) | None => {
+                return builtin_problem_matcher(definition.base.as_deref()?);
+            }
+        };
+        let mut patterns = patterns
+            .into_iter()
+            .map(|pattern| ProblemPattern {
+                regexp: pattern.regexp,
+                file: pattern.file,
+                line: pattern.line,
+                column: pattern.column,
+                end_line: pattern.end_line,
+                end_column: pattern.end_column,
+                severity: pattern.severity,
+                code: pattern.code,
+                message: pattern.message,
+                r#loop: pattern.r#loop,
+            })
+            .collect::<Vec<_>>();
+        Some(ProblemMatcher::Custom(CustomProblemMatcher {
+            pattern: if patterns.len() == 1 {
+                ProblemPatterns::Single(patterns.remove(0))
+            } else {
+                ProblemPatterns::Multiline(patterns)
+            },
+            severity: definition
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse)
+                .unwrap_or_default(),
+        }))
+    }
+}
+
+fn builtin_problem_matcher(name: &str) -> Option<ProblemMatcher> {
+    let builtin = match name.trim_start_matches('$') {
+        "rustc" | "rustc-watch" => BuiltinProblemMatcher::Rustc,
+        "gcc" => BuiltinProblemMatcher::Gcc,
+        "tsc" | "tsc-watch" => BuiltinProblemMatcher::Tsc,
+        "eslint-stylish" => BuiltinProblemMatcher::Eslint,
+        _ => return None,
+    };
+    Some(ProblemMatcher::Builtin(builtin))
+}
+
+/// Background patterns of Code's predefined matchers for watch tasks.
+fn builtin_background_pattern(name: &str) -> Option<BackgroundPattern> {
+    let (begins, ends) = match name.trim_start_matches('$') {
+        "tsc-watch" => (
+            r"Starting (compilation in watch mode|incremental compilation)",
+            r"(Compilation complete|Found \d+ errors?)\. Watching for file changes",
+        ),
+        // The output of `cargo watch`.
+        "rustc-watch" => (r"^\[Running\b", r"^\[Finished running\b"),
+        _ => return None,
+    };
+    Some(BackgroundPattern {
+        begins: begins.to_string(),
+        ends: ends.to_string(),
+    })
+}
+
 #[derive(Clone, Deserialize, PartialEq, Debug)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
@@ -85,22 +267,40 @@ impl EnvVariableReplacer {
 
 impl VsCodeTaskDefinition {
     fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        let depends_on = match self.depends_on {
+            Some(DependsOn::Single(label)) => vec![label],
+            Some(DependsOn::Multiple(labels)) => labels,
+            None => Vec::new(),
+        };
+        // `type` might not be set in tasks that only use `dependsOn` to run other tasks; we still want to deserialize the whole object though (hence command is an Option),
         // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        let depends_order = match self.depends_order {
+            Some(VsCodeDependsOrder::Sequence) => DependsOrder::Sequence,
+            Some(VsCodeDependsOrder::Parallel) => DependsOrder::Parallel,
+            // Unlike Zed, Code runs the dependencies in parallel by default.
+            None if !depends_on.is_empty() => DependsOrder::Parallel,
+            None => DependsOrder::default(),
+        };
+        let problem_matchers = match self.problem_matcher {
+            Some(VsCodeProblemMatchers::Single(matcher)) => vec![matcher],
+            Some(VsCodeProblemMatchers::Multiple(matchers)) => matchers,
+            None => Vec::new(),
         };
+        let background_pattern = problem_matchers
+            .iter()
+            .find_map(VsCodeProblemMatcher::background_pattern)
+            .filter(|_| self.is_background);
+        let problem_matchers = problem_matchers
+            .into_iter()
+            .filter_map(VsCodeProblemMatcher::into_zed_format)
+            .collect();
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
@@ -108,6 +308,11 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
+            is_background: self.is_background,
+            background_pattern,
             ..Default::default()
         };
         if let Some(options) = self.options {
@@ -154,8 +359,12 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        vscode_format::{Command, VsCodeTaskDefinition},
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{
+            builtin_background_pattern, Command, DependsOn, VsCodeProblemMatcher,
+            VsCodeProblemMatcherDefinition, VsCodeProblemMatchers, VsCodeTaskDefinition,
+        },
+        BuiltinProblemMatcher, DependsOrder, ProblemMatcher, TaskTemplate, TaskTemplates,
+        VsCodeTaskFile,
     };
 
     use super::EnvVariableReplacer;
@@ -173,6 +382,19 @@ mod tests {
         );
     }
 
+    fn named(name: &str) -> VsCodeProblemMatcher {
+        VsCodeProblemMatcher::Named(name.to_string())
+    }
+
+    fn based_on(base: &str) -> VsCodeProblemMatcher {
+        VsCodeProblemMatcher::Definition(VsCodeProblemMatcherDefinition {
+            base: Some(base.to_string()),
+            pattern: None,
+            severity: None,
+            background: None,
+        })
+    }
+
     #[test]
     fn test_variable_substitution() {
         let replacer = EnvVariableReplacer::new(Default::default());
@@ -198,6 +420,42 @@ mod tests {
         assert_eq!(replacer.replace("${PATH:food}"), "${ZED_PATH:food}");
     }
 
+    #[test]
+    fn test_depends_order() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    { "label": "Build", "dependsOn": ["Client", "Server"] },
+                    { "label": "Single", "dependsOn": "Client" },
+                    {
+                        "label": "Release",
+                        "dependsOn": ["Client", "Server"],
+                        "dependsOrder": "sequence"
+                    },
+                    { "label": "Client", "type": "shell", "command": "make client" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        // Code runs dependencies in parallel unless `dependsOrder` says otherwise.
+        assert_eq!(
+            tasks
+                .0
+                .iter()
+                .map(|task| (task.label.as_str(), task.depends_order))
+                .collect::<Vec<_>>(),
+            [
+                ("Build", DependsOrder::Parallel),
+                ("Single", DependsOrder::Parallel),
+                ("Release", DependsOrder::Sequence),
+                ("Client", DependsOrder::Sequence),
+            ]
+        );
+        assert_eq!(tasks.0[1].depends_on, ["Client"]);
+    }
+
     #[test]
     fn can_deserialize_ts_tasks() {
         const TYPESCRIPT_TASKS: &str = include_str!("../test_data/typescript.json");
@@ -210,6 +468,10 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:tests:notypecheck".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![named("$tsc")])),
+                is_background: false,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -224,6 +486,10 @@ mod tests {
                         "--watch".to_string(),
                     ],
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![named("$tsc-watch")])),
+                is_background: true,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -232,6 +498,10 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:compiler".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![named("$tsc")])),
+                is_background: false,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -240,6 +510,10 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:tests:notypecheck".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Multiple(vec![named("$tsc")])),
+                is_background: false,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -257,6 +531,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +543,23 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
+                is_background: true,
+                background_pattern: builtin_background_pattern("$tsc-watch"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
@@ -299,7 +579,11 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "watch".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(based_on("$tsc-watch"))),
                 options: None,
+                is_background: true,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -307,7 +591,11 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(based_on("$tsc"))),
                 options: None,
+                is_background: false,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -316,7 +604,11 @@ mod tests {
                     command: "cargo build --package rust-analyzer".to_string(),
                     args: Default::default(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(named("$rustc"))),
                 options: None,
+                is_background: false,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -325,7 +617,11 @@ mod tests {
                     command: "cargo build --release --package rust-analyzer".to_string(),
                     args: Default::default(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(named("$rustc"))),
                 options: None,
+                is_background: false,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -333,19 +629,37 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "pretest".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(based_on("$tsc"))),
                 options: None,
+                is_background: false,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(named("$rustc"))),
                 options: None,
+                is_background: false,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: None,
+                problem_matcher: Some(VsCodeProblemMatchers::Single(named("$rustc"))),
                 options: None,
+                is_background: false,
                 other_attributes: Default::default(),
             },
         ];
@@ -360,28 +674,52 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
+                is_background: true,
+                background_pattern: builtin_background_pattern("$tsc-watch"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Tsc)],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![ProblemMatcher::Builtin(BuiltinProblemMatcher::Rustc)],
                 ..Default::default()
             },
         ];
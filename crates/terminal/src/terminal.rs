@@ -8,12 +8,12 @@ pub mod terminal_settings;
 use alacritty_terminal::{
     event::{Event as AlacTermEvent, EventListener, Notify, WindowSize},
     event_loop::{EventLoop, Msg, Notifier},
-    grid::{Dimensions, Scroll as AlacScroll},
+    grid::{Dimensions, Row, Scroll as AlacScroll},
     index::{Boundary, Column, Direction as AlacDirection, Line, Point as AlacPoint},
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Flags},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
@@ -37,15 +37,16 @@ use mappings::mouse::{
 };
 
 use collections::{HashMap, VecDeque};
-use futures::StreamExt;
+use futures::{channel::oneshot, StreamExt};
 use pty_info::PtyProcessInfo;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{BackgroundPattern, HideStrategy, Shell, TaskId};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
-use util::{paths::home_dir, truncate_and_trailoff};
+use util::{paths::home_dir, truncate_and_trailoff, ResultExt};
 
 use std::{
     cmp::{self, min},
@@ -108,6 +109,8 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// A background task started or finished a cycle of work.
+    TaskReadinessChanged,
 }
 
 #[derive(Clone, Debug)]
@@ -325,7 +328,7 @@ impl TerminalBuilder {
     pub fn new(
         working_directory: Option<PathBuf>,
         python_venv_directory: Option<PathBuf>,
-        task: Option<TaskState>,
+        mut task: Option<TaskState>,
         shell: Shell,
         mut env: HashMap<String, String>,
         cursor_shape: CursorShape,
@@ -449,8 +452,25 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
+        let background_matcher = task.as_mut().and_then(|task| {
+            task.readiness?;
+            let matcher = task
+                .background_pattern
+                .as_ref()
+                .and_then(|pattern| BackgroundMatcher::new(pattern).log_err());
+            if matcher.is_none() {
+                // Without a way to tell when the task is ready, consider it ready once started.
+                task.readiness = Some(TaskReadiness::Ready);
+            }
+            matcher
+        });
+
         let terminal = Terminal {
             task,
+            background_matcher,
+            ready_waiters: Vec::new(),
+            task_output_reader: TaskOutputReader::default(),
+            task_output_senders: Vec::new(),
             pty_tx: Notifier(pty_tx),
             completion_tx,
             term,
@@ -631,6 +651,11 @@ pub struct Terminal {
     url_regex: RegexSearch,
     word_regex: RegexSearch,
     task: Option<TaskState>,
+    background_matcher: Option<BackgroundMatcher>,
+    /// Waiting for the background task to become ready.
+    ready_waiters: Vec<oneshot::Sender<()>>,
+    task_output_reader: TaskOutputReader,
+    task_output_senders: Vec<UnboundedSender<TaskOutput>>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
 }
@@ -645,6 +670,11 @@ pub struct TaskState {
     pub hide: HideStrategy,
     pub show_summary: bool,
     pub show_command: bool,
+    /// Whether a background task finished its current cycle of work, `None` for tasks that
+    /// do not run in the background.
+    pub readiness: Option<TaskReadiness>,
+    /// Patterns in the task output that tell when a background task is busy or ready.
+    pub background_pattern: Option<BackgroundPattern>,
 }
 
 /// A status of the current terminal tab's task.
@@ -659,6 +689,116 @@ pub enum TaskStatus {
     Completed { success: bool },
 }
 
+/// Whether a task running in the background is ready for the tasks that depend on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskReadiness {
+    /// The task is starting up, or doing a cycle of work like a rebuild.
+    Busy,
+    /// The task finished its last cycle of work.
+    Ready,
+}
+
+/// The output of a task, as it is printed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskOutput {
+    /// A complete line of output, with soft-wrapped lines joined back together.
+    Line(String),
+    /// A background task began a new cycle of work, which makes its earlier output stale.
+    CycleStarted,
+}
+
+/// Matches the output of a background task against its [`BackgroundPattern`].
+struct BackgroundMatcher {
+    begins: Regex,
+    ends: Regex,
+}
+
+impl BackgroundMatcher {
+    fn new(pattern: &BackgroundPattern) -> Result<Self> {
+        Ok(Self {
+            begins: Regex::new(&pattern.begins)?,
+            ends: Regex::new(&pattern.ends)?,
+        })
+    }
+
+    /// Matches a line of output, returning whether it begins a new cycle of work, and the
+    /// readiness of the task after it, if the line changes it.
+    fn match_line(&self, line: &str) -> (bool, Option<TaskReadiness>) {
+        let begins = self.begins.is_match(line);
+        let readiness = if self.ends.is_match(line) {
+            Some(TaskReadiness::Ready)
+        } else if begins {
+            Some(TaskReadiness::Busy)
+        } else {
+            None
+        };
+        (begins, readiness)
+    }
+}
+
+/// The number of rows [`TaskOutputReader`] remembers to find its place again.
+const TASK_OUTPUT_ANCHOR_ROWS: usize = 3;
+
+/// Reads the output of a task from the terminal grid as it is printed.
+#[derive(Default)]
+struct TaskOutputReader {
+    /// The first line not read yet, counting from the top of the scrollback.
+    next_line: i32,
+    /// The text of the last rows read. Once the scrollback is full, new output moves all rows
+    /// up rather than growing the scrollback, and these tell how far the rows moved.
+    last_rows: Vec<String>,
+}
+
+impl TaskOutputReader {
+    /// Reads the lines printed since the last call. The line with the cursor is still being
+    /// written, unless the task has finished.
+    fn read<T: EventListener>(&mut self, term: &Term<T>, include_cursor_line: bool) -> Vec<String> {
+        let grid = term.grid();
+        let history_size = grid.history_size() as i32;
+        let row_text = |absolute_line: i32| {
+            let mut text = String::new();
+            push_row_text(&grid[Line(absolute_line - history_size)], &mut text);
+            text
+        };
+        let cursor_line = history_size + grid.cursor.point.line.0;
+        let end = if include_cursor_line {
+            cursor_line + 1
+        } else {
+            cursor_line
+        };
+
+        // Look for the rows read last time, which moved up if new output pushed the oldest rows
+        // out of the scrollback, and are gone if the scrollback was cleared.
+        let anchor_len = self.last_rows.len() as i32;
+        self.next_line = (anchor_len..=self.next_line.min(end))
+            .rev()
+            .find(|next_line| {
+                self.last_rows
+                    .iter()
+                    .zip(next_line - anchor_len..)
+                    .all(|(row, absolute_line)| row_text(absolute_line) == *row)
+            })
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for absolute_line in self.next_line..end {
+            if push_row_text(&grid[Line(absolute_line - history_size)], &mut line)
+                && absolute_line + 1 < end
+            {
+                continue;
+            }
+            lines.push(line.trim_end().to_string());
+            line.clear();
+            self.next_line = absolute_line + 1;
+        }
+
+        let anchor_start = (self.next_line - TASK_OUTPUT_ANCHOR_ROWS as i32).max(0);
+        self.last_rows = (anchor_start..self.next_line).map(row_text).collect();
+        lines
+    }
+}
+
 impl TaskStatus {
     fn register_terminal_exit(&mut self) {
         if self == &Self::Running {
@@ -714,6 +854,7 @@ impl Terminal {
             }
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
+                self.process_task_output(cx);
 
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
@@ -1362,6 +1503,114 @@ impl Terminal {
         lines
     }
 
+    /// All lines of the terminal, including its scrollback, with soft-wrapped lines joined back together.
+    pub fn output_lines(&self) -> Vec<String> {
+        let term = self.term.clone();
+        let terminal = term.lock_unfair();
+
+        let mut lines = Vec::new();
+        let mut line_buffer = String::new();
+        let mut current_line = terminal.topmost_line();
+        loop {
+            if !push_row_text(&terminal.grid()[current_line], &mut line_buffer) {
+                lines.push(line_buffer.trim_end().to_string());
+                line_buffer.clear();
+            }
+
+            if current_line == terminal.bottommost_line() {
+                break;
+            }
+            current_line = Line(current_line.0 + 1);
+        }
+        if !line_buffer.is_empty() {
+            lines.push(line_buffer.trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Receives the output of the running task from now on, until the task finishes.
+    pub fn subscribe_to_task_output(&mut self) -> Option<UnboundedReceiver<TaskOutput>> {
+        if self.task.as_ref()?.status != TaskStatus::Running {
+            return None;
+        }
+        let (output_tx, output_rx) = unbounded();
+        self.task_output_senders.push(output_tx);
+        Some(output_rx)
+    }
+
+    /// Collects the lines the running task prints from now on, until it finishes, however many
+    /// of them scroll out of the scrollback. Once the task has finished, these are the lines
+    /// left in the terminal.
+    pub fn collect_task_output(&mut self, cx: &App) -> Task<Vec<String>> {
+        let Some(output) = self.subscribe_to_task_output() else {
+            return Task::ready(self.output_lines());
+        };
+        cx.background_executor().spawn(async move {
+            output
+                .filter_map(|output| async move {
+                    match output {
+                        TaskOutput::Line(line) => Some(line),
+                        TaskOutput::CycleStarted => None,
+                    }
+                })
+                .collect()
+                .await
+        })
+    }
+
+    /// Reads the lines of task output printed since the last call.
+    fn read_task_output(&mut self, include_cursor_line: bool) -> Vec<String> {
+        let term = self.term.clone();
+        let terminal = term.lock_unfair();
+        self.task_output_reader.read(&terminal, include_cursor_line)
+    }
+
+    fn send_task_output(&mut self, output: TaskOutput) {
+        self.task_output_senders
+            .retain(|output_tx| output_tx.unbounded_send(output.clone()).is_ok());
+    }
+
+    /// Passes the lines the running task printed since the last call on to the subscribers of
+    /// its output, and updates the readiness of background tasks from them.
+    fn process_task_output(&mut self, cx: &mut Context<Self>) {
+        if self
+            .task
+            .as_ref()
+            .map_or(true, |task| task.status != TaskStatus::Running)
+            || (self.background_matcher.is_none() && self.task_output_senders.is_empty())
+        {
+            return;
+        }
+
+        let mut readiness = None;
+        for line in self.read_task_output(false) {
+            if let Some(matcher) = &self.background_matcher {
+                let (cycle_started, line_readiness) = matcher.match_line(&line);
+                if cycle_started {
+                    self.send_task_output(TaskOutput::CycleStarted);
+                }
+                readiness = line_readiness.or(readiness);
+            }
+            if !self.task_output_senders.is_empty() {
+                self.send_task_output(TaskOutput::Line(line));
+            }
+        }
+
+        let Some(task) = self.task.as_mut() else {
+            return;
+        };
+        if readiness.is_some() && readiness != task.readiness {
+            task.readiness = readiness;
+            if readiness == Some(TaskReadiness::Ready) {
+                for waiter in self.ready_waiters.drain(..) {
+                    waiter.send(()).ok();
+                }
+            }
+            cx.emit(Event::TaskReadinessChanged);
+            cx.notify();
+        }
+    }
+
     pub fn focus_in(&self) {
         if self.last_content.mode.contains(TermMode::FOCUS_IN_OUT) {
             self.write_to_pty("\x1b[I".to_string());
@@ -1735,8 +1984,40 @@ impl Terminal {
         Task::ready(())
     }
 
+    /// Waits for the task to finish or, if it runs in the background, to become ready.
+    pub fn wait_for_ready_task(&mut self, cx: &App) -> Task<()> {
+        let Some((readiness, status)) = self.task().map(|task| (task.readiness, task.status))
+        else {
+            return Task::ready(());
+        };
+        match readiness {
+            None => self.wait_for_completed_task(cx),
+            Some(TaskReadiness::Busy) if status == TaskStatus::Running => {
+                let (ready_tx, ready_rx) = oneshot::channel();
+                self.ready_waiters.push(ready_tx);
+                cx.spawn(|_| async move {
+                    ready_rx.await.ok();
+                })
+            }
+            Some(_) => Task::ready(()),
+        }
+    }
+
     fn register_task_finished(&mut self, error_code: Option<i32>, cx: &mut Context<'_, Terminal>) {
         self.completion_tx.try_send(()).ok();
+        self.ready_waiters.clear();
+        if self
+            .task
+            .as_ref()
+            .is_some_and(|task| task.status == TaskStatus::Running)
+            && !self.task_output_senders.is_empty()
+        {
+            // Pass on the last line too, which may not end with a newline.
+            for line in self.read_task_output(true) {
+                self.send_task_output(TaskOutput::Line(line));
+            }
+        }
+        self.task_output_senders.clear();
         let task = match &mut self.task {
             Some(task) => task,
             None => {
@@ -1789,6 +2070,18 @@ impl Terminal {
     }
 }
 
+/// Appends the text of a grid row to `line`, returning whether the row wraps into the next one.
+fn push_row_text(row: &Row<Cell>, line: &mut String) -> bool {
+    let mut wrapped = false;
+    for cell in row {
+        if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+            line.push(cell.c);
+        }
+        wrapped = cell.flags.contains(Flags::WRAPLINE);
+    }
+    wrapped
+}
+
 fn is_path_surrounded_by_common_symbols(path: &str) -> bool {
     // Avoid detecting `[]` or `()` strings as paths, surrounded by common symbols
     path.len() > 2
@@ -1995,16 +2288,122 @@ pub fn rgba_color(r: u8, g: u8, b: u8) -> Hsla {
 #[cfg(test)]
 mod tests {
     use alacritty_terminal::{
+        event::VoidListener,
         index::{Column, Line, Point as AlacPoint},
-        term::cell::Cell,
+        term::{cell::Cell, Config},
+        vte::ansi::Handler,
+        Term,
     };
     use gpui::{point, size, Pixels};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
+    use task::BackgroundPattern;
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, rgb_for_index, BackgroundMatcher, IndexedCell, TaskOutputReader,
+        TaskReadiness, TerminalContent, TerminalSize,
     };
 
+    fn print(term: &mut Term<VoidListener>, text: &str) {
+        for (ix, line) in text.split('\n').enumerate() {
+            if ix > 0 {
+                term.carriage_return();
+                term.linefeed();
+            }
+            for c in line.chars() {
+                term.input(c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_task_output_reader() {
+        let mut term = Term::new(
+            Config {
+                scrolling_history: 4,
+                ..Config::default()
+            },
+            &TerminalSize::default(),
+            VoidListener,
+        );
+        let mut reader = TaskOutputReader::default();
+
+        print(&mut term, "first\nsecond\nthird");
+        assert_eq!(reader.read(&term, false), ["first", "second"]);
+        assert_eq!(reader.read(&term, false), Vec::<String>::new());
+
+        let long_line = "x".repeat(TerminalSize::default().num_columns() + 10);
+        print(&mut term, &format!(" line\n{long_line}\n"));
+        assert_eq!(
+            reader.read(&term, false),
+            ["third line", long_line.as_str()]
+        );
+
+        // Once the scrollback is full, new output keeps moving the rows up.
+        for i in 0..20 {
+            print(&mut term, &format!("line {i}\n"));
+            assert_eq!(reader.read(&term, false), [format!("line {i}")]);
+        }
+        print(&mut term, "line 20\nline 21\nline 22\nno newline");
+        assert_eq!(reader.read(&term, false), ["line 20", "line 21", "line 22"]);
+        assert_eq!(reader.read(&term, true), ["no newline"]);
+    }
+
+    #[test]
+    fn test_background_matcher_cycles() {
+        let matcher = BackgroundMatcher::new(&BackgroundPattern {
+            begins: "^Build started".to_string(),
+            ends: "^Build finished".to_string(),
+        })
+        .unwrap();
+        let mut term = Term::new(
+            Config {
+                scrolling_history: 4,
+                ..Config::default()
+            },
+            &TerminalSize::default(),
+            VoidListener,
+        );
+        let mut reader = TaskOutputReader::default();
+        let mut read_cycle = |term: &mut Term<VoidListener>, output: &str| {
+            print(term, output);
+            let mut cycles_started = 0;
+            let mut readiness = None;
+            for line in reader.read(term, false) {
+                let (cycle_started, line_readiness) = matcher.match_line(&line);
+                cycles_started += cycle_started as usize;
+                readiness = line_readiness.or(readiness);
+            }
+            (cycles_started, readiness)
+        };
+
+        assert_eq!(
+            read_cycle(&mut term, "Build started\ncompiling\n"),
+            (1, Some(TaskReadiness::Busy))
+        );
+        assert_eq!(
+            read_cycle(&mut term, "Build finished\n"),
+            (0, Some(TaskReadiness::Ready))
+        );
+        assert_eq!(read_cycle(&mut term, "watching\n"), (0, None));
+
+        // A running background task is reused by later runs, long after its scrollback filled up.
+        for i in 0..20 {
+            assert_eq!(read_cycle(&mut term, &format!("log {i}\n")), (0, None));
+        }
+        assert_eq!(
+            read_cycle(&mut term, "Build started\n"),
+            (1, Some(TaskReadiness::Busy))
+        );
+        assert_eq!(
+            read_cycle(&mut term, "compiling\nBuild finished\n"),
+            (0, Some(TaskReadiness::Ready))
+        );
+        assert_eq!(
+            read_cycle(&mut term, "Build started\nBuild finished\n"),
+            (1, Some(TaskReadiness::Ready))
+        );
+    }
+
     #[test]
     fn test_rgb_for_index() {
         // Test every possible value in the color cube.
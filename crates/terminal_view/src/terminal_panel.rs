@@ -10,7 +10,10 @@ use crate::{
 use breadcrumbs::Breadcrumbs;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use futures::future::join_all;
+use futures::{
+    channel::{mpsc::UnboundedSender, oneshot},
+    future::join_all,
+};
 use gpui::{
     actions, Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity,
     EventEmitter, ExternalPaths, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
@@ -23,7 +26,7 @@ use settings::Settings;
 use task::{RevealStrategy, RevealTarget, ShellBuilder, SpawnInTerminal, TaskId};
 use terminal::{
     terminal_settings::{TerminalDockPosition, TerminalSettings},
-    Terminal,
+    TaskReadiness, TaskStatus, Terminal,
 };
 use ui::{
     prelude::*, ButtonCommon, Clickable, ContextMenu, FluentBuilder, PopoverMenu, Toggleable,
@@ -254,9 +257,15 @@ impl TerminalPanel {
                     cx.subscribe_in(&workspace, window, |terminal_panel, _, e, window, cx| {
                         if let workspace::Event::SpawnTask {
                             action: spawn_in_terminal,
+                            completion_tx,
                         } = e
                         {
-                            terminal_panel.spawn_task(spawn_in_terminal, window, cx);
+                            terminal_panel.spawn_task_and_report_completion(
+                                spawn_in_terminal,
+                                completion_tx.clone(),
+                                window,
+                                cx,
+                            );
                         };
                     })
                     .detach();
@@ -456,12 +465,41 @@ impl TerminalPanel {
             .detach_and_log_err(cx);
     }
 
-    fn spawn_task(&mut self, task: &SpawnInTerminal, window: &mut Window, cx: &mut Context<Self>) {
-        let Ok(is_local) = self
+    fn spawn_task_and_report_completion(
+        &mut self,
+        task: &SpawnInTerminal,
+        completion_tx: Option<UnboundedSender<bool>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let terminal = self.spawn_task(task, window, cx);
+        let Some(completion_tx) = completion_tx else {
+            terminal.detach_and_log_err(cx);
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let success = match terminal.await.log_err() {
+                Some(terminal) => wait_for_task_success(terminal, &mut cx).await,
+                None => false,
+            };
+            completion_tx.unbounded_send(success).ok();
+        })
+        .detach();
+    }
+
+    /// Runs the task in a terminal, returning that terminal once the task started.
+    pub fn spawn_task(
+        &mut self,
+        task: &SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<WeakEntity<Terminal>>> {
+        let is_local = match self
             .workspace
             .update(cx, |workspace, cx| workspace.project().read(cx).is_local())
-        else {
-            return;
+        {
+            Ok(is_local) => is_local,
+            Err(e) => return Task::ready(Err(e)),
         };
 
         let builder = ShellBuilder::new(is_local, &task.shell);
@@ -476,58 +514,78 @@ impl TerminalPanel {
         };
 
         if task.allow_concurrent_runs && task.use_new_terminal {
-            self.spawn_in_new_terminal(task, window, cx)
-                .detach_and_log_err(cx);
-            return;
+            let new_terminal = self.spawn_in_new_terminal(task, window, cx);
+            return cx.spawn(|_, _| async move { Ok(new_terminal.await?.downgrade()) });
         }
 
         let mut terminals_for_task = self.terminals_for_task(&task.full_label, cx);
+        if task.is_background {
+            // Background tasks do not finish on their own, so reuse the running one instead of waiting for it.
+            let running_terminal = terminals_for_task.iter().find_map(|(_, _, terminal_view)| {
+                let terminal = terminal_view.read(cx).terminal();
+                (terminal.read(cx).task()?.status == TaskStatus::Running)
+                    .then(|| terminal.downgrade())
+            });
+            if let Some(running_terminal) = running_terminal {
+                return Task::ready(Ok(running_terminal));
+            }
+        }
         let Some(existing) = terminals_for_task.pop() else {
-            self.spawn_in_new_terminal(task, window, cx)
-                .detach_and_log_err(cx);
-            return;
+            let new_terminal = self.spawn_in_new_terminal(task, window, cx);
+            return cx.spawn(|_, _| async move { Ok(new_terminal.await?.downgrade()) });
         };
 
         let (existing_item_index, task_pane, existing_terminal) = existing;
         if task.allow_concurrent_runs {
-            self.replace_terminal(
+            let replaced_terminal = self.replace_terminal(
                 task,
                 task_pane,
                 existing_item_index,
                 existing_terminal,
                 window,
                 cx,
-            )
-            .detach();
-            return;
+            );
+            return cx.spawn(|_, _| async move {
+                replaced_terminal
+                    .await
+                    .context("replacing the terminal of the task")
+            });
         }
 
+        let (terminal_tx, terminal_rx) = oneshot::channel();
         self.deferred_tasks.insert(
             task.id.clone(),
             cx.spawn_in(window, |terminal_panel, mut cx| async move {
                 wait_for_terminals_tasks(terminals_for_task, &mut cx).await;
                 let task = terminal_panel.update_in(&mut cx, |terminal_panel, window, cx| {
                     if task.use_new_terminal {
-                        terminal_panel
-                            .spawn_in_new_terminal(task, window, cx)
-                            .detach_and_log_err(cx);
-                        None
+                        let new_terminal = terminal_panel.spawn_in_new_terminal(task, window, cx);
+                        cx.spawn(
+                            |_, _| async move { Some(new_terminal.await.log_err()?.downgrade()) },
+                        )
                     } else {
-                        Some(terminal_panel.replace_terminal(
+                        terminal_panel.replace_terminal(
                             task,
                             task_pane,
                             existing_item_index,
                             existing_terminal,
                             window,
                             cx,
-                        ))
+                        )
                     }
                 });
-                if let Ok(Some(task)) = task {
-                    task.await;
+                if let Ok(task) = task {
+                    if let Some(terminal) = task.await {
+                        terminal_tx.send(terminal).ok();
+                    }
                 }
             }),
         );
+        cx.spawn(|_, _| async move {
+            terminal_rx
+                .await
+                .context("spawning the task after the previous run finished")
+        })
     }
 
     pub fn spawn_in_new_terminal(
@@ -773,7 +831,7 @@ impl TerminalPanel {
         terminal_to_replace: Entity<TerminalView>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Task<Option<()>> {
+    ) -> Task<Option<WeakEntity<Terminal>>> {
         let reveal = spawn_task.reveal;
         let reveal_target = spawn_task.reveal_target;
         let window_handle = window.window_handle();
@@ -794,6 +852,7 @@ impl TerminalPanel {
                 .ok()?
                 .await
                 .log_err()?;
+            let new_terminal_handle = new_terminal.downgrade();
             terminal_to_replace
                 .update_in(&mut cx, |terminal_to_replace, window, cx| {
                     terminal_to_replace.set_terminal(new_terminal, window, cx);
@@ -872,7 +931,7 @@ impl TerminalPanel {
                 RevealStrategy::Never => {}
             }
 
-            Some(())
+            Some(new_terminal_handle)
         })
     }
 
@@ -1122,6 +1181,23 @@ async fn wait_for_terminals_tasks(
     let _: Vec<()> = join_all(pending_tasks).await;
 }
 
+/// Waits for the task to finish, or for a background task to become ready, returning whether it succeeded.
+async fn wait_for_task_success(terminal: WeakEntity<Terminal>, cx: &mut AsyncApp) -> bool {
+    let Ok(ready) = terminal.update(cx, |terminal, cx| terminal.wait_for_ready_task(cx)) else {
+        return false;
+    };
+    ready.await;
+    terminal
+        .read_with(cx, |terminal, _| {
+            terminal.task().map_or(false, |task| match task.status {
+                TaskStatus::Completed { success } => success,
+                TaskStatus::Running => task.readiness == Some(TaskReadiness::Ready),
+                TaskStatus::Unknown => false,
+            })
+        })
+        .unwrap_or(false)
+}
+
 fn add_paths_to_terminal(
     pane: &mut Pane,
     paths: &[PathBuf],
@@ -22,8 +22,8 @@ use terminal::{
     },
     terminal_settings::{self, CursorShape, TerminalBlink, TerminalSettings, WorkingDirectory},
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
-    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize, ToggleViMode,
+    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskReadiness, TaskStatus,
+    Terminal, TerminalSize, ToggleViMode,
 };
 use terminal_element::{is_blank, TerminalElement};
 use terminal_panel::TerminalPanel;
@@ -842,7 +842,7 @@ fn subscribe_for_terminal_events(
                 }
             }
 
-            Event::TitleChanged => {
+            Event::TitleChanged | Event::TaskReadinessChanged => {
                 cx.emit(ItemEvent::UpdateTab);
             }
 
@@ -1244,7 +1244,11 @@ impl Item for TerminalView {
             Some(terminal_task) => match &terminal_task.status {
                 TaskStatus::Running => (
                     IconName::Play,
-                    Color::Disabled,
+                    if terminal_task.readiness == Some(TaskReadiness::Ready) {
+                        Color::Success
+                    } else {
+                        Color::Disabled
+                    },
                     Some(rerun_button(terminal_task.id.clone())),
                 ),
                 TaskStatus::Unknown => (
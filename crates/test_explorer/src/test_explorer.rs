@@ -0,0 +1,12 @@
+use gpui::App;
+use settings::Settings;
+use test_explorer_settings::TestExplorerSettings;
+
+pub mod test_explorer_panel;
+mod test_explorer_settings;
+mod test_runner;
+
+pub fn init(cx: &mut App) {
+    TestExplorerSettings::register(cx);
+    test_explorer_panel::init(cx);
+}
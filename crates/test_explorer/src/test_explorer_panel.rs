@@ -0,0 +1,843 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use collections::HashSet;
+use editor::Editor;
+use fs::RemoveOptions;
+use futures::channel::oneshot;
+use gpui::{
+    actions, Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, Pixels, Render, Subscription, Task, WeakEntity, Window,
+};
+use language::{Buffer, BufferEvent, BufferSnapshot, ToOffset as _, ToPoint as _};
+use panel::PanelHeader;
+use project::{
+    test_store::{TestStatus, TestStore},
+    Project, ProjectPath,
+};
+use settings::Settings;
+use task::TaskContext;
+use terminal_view::terminal_panel::TerminalPanel;
+use text::Anchor;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::{post_inc, ResultExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::{
+    test_explorer_settings::TestExplorerSettings,
+    test_runner::{TestFramework, TestResult, TestTarget},
+};
+
+actions!(test_explorer, [ToggleFocus, RunAllTests, RefreshTests]);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
+            workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<TestExplorerPanel>(window, cx);
+            });
+            workspace.register_action(|workspace, _: &RunAllTests, window, cx| {
+                if let Some(panel) = workspace.panel::<TestExplorerPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.run_all_tests(window, cx));
+                }
+            });
+            workspace.register_action(|workspace, _: &RefreshTests, window, cx| {
+                if let Some(panel) = workspace.panel::<TestExplorerPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.discover_tests(window, cx));
+                }
+            });
+        },
+    )
+    .detach();
+}
+
+/// Lists the tests found by the runnables of the project's files, and runs them as tasks,
+/// reporting their results both here and in the gutter of the editors showing them.
+pub struct TestExplorerPanel {
+    focus_handle: FocusHandle,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    test_store: Entity<TestStore>,
+    files: Vec<TestFile>,
+    collapsed_files: HashSet<ProjectPath>,
+    discovery: Option<Task<()>>,
+    discovered: bool,
+    run_all_when_discovered: bool,
+    next_run_id: usize,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+}
+
+struct TestFile {
+    path: ProjectPath,
+    abs_path: PathBuf,
+    worktree_root: Arc<Path>,
+    framework: TestFramework,
+    buffer: Entity<Buffer>,
+    tests: Vec<TestEntry>,
+    _subscription: Subscription,
+}
+
+struct TestEntry {
+    name: String,
+    /// The class the test is a method of, if any.
+    suite: Option<String>,
+    position: Anchor,
+    status: Option<TestStatus>,
+    message: Option<String>,
+}
+
+/// A test, identified in a way that survives rediscovering the tests of its file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TestKey {
+    path: ProjectPath,
+    name: String,
+    suite: Option<String>,
+}
+
+enum RunScope {
+    All,
+    File(ProjectPath),
+    Test(TestKey),
+}
+
+/// A single invocation of a test framework.
+struct TestRun {
+    framework: TestFramework,
+    worktree_root: Arc<Path>,
+    /// The file whose tests are run, or `None` to run all tests of the worktree.
+    file: Option<PathBuf>,
+    tests: Vec<TestKey>,
+}
+
+impl TestExplorerPanel {
+    pub fn new(
+        workspace: &mut Workspace,
+        _window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let test_store = project.read(cx).test_store().clone();
+        let workspace = cx.entity().downgrade();
+
+        cx.new(|cx| Self {
+            focus_handle: cx.focus_handle(),
+            workspace,
+            project,
+            test_store,
+            files: Vec::new(),
+            collapsed_files: HashSet::default(),
+            discovery: None,
+            discovered: false,
+            run_all_when_discovered: false,
+            next_run_id: 0,
+            width: None,
+            height: None,
+        })
+    }
+
+    /// Finds the tests of all files in the visible worktrees, replacing the ones found before.
+    fn discover_tests(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut candidates = Vec::new();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            let worktree_id = worktree.id();
+            candidates.extend(
+                worktree
+                    .files(false, 0)
+                    .filter(|entry| TestFramework::may_contain_tests(&entry.path))
+                    .map(|entry| ProjectPath {
+                        worktree_id,
+                        path: entry.path.clone(),
+                    }),
+            );
+        }
+
+        let project = self.project.clone();
+        self.discovery = Some(cx.spawn_in(window, |this, mut cx| async move {
+            let mut files = Vec::new();
+            for path in candidates {
+                match Self::discover_file(&this, &project, path, &mut cx).await {
+                    Ok(Some(file)) => files.push(file),
+                    Ok(None) => {}
+                    Err(error) => log::error!("failed to discover tests: {error:#}"),
+                }
+            }
+            this.update_in(&mut cx, |this, window, cx| {
+                this.set_files(files, cx);
+                this.discovery = None;
+                this.discovered = true;
+                if std::mem::take(&mut this.run_all_when_discovered) {
+                    this.run_tests(RunScope::All, window, cx);
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    async fn discover_file(
+        this: &WeakEntity<Self>,
+        project: &Entity<Project>,
+        path: ProjectPath,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Option<TestFile>> {
+        let Some((abs_path, worktree_root, languages, fs)) =
+            project.update(cx, |project, cx| {
+                let abs_path = project.absolute_path(&path, cx)?;
+                let worktree = project.worktree_for_id(path.worktree_id, cx)?;
+                let worktree_root = worktree.read(cx).abs_path();
+                Some((
+                    abs_path,
+                    worktree_root,
+                    project.languages().clone(),
+                    project.fs().clone(),
+                ))
+            })?
+        else {
+            return Ok(None);
+        };
+
+        // Loading the language first lets the buffer be parsed as soon as it is opened.
+        let Ok(language) = languages.language_for_file_path(&abs_path).await else {
+            return Ok(None);
+        };
+        let Some(framework) = TestFramework::for_language(&language.name()) else {
+            return Ok(None);
+        };
+        // Avoid parsing files that cannot contain tests.
+        if !framework.may_mention_tests(&fs.load(&abs_path).await?) {
+            return Ok(None);
+        }
+
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(path.clone(), cx))?
+            .await?;
+        wait_for_parse(&buffer, cx).await?;
+
+        this.update(cx, |_, cx| {
+            let tests = find_tests(framework, buffer.read(cx));
+            if tests.is_empty() {
+                return None;
+            }
+            let subscription = cx.subscribe(&buffer, |this, buffer, event, cx| {
+                if let BufferEvent::Reparsed = event {
+                    this.refresh_file_tests(&buffer, cx);
+                }
+            });
+            Some(TestFile {
+                path,
+                abs_path,
+                worktree_root,
+                framework,
+                buffer,
+                tests,
+                _subscription: subscription,
+            })
+        })
+    }
+
+    fn set_files(&mut self, mut files: Vec<TestFile>, cx: &mut Context<Self>) {
+        files.sort_by(|a, b| {
+            (a.path.worktree_id, &a.path.path).cmp(&(b.path.worktree_id, &b.path.path))
+        });
+        for file in &mut files {
+            if let Some(old_file) = self.files.iter().find(|old| old.path == file.path) {
+                carry_over_statuses(&old_file.tests, &mut file.tests);
+            }
+        }
+        self.files = files;
+        cx.notify();
+    }
+
+    fn refresh_file_tests(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(file) = self.files.iter_mut().find(|file| file.buffer == *buffer) else {
+            return;
+        };
+        let mut tests = find_tests(file.framework, buffer.read(cx));
+        carry_over_statuses(&file.tests, &mut tests);
+        file.tests = tests;
+        cx.notify();
+    }
+
+    fn run_all_tests(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.discovered {
+            self.run_tests(RunScope::All, window, cx);
+        } else {
+            self.run_all_when_discovered = true;
+            if self.discovery.is_none() {
+                self.discover_tests(window, cx);
+            }
+        }
+    }
+
+    fn run_tests(&mut self, scope: RunScope, window: &mut Window, cx: &mut Context<Self>) {
+        let runs = match scope {
+            RunScope::All => {
+                let mut runs = Vec::<TestRun>::new();
+                for file in &self.files {
+                    let tests = file.tests.iter().map(|test| test.key(&file.path));
+                    match runs.iter_mut().find(|run| {
+                        run.framework == file.framework && run.worktree_root == file.worktree_root
+                    }) {
+                        Some(run) => run.tests.extend(tests),
+                        None => runs.push(TestRun {
+                            framework: file.framework,
+                            worktree_root: file.worktree_root.clone(),
+                            file: None,
+                            tests: tests.collect(),
+                        }),
+                    }
+                }
+                runs
+            }
+            RunScope::File(path) => self
+                .files
+                .iter()
+                .filter(|file| file.path == path)
+                .map(|file| TestRun {
+                    framework: file.framework,
+                    worktree_root: file.worktree_root.clone(),
+                    file: Some(file.abs_path.clone()),
+                    tests: file.tests.iter().map(|test| test.key(&file.path)).collect(),
+                })
+                .collect(),
+            RunScope::Test(key) => self
+                .files
+                .iter()
+                .filter(|file| file.path == key.path)
+                .map(|file| TestRun {
+                    framework: file.framework,
+                    worktree_root: file.worktree_root.clone(),
+                    file: Some(file.abs_path.clone()),
+                    tests: vec![key.clone()],
+                })
+                .collect(),
+        };
+
+        for run in runs {
+            self.start_run(run, window, cx);
+        }
+    }
+
+    fn start_run(&mut self, run: TestRun, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(terminal_panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
+        else {
+            return;
+        };
+
+        let report_path = run.framework.report_path(post_inc(&mut self.next_run_id));
+        let targets = run
+            .tests
+            .iter()
+            .map(|test| TestTarget {
+                name: &test.name,
+                suite: test.suite.as_deref(),
+            })
+            .collect::<Vec<_>>();
+        let template = run.framework.task_template(
+            &run.worktree_root,
+            run.file
+                .as_deref()
+                .map(|abs_path| (abs_path, targets.as_slice())),
+            report_path.as_deref(),
+        );
+        let Some(spawn_in_terminal) = template
+            .resolve_task("test_explorer", &TaskContext::default())
+            .and_then(|task| task.resolved)
+        else {
+            return;
+        };
+
+        for test in &run.tests {
+            self.set_test_status(test, Some(TestStatus::Running), None, cx);
+        }
+        let terminal = terminal_panel.update(cx, |terminal_panel, cx| {
+            terminal_panel.spawn_task(&spawn_in_terminal, window, cx)
+        });
+        let fs = self.project.read(cx).fs().clone();
+        cx.spawn_in(window, |this, mut cx| async move {
+            let results = async {
+                let terminal = terminal.await?;
+                // Read while the tests run, as long outputs don't fit in the scrollback.
+                let output = terminal
+                    .update(&mut cx, |terminal, cx| terminal.collect_task_output(cx))?
+                    .await;
+                let report = match &report_path {
+                    Some(report_path) => {
+                        let report = fs.load(report_path).await.ok();
+                        fs.remove_file(
+                            report_path,
+                            RemoveOptions {
+                                ignore_if_not_exists: true,
+                                ..RemoveOptions::default()
+                            },
+                        )
+                        .await
+                        .log_err();
+                        report
+                    }
+                    None => None,
+                };
+                anyhow::Ok(run.framework.parse_results(&output, report.as_deref()))
+            }
+            .await;
+            this.update(&mut cx, |this, cx| {
+                this.finish_run(&run.tests, results.log_err().unwrap_or_default(), cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn finish_run(&mut self, tests: &[TestKey], results: Vec<TestResult>, cx: &mut Context<Self>) {
+        for test in tests {
+            let target = TestTarget {
+                name: &test.name,
+                suite: test.suite.as_deref(),
+            };
+            // Parametrized tests report a result per case, any failing case fails the test.
+            // Tests without a result did not run, e.g. because the build failed.
+            let result = results
+                .iter()
+                .filter(|result| result.matches(&target))
+                .max_by_key(|result| match result.status {
+                    TestStatus::Failed => 3,
+                    TestStatus::Passed => 2,
+                    TestStatus::Skipped => 1,
+                    TestStatus::Running => 0,
+                });
+            self.set_test_status(
+                test,
+                result.map(|result| result.status),
+                result.and_then(|result| result.message.clone()),
+                cx,
+            );
+        }
+    }
+
+    fn set_test_status(
+        &mut self,
+        key: &TestKey,
+        status: Option<TestStatus>,
+        message: Option<String>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(file) = self.files.iter_mut().find(|file| file.path == key.path) else {
+            return;
+        };
+        let Some(test) = file
+            .tests
+            .iter_mut()
+            .find(|test| test.name == key.name && test.suite == key.suite)
+        else {
+            return;
+        };
+        test.status = status;
+        test.message = message;
+        let position = test.position;
+        self.test_store.update(cx, |test_store, cx| {
+            test_store.set_test_status(&file.buffer, position, status, cx)
+        });
+        cx.notify();
+    }
+
+    fn open_test(&mut self, key: &TestKey, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(point) = self
+            .files
+            .iter()
+            .find(|file| file.path == key.path)
+            .and_then(|file| {
+                let snapshot = file.buffer.read(cx).snapshot();
+                file.tests
+                    .iter()
+                    .find(|test| test.name == key.name && test.suite == key.suite)
+                    .map(|test| test.position.to_point(&snapshot))
+            })
+        else {
+            return;
+        };
+        let Some(open_task) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(key.path.clone(), None, true, window, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn_in(window, |_, mut cx| async move {
+            let item = open_task.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update_in(&mut cx, |editor, window, cx| {
+                    editor.go_to_singleton_buffer_point(point, window, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_header(&self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let is_discovering = self.discovery.is_some();
+        self.panel_header_container(window, cx)
+            .gap_1()
+            .child(Label::new("Tests").size(LabelSize::Small))
+            .child(div().flex_1())
+            .child(
+                IconButton::new("refresh-tests", IconName::ArrowCircle)
+                    .icon_size(IconSize::Small)
+                    .disabled(is_discovering)
+                    .tooltip(Tooltip::for_action_title("Refresh Tests", &RefreshTests))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(RefreshTests.boxed_clone(), cx)
+                    }),
+            )
+            .child(
+                IconButton::new("run-all-tests", IconName::Play)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.discovered && self.files.is_empty())
+                    .tooltip(Tooltip::for_action_title("Run All Tests", &RunAllTests))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(RunAllTests.boxed_clone(), cx)
+                    }),
+            )
+    }
+
+    fn render_file(&self, file: &TestFile, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_open = !self.collapsed_files.contains(&file.path);
+        let path = file.path.clone();
+        let status = aggregate_status(file.tests.iter().map(|test| test.status));
+
+        v_flex()
+            .child(
+                ListItem::new(SharedString::from(format!(
+                    "test-file-{}",
+                    file.path.path.display()
+                )))
+                .toggle(is_open)
+                .on_toggle({
+                    let path = path.clone();
+                    cx.listener(move |this, _, _, cx| {
+                        if !this.collapsed_files.remove(&path) {
+                            this.collapsed_files.insert(path.clone());
+                        }
+                        cx.notify();
+                    })
+                })
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .children(status.map(|status| render_status_icon(Some(status))))
+                        .child(
+                            Label::new(file.path.path.to_string_lossy().into_owned())
+                                .size(LabelSize::Small)
+                                .single_line(),
+                        ),
+                )
+                .end_hover_slot(
+                    IconButton::new("run-file-tests", IconName::Play)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Run Tests in File"))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.run_tests(RunScope::File(path.clone()), window, cx);
+                        })),
+                ),
+            )
+            .when(is_open, |this| {
+                this.children(file.tests.iter().enumerate().map(|(ix, test)| {
+                    let key = test.key(&file.path);
+                    let label = match &test.suite {
+                        Some(suite) => format!("{suite}::{}", test.name),
+                        None => test.name.clone(),
+                    };
+                    ListItem::new(SharedString::from(format!(
+                        "test-{}-{ix}",
+                        file.path.path.display()
+                    )))
+                    .indent_level(1)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(render_status_icon(test.status))
+                            .child(Label::new(label).size(LabelSize::Small).single_line()),
+                    )
+                    .when_some(test.message.clone(), |this, message| {
+                        this.tooltip(Tooltip::text(message))
+                    })
+                    .end_hover_slot(
+                        IconButton::new(("run-test", ix), IconName::Play)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Run Test"))
+                            .on_click({
+                                let key = key.clone();
+                                cx.listener(move |this, _, window, cx| {
+                                    this.run_tests(RunScope::Test(key.clone()), window, cx);
+                                })
+                            }),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_test(&key, window, cx);
+                    }))
+                }))
+            })
+    }
+}
+
+impl TestEntry {
+    fn key(&self, path: &ProjectPath) -> TestKey {
+        TestKey {
+            path: path.clone(),
+            name: self.name.clone(),
+            suite: self.suite.clone(),
+        }
+    }
+}
+
+/// Finds the tests in a buffer, using the runnables of its language.
+fn find_tests(framework: TestFramework, buffer: &Buffer) -> Vec<TestEntry> {
+    let snapshot = buffer.snapshot();
+    let mut tests = snapshot
+        .runnable_ranges(0..snapshot.len())
+        .filter(|runnable| {
+            runnable.runnable.tags.iter().any(|tag| {
+                framework
+                    .test_tags()
+                    .iter()
+                    .any(|test_tag| *test_tag == &*tag.0)
+            })
+        })
+        .map(|runnable| TestEntry {
+            name: snapshot
+                .text_for_range(runnable.run_range.clone())
+                .collect(),
+            suite: framework
+                .suite_captures()
+                .iter()
+                .find_map(|capture| runnable.extra_captures.get(*capture).cloned())
+                .or_else(|| enclosing_modules(framework, &snapshot, runnable.run_range.clone())),
+            position: snapshot.anchor_before(runnable.run_range.start),
+            status: None,
+            message: None,
+        })
+        .collect::<Vec<_>>();
+    tests.sort_by_key(|test| test.position.to_offset(&snapshot));
+    tests.dedup_by(|a, b| a.name == b.name && a.suite == b.suite);
+    tests
+}
+
+/// The path of the modules a test is nested in within its buffer, for frameworks whose tests are
+/// grouped by modules.
+fn enclosing_modules(
+    framework: TestFramework,
+    snapshot: &BufferSnapshot,
+    range: Range<usize>,
+) -> Option<String> {
+    let module_kinds = framework.suite_node_kinds();
+    if module_kinds.is_empty() {
+        return None;
+    }
+    let mut node = snapshot.syntax_ancestor(range)?;
+    let mut modules = Vec::new();
+    while let Some(parent) = node.parent() {
+        if module_kinds.contains(&parent.kind()) {
+            if let Some(name) = parent.child_by_field_name("name") {
+                modules.push(
+                    snapshot
+                        .text_for_range(name.byte_range())
+                        .collect::<String>(),
+                );
+            }
+        }
+        node = parent;
+    }
+    modules.reverse();
+    (!modules.is_empty()).then(|| modules.join("::"))
+}
+
+fn carry_over_statuses(old_tests: &[TestEntry], tests: &mut [TestEntry]) {
+    for test in tests {
+        if let Some(old_test) = old_tests
+            .iter()
+            .find(|old| old.name == test.name && old.suite == test.suite)
+        {
+            test.status = old_test.status;
+            test.message = old_test.message.clone();
+        }
+    }
+}
+
+/// The status of a group of tests: failed if any failed, running if any still runs, and
+/// passed if all others passed.
+fn aggregate_status(statuses: impl Iterator<Item = Option<TestStatus>>) -> Option<TestStatus> {
+    let mut aggregate = None;
+    for status in statuses.flatten() {
+        aggregate = match (aggregate, status) {
+            (_, TestStatus::Failed) | (Some(TestStatus::Failed), _) => Some(TestStatus::Failed),
+            (_, TestStatus::Running) | (Some(TestStatus::Running), _) => Some(TestStatus::Running),
+            (_, TestStatus::Passed) | (Some(TestStatus::Passed), _) => Some(TestStatus::Passed),
+            (_, TestStatus::Skipped) => Some(TestStatus::Skipped),
+        };
+    }
+    aggregate
+}
+
+fn render_status_icon(status: Option<TestStatus>) -> Icon {
+    let (icon, color) = match status {
+        Some(TestStatus::Running) => (IconName::ArrowCircle, Color::Accent),
+        Some(TestStatus::Passed) => (IconName::Check, Color::Success),
+        Some(TestStatus::Failed) => (IconName::XCircle, Color::Error),
+        Some(TestStatus::Skipped) => (IconName::Dash, Color::Muted),
+        None => (IconName::Circle, Color::Muted),
+    };
+    Icon::new(icon).size(IconSize::Small).color(color)
+}
+
+/// Waits until the syntax tree of the buffer is up to date.
+async fn wait_for_parse(buffer: &Entity<Buffer>, cx: &mut AsyncWindowContext) -> Result<()> {
+    let (parsed_tx, parsed_rx) = oneshot::channel();
+    let subscription = cx.update(|_, cx| {
+        if !buffer.read(cx).is_parsing() {
+            return None;
+        }
+        let mut parsed_tx = Some(parsed_tx);
+        Some(cx.subscribe(buffer, move |_, event, _| {
+            if let BufferEvent::Reparsed = event {
+                if let Some(parsed_tx) = parsed_tx.take() {
+                    parsed_tx.send(()).ok();
+                }
+            }
+        }))
+    })?;
+    if let Some(_subscription) = subscription {
+        parsed_rx.await.ok();
+    }
+    Ok(())
+}
+
+impl Render for TestExplorerPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .key_context("TestExplorerPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_header(window, cx));
+
+        if self.files.is_empty() {
+            let (title, description) = if self.discovery.is_some() || !self.discovered {
+                ("Searching for tests…", None)
+            } else {
+                (
+                    "No tests found",
+                    Some("Tests are found in Rust, Go and Python files of the project."),
+                )
+            };
+            return panel.child(
+                v_flex()
+                    .size_full()
+                    .items_center()
+                    .justify_center()
+                    .gap_1()
+                    .child(Label::new(title))
+                    .children(description.map(|description| {
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    })),
+            );
+        }
+
+        let files = self
+            .files
+            .iter()
+            .map(|file| self.render_file(file, cx).into_any_element())
+            .collect::<Vec<_>>();
+        panel.child(
+            v_flex()
+                .id("test-explorer-tests")
+                .flex_1()
+                .overflow_y_scroll()
+                .children(files),
+        )
+    }
+}
+
+impl Focusable for TestExplorerPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TestExplorerPanel {}
+
+impl Panel for TestExplorerPanel {
+    fn persistent_name() -> &'static str {
+        "TestExplorerPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        TestExplorerSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        let fs = self.project.read(cx).fs().clone();
+        settings::update_settings_file::<TestExplorerSettings>(fs, cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, window: &Window, cx: &App) -> Pixels {
+        let settings = TestExplorerSettings::get_global(cx);
+        match self.position(window, cx) {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        match self.position(window, cx) {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if active && !self.discovered && self.discovery.is_none() {
+            self.discover_tests(window, cx);
+        }
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        Some(IconName::Flask).filter(|_| TestExplorerSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Test Explorer")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
+
+impl PanelHeader for TestExplorerPanel {}
@@ -0,0 +1,649 @@
+use std::path::{Path, PathBuf};
+
+use collections::HashMap;
+use language::LanguageName;
+use project::test_store::TestStatus;
+use regex::Regex;
+use serde::Deserialize;
+use task::{RevealStrategy, TaskTemplate};
+
+/// The tool that runs the tests of a language, and reports their results in a format we can parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TestFramework {
+    /// `cargo test`, with libtest's human-readable output.
+    Cargo,
+    /// `go test -json`.
+    Go,
+    /// `pytest`, with a JUnit XML report.
+    Pytest,
+}
+
+/// A test to run, as named by the runnable that found it.
+pub struct TestTarget<'a> {
+    pub name: &'a str,
+    /// The class the test is a method of, or the modules it is nested in within its file, if any.
+    pub suite: Option<&'a str>,
+}
+
+/// The result of a single test, as reported by a test framework.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestResult {
+    pub name: String,
+    /// The module path of a Rust test, or the last segment of the class path of a Python test.
+    pub suite: Option<String>,
+    pub status: TestStatus,
+    pub message: Option<String>,
+}
+
+impl TestFramework {
+    pub fn for_language(language: &LanguageName) -> Option<Self> {
+        match language.as_ref() {
+            "Rust" => Some(Self::Cargo),
+            "Go" => Some(Self::Go),
+            "Python" => Some(Self::Pytest),
+            _ => None,
+        }
+    }
+
+    /// Whether the file at the given path may contain tests, judging by its name.
+    pub fn may_contain_tests(path: &Path) -> bool {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+        file_name.ends_with(".rs")
+            || file_name.ends_with("_test.go")
+            || (file_name.ends_with(".py")
+                && (file_name.starts_with("test_") || file_name.ends_with("_test.py")))
+    }
+
+    /// Whether a file with the given contents may contain tests, to skip parsing the ones that
+    /// cannot.
+    pub fn may_mention_tests(&self, text: &str) -> bool {
+        match self {
+            Self::Cargo => text.contains("test"),
+            Self::Go => text.contains("func Test"),
+            Self::Pytest => text.contains("def test"),
+        }
+    }
+
+    /// The runnable tags that mark tests in this framework's language.
+    pub fn test_tags(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["rust-test"],
+            Self::Go => &["go-test"],
+            Self::Pytest => &["python-pytest-method", "python-unittest-method"],
+        }
+    }
+
+    /// The runnable captures holding the name of the class a test is a method of.
+    pub fn suite_captures(&self) -> &'static [&'static str] {
+        match self {
+            Self::Pytest => &["_pytest_class_name", "_unittest_class_name"],
+            Self::Cargo | Self::Go => &[],
+        }
+    }
+
+    /// The syntax nodes that make up the suite of the tests nested in them, for languages whose
+    /// tests are grouped by modules rather than classes.
+    pub fn suite_node_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["mod_item"],
+            Self::Go | Self::Pytest => &[],
+        }
+    }
+
+    /// Builds the task that runs the given tests of a file, or all tests of the worktree if
+    /// `file` is `None`. Frameworks that write a report file write it to `report_path`.
+    pub fn task_template(
+        &self,
+        worktree_root: &Path,
+        file: Option<(&Path, &[TestTarget<'_>])>,
+        report_path: Option<&Path>,
+    ) -> TaskTemplate {
+        let (command, args, cwd) = match self {
+            Self::Cargo => {
+                let mut args = vec!["test".to_string()];
+                let cwd = match file {
+                    // Cargo picks the package to test from the working directory.
+                    Some((abs_path, tests)) => {
+                        let module_path = rust_module_path(abs_path);
+                        args.extend(["--".to_string(), "--exact".to_string()]);
+                        args.extend(tests.iter().map(|test| {
+                            module_path
+                                .iter()
+                                .map(String::as_str)
+                                .chain(test.suite)
+                                .chain([test.name])
+                                .collect::<Vec<_>>()
+                                .join("::")
+                        }));
+                        abs_path.parent().unwrap_or(worktree_root)
+                    }
+                    None => {
+                        args.extend(["--workspace".to_string(), "--no-fail-fast".to_string()]);
+                        worktree_root
+                    }
+                };
+                ("cargo", args, cwd)
+            }
+            Self::Go => {
+                let mut args = vec!["test".to_string(), "-json".to_string()];
+                let cwd = match file {
+                    Some((abs_path, tests)) => {
+                        let names = tests
+                            .iter()
+                            .map(|test| regex::escape(test.name))
+                            .collect::<Vec<_>>();
+                        args.extend([
+                            "-run".to_string(),
+                            format!("^({})$", names.join("|")),
+                            ".".to_string(),
+                        ]);
+                        abs_path.parent().unwrap_or(worktree_root)
+                    }
+                    None => {
+                        args.push("./...".to_string());
+                        worktree_root
+                    }
+                };
+                ("go", args, cwd)
+            }
+            Self::Pytest => {
+                let mut args = vec!["-m".to_string(), "pytest".to_string()];
+                if let Some(report_path) = report_path {
+                    args.push(format!("--junitxml={}", report_path.display()));
+                }
+                if let Some((abs_path, tests)) = file {
+                    let relative_path = abs_path
+                        .strip_prefix(worktree_root)
+                        .unwrap_or(abs_path)
+                        .to_string_lossy()
+                        .replace('\\', "/");
+                    args.extend(tests.iter().map(|test| match test.suite {
+                        Some(suite) => format!("{relative_path}::{suite}::{}", test.name),
+                        None => format!("{relative_path}::{}", test.name),
+                    }));
+                }
+                let python = if cfg!(windows) { "python" } else { "python3" };
+                (python, args, worktree_root)
+            }
+        };
+
+        let label = match file {
+            Some((_, [test])) => format!("test {}", test.name),
+            Some((abs_path, _)) => format!(
+                "test {}",
+                abs_path
+                    .strip_prefix(worktree_root)
+                    .unwrap_or(abs_path)
+                    .display()
+            ),
+            None => format!(
+                "test {}",
+                worktree_root.file_name().map_or_else(
+                    || worktree_root.display().to_string(),
+                    |name| { name.to_string_lossy().into_owned() }
+                )
+            ),
+        };
+        TaskTemplate {
+            label,
+            command: command.to_string(),
+            args,
+            cwd: Some(cwd.to_string_lossy().into_owned()),
+            reveal: RevealStrategy::NoFocus,
+            show_summary: true,
+            show_command: true,
+            ..TaskTemplate::default()
+        }
+    }
+
+    /// Extracts test results from the terminal output of a test run, or from the report file
+    /// it wrote.
+    pub fn parse_results(&self, output: &[String], report: Option<&str>) -> Vec<TestResult> {
+        match self {
+            Self::Cargo => parse_libtest_output(output),
+            Self::Go => parse_go_test_json(output),
+            Self::Pytest => report.map(parse_junit_xml).unwrap_or_default(),
+        }
+    }
+
+    /// Where the framework writes its report file to, if it does not print its results.
+    pub fn report_path(&self, run_id: usize) -> Option<PathBuf> {
+        match self {
+            Self::Pytest => Some(std::env::temp_dir().join(format!(
+                "zed-test-report-{}-{run_id}.xml",
+                std::process::id()
+            ))),
+            Self::Cargo | Self::Go => None,
+        }
+    }
+}
+
+impl TestResult {
+    pub fn matches(&self, target: &TestTarget<'_>) -> bool {
+        self.name == target.name
+            && target.suite.map_or(true, |suite| {
+                // Rust tests know the modules they are nested in within their file, but not the
+                // module of the file itself.
+                self.suite.as_deref().map_or(false, |result_suite| {
+                    result_suite == suite
+                        || result_suite
+                            .strip_suffix(suite)
+                            .is_some_and(|prefix| prefix.ends_with("::"))
+                })
+            })
+    }
+}
+
+/// The path of the module a Rust file holds within its crate, assuming the usual layout of Cargo
+/// packages. Crate roots, such as `src/lib.rs` or the files in `tests`, have an empty path.
+fn rust_module_path(abs_path: &Path) -> Vec<String> {
+    let components = abs_path
+        .iter()
+        .map(|component| component.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    let Some(src_ix) = components.iter().rposition(|component| component == "src") else {
+        return Vec::new();
+    };
+    let mut module_path = components[src_ix + 1..].to_vec();
+    let Some(file_name) = module_path.pop() else {
+        return Vec::new();
+    };
+    if module_path
+        .first()
+        .is_some_and(|component| component == "bin")
+    {
+        return Vec::new();
+    }
+    let module_name = file_name.strip_suffix(".rs").unwrap_or(&file_name);
+    let is_crate_root = module_path.is_empty() && matches!(module_name, "lib" | "main");
+    if !is_crate_root && module_name != "mod" {
+        module_path.push(module_name.to_string());
+    }
+    module_path
+}
+
+/// Parses the output libtest prints by default, which, unlike its JSON output, is stable.
+fn parse_libtest_output(output: &[String]) -> Vec<TestResult> {
+    let result =
+        Regex::new(r"^test (\S+) (?:- should panic )?\.\.\. (ok|FAILED|ignored)\b").unwrap();
+    let failure_header = Regex::new(r"^---- (\S+) stdout ----$").unwrap();
+
+    let mut results = Vec::new();
+    let mut failure: Option<(String, Vec<&str>)> = None;
+    let mut messages = HashMap::<String, String>::default();
+    let mut finish_failure = |failure: &mut Option<(String, Vec<&str>)>| {
+        if let Some((path, mut lines)) = failure.take() {
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
+            }
+            let message = lines.iter().map(|line| format!("{line}\n")).collect();
+            messages.insert(path, message);
+        }
+    };
+    for line in output {
+        let line = line.trim_end();
+        if let Some(captures) = result.captures(line) {
+            let status = match &captures[2] {
+                "ok" => TestStatus::Passed,
+                "FAILED" => TestStatus::Failed,
+                _ => TestStatus::Skipped,
+            };
+            results.push((captures[1].to_string(), status));
+        } else if let Some(captures) = failure_header.captures(line) {
+            finish_failure(&mut failure);
+            failure = Some((captures[1].to_string(), Vec::new()));
+        } else if line == "failures:" {
+            finish_failure(&mut failure);
+        } else if let Some((_, lines)) = failure.as_mut() {
+            lines.push(line);
+        }
+    }
+    finish_failure(&mut failure);
+
+    results
+        .into_iter()
+        .map(|(path, status)| {
+            let message = messages.remove(&path);
+            let (suite, name) = match path.rsplit_once("::") {
+                Some((module, name)) => (Some(module.to_string()), name.to_string()),
+                None => (None, path),
+            };
+            TestResult {
+                name,
+                suite,
+                status,
+                message,
+            }
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GoTestEvent {
+    action: String,
+    test: Option<String>,
+    output: Option<String>,
+}
+
+fn parse_go_test_json(output: &[String]) -> Vec<TestResult> {
+    let mut test_output = HashMap::<String, String>::default();
+    let mut results = Vec::new();
+    for event in output
+        .iter()
+        .filter_map(|line| serde_json::from_str::<GoTestEvent>(line.trim()).ok())
+    {
+        // Subtests report their results under `Test/subtest`, which the runnables do not find.
+        let Some(name) = event.test.filter(|name| !name.contains('/')) else {
+            continue;
+        };
+        let status = match event.action.as_str() {
+            "output" => {
+                if let Some(output) = event.output {
+                    test_output.entry(name).or_default().push_str(&output);
+                }
+                continue;
+            }
+            "pass" => TestStatus::Passed,
+            "fail" => TestStatus::Failed,
+            "skip" => TestStatus::Skipped,
+            _ => continue,
+        };
+        let message = test_output.remove(&name);
+        results.push(TestResult {
+            name,
+            suite: None,
+            status,
+            message: message.filter(|_| status == TestStatus::Failed),
+        });
+    }
+    results
+}
+
+fn parse_junit_xml(report: &str) -> Vec<TestResult> {
+    let testcase = Regex::new(r"(?s)<testcase\b([^>]*?)(?:/>|>(.*?)</testcase>)").unwrap();
+    let attribute = Regex::new(r#"(\w+)="([^"]*)""#).unwrap();
+    let outcome = Regex::new(r"<(failure|error|skipped)\b([^>]*)").unwrap();
+
+    testcase
+        .captures_iter(report)
+        .filter_map(|testcase| {
+            let attributes = attribute
+                .captures_iter(testcase.get(1)?.as_str())
+                .map(|attribute| (attribute[1].to_string(), unescape_xml(&attribute[2])))
+                .collect::<HashMap<_, _>>();
+            let name = attributes.get("name")?;
+            // Parametrized tests are reported as `test_name[parameters]`.
+            let name = name.split('[').next().unwrap_or(name).to_string();
+            let suite = attributes
+                .get("classname")
+                .and_then(|classname| classname.rsplit('.').next())
+                .map(ToString::to_string);
+            let body = testcase.get(2).map_or("", |body| body.as_str());
+            let (status, message) = match outcome.captures(body) {
+                Some(outcome) => {
+                    let status = if &outcome[1] == "skipped" {
+                        TestStatus::Skipped
+                    } else {
+                        TestStatus::Failed
+                    };
+                    let message = attribute
+                        .captures_iter(&outcome[2])
+                        .find(|attribute| &attribute[1] == "message")
+                        .map(|attribute| unescape_xml(&attribute[2]));
+                    (status, message)
+                }
+                None => (TestStatus::Passed, None),
+            };
+            Some(TestResult {
+                name,
+                suite,
+                status,
+                message,
+            })
+        })
+        .collect()
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(output: &str) -> Vec<String> {
+        output.lines().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_libtest_output() {
+        let output = lines(
+            r#"   Compiling foo v0.1.0 (/home/user/foo)
+    Finished `test` profile [unoptimized + debuginfo] target(s) in 0.52s
+     Running unittests src/lib.rs (target/debug/deps/foo-0123456789abcdef)
+
+running 4 tests
+test tests::adds ... ok
+test tests::overflows - should panic ... ok
+test slow ... ignored, needs a network connection
+test tests::subtracts ... FAILED
+
+failures:
+
+---- tests::subtracts stdout ----
+assertion failed
+
+
+failures:
+    tests::subtracts
+
+test result: FAILED. 2 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+"#,
+        );
+        assert_eq!(
+            TestFramework::Cargo.parse_results(&output, None),
+            vec![
+                TestResult {
+                    name: "adds".to_string(),
+                    suite: Some("tests".to_string()),
+                    status: TestStatus::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "overflows".to_string(),
+                    suite: Some("tests".to_string()),
+                    status: TestStatus::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "slow".to_string(),
+                    suite: None,
+                    status: TestStatus::Skipped,
+                    message: None,
+                },
+                TestResult {
+                    name: "subtracts".to_string(),
+                    suite: Some("tests".to_string()),
+                    status: TestStatus::Failed,
+                    message: Some("assertion failed\n".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_template_args() {
+        let worktree_root = Path::new("/home/user/foo");
+        let tests = [
+            TestTarget {
+                name: "adds",
+                suite: Some("tests"),
+            },
+            TestTarget {
+                name: "top_level",
+                suite: None,
+            },
+        ];
+
+        let template = TestFramework::Cargo.task_template(
+            worktree_root,
+            Some((Path::new("/home/user/foo/src/math/ops.rs"), &tests)),
+            None,
+        );
+        assert_eq!(template.command, "cargo");
+        assert_eq!(
+            template.args,
+            [
+                "test",
+                "--",
+                "--exact",
+                "math::ops::tests::adds",
+                "math::ops::top_level"
+            ]
+        );
+        assert_eq!(template.cwd.as_deref(), Some("/home/user/foo/src/math"));
+        assert!(template.env.is_empty());
+
+        for (crate_root, cwd) in [
+            ("/home/user/foo/src/lib.rs", "/home/user/foo/src"),
+            ("/home/user/foo/src/bin/tool.rs", "/home/user/foo/src/bin"),
+            (
+                "/home/user/foo/tests/integration.rs",
+                "/home/user/foo/tests",
+            ),
+        ] {
+            let template = TestFramework::Cargo.task_template(
+                worktree_root,
+                Some((Path::new(crate_root), &tests[..1])),
+                None,
+            );
+            assert_eq!(template.args, ["test", "--", "--exact", "tests::adds"]);
+            assert_eq!(template.cwd.as_deref(), Some(cwd));
+        }
+
+        let template = TestFramework::Cargo.task_template(
+            worktree_root,
+            Some((Path::new("/home/user/foo/src/math/mod.rs"), &tests[1..])),
+            None,
+        );
+        assert_eq!(template.args, ["test", "--", "--exact", "math::top_level"]);
+
+        let template = TestFramework::Cargo.task_template(worktree_root, None, None);
+        assert_eq!(template.args, ["test", "--workspace", "--no-fail-fast"]);
+        assert_eq!(template.cwd.as_deref(), Some("/home/user/foo"));
+
+        let template = TestFramework::Go.task_template(
+            worktree_root,
+            Some((Path::new("/home/user/foo/math/ops_test.go"), &tests[1..])),
+            None,
+        );
+        assert_eq!(
+            template.args,
+            ["test", "-json", "-run", "^(top_level)$", "."]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_test_json() {
+        let output = lines(
+            r#"{"Action":"run","Package":"example.com/foo","Test":"TestAdd"}
+{"Action":"output","Package":"example.com/foo","Test":"TestAdd","Output":"=== RUN   TestAdd\n"}
+{"Action":"pass","Package":"example.com/foo","Test":"TestAdd","Elapsed":0}
+{"Action":"run","Package":"example.com/foo","Test":"TestSub"}
+{"Action":"output","Package":"example.com/foo","Test":"TestSub","Output":"    foo_test.go:12: got 1, want 2\n"}
+{"Action":"fail","Package":"example.com/foo","Test":"TestSub/negative","Elapsed":0}
+{"Action":"fail","Package":"example.com/foo","Test":"TestSub","Elapsed":0}
+{"Action":"fail","Package":"example.com/foo","Elapsed":0.1}"#,
+        );
+        assert_eq!(
+            TestFramework::Go.parse_results(&output, None),
+            vec![
+                TestResult {
+                    name: "TestAdd".to_string(),
+                    suite: None,
+                    status: TestStatus::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "TestSub".to_string(),
+                    suite: None,
+                    status: TestStatus::Failed,
+                    message: Some("    foo_test.go:12: got 1, want 2\n".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_junit_xml() {
+        let report = r#"<?xml version="1.0" encoding="utf-8"?>
+<testsuites><testsuite name="pytest" errors="0" failures="1" skipped="1" tests="4">
+<testcase classname="tests.test_math" name="test_add" time="0.001" />
+<testcase classname="tests.test_math.TestDivision" name="test_by_zero" time="0.002"><failure message="ZeroDivisionError: division by zero">Traceback &lt;...&gt;</failure></testcase>
+<testcase classname="tests.test_math" name="test_pow[2-4]" time="0.001"></testcase>
+<testcase classname="tests.test_math" name="test_later" time="0.000"><skipped type="pytest.skip" message="not &quot;yet&quot;">skipped</skipped></testcase>
+</testsuite></testsuites>"#;
+        let results = TestFramework::Pytest.parse_results(&[], Some(report));
+        assert_eq!(
+            results,
+            vec![
+                TestResult {
+                    name: "test_add".to_string(),
+                    suite: Some("test_math".to_string()),
+                    status: TestStatus::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "test_by_zero".to_string(),
+                    suite: Some("TestDivision".to_string()),
+                    status: TestStatus::Failed,
+                    message: Some("ZeroDivisionError: division by zero".to_string()),
+                },
+                TestResult {
+                    name: "test_pow".to_string(),
+                    suite: Some("test_math".to_string()),
+                    status: TestStatus::Passed,
+                    message: None,
+                },
+                TestResult {
+                    name: "test_later".to_string(),
+                    suite: Some("test_math".to_string()),
+                    status: TestStatus::Skipped,
+                    message: Some("not \"yet\"".to_string()),
+                },
+            ]
+        );
+        assert!(results[1].matches(&TestTarget {
+            name: "test_by_zero",
+            suite: Some("TestDivision"),
+        }));
+        assert!(!results[0].matches(&TestTarget {
+            name: "test_add",
+            suite: Some("TestMath"),
+        }));
+
+        let rust_result = TestResult {
+            name: "adds".to_string(),
+            suite: Some("math::ops::tests".to_string()),
+            status: TestStatus::Passed,
+            message: None,
+        };
+        assert!(rust_result.matches(&TestTarget {
+            name: "adds",
+            suite: Some("tests"),
+        }));
+        assert!(!rust_result.matches(&TestTarget {
+            name: "adds",
+            suite: Some("sts"),
+        }));
+    }
+}
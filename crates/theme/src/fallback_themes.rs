@@ -3,7 +3,7 @@ use std::sync::Arc;
 use gpui::{hsla, FontStyle, FontWeight, HighlightStyle, Hsla, WindowBackgroundAppearance};
 
 use crate::{
-    default_color_scales, AccentColors, Appearance, PlayerColors, StatusColors,
+    default_color_scales, AccentColors, Appearance, PlayerColors, SemanticTokenTheme, StatusColors,
     StatusColorsRefinement, SyntaxTheme, SystemColors, Theme, ThemeColors, ThemeFamily,
     ThemeStyles,
 };
@@ -75,6 +75,51 @@ pub(crate) fn zed_default_dark() -> Theme {
         a: 1.0,
     };
 
+    let status = StatusColors {
+        conflict: yellow,
+        conflict_background: yellow,
+        conflict_border: yellow,
+        created: green,
+        created_background: green,
+        created_border: green,
+        deleted: red,
+        deleted_background: red,
+        deleted_border: red,
+        error: red,
+        error_background: red,
+        error_border: red,
+        hidden: gray,
+        hidden_background: gray,
+        hidden_border: gray,
+        hint: blue,
+        hint_background: blue,
+        hint_border: blue,
+        ignored: gray,
+        ignored_background: gray,
+        ignored_border: gray,
+        info: blue,
+        info_background: blue,
+        info_border: blue,
+        modified: yellow,
+        modified_background: yellow,
+        modified_border: yellow,
+        predictive: gray,
+        predictive_background: gray,
+        predictive_border: gray,
+        renamed: blue,
+        renamed_background: blue,
+        renamed_border: blue,
+        success: green,
+        success_background: green,
+        success_border: green,
+        unreachable: gray,
+        unreachable_background: gray,
+        unreachable_border: gray,
+        warning: yellow,
+        warning_background: yellow,
+        warning_border: yellow,
+    };
+
     Theme {
         id: "one_dark".to_string(),
         name: "One Dark".into(),
@@ -200,50 +245,7 @@ pub(crate) fn zed_default_dark() -> Theme {
                 version_control_conflict_background: crate::orange().light().step_12().opacity(0.1),
                 version_control_ignored: crate::gray().light().step_12(),
             },
-            status: StatusColors {
-                conflict: yellow,
-                conflict_background: yellow,
-                conflict_border: yellow,
-                created: green,
-                created_background: green,
-                created_border: green,
-                deleted: red,
-                deleted_background: red,
-                deleted_border: red,
-                error: red,
-                error_background: red,
-                error_border: red,
-                hidden: gray,
-                hidden_background: gray,
-                hidden_border: gray,
-                hint: blue,
-                hint_background: blue,
-                hint_border: blue,
-                ignored: gray,
-                ignored_background: gray,
-                ignored_border: gray,
-                info: blue,
-                info_background: blue,
-                info_border: blue,
-                modified: yellow,
-                modified_background: yellow,
-                modified_border: yellow,
-                predictive: gray,
-                predictive_background: gray,
-                predictive_border: gray,
-                renamed: blue,
-                renamed_background: blue,
-                renamed_border: blue,
-                success: green,
-                success_background: green,
-                success_border: green,
-                unreachable: gray,
-                unreachable_background: gray,
-                unreachable_border: gray,
-                warning: yellow,
-                warning_background: yellow,
-                warning_border: yellow,
-            },
+            status: status.clone(),
             player: PlayerColors::dark(),
             syntax: Arc::new(SyntaxTheme {
                 highlights: vec![
@@ -309,6 +311,7 @@ pub(crate) fn zed_default_dark() -> Theme {
                     ("variant".into(), HighlightStyle::default()),
                 ],
             }),
+            semantic_tokens: Arc::new(SemanticTokenTheme::new(&status)),
         },
     }
 }
@@ -1,7 +1,7 @@
 use crate::fallback_themes::zed_default_dark;
 use crate::{
-    Appearance, IconTheme, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent,
-    DEFAULT_ICON_THEME_NAME,
+    Appearance, IconTheme, SemanticTokenTheme, SyntaxTheme, Theme, ThemeRegistry,
+    ThemeStyleContent, DEFAULT_ICON_THEME_NAME,
 };
 use anyhow::Result;
 use derive_more::{Deref, DerefMut};
@@ -611,6 +611,10 @@ impl ThemeSettings {
             base_theme.styles.accents.merge(&theme_overrides.accents);
             base_theme.styles.syntax =
                 SyntaxTheme::merge(base_theme.styles.syntax, theme_overrides.syntax_overrides());
+            base_theme.styles.semantic_tokens = SemanticTokenTheme::merge(
+                base_theme.styles.semantic_tokens,
+                theme_overrides.semantic_token_overrides(),
+            );
 
             self.active_theme = Arc::new(base_theme);
         }
@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use gpui::{px, HighlightStyle, StrikethroughStyle, UnderlineStyle};
+
+use crate::{StatusColors, SyntaxTheme};
+
+/// The styles for the semantic tokens reported by language servers.
+///
+/// Rules are keyed by token type (`variable`), optionally followed by a modifier
+/// (`variable.mutable`). A `*` in place of the token type applies the modifier style to
+/// tokens of any type (`*.deprecated`).
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SemanticTokenTheme {
+    pub rules: Vec<(String, HighlightStyle)>,
+}
+
+impl SemanticTokenTheme {
+    /// The styles used for modifiers that convey something syntax highlighting cannot.
+    pub fn new(status: &StatusColors) -> Self {
+        Self {
+            rules: vec![
+                (
+                    "*.mutable".into(),
+                    HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            color: None,
+                            wavy: false,
+                        }),
+                        ..HighlightStyle::default()
+                    },
+                ),
+                (
+                    "*.unsafe".into(),
+                    HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            color: Some(status.warning),
+                            wavy: false,
+                        }),
+                        ..HighlightStyle::default()
+                    },
+                ),
+                (
+                    "*.deprecated".into(),
+                    HighlightStyle {
+                        strikethrough: Some(StrikethroughStyle {
+                            thickness: px(1.),
+                            color: None,
+                        }),
+                        ..HighlightStyle::default()
+                    },
+                ),
+                (
+                    "unresolvedReference".into(),
+                    HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            color: Some(status.error),
+                            wavy: true,
+                        }),
+                        ..HighlightStyle::default()
+                    },
+                ),
+            ],
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<HighlightStyle> {
+        self.rules
+            .iter()
+            .find_map(|(rule, style)| (rule == name).then_some(*style))
+    }
+
+    /// Returns the style of a token with the given type and modifiers, if there is any.
+    ///
+    /// Token types without a rule fall back to the syntax style of the closest capture name,
+    /// which only applies to identifiers: literals, comments and keywords are left to the
+    /// syntax highlighting, which knows more about their contents.
+    pub fn style<'a>(
+        &self,
+        token_type: &str,
+        modifiers: impl IntoIterator<Item = &'a str>,
+        syntax: &SyntaxTheme,
+    ) -> Option<HighlightStyle> {
+        let mut style = self.get(token_type).or_else(|| {
+            syntax_fallbacks(token_type)
+                .iter()
+                .find(|name| syntax.highlight_id(name).is_some())
+                .map(|name| syntax.get(name))
+        });
+        for modifier in modifiers {
+            let modifier_style = self
+                .get(&format!("{token_type}.{modifier}"))
+                .or_else(|| self.get(&format!("*.{modifier}")));
+            if let Some(modifier_style) = modifier_style {
+                overlay(
+                    style.get_or_insert_with(HighlightStyle::default),
+                    modifier_style,
+                );
+            }
+        }
+        style
+    }
+
+    /// Returns a new [`Arc<SemanticTokenTheme>`] with the given rules merged in.
+    pub fn merge(base: Arc<Self>, user_rules: Vec<(String, HighlightStyle)>) -> Arc<Self> {
+        if user_rules.is_empty() {
+            return base;
+        }
+
+        let mut merged_rules = base.rules.clone();
+        for (name, highlight) in user_rules {
+            if let Some((_, existing_highlight)) = merged_rules
+                .iter_mut()
+                .find(|(existing_name, _)| existing_name == &name)
+            {
+                overlay(existing_highlight, highlight);
+            } else {
+                merged_rules.push((name, highlight));
+            }
+        }
+
+        Arc::new(Self {
+            rules: merged_rules,
+        })
+    }
+}
+
+fn overlay(style: &mut HighlightStyle, other: HighlightStyle) {
+    style.color = other.color.or(style.color);
+    style.font_weight = other.font_weight.or(style.font_weight);
+    style.font_style = other.font_style.or(style.font_style);
+    style.background_color = other.background_color.or(style.background_color);
+    style.underline = other.underline.or(style.underline);
+    style.strikethrough = other.strikethrough.or(style.strikethrough);
+    style.fade_out = other.fade_out.or(style.fade_out);
+}
+
+fn syntax_fallbacks(token_type: &str) -> &'static [&'static str] {
+    match token_type {
+        "namespace" => &["namespace", "module"],
+        "type" | "class" | "struct" | "interface" | "typeAlias" => &["type"],
+        "enum" => &["enum", "type"],
+        "typeParameter" => &["type.parameter", "type"],
+        "parameter" => &["variable.parameter", "variable"],
+        "variable" => &["variable"],
+        "property" => &["property"],
+        "enumMember" => &["variant", "constant"],
+        "function" => &["function"],
+        "method" => &["function.method", "function"],
+        "macro" => &["function.macro", "function"],
+        "decorator" | "attribute" => &["attribute"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_token_style() {
+        let syntax = SyntaxTheme::new_test([("function", gpui::blue()), ("variable", gpui::red())]);
+        let theme = SemanticTokenTheme::merge(
+            Arc::new(SemanticTokenTheme::new(&StatusColors::dark())),
+            vec![(
+                "variable.mutable".into(),
+                HighlightStyle {
+                    color: Some(gpui::green()),
+                    ..HighlightStyle::default()
+                },
+            )],
+        );
+
+        // Token types without rules fall back to the syntax theme.
+        assert_eq!(
+            theme.style("method", [], &syntax),
+            Some(HighlightStyle {
+                color: Some(gpui::blue()),
+                ..HighlightStyle::default()
+            })
+        );
+        // Literals are left to the syntax highlighting.
+        assert_eq!(theme.style("string", [], &syntax), None);
+
+        // Type specific modifier rules take precedence over the ones for any type.
+        let mutable_variable = theme.style("variable", ["mutable"], &syntax).unwrap();
+        assert_eq!(mutable_variable.color, Some(gpui::green()));
+        assert_eq!(mutable_variable.underline, None);
+        let mutable_function = theme.style("function", ["mutable"], &syntax).unwrap();
+        assert_eq!(mutable_function.color, Some(gpui::blue()));
+        assert!(mutable_function.underline.is_some());
+
+        let unresolved = theme.style("unresolvedReference", [], &syntax).unwrap();
+        assert_eq!(
+            unresolved.underline.and_then(|underline| underline.color),
+            Some(StatusColors::dark().error)
+        );
+        assert!(unresolved.color.is_none());
+    }
+}
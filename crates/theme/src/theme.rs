@@ -207,6 +207,10 @@ impl ThemeFamily {
             })
             .collect::<Vec<_>>();
         let syntax_theme = SyntaxTheme::merge(Arc::new(SyntaxTheme::default()), syntax_highlights);
+        let semantic_token_theme = SemanticTokenTheme::merge(
+            Arc::new(SemanticTokenTheme::new(&refined_status_colors)),
+            theme.style.semantic_token_overrides(),
+        );
 
         let window_background_appearance = theme
             .style
@@ -226,6 +230,7 @@ impl ThemeFamily {
                 status: refined_status_colors,
                 player: refined_player_colors,
                 syntax: syntax_theme,
+                semantic_tokens: semantic_token_theme,
             },
         }
     }
@@ -300,6 +305,12 @@ impl Theme {
         &self.styles.syntax
     }
 
+    /// Returns the [`SemanticTokenTheme`] for the theme.
+    #[inline(always)]
+    pub fn semantic_tokens(&self) -> &Arc<SemanticTokenTheme> {
+        &self.styles.semantic_tokens
+    }
+
     /// Returns the [`StatusColors`] for the theme.
     #[inline(always)]
     pub fn status(&self) -> &StatusColors {
@@ -1,5 +1,6 @@
 mod avatar;
 mod button;
+mod color_picker;
 mod content_group;
 mod context_menu;
 mod disclosure;
@@ -36,6 +37,7 @@ mod stories;
 
 pub use avatar::*;
 pub use button::*;
+pub use color_picker::*;
 pub use content_group::*;
 pub use context_menu::*;
 pub use disclosure::*;
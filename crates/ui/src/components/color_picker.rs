@@ -0,0 +1,232 @@
+use std::rc::Rc;
+
+use gpui::{hsla, ClickEvent, Hsla, Rgba, Stateful};
+
+use crate::{prelude::*, NumericStepper};
+
+const PALETTE_HUES: usize = 12;
+const PALETTE_LIGHTNESSES: [f32; 4] = [0.25, 0.4, 0.55, 0.75];
+const GRAYS: usize = 12;
+
+/// A color picker, with a palette to pick a color from and steppers to fine-tune its
+/// hue, saturation, lightness and opacity.
+///
+/// The picker doesn't hold the picked color itself: its owner keeps it and renders the
+/// picker again with the color passed to [`ColorPicker::on_change`].
+#[derive(IntoElement, IntoComponent)]
+#[component(scope = "input")]
+pub struct ColorPicker {
+    id: ElementId,
+    color: Hsla,
+    original_color: Option<Hsla>,
+    on_change: Option<Rc<dyn Fn(Hsla, &mut Window, &mut App) + 'static>>,
+}
+
+impl ColorPicker {
+    /// Creates a new color picker showing the given color.
+    pub fn new(id: impl Into<ElementId>, color: Hsla) -> Self {
+        Self {
+            id: id.into(),
+            color,
+            original_color: None,
+            on_change: None,
+        }
+    }
+
+    /// Shows the color being replaced next to the picked one.
+    pub fn original_color(mut self, color: Hsla) -> Self {
+        self.original_color = Some(color);
+        self
+    }
+
+    /// Sets the handler called with the newly picked color.
+    pub fn on_change(mut self, handler: impl Fn(Hsla, &mut Window, &mut App) + 'static) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+/// Formats a color as a `#rrggbb` hex string, or `#rrggbbaa` if it is not opaque.
+pub fn color_to_hex(color: Hsla) -> String {
+    let rgba = Rgba::from(color);
+    let channel = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+    let hex = format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(rgba.r),
+        channel(rgba.g),
+        channel(rgba.b)
+    );
+    if rgba.a < 1. {
+        format!("{hex}{:02x}", channel(rgba.a))
+    } else {
+        hex
+    }
+}
+
+fn swatch(id: impl Into<ElementId>, color: Hsla, selected: bool, cx: &App) -> Stateful<Div> {
+    let colors = cx.theme().colors();
+    div()
+        .id(id)
+        .size_4()
+        .flex_none()
+        .rounded_sm()
+        .border_1()
+        .border_color(if selected {
+            colors.text_accent
+        } else {
+            colors.border_variant
+        })
+        .bg(color)
+}
+
+impl RenderOnce for ColorPicker {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let color = self.color;
+        let on_change = self.on_change;
+        let pick = |new_color: Hsla| {
+            let on_change = on_change.clone();
+            move |_: &ClickEvent, window: &mut Window, cx: &mut App| {
+                if let Some(on_change) = on_change.as_ref() {
+                    on_change(new_color, window, cx);
+                }
+            }
+        };
+        let is_picked = |candidate: Hsla| color_to_hex(candidate) == color_to_hex(color);
+
+        let preview = h_flex()
+            .gap_2()
+            .children(
+                self.original_color
+                    .map(|original| swatch("original", original, false, cx).size_6()),
+            )
+            .child(swatch("picked", color, false, cx).size_6())
+            .child(Label::new(color_to_hex(color)).buffer_font(cx));
+
+        let grays = (0..GRAYS).map(|ix| hsla(0., 0., ix as f32 / (GRAYS - 1) as f32, 1.));
+        let palette = v_flex()
+            .gap_0p5()
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .children(grays.enumerate().map(|(ix, gray)| {
+                        swatch(("gray", ix), gray, is_picked(gray), cx).on_click(pick(gray))
+                    })),
+            )
+            .children(
+                PALETTE_LIGHTNESSES
+                    .iter()
+                    .enumerate()
+                    .map(|(row, lightness)| {
+                        h_flex().gap_0p5().children((0..PALETTE_HUES).map(|column| {
+                            let hue = column as f32 / PALETTE_HUES as f32;
+                            let swatch_color = hsla(hue, 0.7, *lightness, 1.);
+                            swatch(
+                                ("hue", row * PALETTE_HUES + column),
+                                swatch_color,
+                                is_picked(swatch_color),
+                                cx,
+                            )
+                            .on_click(pick(swatch_color))
+                        }))
+                    }),
+            );
+
+        let hue_degrees = (color.h * 360.).round();
+        let with_hue = |degrees: f32| Hsla {
+            h: degrees.rem_euclid(360.) / 360.,
+            ..color
+        };
+        // Saturation, lightness and opacity are adjusted in steps of 5%.
+        let step = |value: f32, delta: f32| (((value + delta) * 20.).round() / 20.).clamp(0., 1.);
+        let percentage = |value: f32| format!("{}%", (value * 100.).round());
+        let channel = |name: &'static str, value: String, decrement: Hsla, increment: Hsla| {
+            h_flex()
+                .justify_between()
+                .gap_2()
+                .child(Label::new(name).size(LabelSize::Small).color(Color::Muted))
+                .child(NumericStepper::new(
+                    name,
+                    value,
+                    pick(decrement),
+                    pick(increment),
+                ))
+        };
+        let steppers = v_flex()
+            .gap_1()
+            .child(channel(
+                "Hue",
+                format!("{hue_degrees}°"),
+                with_hue(hue_degrees - 10.),
+                with_hue(hue_degrees + 10.),
+            ))
+            .child(channel(
+                "Saturation",
+                percentage(color.s),
+                Hsla {
+                    s: step(color.s, -0.05),
+                    ..color
+                },
+                Hsla {
+                    s: step(color.s, 0.05),
+                    ..color
+                },
+            ))
+            .child(channel(
+                "Lightness",
+                percentage(color.l),
+                Hsla {
+                    l: step(color.l, -0.05),
+                    ..color
+                },
+                Hsla {
+                    l: step(color.l, 0.05),
+                    ..color
+                },
+            ))
+            .child(channel(
+                "Opacity",
+                percentage(color.a),
+                Hsla {
+                    a: step(color.a, -0.05),
+                    ..color
+                },
+                Hsla {
+                    a: step(color.a, 0.05),
+                    ..color
+                },
+            ));
+
+        v_flex()
+            .id(self.id)
+            .gap_2()
+            .p_1()
+            .child(preview)
+            .child(palette)
+            .child(steppers)
+    }
+}
+
+// View this component preview using `workspace: open component-preview`
+impl ComponentPreview for ColorPicker {
+    fn preview(_window: &mut Window, _cx: &App) -> AnyElement {
+        v_flex()
+            .gap_6()
+            .children(vec![example_group_with_title(
+                "States",
+                vec![
+                    single_example(
+                        "Default",
+                        ColorPicker::new("color_picker", hsla(0.58, 0.7, 0.55, 1.))
+                            .into_any_element(),
+                    ),
+                    single_example(
+                        "Replacing a Color",
+                        ColorPicker::new("color_picker_original", hsla(0.33, 0.7, 0.4, 0.5))
+                            .original_color(hsla(0., 0.7, 0.55, 1.))
+                            .into_any_element(),
+                    ),
+                ],
+            )])
+            .into_any_element()
+    }
+}
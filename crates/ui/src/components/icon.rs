@@ -156,6 +156,7 @@ pub enum IconName {
     Book,
     BookCopy,
     BookPlus,
+    Bookmark,
     CaseSensitive,
     Check,
     ChevronDown,
@@ -180,6 +181,7 @@ pub enum IconName {
     CursorIBeam,
     Dash,
     DatabaseZap,
+    Debug,
     Delete,
     Diff,
     Disconnected,
@@ -206,6 +208,7 @@ pub enum IconName {
     FileToml,
     FileTree,
     Filter,
+    Flask,
     Folder,
     FolderOpen,
     FolderX,
@@ -246,6 +249,7 @@ pub enum IconName {
     PageUp,
     PanelLeft,
     PanelRight,
+    Pause,
     Pencil,
     Person,
     PersonCircle,
@@ -1433,7 +1433,12 @@ impl ShellExec {
                         shell,
                         show_summary: false,
                         show_command: false,
+                        problem_matchers: Vec::new(),
+                        output_values: Vec::new(),
+                        is_background: false,
+                        background_pattern: None,
                     }),
+                    completion_tx: None,
                 });
             });
             return;
@@ -42,6 +42,7 @@ pub enum Object {
     Class,
     Comment,
     EntireFile,
+    SelectionRange,
 }
 
 #[derive(Clone, Deserialize, JsonSchema, PartialEq)]
@@ -86,7 +87,8 @@ actions!(
         Method,
         Class,
         Comment,
-        EntireFile
+        EntireFile,
+        SelectionRange
     ]
 );
 
@@ -156,6 +158,21 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, |vim, _: &EntireFile, window, cx| {
         vim.object(Object::EntireFile, window, cx)
     });
+    Vim::action(editor, cx, |vim, _: &SelectionRange, window, cx| {
+        // The ranges come from the language server, which is asked for them first.
+        let this = cx.entity().downgrade();
+        let fetching = vim
+            .update_editor(window, cx, |_, editor, window, cx| {
+                editor.fetch_selection_ranges(window, cx, move |window, cx| {
+                    this.update(cx, |vim, cx| vim.object(Object::SelectionRange, window, cx))
+                        .ok();
+                })
+            })
+            .unwrap_or(false);
+        if !fetching {
+            vim.object(Object::SelectionRange, window, cx)
+        }
+    });
     Vim::action(editor, cx, |vim, _: &Comment, window, cx| {
         if !matches!(vim.active_operator(), Some(Operator::Object { .. })) {
             vim.push_operator(Operator::Object { around: true }, window, cx);
@@ -194,7 +211,8 @@ impl Object {
             | Object::BackQuotes
             | Object::AnyQuotes
             | Object::VerticalBars
-            | Object::DoubleQuotes => false,
+            | Object::DoubleQuotes
+            | Object::SelectionRange => false,
             Object::Sentence
             | Object::Paragraph
             | Object::AnyBrackets
@@ -233,6 +251,7 @@ impl Object {
             | Object::Class
             | Object::Comment
             | Object::EntireFile
+            | Object::SelectionRange
             | Object::CurlyBrackets
             | Object::AngleBrackets => true,
         }
@@ -262,6 +281,7 @@ impl Object {
             | Object::Tag
             | Object::Comment
             | Object::Argument
+            | Object::SelectionRange
             | Object::IndentObj { .. } => Mode::Visual,
             Object::Method | Object::Class => {
                 if around {
@@ -395,6 +415,7 @@ impl Object {
             Object::Argument => argument(map, relative_to, around),
             Object::IndentObj { include_below } => indent(map, relative_to, around, include_below),
             Object::EntireFile => entire_file(map),
+            Object::SelectionRange => selection_range(map, &selection),
         }
     }
 
@@ -831,6 +852,20 @@ fn text_object(
     return Some(buffer_range.start.to_display_point(map)..buffer_range.end.to_display_point(map));
 }
 
+/// Returns the smallest range reported by the language server that contains the selection.
+///
+/// Language servers are only asked for these ranges when the language's `selection_ranges`
+/// setting prefers them, so this returns `None` otherwise.
+fn selection_range(
+    map: &DisplaySnapshot,
+    selection: &Selection<DisplayPoint>,
+) -> Option<Range<DisplayPoint>> {
+    let range =
+        selection.start.to_offset(map, Bias::Left)..selection.end.to_offset(map, Bias::Right);
+    let range = map.larger_selection_range(range)?;
+    Some(range.start.to_display_point(map)..range.end.to_display_point(map))
+}
+
 fn argument(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
@@ -1445,8 +1480,12 @@ fn surrounding_markers(
 
 #[cfg(test)]
 mod test {
+    use editor::test::editor_lsp_test_context::EditorLspTestContext;
+    use futures::StreamExt;
     use gpui::KeyBinding;
     use indoc::indoc;
+    use language::language_settings::{AllLanguageSettings, SelectionRangesProvider};
+    use settings::SettingsStore;
 
     use crate::{
         object::AnyBrackets,
@@ -1927,6 +1966,56 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_selection_range_object(cx: &mut gpui::TestAppContext) {
+        VimTestContext::init(cx);
+        let mut cx = VimTestContext::new_with_lsp(
+            EditorLspTestContext::new_rust(
+                lsp::ServerCapabilities {
+                    selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(
+                        true,
+                    )),
+                    ..Default::default()
+                },
+                cx,
+            )
+            .await,
+            true,
+        );
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.selection_ranges = Some(SelectionRangesProvider::LanguageServer);
+            });
+        });
+        let mut requests =
+            cx.handle_request::<lsp::request::SelectionRangeRequest, _, _>(|_, _, _| async move {
+                Ok(Some(vec![lsp::SelectionRange {
+                    range: lsp::Range::new(lsp::Position::new(0, 8), lsp::Position::new(0, 11)),
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 15)),
+                        parent: None,
+                    })),
+                }]))
+            });
+
+        // The syntax-based objects don't use the language server's ranges.
+        cx.set_state("let x = aˇbc + 1;", Mode::Normal);
+        cx.simulate_keystrokes("v i c");
+        cx.assert_state("let x = a«bˇ»c + 1;", Mode::Visual);
+        cx.simulate_keystrokes("escape");
+
+        cx.set_state("let x = aˇbc + 1;", Mode::Normal);
+        cx.simulate_keystrokes("v i n");
+        requests.next().await.unwrap();
+        cx.run_until_parked();
+        cx.assert_state("let x = «abcˇ» + 1;", Mode::Visual);
+
+        cx.simulate_keystrokes("i n");
+        requests.next().await.unwrap();
+        cx.run_until_parked();
+        cx.assert_state("let «x = abc + 1ˇ»;", Mode::Visual);
+    }
+
     #[gpui::test]
     async fn test_argument_object(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
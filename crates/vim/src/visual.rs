@@ -353,8 +353,12 @@ impl Vim {
                         // after the cursor; however in the case of a visual selection
                         // the current character is before the cursor.
                         // But this will affect the judgment of the html tag
-                        // so the html tag needs to skip this logic.
-                        if !selection.reversed && object != Object::Tag {
+                        // so the html tag needs to skip this logic, as do the ranges
+                        // of the language server, which must contain the whole selection.
+                        if !selection.reversed
+                            && object != Object::Tag
+                            && object != Object::SelectionRange
+                        {
                             mut_selection.set_head(
                                 movement::left(map, mut_selection.head()),
                                 mut_selection.goal,
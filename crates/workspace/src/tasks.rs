@@ -1,5 +1,13 @@
-use gpui::Context;
-use project::TaskSourceKind;
+use futures::{
+    channel::{mpsc, oneshot},
+    future::join_all,
+    StreamExt as _,
+};
+use gpui::{App, Context, Entity, Subscription};
+use project::{
+    debug_session::{DebugSession, DebugSessionEvent, SessionStatus},
+    TaskSourceKind,
+};
 use remote::ConnectionState;
 use task::{ResolvedTask, TaskContext, TaskTemplate};
 
@@ -42,26 +50,173 @@ pub fn schedule_task(
 pub fn schedule_resolved_task(
     workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
-    mut resolved_task: ResolvedTask,
+    resolved_task: ResolvedTask,
     omit_history: bool,
     cx: &mut Context<Workspace>,
 ) {
-    if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
-        if !omit_history {
-            resolved_task.resolved = Some(spawn_in_terminal.clone());
-            workspace.project().update(cx, |project, cx| {
-                if let Some(task_inventory) =
-                    project.task_store().read(cx).task_inventory().cloned()
-                {
-                    task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+    if !resolved_task.original_task().has_dependencies() {
+        spawn_resolved_task(
+            workspace,
+            task_source_kind,
+            resolved_task,
+            omit_history,
+            None,
+            cx,
+        );
+        return;
+    }
+
+    let task_inventory = workspace
+        .project()
+        .read(cx)
+        .task_store()
+        .read(cx)
+        .task_inventory()
+        .cloned();
+    let Some(task_inventory) = task_inventory else {
+        return;
+    };
+    let root_task_id = resolved_task.id.clone();
+    let stages = match task_inventory
+        .read(cx)
+        .resolve_dependencies(task_source_kind.clone(), resolved_task.clone())
+    {
+        Ok(stages) => stages,
+        Err(e) => {
+            log::error!("Failed to resolve dependencies of task: {e:#}");
+            workspace.show_error(&e, cx);
+            return;
+        }
+    };
+
+    cx.spawn(|workspace, mut cx| async move {
+        for stage in stages {
+            let completions = workspace.update(&mut cx, |workspace, cx| {
+                stage
+                    .into_iter()
+                    .map(|(task_source_kind, task)| {
+                        let (completion_tx, mut completion_rx) = mpsc::unbounded();
+                        let label = task.resolved_label.clone();
+                        // Only the task that was asked for is worth rerunning later.
+                        let omit_history = omit_history || task.id != root_task_id;
+                        spawn_resolved_task(
+                            workspace,
+                            task_source_kind,
+                            task,
+                            omit_history,
+                            Some(completion_tx),
+                            cx,
+                        );
+                        async move { (label, completion_rx.next().await.unwrap_or(false)) }
                     })
-                }
-            });
+                    .collect::<Vec<_>>()
+            })?;
+            let failed_tasks = join_all(completions)
+                .await
+                .into_iter()
+                .filter(|(_, success)| !success)
+                .map(|(label, _)| label)
+                .collect::<Vec<_>>();
+            if !failed_tasks.is_empty() {
+                anyhow::bail!(
+                    "Not running the remaining tasks, as these failed: {}",
+                    failed_tasks.join(", ")
+                );
+            }
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Spawns a single task, without regard for the tasks it depends on.
+/// If `completion_tx` is given, it receives whether the task succeeded once it finishes.
+fn spawn_resolved_task(
+    workspace: &mut Workspace,
+    task_source_kind: TaskSourceKind,
+    mut resolved_task: ResolvedTask,
+    omit_history: bool,
+    completion_tx: Option<mpsc::UnboundedSender<bool>>,
+    cx: &mut Context<Workspace>,
+) {
+    let debug_config = resolved_task.debug.clone();
+    let spawn_in_terminal = resolved_task.resolved.take();
+    if !omit_history
+        && (spawn_in_terminal.is_some() || resolved_task.original_task().has_dependencies())
+    {
+        resolved_task.resolved = spawn_in_terminal.clone();
+        workspace.project().update(cx, |project, cx| {
+            if let Some(task_inventory) = project.task_store().read(cx).task_inventory().cloned() {
+                task_inventory.update(cx, |inventory, _| {
+                    inventory.task_scheduled(task_source_kind, resolved_task);
+                })
+            }
+        });
+    }
+
+    let Some(spawn_in_terminal) = spawn_in_terminal else {
+        // Tasks without a command only run their dependencies, which succeeded at this point.
+        if let Some(completion_tx) = completion_tx {
+            completion_tx.unbounded_send(true).ok();
         }
+        return;
+    };
 
-        cx.emit(crate::Event::SpawnTask {
-            action: Box::new(spawn_in_terminal),
+    if let Some(debug_config) = debug_config {
+        let session = workspace.project().update(cx, |project, cx| {
+            project.start_debug_session(spawn_in_terminal, debug_config, cx)
         });
+        cx.spawn(|_, mut cx| async move {
+            let session = session.await;
+            let Some(completion_tx) = completion_tx else {
+                return session.map(|_| ());
+            };
+            let session = match session {
+                Ok(session) => session,
+                Err(error) => {
+                    completion_tx.unbounded_send(false).ok();
+                    return Err(error);
+                }
+            };
+            // Tasks that depend on a debug task only run once the debugging session has ended.
+            let (_subscription, exited) = cx.update(|cx| watch_debug_session_exit(session, cx))?;
+            completion_tx
+                .unbounded_send(exited.await.unwrap_or(false))
+                .ok();
+            Ok(())
+        })
+        .detach_and_log_err(cx);
+        return;
     }
+
+    cx.emit(crate::Event::SpawnTask {
+        action: Box::new(spawn_in_terminal),
+        completion_tx,
+    });
+}
+
+/// Resolves to whether the debug session succeeded once it has ended, for as long as the returned
+/// subscription is kept. Debuggees that exit without reporting an exit code count as successful.
+fn watch_debug_session_exit(
+    session: Entity<DebugSession>,
+    cx: &mut App,
+) -> (Subscription, oneshot::Receiver<bool>) {
+    let (exited_tx, exited_rx) = oneshot::channel();
+    let mut exited_tx = Some(exited_tx);
+    let mut check_exit = move |session: &DebugSession| {
+        if session.status() == SessionStatus::Exited {
+            if let Some(exited_tx) = exited_tx.take() {
+                exited_tx
+                    .send(session.exit_code().map_or(true, |code| code == 0))
+                    .ok();
+            }
+        }
+    };
+    check_exit(session.read(cx));
+    let subscription = cx.subscribe(&session, move |session, event, cx| {
+        if let DebugSessionEvent::StatusChanged = event {
+            check_exit(session.read(cx));
+        }
+    });
+    (subscription, exited_rx)
 }
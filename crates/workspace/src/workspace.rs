@@ -23,6 +23,7 @@ use collections::{hash_map, HashMap, HashSet};
 use derive_more::{Deref, DerefMut};
 pub use dock::Panel;
 use dock::{Dock, DockPosition, PanelButtons, PanelHandle, RESIZE_HANDLE_SIZE};
+use fs::InvalidTextError;
 use futures::{
     channel::{
         mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -433,9 +434,26 @@ type WorkspaceItemBuilder = Box<dyn FnOnce(&mut Window, &mut Context<Pane>) -> B
 
 impl Global for ProjectItemOpeners {}
 
+/// The error a [ProjectItem] fails to open a file with once it finds that it doesn't
+/// support the file's contents, so that other items get a chance to open it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsupportedFileError;
+
+impl std::fmt::Display for UnsupportedFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("file is not supported")
+    }
+}
+
+impl std::error::Error for UnsupportedFileError {}
+
 /// Registers a [ProjectItem] for the app. When opening a file, all the registered
 /// items will get a chance to open the file, starting from the project item that
 /// was added last.
+///
+/// When an item fails to open a file because it isn't text, as with binary files
+/// that can't be opened in a buffer, or with an [UnsupportedFileError], the items
+/// added before it get a chance too.
 pub fn register_project_item<I: ProjectItem>(cx: &mut App) {
     let builders = cx.default_global::<ProjectItemOpeners>();
     builders.push(|project, project_path, window, cx| {
@@ -757,6 +775,8 @@ pub enum Event {
     WorkspaceCreated(WeakEntity<Workspace>),
     SpawnTask {
         action: Box<SpawnInTerminal>,
+        /// Receives whether the task succeeded once it finishes, for tasks that others wait on.
+        completion_tx: Option<UnboundedSender<bool>>,
     },
     OpenBundledFile {
         text: Cow<'static, str>,
@@ -764,6 +784,8 @@ pub enum Event {
         language: &'static str,
     },
     ZoomChanged,
+    /// The workspace is being serialized, for state kept outside of it to be saved alongside.
+    Serialized,
 }
 
 #[derive(Debug)]
@@ -2907,14 +2929,41 @@ impl Workspace {
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
         let project = self.project().clone();
         let project_item_builders = cx.default_global::<ProjectItemOpeners>().clone();
+        let mut project_item_builders = project_item_builders.0.into_iter().rev();
         let Some(open_project_item) = project_item_builders
-            .iter()
-            .rev()
+            .by_ref()
             .find_map(|open_project_item| open_project_item(&project, &path, window, cx))
         else {
             return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
         };
-        open_project_item
+
+        window.spawn(cx, |mut cx| async move {
+            let mut open_project_item = open_project_item;
+            loop {
+                match open_project_item.await {
+                    // Give the items registered earlier a chance to open files that aren't text,
+                    // or that the item doesn't support.
+                    Err(error)
+                        if error.is::<InvalidTextError>() || error.is::<UnsupportedFileError>() =>
+                    {
+                        let next_open_project_item = cx.update(|window, cx| {
+                            project_item_builders
+                                .by_ref()
+                                .find_map(|open_project_item| {
+                                    open_project_item(&project, &path, window, cx)
+                                })
+                        })?;
+                        match next_open_project_item {
+                            Some(next_open_project_item) => {
+                                open_project_item = next_open_project_item
+                            }
+                            None => return Err(error),
+                        }
+                    }
+                    result => return result,
+                }
+            }
+        })
     }
 
     pub fn find_project_item<T>(
@@ -4452,6 +4501,7 @@ impl Workspace {
                 this.update_in(&mut cx, |this, window, cx| {
                     this.serialize_workspace_internal(window, cx).detach();
                     this._schedule_serialize.take();
+                    cx.emit(Event::Serialized);
                 })
                 .log_err();
             }));
@@ -1,6 +1,7 @@
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
-use anyhow::Context as _;
+use anyhow::{anyhow, Context as _};
+use fs::Encoding;
 use gpui::App;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -12,6 +13,7 @@ pub struct WorktreeSettings {
     pub file_scan_inclusions: PathMatcher,
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    pub file_encodings: Vec<(PathMatcher, Encoding)>,
 }
 
 impl WorktreeSettings {
@@ -29,6 +31,14 @@ impl WorktreeSettings {
         path.ancestors()
             .any(|ancestor| self.file_scan_inclusions.is_match(&ancestor))
     }
+
+    /// The encoding configured for files at the given path, if any.
+    pub fn encoding_for_path(&self, path: &Path) -> Option<Encoding> {
+        self.file_encodings
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(path))
+            .map(|(_, encoding)| *encoding)
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -62,6 +72,12 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// The encodings to read and write files matching globs with, when they don't start
+    /// with a byte order mark, such as `{ "legacy/**/*.cpp": "shift_jis" }`.
+    ///
+    /// Default: {}
+    pub file_encodings: Option<BTreeMap<String, String>>,
 }
 
 impl Settings for WorktreeSettings {
@@ -84,6 +100,16 @@ impl Settings for WorktreeSettings {
             })
             .filter(|p| p != "")
             .collect();
+        let file_encodings = result
+            .file_encodings
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(glob, label)| {
+                let encoding = Encoding::for_label(&label)
+                    .ok_or_else(|| anyhow!("Unknown encoding {label:?} in file_encodings"))?;
+                Ok((path_matchers(&[glob], "file_encodings")?, encoding))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
         file_scan_exclusions.sort();
         private_files.sort();
         parsed_file_scan_inclusions.sort();
@@ -94,6 +120,7 @@ impl Settings for WorktreeSettings {
                 &parsed_file_scan_inclusions,
                 "file_scan_inclusions",
             )?,
+            file_encodings,
         })
     }
 }
@@ -3,7 +3,7 @@ use crate::{
     WorkDirectory, Worktree, WorktreeModelHandle,
 };
 use anyhow::Result;
-use fs::{FakeFs, Fs, RealFs, RemoveOptions};
+use fs::{Encoding, FakeFs, Fs, RealFs, RemoveOptions};
 use git::{
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, TrackedSummary, UnmergedStatus,
@@ -876,6 +876,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
             Path::new("tracked-dir/file.txt"),
             "hello".into(),
             Default::default(),
+            Default::default(),
             cx,
         )
     })
@@ -886,6 +887,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
             Path::new("ignored-dir/file.txt"),
             "world".into(),
             Default::default(),
+            Default::default(),
             cx,
         )
     })
@@ -900,6 +902,105 @@ async fn test_write_file(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_file_encodings(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<WorktreeSettings>(cx, |project_settings| {
+                project_settings.file_encodings = Some(
+                    [("legacy/**".to_string(), "shift_jis".to_string())]
+                        .into_iter()
+                        .collect(),
+                );
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree("/root", json!({ "legacy": {} })).await;
+    fs.insert_file("/root/legacy/a.txt", b"\x93\xfa\x96\x7b\n".to_vec())
+        .await;
+    fs.insert_file("/root/b.txt", b"\xff\xfeh\0i\0\n\0".to_vec())
+        .await;
+    fs.insert_file("/root/c.bin", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec())
+        .await;
+
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    let shift_jis = Encoding::for_label("shift_jis").unwrap();
+    let loaded = tree
+        .update(cx, |tree, cx| tree.load_file(Path::new("legacy/a.txt"), cx))
+        .await
+        .unwrap();
+    assert_eq!(loaded.text, "\u{65e5}\u{672c}\n");
+    assert_eq!(loaded.encoding, shift_jis);
+
+    let loaded = tree
+        .update(cx, |tree, cx| tree.load_file(Path::new("b.txt"), cx))
+        .await
+        .unwrap();
+    assert_eq!(loaded.text, "hi\n");
+    assert_eq!(
+        loaded.encoding,
+        Encoding::for_label("utf-16le").unwrap().with_bom(true)
+    );
+
+    assert!(tree
+        .update(cx, |tree, cx| tree.load_file(Path::new("c.bin"), cx))
+        .await
+        .is_err());
+
+    // Files are written back in their encoding.
+    tree.update(cx, |tree, cx| {
+        tree.write_file(
+            Path::new("legacy/a.txt"),
+            "\u{65e5}\u{672c}\u{8a9e}\n".into(),
+            Default::default(),
+            shift_jis,
+            cx,
+        )
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/root/legacy/a.txt"))
+            .await
+            .unwrap(),
+        b"\x93\xfa\x96\x7b\x8c\xea\n"
+    );
+
+    // Saving fails without touching the file when the text can't be encoded.
+    assert!(tree
+        .update(cx, |tree, cx| {
+            tree.write_file(
+                Path::new("legacy/a.txt"),
+                "\u{1f600}\n".into(),
+                Default::default(),
+                shift_jis,
+                cx,
+            )
+        })
+        .await
+        .is_err());
+    assert_eq!(
+        fs.load_bytes(Path::new("/root/legacy/a.txt"))
+            .await
+            .unwrap(),
+        b"\x93\xfa\x96\x7b\x8c\xea\n"
+    );
+}
+
 #[gpui::test]
 async fn test_file_scan_inclusions(cx: &mut TestAppContext) {
     init_test(cx);
@@ -1975,8 +2076,13 @@ fn randomly_mutate_worktree(
                 })
             } else {
                 log::info!("overwriting file {:?} ({})", entry.path, entry.id.0);
-                let task =
-                    worktree.write_file(entry.path.clone(), "".into(), Default::default(), cx);
+                let task = worktree.write_file(
+                    entry.path.clone(),
+                    "".into(),
+                    Default::default(),
+                    Default::default(),
+                    cx,
+                );
                 cx.background_executor().spawn(async move {
                     task.await?;
                     Ok(())
@@ -475,6 +475,9 @@ fn main() {
         crate::zed::linux_prompts::init(cx);
 
         app_state.languages.set_theme(cx.theme().clone());
+        // Registered before the editor, so that text files are only read by the editor, which
+        // hands the files it can't open as text to the hex editor.
+        hex_editor::init(cx);
         editor::init(cx);
         image_viewer::init(cx);
         repl::notebook::init(cx);
@@ -500,6 +503,8 @@ fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        encoding_selector::init(cx);
+        bookmarks::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         language_tools::init(cx);
@@ -507,6 +512,9 @@ fn main() {
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         collab_ui::init(&app_state, cx);
         git_ui::init(cx);
+        debugger_ui::init(cx);
+        test_explorer::init(cx);
+        hierarchy_panel::init(cx);
         feedback::init(cx);
         markdown_preview::init(cx);
         welcome::init(cx);
@@ -20,7 +20,7 @@ use collections::VecDeque;
 use command_palette_hooks::CommandPaletteFilter;
 use editor::ProposedChangesEditorToolbar;
 use editor::{scroll::Autoscroll, Editor, MultiBuffer};
-use feature_flags::{FeatureFlagAppExt, FeatureFlagViewExt, GitUiFeatureFlag};
+use feature_flags::{DebuggerFeatureFlag, FeatureFlagAppExt, FeatureFlagViewExt, GitUiFeatureFlag};
 use futures::{channel::mpsc, select_biased, StreamExt};
 use gpui::{
     actions, point, px, Action, App, AppContext as _, AsyncApp, Context, DismissEvent, Element,
@@ -203,6 +203,7 @@ pub fn initialize_workspace(
         );
         let active_buffer_language =
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding = cx.new(|_| encoding_selector::ActiveBufferEncoding::default());
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
@@ -213,6 +214,7 @@ pub fn initialize_workspace(
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
             status_bar.add_right_item(inline_completion_button, window, cx);
+            status_bar.add_right_item(active_buffer_encoding, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
@@ -412,6 +414,16 @@ fn initialize_panels(
                 let git_panel = git_ui::git_panel::GitPanel::new(workspace, window, cx);
                 workspace.add_panel(git_panel, window, cx);
             });
+            cx.when_flag_enabled::<DebuggerFeatureFlag>(window, |workspace, window, cx| {
+                let debug_panel =
+                    debugger_ui::debugger_panel::DebugPanel::new(workspace, window, cx);
+                workspace.add_panel(debug_panel, window, cx);
+            });
+            let test_explorer_panel =
+                test_explorer::test_explorer_panel::TestExplorerPanel::new(workspace, window, cx);
+            workspace.add_panel(test_explorer_panel, window, cx);
+            let hierarchy_panel = hierarchy_panel::HierarchyPanel::new(workspace, window, cx);
+            workspace.add_panel(hierarchy_panel, window, cx);
         })?;
 
         let is_assistant2_enabled = if cfg!(test) {
@@ -4198,6 +4210,9 @@ mod tests {
             editor::init(cx);
             collab_ui::init(&app_state, cx);
             git_ui::init(cx);
+            debugger_ui::init(cx);
+            test_explorer::init(cx);
+            hierarchy_panel::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
             terminal_view::init(cx);
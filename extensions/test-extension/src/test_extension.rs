@@ -100,6 +100,7 @@ impl zed::Extension for TestExtension {
             command: self.language_server_binary_path(language_server_id, worktree)?,
             args: vec!["lsp".to_string()],
             env: Default::default(),
+            transport: zed::Transport::Stdio,
         })
     }
 